@@ -37,12 +37,36 @@ use sr_primitives::{
 use transaction_pool::txpool::{self, Pool as TransactionPool};
 use substrate_telemetry::{telemetry, CONSENSUS_INFO};
 
+/// Configuration of the block packing performed by the proposer.
+#[derive(Debug, Clone)]
+pub struct ProposerConfig {
+	/// Percentage of the proposal duration that may be spent on pushing transactions to the
+	/// block. The rest is left for the block finalization and evaluation. Values above 100
+	/// are treated as 100.
+	pub block_construction_percent: u32,
+	/// Number of transactions that are skipped because they don't fit into the block
+	/// anymore, before the block is considered full. Smaller transactions that come after
+	/// a skipped one may still fit.
+	pub max_skipped_transactions: usize,
+}
+
+impl Default for ProposerConfig {
+	fn default() -> Self {
+		ProposerConfig {
+			block_construction_percent: 67,
+			max_skipped_transactions: 8,
+		}
+	}
+}
+
 /// Proposer factory.
 pub struct ProposerFactory<C, A> where A: txpool::ChainApi {
 	/// The client instance.
 	pub client: Arc<C>,
 	/// The transaction pool.
 	pub transaction_pool: Arc<TransactionPool<A>>,
+	/// The block packing configuration.
+	pub config: ProposerConfig,
 }
 
 impl<B, E, Block, RA, A> consensus_common::Environment<Block> for
@@ -75,6 +99,7 @@ where
 			parent_id: id,
 			parent_number: *parent_header.number(),
 			transaction_pool: self.transaction_pool.clone(),
+			config: self.config.clone(),
 			now: Box::new(time::Instant::now),
 		};
 
@@ -89,6 +114,7 @@ pub struct Proposer<Block: BlockT, C, A: txpool::ChainApi> {
 	parent_id: BlockId<Block>,
 	parent_number: <<Block as BlockT>::Header as HeaderT>::Number,
 	transaction_pool: Arc<TransactionPool<A>>,
+	config: ProposerConfig,
 	now: Box<dyn Fn() -> time::Instant>,
 }
 
@@ -112,8 +138,9 @@ where
 		inherent_digests: DigestFor<Block>,
 		max_duration: time::Duration,
 	) -> Self::Create {
-		// leave some time for evaluation and block finalization (33%)
-		let deadline = (self.now)() + max_duration - max_duration / 3;
+		// leave some time for evaluation and block finalization
+		let block_construction_percent = self.config.block_construction_percent.min(100);
+		let deadline = (self.now)() + max_duration * block_construction_percent / 100;
 		futures::future::ready(self.propose_with(inherent_data, inherent_digests, deadline))
 	}
 }
@@ -133,11 +160,7 @@ impl<Block, B, E, RA, A> Proposer<Block, SubstrateClient<B, E, Block, RA>, A>	wh
 		inherent_digests: DigestFor<Block>,
 		deadline: time::Instant,
 	) -> Result<Block, error::Error> {
-		/// If the block is full we will attempt to push at most
-		/// this number of transactions before quitting for real.
-		/// It allows us to increase block utilization.
-		const MAX_SKIPPED_TRANSACTIONS: usize = 8;
-
+		let max_skipped_transactions = self.config.max_skipped_transactions;
 		let mut block_builder = self.client.new_block_at(&self.parent_id, inherent_digests)?;
 
 		// We don't check the API versions any further here since the dispatch compatibility
@@ -155,6 +178,9 @@ impl<Block, B, E, RA, A> Proposer<Block, SubstrateClient<B, E, Block, RA>, A>	wh
 		// proceed with transactions
 		let mut is_first = true;
 		let mut skipped = 0;
+		let mut pushed_bytes = 0;
+		let mut is_full = false;
+		let mut deadline_reached = false;
 		let mut unqueue_invalid = Vec::new();
 		let pending_iterator = self.transaction_pool.ready();
 
@@ -162,26 +188,32 @@ impl<Block, B, E, RA, A> Proposer<Block, SubstrateClient<B, E, Block, RA>, A>	wh
 		for pending in pending_iterator {
 			if (self.now)() > deadline {
 				debug!("Consensus deadline reached when pushing block transactions, proceeding with proposing.");
+				deadline_reached = true;
 				break;
 			}
 
 			trace!("[{:?}] Pushing to the block.", pending.hash);
 			match client::block_builder::BlockBuilder::push(&mut block_builder, pending.data.clone()) {
 				Ok(()) => {
+					pushed_bytes += pending.bytes;
 					debug!("[{:?}] Pushed to the block.", pending.hash);
 				}
 				Err(error::Error::ApplyExtrinsicFailed(ApplyError::FullBlock)) => {
 					if is_first {
 						debug!("[{:?}] Invalid transaction: FullBlock on empty block", pending.hash);
 						unqueue_invalid.push(pending.hash.clone());
-					} else if skipped < MAX_SKIPPED_TRANSACTIONS {
+					} else if skipped < max_skipped_transactions {
+						// the transaction exceeds the remaining weight or length of the block,
+						// but the next ones might be small enough to fit
 						skipped += 1;
 						debug!(
-							"Block seems full, but will try {} more transactions before quitting.",
-							MAX_SKIPPED_TRANSACTIONS - skipped
+							"[{:?}] Skipped: block seems full, but will try {} more transactions before quitting.",
+							pending.hash,
+							max_skipped_transactions - skipped,
 						);
 					} else {
 						debug!("Block is full, proceed with proposing.");
+						is_full = true;
 						break;
 					}
 				}
@@ -196,6 +228,11 @@ impl<Block, B, E, RA, A> Proposer<Block, SubstrateClient<B, E, Block, RA>, A>	wh
 
 		self.transaction_pool.remove_invalid(&unqueue_invalid);
 
+		// the weight is cleared when the block is finalized, so read it before
+		let block_weight = block_builder.block_weight().unwrap_or_else(|e| {
+			debug!("Failed to read the weight of the block: {:?}", e);
+			None
+		});
 		let block = block_builder.bake()?;
 
 		info!("Prepared block for proposing at {} [hash: {:?}; parent_hash: {}; extrinsics: [{}]]",
@@ -208,12 +245,30 @@ impl<Block, B, E, RA, A> Proposer<Block, SubstrateClient<B, E, Block, RA>, A>	wh
 				.collect::<Vec<_>>()
 				.join(", ")
 		);
+		let encoded_block = block.encode();
+		debug!(
+			"Block packing stats: {} bytes, {} bytes of transactions, weight: {:?}, {} transactions skipped, \
+			full: {}, deadline reached: {}",
+			encoded_block.len(),
+			pushed_bytes,
+			block_weight,
+			skipped,
+			is_full,
+			deadline_reached,
+		);
 		telemetry!(CONSENSUS_INFO; "prepared_block_for_proposing";
 			"number" => ?block.header().number(),
 			"hash" => ?<Block as BlockT>::Hash::from(block.header().hash()),
+			"extrinsics" => block.extrinsics().len(),
+			"bytes" => encoded_block.len(),
+			"transactions_bytes" => pushed_bytes,
+			"weight" => ?block_weight,
+			"skipped" => skipped,
+			"full" => is_full,
+			"deadline_reached" => deadline_reached,
 		);
 
-		if Decode::decode(&mut block.encode().as_slice()).as_ref() != Ok(&block) {
+		if Decode::decode(&mut encoded_block.as_slice()).as_ref() != Ok(&block) {
 			error!("Failed to verify block encoding/decoding");
 		}
 
@@ -254,6 +309,7 @@ mod tests {
 		let mut proposer_factory = ProposerFactory {
 			client: client.clone(),
 			transaction_pool: txpool.clone(),
+			config: Default::default(),
 		};
 
 		let mut proposer = proposer_factory.init(
@@ -275,4 +331,44 @@ mod tests {
 		assert_eq!(block.extrinsics().len(), 1);
 		assert_eq!(txpool.ready().count(), 2);
 	}
+
+	#[test]
+	fn should_respect_block_construction_percent() {
+		let client = Arc::new(test_client::new());
+		let chain_api = transaction_pool::ChainApi::new(client.clone());
+		let txpool = Arc::new(TransactionPool::new(Default::default(), chain_api));
+
+		txpool.submit_at(&BlockId::number(0), vec![extrinsic(0), extrinsic(1)]).unwrap();
+
+		let propose = |block_construction_percent| {
+			let mut proposer_factory = ProposerFactory {
+				client: client.clone(),
+				transaction_pool: txpool.clone(),
+				config: ProposerConfig {
+					block_construction_percent,
+					..Default::default()
+				},
+			};
+
+			let mut proposer = proposer_factory.init(
+				&client.header(&BlockId::number(0)).unwrap().unwrap(),
+			).unwrap();
+
+			// every call to `now` advances the time by 2 seconds
+			let cell = RefCell::new(time::Instant::now());
+			proposer.now = Box::new(move || {
+				let new = *cell.borrow() + time::Duration::from_secs(2);
+				cell.replace(new)
+			});
+			let deadline = time::Duration::from_secs(5);
+			futures::executor::block_on(proposer.propose(Default::default(), Default::default(), deadline))
+				.unwrap()
+		};
+
+		// with the default configuration the deadline is reached after the first transaction
+		assert_eq!(propose(ProposerConfig::default().block_construction_percent).extrinsics().len(), 1);
+
+		// the whole duration is enough to push both transactions
+		assert_eq!(propose(100).extrinsics().len(), 2);
+	}
 }
@@ -734,6 +734,12 @@ where
 		])
 	}).into();
 
+	let prometheus_interface: &str = if cli.prometheus_external { "0.0.0.0" } else { "127.0.0.1" };
+	config.prometheus_endpoint = match cli.prometheus_port {
+		Some(port) => Some(parse_address(&format!("{}:{}", prometheus_interface, port), None)?),
+		None => None,
+	};
+
 	// Override telemetry
 	if cli.no_telemetry {
 		config.telemetry_endpoints = None;
@@ -338,6 +338,14 @@ pub struct RunCmd {
 	#[structopt(long = "ws-max-connections", value_name = "COUNT")]
 	pub ws_max_connections: Option<usize>,
 
+	/// Listen to all Prometheus endpoint interfaces (default is local)
+	#[structopt(long = "prometheus-external")]
+	pub prometheus_external: bool,
+
+	/// Serve Prometheus metrics on the given TCP port (the endpoint is disabled by default)
+	#[structopt(long = "prometheus-port", value_name = "PORT")]
+	pub prometheus_port: Option<u16>,
+
 	/// Specify browser Origins allowed to access the HTTP & WS RPC servers.
 	/// It's a comma-separated list of origins (protocol://domain or special `null` value).
 	/// Value of `all` will disable origin validation.
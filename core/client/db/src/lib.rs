@@ -30,7 +30,10 @@ pub mod light;
 pub mod offchain;
 
 mod cache;
+mod metrics;
 mod storage_cache;
+#[cfg(feature = "kvdb-rocksdb")]
+mod upgrade;
 mod utils;
 
 use std::sync::Arc;
@@ -133,6 +136,10 @@ impl<B: BlockT> StateBackend<Blake2Hasher> for RefTrackingState<B> {
 		self.state.exists_child_storage(storage_key, key)
 	}
 
+	fn next_storage_key(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
+		self.state.next_storage_key(key)
+	}
+
 	fn for_keys_with_prefix<F: FnMut(&[u8])>(&self, prefix: &[u8], f: F) {
 		self.state.for_keys_with_prefix(prefix, f)
 	}
@@ -192,6 +199,12 @@ pub struct DatabaseSettings {
 	pub path: PathBuf,
 	/// Pruning mode.
 	pub pruning: PruningMode,
+	/// Storage key prefix of the runtime's map of event topics.
+	///
+	/// The rest of a key in the map is the `blake2_256` hash of the encoded topic, its value the
+	/// encoded list of `(block number, event index)` of the events deposited under the topic.
+	/// `None` disables the event topics index.
+	pub event_topics_key_prefix: Option<Vec<u8>>,
 }
 
 /// Create an instance of db-backed client.
@@ -238,6 +251,8 @@ pub(crate) mod columns {
 	pub const AUX: Option<u32> = Some(8);
 	/// Offchain workers local storage
 	pub const OFFCHAIN: Option<u32> = Some(9);
+	/// maps hashed event topics to lookup keys of the blocks that deposited them.
+	pub const EVENT_TOPICS: Option<u32> = Some(10);
 }
 
 struct PendingBlock<Block: BlockT> {
@@ -350,6 +365,40 @@ impl<Block: BlockT> client::blockchain::HeaderBackend<Block> for BlockchainDb<Bl
 	}
 }
 
+impl<Block: BlockT> client::backend::EventTopicsIndex<Block> for BlockchainDb<Block> {
+	fn events_with_topic(
+		&self,
+		topic: &[u8],
+		from: NumberFor<Block>,
+		to: NumberFor<Block>,
+	) -> Result<Vec<(NumberFor<Block>, Block::Hash, Vec<u32>)>, client::error::Error> {
+		let hashed_topic = primitives::blake2_256(topic);
+		let mut events = Vec::new();
+		// entries are keyed by hashed topic + lookup key, so they are iterated in block order.
+		for (key, value) in self.db.iter_from_prefix(columns::EVENT_TOPICS, &hashed_topic) {
+			let lookup_key = &key[hashed_topic.len()..];
+			let number: NumberFor<Block> = utils::lookup_key_to_number(lookup_key)?;
+			if number < from {
+				continue;
+			}
+			if number > to {
+				break;
+			}
+			// entries of retracted forks are never removed, so only report canonical blocks.
+			match client::blockchain::HeaderBackend::hash(self, number)? {
+				Some(hash) if hash.as_ref() == &lookup_key[4..] => {
+					let indices = Vec::<u32>::decode(&mut &value[..]).map_err(|_|
+						client::error::Error::Backend("Error decoding event topics index entry".into())
+					)?;
+					events.push((number, hash, indices));
+				},
+				_ => {},
+			}
+		}
+		Ok(events)
+	}
+}
+
 impl<Block: BlockT> client::blockchain::Backend<Block> for BlockchainDb<Block> {
 	fn body(&self, id: BlockId<Block>) -> Result<Option<Vec<Block::Extrinsic>>, client::error::Error> {
 		match read_db(&*self.db, columns::KEY_LOOKUP, columns::BODY, id)? {
@@ -718,6 +767,7 @@ pub struct Backend<Block: BlockT> {
 	canonicalization_delay: u64,
 	shared_cache: SharedCache<Block, Blake2Hasher>,
 	import_lock: Mutex<()>,
+	event_topics_key_prefix: Option<Vec<u8>>,
 }
 
 impl<Block: BlockT<Hash=H256>> Backend<Block> {
@@ -746,6 +796,19 @@ impl<Block: BlockT<Hash=H256>> Backend<Block> {
 		Self::new_test_db(keep_blocks, canonicalization_delay, db as Arc<_>)
 	}
 
+	/// Create new memory-backed client backend for tests that indexes the event topics stored
+	/// under the given key prefix.
+	#[cfg(any(test, feature = "test-helpers"))]
+	pub fn new_test_with_event_topics_index(
+		keep_blocks: u32,
+		canonicalization_delay: u64,
+		event_topics_key_prefix: Vec<u8>,
+	) -> Self {
+		let mut backend = Self::new_test(keep_blocks, canonicalization_delay);
+		backend.event_topics_key_prefix = Some(event_topics_key_prefix);
+		backend
+	}
+
 	/// Creates a client backend with test settings.
 	#[cfg(any(test, feature = "test-helpers"))]
 	pub fn new_test_db(keep_blocks: u32, canonicalization_delay: u64, db: Arc<dyn KeyValueDB>) -> Self {
@@ -756,6 +819,7 @@ impl<Block: BlockT<Hash=H256>> Backend<Block> {
 			state_cache_child_ratio: Some((50, 100)),
 			path: Default::default(),
 			pruning: PruningMode::keep_blocks(keep_blocks),
+			event_topics_key_prefix: None,
 		};
 		Self::from_kvdb(
 			db,
@@ -798,6 +862,7 @@ impl<Block: BlockT<Hash=H256>> Backend<Block> {
 				config.state_cache_child_ratio.unwrap_or(DEFAULT_CHILD_RATIO),
 			),
 			import_lock: Default::default(),
+			event_topics_key_prefix: config.event_topics_key_prefix.clone(),
 		})
 	}
 
@@ -1070,6 +1135,32 @@ impl<Block: BlockT<Hash=H256>> Backend<Block> {
 				transaction.put(columns::JUSTIFICATION, &lookup_key, &justification.encode());
 			}
 
+			if let Some(event_topics_prefix) = self.event_topics_key_prefix.as_ref() {
+				for (key, value) in &operation.storage_updates {
+					let value = match value {
+						Some(value) if key.starts_with(event_topics_prefix) => value,
+						_ => continue,
+					};
+					// only index the records of this block, the runtime may keep those of earlier blocks.
+					let indices = match Vec::<(NumberFor<Block>, u32)>::decode(&mut &value[..]) {
+						Ok(records) => records.into_iter()
+							.filter(|(record_number, _)| *record_number == number)
+							.map(|(_, index)| index)
+							.collect::<Vec<_>>(),
+						Err(_) => {
+							warn!("Failed to decode the event topics at {}, not indexing them", hash);
+							continue;
+						},
+					};
+					if indices.is_empty() {
+						continue;
+					}
+					let mut index_key = key[event_topics_prefix.len()..].to_vec();
+					index_key.extend_from_slice(&lookup_key);
+					transaction.put(columns::EVENT_TOPICS, &index_key, &indices.encode());
+				}
+			}
+
 			if number.is_zero() {
 				transaction.put(columns::META, meta_keys::FINALIZED_BLOCK, &lookup_key);
 				transaction.put(columns::META, meta_keys::GENESIS_HASH, hash.as_ref());
@@ -1185,6 +1276,9 @@ impl<Block: BlockT<Hash=H256>> Backend<Block> {
 			self.shared_cache.lock().sync(&enacted, &retracted);
 		}
 
+		let used_cache_size = self.shared_cache.lock().used_storage_cache_size();
+		metrics::STATE_CACHE_SIZE.set(used_cache_size as i64);
+
 		for (hash, number, is_best, is_finalized) in meta_updates {
 			self.blockchain.update_meta(hash, number, is_best, is_finalized);
 		}
@@ -1355,6 +1449,13 @@ impl<Block> client::backend::Backend<Block, Blake2Hasher> for Backend<Block> whe
 		Some(self.offchain_storage.clone())
 	}
 
+	fn event_topics_index(&self) -> Option<&dyn client::backend::EventTopicsIndex<Block>> {
+		match self.event_topics_key_prefix {
+			Some(_) => Some(&self.blockchain),
+			None => None,
+		}
+	}
+
 	fn revert(&self, n: NumberFor<Block>) -> Result<NumberFor<Block>, client::error::Error> {
 		let mut best = self.blockchain.info().best_number;
 		let finalized = self.blockchain.info().finalized_number;
@@ -2168,6 +2269,75 @@ mod tests {
 		assert!(backend.get_aux(b"test").unwrap().is_none());
 	}
 
+	#[test]
+	fn event_topics_index_returns_canonical_events_in_range() {
+		let prefix = vec![7u8; 32];
+		let backend = Backend::<Block>::new_test_with_event_topics_index(1000, 100, prefix.clone());
+		let topic_key = |topic: &[u8]| {
+			let mut key = prefix.clone();
+			key.extend_from_slice(&primitives::blake2_256(topic));
+			key
+		};
+		let topic = [1u8; 32];
+		let other_topic = [2u8; 32];
+
+		let import = |
+			number: u64,
+			parent_hash: H256,
+			extrinsics_root: H256,
+			updates: StorageCollection,
+			state: NewBlockState,
+		| {
+			let header = Header {
+				number,
+				parent_hash,
+				state_root: Default::default(),
+				digest: Default::default(),
+				extrinsics_root,
+			};
+			let hash = header.hash();
+			let parent_id = if number == 0 {
+				BlockId::Hash(Default::default())
+			} else {
+				BlockId::Hash(parent_hash)
+			};
+			let mut op = backend.begin_operation().unwrap();
+			backend.begin_state_operation(&mut op, parent_id).unwrap();
+			op.update_storage(updates, Vec::new()).unwrap();
+			op.set_block_data(header, Some(vec![]), None, state).unwrap();
+			backend.commit_operation(op).unwrap();
+			hash
+		};
+
+		let block0 = import(0, Default::default(), Default::default(), vec![], NewBlockState::Final);
+		let block1 = import(1, block0, Default::default(), vec![
+			(topic_key(&topic), Some(vec![(1u64, 0u32), (1, 3)].encode())),
+		], NewBlockState::Best);
+		let block2 = import(2, block1, Default::default(), vec![
+			(topic_key(&topic), None),
+			(topic_key(&other_topic), Some(vec![(2u64, 1u32)].encode())),
+		], NewBlockState::Best);
+		let _fork2 = import(2, block1, H256::from_low_u64_be(42), vec![
+			(topic_key(&topic), Some(vec![(2u64, 0u32)].encode())),
+		], NewBlockState::Normal);
+		// records of earlier blocks that are kept by the runtime are not indexed again.
+		let block3 = import(3, block2, Default::default(), vec![
+			(topic_key(&topic), Some(vec![(1u64, 0u32), (3, 2)].encode())),
+		], NewBlockState::Best);
+
+		let index = backend.event_topics_index().unwrap();
+		assert_eq!(
+			index.events_with_topic(&topic, 0, 3).unwrap(),
+			vec![(1, block1, vec![0, 3]), (3, block3, vec![2])],
+		);
+		assert_eq!(index.events_with_topic(&topic, 2, 3).unwrap(), vec![(3, block3, vec![2])]);
+		assert_eq!(index.events_with_topic(&topic, 0, 2).unwrap(), vec![(1, block1, vec![0, 3])]);
+		assert_eq!(index.events_with_topic(&other_topic, 0, 3).unwrap(), vec![(2, block2, vec![1])]);
+
+		// the index is disabled without a prefix.
+		assert!(Backend::<Block>::new_test(1000, 100).event_topics_index().is_none());
+	}
+
 	#[test]
 	fn test_finalize_block_with_justification() {
 		use client::blockchain::{Backend as BlockChainBackend};
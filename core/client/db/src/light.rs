@@ -276,6 +276,12 @@ impl<Block: BlockT> LightStorage<Block> {
 		if let Some(new_cht_number) = cht::is_build_required(cht::size(), *header.number()) {
 			let new_cht_start: NumberFor<Block> = cht::start_number(cht::size(), new_cht_number);
 
+			// headers that precede a warp sync header are unknown => the CHT can't be built
+			if self.hash(new_cht_start)?.is_none() {
+				trace!(target: "db", "Not building CHT#{}: headers are unknown", new_cht_number);
+				return Ok(());
+			}
+
 			let mut current_num = new_cht_start;
 			let cht_range = ::std::iter::from_fn(|| {
 				let old_current_num = current_num;
@@ -514,6 +520,71 @@ impl<Block> LightBlockchainStorage<Block> for LightStorage<Block>
 		}
 	}
 
+	fn import_warp_header(
+		&self,
+		header: Block::Header,
+		aux_ops: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+	) -> ClientResult<()> {
+		let mut transaction = DBTransaction::new();
+
+		let hash = header.hash();
+		let number = *header.number();
+
+		let (finalized_hash, finalized_number, best_number) = {
+			let meta = self.meta.read();
+			(meta.finalized_hash, meta.finalized_number, meta.best_number)
+		};
+		if number <= finalized_number {
+			return Err(ClientError::NotInFinalizedChain);
+		}
+
+		for (key, maybe_val) in aux_ops {
+			match maybe_val {
+				Some(val) => transaction.put_vec(columns::AUX, &key, val),
+				None => transaction.delete(columns::AUX, &key),
+			}
+		}
+
+		// headers that were imported on top of the last finalized block are not
+		// necessarily ancestors of the new header => forget their canonical mappings
+		let mut stale_number = finalized_number + One::one();
+		while stale_number <= best_number {
+			if stale_number != number {
+				utils::remove_number_to_key_mapping(&mut transaction, columns::KEY_LOOKUP, stale_number)?;
+			}
+			stale_number += One::one();
+		}
+
+		let lookup_key = utils::number_and_hash_to_lookup_key(number, &hash)?;
+		utils::insert_hash_to_key_mapping(&mut transaction, columns::KEY_LOOKUP, number, hash)?;
+		utils::insert_number_to_key_mapping(&mut transaction, columns::KEY_LOOKUP, number, hash)?;
+		transaction.put(columns::HEADER, &lookup_key, &header.encode());
+		transaction.put(columns::META, meta_keys::BEST_BLOCK, &lookup_key);
+		transaction.put(columns::META, meta_keys::FINALIZED_BLOCK, &lookup_key);
+
+		{
+			// the ancestry of the header is unknown, so cached values are carried over from
+			// the last finalized block we know of
+			let mut cache = self.cache.0.write();
+			let cache_ops = cache.transaction(&mut transaction)
+				.on_block_insert(
+					ComplexBlockId::new(finalized_hash, finalized_number),
+					ComplexBlockId::new(hash, number),
+					HashMap::new(),
+					CacheEntryType::Final,
+				)?
+				.into_ops();
+
+			debug!("Light DB Commit warp header {:?} ({})", hash, number);
+			self.db.write(transaction).map_err(db_err)?;
+			cache.commit(cache_ops);
+		}
+
+		self.update_meta(hash, number, true, true);
+
+		Ok(())
+	}
+
 	fn last_finalized(&self) -> ClientResult<Block::Hash> {
 		Ok(self.meta.read().finalized_hash.clone())
 	}
@@ -657,6 +728,38 @@ pub(crate) mod tests {
 		assert_eq!(db.db.iter(columns::KEY_LOOKUP).count(), 4);
 	}
 
+	#[test]
+	fn import_warp_header_works() {
+		let db = LightStorage::new_test();
+		let cht_size: u64 = cht::size();
+
+		let genesis_hash = insert_block(&db, HashMap::new(), || default_header(&Default::default(), 0));
+		let _ = insert_block(&db, HashMap::new(), || default_header(&genesis_hash, 1));
+
+		// the warp header is not a descendant of any known header
+		let warp_header = default_header(&Hash::from_low_u64_be(42), cht_size);
+		let warp_hash = warp_header.hash();
+		db.import_warp_header(warp_header, vec![(b"key".to_vec(), Some(b"value".to_vec()))]).unwrap();
+
+		let info = db.info();
+		assert_eq!((info.best_hash, info.best_number), (warp_hash, cht_size));
+		assert_eq!((info.finalized_hash, info.finalized_number), (warp_hash, cht_size));
+		assert_eq!(db.hash(1).unwrap(), None);
+		assert_eq!(db.hash(cht_size).unwrap(), Some(warp_hash));
+		assert_eq!(db.get_aux(b"key").unwrap(), Some(b"value".to_vec()));
+
+		// it is impossible to warp back in time
+		assert!(db.import_warp_header(default_header(&Default::default(), 1), Vec::new()).is_err());
+
+		// finalizing descendants doesn't try to build CHTs for the unknown headers
+		let mut prev_hash = warp_hash;
+		for number in cht_size + 1..cht_size * 2 + 2 {
+			prev_hash = insert_final_block(&db, HashMap::new(), || default_header(&prev_hash, number));
+		}
+		assert_eq!(db.info().finalized_number, cht_size * 2 + 1);
+		assert!(db.header_cht_root(cht_size, cht_size / 2).is_err());
+	}
+
 	#[test]
 	fn finalized_ancient_headers_are_replaced_with_cht() {
 		fn insert_headers<F: Fn(&Hash, u64) -> Header>(header_producer: F) -> LightStorage<Block> {
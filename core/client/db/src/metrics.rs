@@ -0,0 +1,26 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Prometheus metrics of the database backend.
+
+use substrate_prometheus::{IntGauge, create_int_gauge, lazy_static};
+
+lazy_static! {
+	/// Memory used by the shared state cache, in bytes.
+	pub static ref STATE_CACHE_SIZE: IntGauge = create_int_gauge(
+		"substrate_state_cache_bytes", "Memory used by the shared state cache, in bytes",
+	);
+}
@@ -0,0 +1,97 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Database upgrade logic.
+
+use std::fs;
+use std::io::{Read, Write, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use kvdb_rocksdb::{Database, DatabaseConfig};
+
+use crate::utils::{NUM_COLUMNS, db_err};
+
+/// Version file name.
+const VERSION_FILE_NAME: &str = "db_version";
+
+/// Current db version.
+const CURRENT_VERSION: u32 = 2;
+
+/// Number of columns in the v1 database.
+const V1_NUM_COLUMNS: u32 = 10;
+
+/// Upgrade database to current version.
+pub fn upgrade_db(db_path: &Path) -> client::error::Result<()> {
+	let is_empty = db_path.read_dir().map_or(true, |mut d| d.next().is_none());
+	if !is_empty {
+		let db_version = current_version(db_path)?;
+		match db_version {
+			1 => migrate_1_to_2(db_path)?,
+			CURRENT_VERSION => (),
+			_ => return Err(client::error::Error::Backend(
+				format!("Future database version: {}", db_version)
+			)),
+		}
+	}
+
+	update_version(db_path)
+}
+
+/// Migration from version 1 to version 2: add the column of the event topics index.
+///
+/// The index is only built for the blocks that are imported after the migration.
+fn migrate_1_to_2(db_path: &Path) -> client::error::Result<()> {
+	let db_path = db_path.to_str()
+		.ok_or_else(|| client::error::Error::Backend("Invalid database path".into()))?;
+	let db_config = DatabaseConfig::with_columns(Some(V1_NUM_COLUMNS));
+	let db = Database::open(&db_config, db_path).map_err(db_err)?;
+	for _ in V1_NUM_COLUMNS..NUM_COLUMNS {
+		db.add_column().map_err(db_err)?;
+	}
+	Ok(())
+}
+
+/// Reads current database version from the file at given path.
+/// If the file does not exist, the database predates the version file and is v1.
+fn current_version(path: &Path) -> client::error::Result<u32> {
+	let unknown_version_err = || client::error::Error::Backend("Unknown database version".into());
+
+	match fs::File::open(version_file_path(path)) {
+		Err(ref err) if err.kind() == ErrorKind::NotFound => Ok(1),
+		Err(_) => Err(unknown_version_err()),
+		Ok(mut file) => {
+			let mut s = String::new();
+			file.read_to_string(&mut s).map_err(|_| unknown_version_err())?;
+			s.trim().parse::<u32>().map_err(|_| unknown_version_err())
+		},
+	}
+}
+
+/// Writes current database version to the file.
+/// Creates a new file if the version file does not exist yet.
+fn update_version(path: &Path) -> client::error::Result<()> {
+	fs::create_dir_all(path).map_err(db_err)?;
+	let mut file = fs::File::create(version_file_path(path)).map_err(db_err)?;
+	file.write_all(format!("{}", CURRENT_VERSION).as_bytes()).map_err(db_err)?;
+	Ok(())
+}
+
+/// Returns the version file path.
+fn version_file_path(path: &Path) -> PathBuf {
+	let mut file_path = path.to_owned();
+	file_path.push(VERSION_FILE_NAME);
+	file_path
+}
@@ -220,6 +220,10 @@ pub trait Backend<Block, H>: AuxStore + Send + Sync where
 	fn changes_trie_storage(&self) -> Option<&Self::ChangesTrieStorage>;
 	/// Returns a handle to offchain storage.
 	fn offchain_storage(&self) -> Option<Self::OffchainStorage>;
+	/// Returns a reference to the event topics index, if the backend maintains one.
+	fn event_topics_index(&self) -> Option<&dyn EventTopicsIndex<Block>> {
+		None
+	}
 	/// Returns true if state for given block is available.
 	fn have_state_at(&self, hash: &Block::Hash, _number: NumberFor<Block>) -> bool {
 		self.state_at(BlockId::Hash(hash.clone())).is_ok()
@@ -278,6 +282,22 @@ pub trait OffchainStorage: Clone + Send + Sync {
 	) -> bool;
 }
 
+/// Index of the events that blocks have deposited under a given topic.
+///
+/// The index is built at block import from the storage changes of the runtime's map of event
+/// topics, so it does not depend on the state of the indexed blocks.
+pub trait EventTopicsIndex<Block: BlockT>: Send + Sync {
+	/// Get the canonical blocks in the inclusive range `from..=to` that have deposited
+	/// at least one event under the given encoded topic, in ascending order, together with
+	/// the indices of these events in the block's list of events.
+	fn events_with_topic(
+		&self,
+		topic: &[u8],
+		from: NumberFor<Block>,
+		to: NumberFor<Block>,
+	) -> error::Result<Vec<(NumberFor<Block>, Block::Hash, Vec<u32>)>>;
+}
+
 /// Changes trie storage that supports pruning.
 pub trait PrunableStateChangesTrieStorage<Block: BlockT, H: Hasher>:
 	StateChangesTrieStorage<H, NumberFor<Block>>
@@ -308,4 +328,12 @@ where
 	/// Returns reference to blockchain backend that either resolves blockchain data
 	/// locally, or prepares request to fetch that data from remote node.
 	fn remote_blockchain(&self) -> Arc<dyn RemoteBlockchain<Block>>;
+	/// Import a finalized header whose ancestry is unknown, after its finality has been
+	/// proven by a warp sync proof. Any auxiliary storage updates are applied in the same
+	/// operation.
+	fn import_warp_header(
+		&self,
+		header: Block::Header,
+		aux_ops: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+	) -> error::Result<()>;
 }
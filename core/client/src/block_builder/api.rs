@@ -16,14 +16,14 @@
 
 //! The runtime api for building blocks.
 
-use sr_primitives::{traits::Block as BlockT, ApplyResult};
+use sr_primitives::{traits::Block as BlockT, weights::Weight, ApplyResult};
 use rstd::vec::Vec;
 use sr_api_macros::decl_runtime_apis;
 pub use inherents::{InherentData, CheckInherentsResult};
 
 decl_runtime_apis! {
 	/// The `BlockBuilder` api trait that provides required functions for building a block for a runtime.
-	#[api_version(3)]
+	#[api_version(4)]
 	pub trait BlockBuilder {
 		/// Apply the given extrinsics.
 		fn apply_extrinsic(extrinsic: <Block as BlockT>::Extrinsic) -> ApplyResult;
@@ -36,5 +36,7 @@ decl_runtime_apis! {
 		fn check_inherents(block: Block, data: InherentData) -> CheckInherentsResult;
 		/// Generate a random seed.
 		fn random_seed() -> <Block as BlockT>::Hash;
+		/// Get the weight consumed by the extrinsics applied to the current block so far.
+		fn block_weight() -> Weight;
 	}
 }
@@ -17,7 +17,7 @@
 use super::api::BlockBuilder as BlockBuilderApi;
 use std::vec::Vec;
 use codec::Encode;
-use sr_primitives::ApplyOutcome;
+use sr_primitives::{ApplyOutcome, weights::Weight};
 use sr_primitives::generic::BlockId;
 use sr_primitives::traits::{
 	Header as HeaderT, Hash, Block as BlockT, One, HashFor, ProvideRuntimeApi, ApiRef, DigestFor,
@@ -115,6 +115,17 @@ where
 		})
 	}
 
+	/// Returns the weight consumed by the extrinsics pushed so far.
+	///
+	/// Returns `None` if the runtime doesn't report the block weight.
+	pub fn block_weight(&self) -> error::Result<Option<Weight>> {
+		if !self.api.has_api_with::<dyn BlockBuilderApi<Block>, _>(&self.block_id, |v| v >= 4)? {
+			return Ok(None)
+		}
+
+		self.api.block_weight_with_context(&self.block_id, ExecutionContext::BlockConstruction).map(Some)
+	}
+
 	/// Consume the builder to return a valid `Block` containing all pushed extrinsics.
 	pub fn bake(mut self) -> error::Result<Block> {
 		self.bake_impl()?;
@@ -72,7 +72,7 @@ use crate::{
 	light::{call_executor::prove_execution, fetcher::ChangesProof},
 	block_builder::{self, api::BlockBuilder as BlockBuilderAPI},
 	error::Error,
-	cht, error, in_mem, genesis
+	cht, error, in_mem, genesis, metrics
 };
 
 /// Type that implements `futures::Stream` of block import events.
@@ -806,6 +806,11 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 
 			let ClientImportOperation { op, notify_imported, notify_finalized } = op;
 			self.backend.commit_operation(op)?;
+
+			let info = self.backend.blockchain().info();
+			metrics::BEST_BLOCK_HEIGHT.set(info.best_number.saturated_into::<u64>() as i64);
+			metrics::FINALIZED_BLOCK_HEIGHT.set(info.finalized_number.saturated_into::<u64>() as i64);
+
 			self.notify_finalized(notify_finalized)?;
 
 			if let Some(notify_imported) = notify_imported {
@@ -866,6 +871,7 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 
 		*self.importing_block.write() = Some(hash);
 
+		let import_timer = metrics::BLOCK_IMPORT_TIME.start_timer();
 		let result = self.execute_and_import_block(
 			operation,
 			origin,
@@ -878,6 +884,7 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 			auxiliary,
 			fork_choice,
 		);
+		import_timer.observe_duration();
 
 		telemetry!(SUBSTRATE_INFO; "block.import";
 			"height" => height,
@@ -2813,6 +2820,7 @@ pub(crate) mod tests {
 				state_cache_child_ratio: None,
 				path: tmp.path().into(),
 				pruning: PruningMode::ArchiveAll,
+				event_topics_key_prefix: None,
 			},
 			u64::max_value(),
 		).unwrap());
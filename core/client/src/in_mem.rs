@@ -176,6 +176,29 @@ impl<Block: BlockT> Blockchain<Block> {
 		Ok(())
 	}
 
+	/// Insert a finalized header whose ancestry is unknown (e.g. after its finality has
+	/// been proven by a warp sync proof). The header becomes both the best and the last
+	/// finalized block.
+	pub fn insert_warp_header(&self, header: <Block as BlockT>::Header) -> crate::error::Result<()> {
+		let hash = header.hash();
+		let number = header.number().clone();
+
+		let mut storage = self.storage.write();
+		if number <= storage.finalized_number {
+			return Err(error::Error::NotInFinalizedChain);
+		}
+
+		storage.leaves.import(hash.clone(), number.clone(), header.parent_hash().clone());
+		storage.blocks.insert(hash.clone(), StoredBlock::new(header, None, None));
+		storage.hashes.insert(number.clone(), hash.clone());
+		storage.best_hash = hash.clone();
+		storage.best_number = number.clone();
+		storage.finalized_hash = hash;
+		storage.finalized_number = number;
+
+		Ok(())
+	}
+
 	/// Get total number of blocks.
 	pub fn blocks_count(&self) -> usize {
 		self.storage.read().blocks.len()
@@ -412,6 +435,17 @@ impl<Block: BlockT> light::blockchain::Storage<Block> for Blockchain<Block>
 		Blockchain::finalize_header(self, id, None)
 	}
 
+	fn import_warp_header(
+		&self,
+		header: Block::Header,
+		aux_ops: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+	) -> error::Result<()> {
+		self.insert_warp_header(header)?;
+
+		self.write_aux(aux_ops);
+		Ok(())
+	}
+
 	fn header_cht_root(
 		&self,
 		_cht_size: NumberFor<Block>,
@@ -722,6 +756,17 @@ where
 	fn remote_blockchain(&self) -> Arc<dyn crate::light::blockchain::RemoteBlockchain<Block>> {
 		unimplemented!()
 	}
+
+	fn import_warp_header(
+		&self,
+		header: Block::Header,
+		aux_ops: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+	) -> error::Result<()> {
+		self.blockchain.insert_warp_header(header)?;
+
+		self.blockchain.write_aux(aux_ops);
+		Ok(())
+	}
 }
 
 /// Prunable in-memory changes trie storage.
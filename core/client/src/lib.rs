@@ -103,6 +103,8 @@ mod call_executor;
 mod client;
 #[cfg(feature = "std")]
 mod notifications;
+#[cfg(feature = "std")]
+mod metrics;
 
 
 #[cfg(feature = "std")]
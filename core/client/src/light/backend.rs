@@ -246,6 +246,14 @@ where
 	fn remote_blockchain(&self) -> Arc<dyn crate::light::blockchain::RemoteBlockchain<Block>> {
 		self.blockchain.clone()
 	}
+
+	fn import_warp_header(
+		&self,
+		header: Block::Header,
+		aux_ops: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+	) -> ClientResult<()> {
+		self.blockchain.storage().import_warp_header(header, aux_ops)
+	}
 }
 
 impl<S, F, Block, H> BlockImportOperation<Block, H> for ImportOperation<Block, S, F, H>
@@ -377,6 +385,10 @@ where
 		Err(ClientError::NotAvailableOnLightClient.into())
 	}
 
+	fn next_storage_key(&self, _key: &[u8]) -> ClientResult<Option<Vec<u8>>> {
+		Err(ClientError::NotAvailableOnLightClient.into())
+	}
+
 	fn for_keys_with_prefix<A: FnMut(&[u8])>(&self, _prefix: &[u8], _action: A) {
 		// whole state is not available on light node
 	}
@@ -457,6 +469,15 @@ where
 		}
 	}
 
+	fn next_storage_key(&self, key: &[u8]) -> ClientResult<Option<Vec<u8>>> {
+		match *self {
+			OnDemandOrGenesisState::OnDemand(ref state) =>
+				StateBackend::<H>::next_storage_key(state, key),
+			OnDemandOrGenesisState::Genesis(ref state) =>
+				Ok(state.next_storage_key(key).expect(IN_MEMORY_EXPECT_PROOF)),
+		}
+	}
+
 	fn for_keys_with_prefix<A: FnMut(&[u8])>(&self, prefix: &[u8], action: A) {
 		match *self {
 			OnDemandOrGenesisState::OnDemand(ref state) =>
@@ -52,6 +52,15 @@ pub trait Storage<Block: BlockT>: AuxStore + BlockchainHeaderBackend<Block> {
 	/// Mark historic header as finalized.
 	fn finalize_header(&self, block: BlockId<Block>) -> ClientResult<()>;
 
+	/// Store a finalized header whose ancestry is unknown, e.g. because its finality has
+	/// been proven by a warp sync proof. The header becomes both the best and the last
+	/// finalized block. Should refuse to revert any finalized blocks.
+	fn import_warp_header(
+		&self,
+		header: Block::Header,
+		aux_ops: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+	) -> ClientResult<()>;
+
 	/// Get last finalized header.
 	fn last_finalized(&self) -> ClientResult<Block::Hash>;
 
@@ -342,6 +351,14 @@ pub mod tests {
 			Err(ClientError::Backend("Test error".into()))
 		}
 
+		fn import_warp_header(
+			&self,
+			_header: Header,
+			_aux_ops: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+		) -> ClientResult<()> {
+			Err(ClientError::Backend("Test error".into()))
+		}
+
 		fn last_finalized(&self) -> ClientResult<Hash> {
 			Err(ClientError::Backend("Test error".into()))
 		}
@@ -0,0 +1,36 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Prometheus metrics of the client.
+
+use substrate_prometheus::{Histogram, IntGauge, create_histogram, create_int_gauge, lazy_static};
+
+lazy_static! {
+	/// Height of the best block.
+	pub static ref BEST_BLOCK_HEIGHT: IntGauge = create_int_gauge(
+		"substrate_block_height_best", "Height of the best block",
+	);
+
+	/// Height of the last finalized block.
+	pub static ref FINALIZED_BLOCK_HEIGHT: IntGauge = create_int_gauge(
+		"substrate_block_height_finalized", "Height of the last finalized block",
+	);
+
+	/// Time taken to import a block, in seconds.
+	pub static ref BLOCK_IMPORT_TIME: Histogram = create_histogram(
+		"substrate_block_import_time", "Time taken to import a block, in seconds",
+	);
+}
@@ -131,6 +131,14 @@ impl RawBabePreDigest {
 			RawBabePreDigest::Secondary { slot_number, .. } => *slot_number,
 		}
 	}
+
+	/// Returns the authority index of the pre digest.
+	pub fn authority_index(&self) -> AuthorityIndex {
+		match self {
+			RawBabePreDigest::Primary { authority_index, .. } => *authority_index,
+			RawBabePreDigest::Secondary { authority_index, .. } => *authority_index,
+		}
+	}
 }
 
 #[cfg(feature = "std")]
@@ -23,7 +23,7 @@ mod digest;
 
 use codec::{Encode, Decode};
 use rstd::vec::Vec;
-use sr_primitives::ConsensusEngineId;
+use sr_primitives::{ConsensusEngineId, traits::Header};
 use substrate_client::decl_runtime_apis;
 
 #[cfg(feature = "std")]
@@ -105,6 +105,72 @@ pub enum ConsensusLog {
 	OnDisabled(AuthorityIndex),
 }
 
+/// Proof that an authority sealed two different headers in the same slot.
+///
+/// The headers are kept with their seals, which are what prove the equivocation.
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Clone, Eq, PartialEq, Encode, Decode)]
+pub struct EquivocationProof<H> {
+	/// The authority that equivocated.
+	pub offender: AuthorityId,
+	/// The slot in which the equivocation happened.
+	pub slot_number: SlotNumber,
+	/// The first header sealed by the offender.
+	pub first_header: H,
+	/// The second header sealed by the offender.
+	pub second_header: H,
+}
+
+impl<H: Header> EquivocationProof<H> {
+	/// Check that both headers differ, are claimed by the same authority index for the proof's
+	/// slot and are sealed by the offender.
+	///
+	/// Returns the authority index claimed by the headers.
+	pub fn check(&self) -> Option<AuthorityIndex> {
+		if self.first_header.hash() == self.second_header.hash() {
+			return None;
+		}
+
+		let first = check_seal(self.first_header.clone(), &self.offender)?;
+		let second = check_seal(self.second_header.clone(), &self.offender)?;
+		if first.slot_number() != self.slot_number || second.slot_number() != self.slot_number {
+			return None;
+		}
+		if first.authority_index() != second.authority_index() {
+			return None;
+		}
+
+		Some(first.authority_index())
+	}
+}
+
+/// Check that the header is sealed by the given authority and return its BABE pre-digest.
+fn check_seal<H: Header>(mut header: H, author: &AuthorityId) -> Option<RawBabePreDigest> {
+	use app_crypto::RuntimeAppPublic;
+
+	let signature = match header.digest_mut().pop()?.as_seal()? {
+		(id, mut data) if id == BABE_ENGINE_ID => AuthoritySignature::decode(&mut data).ok()?,
+		_ => return None,
+	};
+
+	// the seal signs the hash of the header without it.
+	let pre_hash = header.hash();
+	if !author.verify(&pre_hash, &signature) {
+		return None;
+	}
+
+	header.digest().logs().iter()
+		.filter_map(|log| log.as_pre_runtime())
+		.find(|(id, _)| *id == BABE_ENGINE_ID)
+		.and_then(|(_, mut data)| RawBabePreDigest::decode(&mut data).ok())
+}
+
+/// Key in the offchain persistent storage under which the node queues the equivocation proofs it
+/// observed, for the runtime's offchain worker to submit them.
+///
+/// The value is an encoded `Vec<EquivocationProof<Block::Header>>`.
+pub const EQUIVOCATION_REPORTS_KEY: &[u8] = b"babe:equivocation_reports";
+
 /// Configuration data used by the BABE consensus engine.
 #[derive(Copy, Clone, Hash, PartialEq, Eq, Debug, Encode, Decode)]
 pub struct BabeConfiguration {
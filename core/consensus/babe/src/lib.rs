@@ -59,6 +59,7 @@
 #![forbid(unsafe_code, missing_docs)]
 pub use babe_primitives::*;
 pub use consensus_common::SyncOracle;
+pub use slots::{BackoffAuthoringBlocksStrategy, BackoffAuthoringOnFinalizedHeadLagging};
 use std::{collections::HashMap, sync::Arc, u64, pin::Pin, time::{Instant, Duration}};
 use babe_primitives;
 use consensus_common::ImportResult;
@@ -74,7 +75,9 @@ use sr_primitives::traits::{
 use keystore::KeyStorePtr;
 use codec::{Decode, Encode};
 use parking_lot::{Mutex, MutexGuard};
-use primitives::{blake2_256, Blake2Hasher, H256, Pair, Public, U256};
+use primitives::{
+	blake2_256, Blake2Hasher, H256, Pair, Public, U256, offchain::PERSISTENT_STORAGE_PREFIX,
+};
 use merlin::Transcript;
 use inherents::{InherentDataProviders, InherentData};
 use substrate_telemetry::{
@@ -101,7 +104,8 @@ use consensus_common::import_queue::{Verifier, BasicQueue};
 use client::{
 	block_builder::api::BlockBuilder as BlockBuilderApi,
 	blockchain::{self, HeaderBackend, ProvideCache}, BlockchainEvents, CallExecutor, Client,
-	runtime_api::ApiExt, error::Result as ClientResult, backend::{AuxStore, Backend},
+	runtime_api::ApiExt, error::Result as ClientResult,
+	backend::{AuxStore, Backend, OffchainStorage},
 	ProvideUncles,
 	utils::is_descendent_of,
 };
@@ -167,7 +171,7 @@ impl SlotCompatible for BabeLink {
 }
 
 /// Parameters for BABE.
-pub struct BabeParams<C, E, I, SO, SC> {
+pub struct BabeParams<C, E, I, SO, SC, BS> {
 	/// The configuration for BABE. Includes the slot duration, threshold, and
 	/// other parameters.
 	pub config: Config,
@@ -196,12 +200,15 @@ pub struct BabeParams<C, E, I, SO, SC> {
 	/// Force authoring of blocks even if we are offline
 	pub force_authoring: bool,
 
+	/// Strategy and parameters for backing off block production.
+	pub backoff_authoring_blocks: Option<BS>,
+
 	/// The source of timestamps for relative slots
 	pub time_source: BabeLink,
 }
 
 /// Start the babe worker. The returned future should be run in a tokio runtime.
-pub fn start_babe<B, C, SC, E, I, SO, Error, H>(BabeParams {
+pub fn start_babe<B, C, SC, E, I, SO, BS, Error, H>(BabeParams {
 	config,
 	client,
 	keystore,
@@ -211,13 +218,14 @@ pub fn start_babe<B, C, SC, E, I, SO, Error, H>(BabeParams {
 	sync_oracle,
 	inherent_data_providers,
 	force_authoring,
+	backoff_authoring_blocks,
 	time_source,
-}: BabeParams<C, E, I, SO, SC>) -> Result<
+}: BabeParams<C, E, I, SO, SC, BS>) -> Result<
 	impl futures01::Future<Item=(), Error=()>,
 	consensus_common::Error,
 > where
 	B: BlockT<Header=H>,
-	C: ProvideRuntimeApi + ProvideCache<B> + ProvideUncles<B> + Send + Sync + 'static,
+	C: ProvideRuntimeApi + ProvideCache<B> + ProvideUncles<B> + HeaderBackend<B> + Send + Sync + 'static,
 	C::Api: BabeApi<B>,
 	SC: SelectChain<B> + 'static,
 	E: Environment<B, Error=Error> + Send + Sync,
@@ -227,6 +235,7 @@ pub fn start_babe<B, C, SC, E, I, SO, Error, H>(BabeParams {
 	I: BlockImport<B> + Send + Sync + 'static,
 	Error: std::error::Error + Send + From<::consensus_common::Error> + From<I::Error> + 'static,
 	SO: SyncOracle + Send + Sync + Clone,
+	BS: BackoffAuthoringBlocksStrategy<NumberFor<B>> + Send + Sync + 'static,
 {
 	let worker = BabeWorker {
 		client: client.clone(),
@@ -234,6 +243,7 @@ pub fn start_babe<B, C, SC, E, I, SO, Error, H>(BabeParams {
 		env,
 		sync_oracle: sync_oracle.clone(),
 		force_authoring,
+		backoff_authoring_blocks,
 		c: config.c(),
 		keystore,
 	};
@@ -253,19 +263,20 @@ pub fn start_babe<B, C, SC, E, I, SO, Error, H>(BabeParams {
 	).map(|()| Ok::<(), ()>(())).compat())
 }
 
-struct BabeWorker<C, E, I, SO> {
+struct BabeWorker<C, E, I, SO, BS> {
 	client: Arc<C>,
 	block_import: Arc<Mutex<I>>,
 	env: E,
 	sync_oracle: SO,
 	force_authoring: bool,
+	backoff_authoring_blocks: Option<BS>,
 	c: (u64, u64),
 	keystore: KeyStorePtr,
 }
 
-impl<H, B, C, E, I, Error, SO> slots::SimpleSlotWorker<B> for BabeWorker<C, E, I, SO> where
+impl<H, B, C, E, I, Error, SO, BS> slots::SimpleSlotWorker<B> for BabeWorker<C, E, I, SO, BS> where
 	B: BlockT<Header=H>,
-	C: ProvideRuntimeApi + ProvideCache<B>,
+	C: ProvideRuntimeApi + ProvideCache<B> + HeaderBackend<B>,
 	C::Api: BabeApi<B>,
 	E: Environment<B, Error=Error>,
 	E::Proposer: Proposer<B, Error=Error>,
@@ -273,6 +284,7 @@ impl<H, B, C, E, I, Error, SO> slots::SimpleSlotWorker<B> for BabeWorker<C, E, I
 	H: Header<Hash=B::Hash>,
 	I: BlockImport<B> + Send + Sync + 'static,
 	SO: SyncOracle + Send + Clone,
+	BS: BackoffAuthoringBlocksStrategy<NumberFor<B>>,
 	Error: std::error::Error + Send + From<::consensus_common::Error> + From<I::Error> + 'static,
 {
 	type EpochData = Epoch;
@@ -356,6 +368,21 @@ impl<H, B, C, E, I, Error, SO> slots::SimpleSlotWorker<B> for BabeWorker<C, E, I
 		self.force_authoring
 	}
 
+	fn should_backoff(&self, slot_number: u64, chain_head: &B::Header) -> bool {
+		if let Some(ref strategy) = self.backoff_authoring_blocks {
+			if let Ok(chain_head_slot) = find_pre_digest::<B>(chain_head).map(|digest| digest.slot_number()) {
+				return strategy.should_backoff(
+					*chain_head.number(),
+					chain_head_slot,
+					self.client.info().finalized_number,
+					slot_number,
+					self.logging_target(),
+				);
+			}
+		}
+		false
+	}
+
 	fn sync_oracle(&mut self) -> &mut Self::SyncOracle {
 		&mut self.sync_oracle
 	}
@@ -367,9 +394,9 @@ impl<H, B, C, E, I, Error, SO> slots::SimpleSlotWorker<B> for BabeWorker<C, E, I
 	}
 }
 
-impl<H, B, C, E, I, Error, SO> SlotWorker<B> for BabeWorker<C, E, I, SO> where
+impl<H, B, C, E, I, Error, SO, BS> SlotWorker<B> for BabeWorker<C, E, I, SO, BS> where
 	B: BlockT<Header=H>,
-	C: ProvideRuntimeApi + ProvideCache<B> + Send + Sync,
+	C: ProvideRuntimeApi + ProvideCache<B> + HeaderBackend<B> + Send + Sync,
 	C::Api: BabeApi<B>,
 	E: Environment<B, Error=Error> + Send + Sync,
 	E::Proposer: Proposer<B, Error=Error>,
@@ -377,6 +404,7 @@ impl<H, B, C, E, I, Error, SO> SlotWorker<B> for BabeWorker<C, E, I, SO> where
 	H: Header<Hash=B::Hash>,
 	I: BlockImport<B> + Send + Sync + 'static,
 	SO: SyncOracle + Send + Sync + Clone,
+	BS: BackoffAuthoringBlocksStrategy<NumberFor<B>> + Send + Sync,
 	Error: std::error::Error + Send + From<::consensus_common::Error> + From<I::Error> + 'static,
 {
 	type OnSlot = Pin<Box<dyn Future<Output = Result<(), consensus_common::Error>> + Send>>;
@@ -450,9 +478,10 @@ fn find_next_epoch_digest<B: BlockT>(header: &B::Header) -> Result<Option<Epoch>
 ///
 /// The given header can either be from a primary or secondary slot assignment,
 /// with each having different validation logic.
-// FIXME #1018 needs misbehavior types. The `transaction_pool` parameter will be
-// used to submit such misbehavior reports.
-fn check_header<B: BlockT + Sized, C: AuxStore, T>(
+///
+/// Equivocations of the slot author are queued in the given offchain storage,
+/// from where the runtime's offchain worker reports them on-chain.
+fn check_header<B: BlockT + Sized, C: AuxStore, S: OffchainStorage, T>(
 	mut header: B::Header,
 	parent_header: B::Header,
 	slot_now: u64,
@@ -462,6 +491,7 @@ fn check_header<B: BlockT + Sized, C: AuxStore, T>(
 	epoch_index: u64,
 	secondary_slots: bool,
 	c: (u64, u64),
+	offchain_storage: Option<S>,
 	_transaction_pool: Option<&T>,
 ) -> Result<CheckedHeader<B::Header, (DigestItemFor<B>, DigestItemFor<B>)>, String> where
 	DigestItemFor<B>: CompatibleDigestItem,
@@ -536,12 +566,15 @@ fn check_header<B: BlockT + Sized, C: AuxStore, T>(
 	let author = &authorities[pre_digest.authority_index() as usize].0;
 
 	// the header is valid but let's check if there was something else already
-	// proposed at the same slot by the given author
+	// proposed at the same slot by the given author. the headers are kept with
+	// their seal, since that is what proves the equivocation.
+	let mut sealed_header = header.clone();
+	sealed_header.digest_mut().push(seal.clone());
 	if let Some(equivocation_proof) = check_equivocation(
 		client,
 		slot_now,
 		pre_digest.slot_number(),
-		&header,
+		&sealed_header,
 		author,
 	).map_err(|e| e.to_string())? {
 		info!(
@@ -551,12 +584,59 @@ fn check_header<B: BlockT + Sized, C: AuxStore, T>(
 			equivocation_proof.fst_header().hash(),
 			equivocation_proof.snd_header().hash(),
 		);
+
+		report_equivocation::<B, _>(offchain_storage, EquivocationProof {
+			offender: author.clone(),
+			slot_number: equivocation_proof.slot(),
+			first_header: equivocation_proof.fst_header().clone(),
+			second_header: equivocation_proof.snd_header().clone(),
+		});
 	}
 
 	let pre_digest = CompatibleDigestItem::babe_pre_digest(pre_digest);
 	Ok(CheckedHeader::Checked(header, (pre_digest, seal)))
 }
 
+/// Queue an equivocation proof in the offchain storage, from where the runtime's
+/// offchain worker picks it up and reports it on-chain.
+fn report_equivocation<B: BlockT, S: OffchainStorage>(
+	offchain_storage: Option<S>,
+	proof: EquivocationProof<B::Header>,
+) {
+	let mut storage = match offchain_storage {
+		Some(storage) => storage,
+		None => {
+			warn!(target: "babe", "Offchain storage unavailable, equivocation will not be reported.");
+			return;
+		},
+	};
+
+	loop {
+		let current = storage.get(PERSISTENT_STORAGE_PREFIX, EQUIVOCATION_REPORTS_KEY);
+		let mut reports = current.as_ref()
+			.and_then(|encoded| Vec::<EquivocationProof<B::Header>>::decode(&mut &encoded[..]).ok())
+			.unwrap_or_default();
+
+		if reports.contains(&proof) {
+			return;
+		}
+		reports.push(proof.clone());
+
+		if storage.compare_and_set(
+			PERSISTENT_STORAGE_PREFIX,
+			EQUIVOCATION_REPORTS_KEY,
+			current.as_ref().map(|v| &v[..]),
+			&reports.encode(),
+		) {
+			debug!(
+				target: "babe", "Queued equivocation of {:?} at slot {} for reporting",
+				proof.offender, proof.slot_number,
+			);
+			return;
+		}
+	}
+}
+
 /// Check a primary slot proposal header. We validate that the given header is
 /// properly signed by the expected authority, and that the contained VRF proof
 /// is valid. Additionally, the weight of this block must increase compared to
@@ -781,9 +861,12 @@ impl<B, E, Block, RA, PRA, T> Verifier<Block> for BabeVerifier<B, E, Block, RA,
 			.map_err(|e| format!("Could not fetch parent header {:?}: {:?}", parent_hash, e))?
 			.ok_or_else(|| format!("Parent header {:?} not found.", parent_hash))?;
 
+		#[allow(deprecated)]
+		let offchain_storage = self.client.backend().offchain_storage();
+
 		// We add one to allow for some small drift.
 		// FIXME #1019 in the future, alter this queue to allow deferring of headers
-		let mut checked_header = check_header::<Block, PRA, T>(
+		let mut checked_header = check_header::<Block, PRA, _, T>(
 			header.clone(),
 			parent_header.clone(),
 			slot_now + 1,
@@ -793,6 +876,7 @@ impl<B, E, Block, RA, PRA, T> Verifier<Block> for BabeVerifier<B, E, Block, RA,
 			epoch_index,
 			secondary_slots,
 			self.config.c(),
+			offchain_storage.clone(),
 			self.transaction_pool.as_ref().map(|x| &**x),
 		);
 
@@ -801,7 +885,7 @@ impl<B, E, Block, RA, PRA, T> Verifier<Block> for BabeVerifier<B, E, Block, RA,
 		// (this is only possible on the light client at epoch#0)
 		if epoch_index == 0 && checked_header.is_err() {
 			if let Some(Epoch { authorities, randomness, epoch_index, .. }) = maybe_next_epoch {
-				let checked_header_next = check_header::<Block, PRA, T>(
+				let checked_header_next = check_header::<Block, PRA, _, T>(
 					header,
 					parent_header,
 					slot_now + 1,
@@ -811,6 +895,7 @@ impl<B, E, Block, RA, PRA, T> Verifier<Block> for BabeVerifier<B, E, Block, RA,
 					epoch_index,
 					secondary_slots,
 					self.config.c(),
+					offchain_storage,
 					self.transaction_pool.as_ref().map(|x| &**x),
 				);
 
@@ -236,6 +236,7 @@ fn run_one_test() {
 			sync_oracle: DummyOracle,
 			inherent_data_providers,
 			force_authoring: false,
+			backoff_authoring_blocks: None::<()>,
 			time_source: Default::default(),
 			keystore,
 		}).expect("Starts babe"));
@@ -369,3 +370,47 @@ fn authorities_call_works() {
 		(Keyring::Charlie.public().into(), 1),
 	]);
 }
+
+#[test]
+fn worker_backs_off_authoring_when_finality_lags() {
+	let _ = env_logger::try_init();
+	let client = Arc::new(test_client::new());
+	let keystore_path = tempfile::tempdir().expect("Creates keystore path");
+	let keystore = keystore::Store::open(keystore_path.path(), None).expect("Creates keystore");
+
+	let worker = BabeWorker {
+		client: client.clone(),
+		block_import: Arc::new(Mutex::new(client.clone())),
+		env: DummyFactory(client.clone()),
+		sync_oracle: DummyOracle,
+		force_authoring: false,
+		backoff_authoring_blocks: Some(BackoffAuthoringOnFinalizedHeadLagging {
+			max_interval: 100,
+			unfinalized_slack: 5,
+			authoring_bias: 2,
+		}),
+		c: (3, 10),
+		keystore,
+	};
+
+	// only the genesis block is finalized, so a chain head authored at slot 10 leaves
+	// 21 unfinalized blocks.
+	let mut chain_head = TestHeader::new(
+		21,
+		Default::default(),
+		Default::default(),
+		Default::default(),
+		Default::default(),
+	);
+	chain_head.digest_mut().push(Item::babe_pre_digest(BabePreDigest::Secondary {
+		authority_index: 0,
+		slot_number: 10,
+		weight: 0,
+	}));
+
+	// authoring is skipped for (21 - 5) / 2 = 8 slots after the chain head.
+	for slot_number in 11..=18 {
+		assert!(slots::SimpleSlotWorker::should_backoff(&worker, slot_number, &chain_head));
+	}
+	assert!(!slots::SimpleSlotWorker::should_backoff(&worker, 19, &chain_head));
+}
@@ -0,0 +1,84 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Manual sealing errors.
+
+use consensus_common::ImportResult;
+use futures::channel::{oneshot, mpsc::SendError};
+use jsonrpc_core as rpc;
+
+/// Base code for all manual sealing errors.
+const BASE_ERROR: i64 = 15000;
+/// A block could not be sealed or finalized.
+const ENGINE_ERROR: i64 = BASE_ERROR + 1;
+/// The sealing engine isn't running anymore.
+const ENGINE_STOPPED: i64 = BASE_ERROR + 2;
+
+/// Manual sealing errors.
+#[derive(Debug, derive_more::Display, derive_more::From)]
+pub enum Error {
+	/// The block import rejected the sealed block.
+	#[display(fmt="Failed to import the sealed block: {:?}", _0)]
+	BlockImportError(ImportResult),
+	/// Nothing to seal, and empty blocks were not requested.
+	#[display(fmt="Transaction pool is empty, set create_empty to true to seal an empty block")]
+	EmptyTransactionPool,
+	/// Consensus error.
+	#[display(fmt="Consensus error: {}", _0)]
+	ConsensusError(consensus_common::Error),
+	/// Client error.
+	#[display(fmt="Client error: {}", _0)]
+	ClientError(client::error::Error),
+	/// Error while creating the inherent data.
+	#[display(fmt="Inherent data error: {}", _0)]
+	InherentError(String),
+	/// The proposer failed to create a block.
+	#[display(fmt="Failed to propose a block: {}", _0)]
+	ProposerError(String),
+	/// The command could not be sent to the sealing engine.
+	#[display(fmt="Failed to send the command to the sealing engine: {}", _0)]
+	SendError(SendError),
+	/// The sealing engine dropped the command without answering.
+	#[display(fmt="The sealing engine stopped before answering")]
+	Canceled(oneshot::Canceled),
+}
+
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Error::ConsensusError(ref err) => Some(err),
+			Error::ClientError(ref err) => Some(err),
+			Error::SendError(ref err) => Some(err),
+			Error::Canceled(ref err) => Some(err),
+			_ => None,
+		}
+	}
+}
+
+impl From<Error> for rpc::Error {
+	fn from(e: Error) -> Self {
+		let code = match e {
+			Error::SendError(_) | Error::Canceled(_) => ENGINE_STOPPED,
+			_ => ENGINE_ERROR,
+		};
+
+		rpc::Error {
+			code: rpc::ErrorCode::ServerError(code),
+			message: format!("{}", e),
+			data: None,
+		}
+	}
+}
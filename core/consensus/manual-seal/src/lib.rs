@@ -0,0 +1,386 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A manual sealing engine: blocks are authored on demand instead of in slots.
+//!
+//! This is meant for local development and tests. Blocks are sealed either when
+//! a transaction enters the pool (`run_instant_seal`) or when requested through
+//! the `engine_createBlock` RPC (`run_manual_seal`), which may also finalize
+//! them through `engine_finalizeBlock`. Blocks are proposed by the given
+//! `Environment` (e.g. the `basic-authorship` `ProposerFactory`) and go through
+//! the normal `BlockImport` pipeline, but carry no seal and aren't verified.
+
+#![forbid(unsafe_code, missing_docs)]
+
+mod error;
+pub mod rpc;
+
+pub use error::Error;
+pub use rpc::{EngineCommand, CreatedBlock};
+
+use std::{collections::HashMap, pin::Pin, sync::Arc, time::Duration};
+use client::backend::{Backend as ClientBackend, Finalizer};
+use consensus_common::{
+	self, BlockImport, BlockImportParams, BlockOrigin, Environment, ForkChoiceStrategy,
+	ImportResult, Proposer, SelectChain,
+	import_queue::{BasicQueue, BoxBlockImport, BoxJustificationImport, Verifier},
+	well_known_cache_keys::Id as CacheKeyId,
+};
+use futures::prelude::*;
+use inherents::InherentDataProviders;
+use log::{debug, warn};
+use parking_lot::Mutex;
+use primitives::{Blake2Hasher, H256};
+use sr_primitives::{Justification, generic::BlockId, traits::{Block as BlockT, Header as HeaderT}};
+use transaction_pool::txpool::{self, Pool};
+
+use crate::rpc::send_result;
+
+/// The maximum duration given to the proposer to build a block.
+const MAX_PROPOSAL_DURATION: Duration = Duration::from_secs(10);
+
+/// A verifier which accepts every block as is, since manually sealed blocks carry no seal.
+struct ManualSealVerifier;
+
+impl<B: BlockT> Verifier<B> for ManualSealVerifier {
+	fn verify(
+		&mut self,
+		origin: BlockOrigin,
+		header: B::Header,
+		justification: Option<Justification>,
+		body: Option<Vec<B::Extrinsic>>,
+	) -> Result<(BlockImportParams<B>, Option<Vec<(CacheKeyId, Vec<u8>)>>), String> {
+		let import_params = BlockImportParams {
+			origin,
+			header,
+			justification,
+			post_digests: Vec::new(),
+			body,
+			finalized: false,
+			auxiliary: Vec::new(),
+			fork_choice: ForkChoiceStrategy::LongestChain,
+		};
+
+		Ok((import_params, None))
+	}
+}
+
+/// Create a new import queue for blocks sealed by the manual sealing engine.
+pub fn import_queue<B: BlockT>(
+	block_import: BoxBlockImport<B>,
+	justification_import: Option<BoxJustificationImport<B>>,
+) -> BasicQueue<B> {
+	BasicQueue::new(ManualSealVerifier, block_import, justification_import, None)
+}
+
+/// Run the manual sealing engine, executing the commands received on `commands_stream`.
+///
+/// The returned future never resolves before the stream ends and should be run
+/// in the background.
+pub fn run_manual_seal<B, CB, E, C, A, SC, S>(
+	block_import: BoxBlockImport<B>,
+	mut env: E,
+	client: Arc<C>,
+	pool: Arc<Pool<A>>,
+	commands_stream: S,
+	select_chain: SC,
+	inherent_data_providers: InherentDataProviders,
+) -> impl Future<Output = ()> where
+	B: BlockT<Hash = H256> + 'static,
+	CB: ClientBackend<B, Blake2Hasher> + 'static,
+	E: Environment<B> + 'static,
+	E::Error: std::fmt::Debug,
+	<E::Proposer as Proposer<B>>::Create: Send + 'static,
+	C: Finalizer<B, Blake2Hasher, CB> + Send + Sync + 'static,
+	A: txpool::ChainApi<Block = B> + 'static,
+	SC: SelectChain<B> + 'static,
+	S: Stream<Item = EngineCommand<B::Hash>>,
+{
+	let block_import = Arc::new(Mutex::new(block_import));
+
+	commands_stream.for_each(move |command| match command {
+		EngineCommand::SealNewBlock { create_empty, finalize, sender } => {
+			let proposal = propose(
+				&mut env,
+				&*pool,
+				&select_chain,
+				&inherent_data_providers,
+				create_empty,
+			);
+			let block_import = block_import.clone();
+			let client = client.clone();
+
+			let seal = proposal.map(move |block| {
+				let mut block_import = block_import.lock();
+				let result = block.and_then(|block| {
+					import::<B, CB, C>(&mut **block_import, &*client, block, finalize)
+				});
+				send_result(sender, result);
+			});
+			future::Either::Left(seal)
+		},
+		EngineCommand::FinalizeBlock { hash, justification, sender } => {
+			let result = finalize_block::<B, CB, C>(&*client, hash, justification);
+			send_result(sender, result);
+			future::Either::Right(future::ready(()))
+		},
+	})
+}
+
+/// Run the instant sealing engine, which seals a new block each time a transaction
+/// enters the pool.
+///
+/// The returned future never resolves and should be run in the background.
+pub fn run_instant_seal<B, CB, E, C, A, SC>(
+	block_import: BoxBlockImport<B>,
+	env: E,
+	client: Arc<C>,
+	pool: Arc<Pool<A>>,
+	select_chain: SC,
+	inherent_data_providers: InherentDataProviders,
+) -> impl Future<Output = ()> where
+	B: BlockT<Hash = H256> + 'static,
+	CB: ClientBackend<B, Blake2Hasher> + 'static,
+	E: Environment<B> + 'static,
+	E::Error: std::fmt::Debug,
+	<E::Proposer as Proposer<B>>::Create: Send + 'static,
+	C: Finalizer<B, Blake2Hasher, CB> + Send + Sync + 'static,
+	A: txpool::ChainApi<Block = B> + 'static,
+	SC: SelectChain<B> + 'static,
+{
+	// the pool notifies once per imported transaction, seal only what is ready.
+	let commands_stream = pool.import_notification_stream()
+		.map(|_| EngineCommand::SealNewBlock {
+			create_empty: false,
+			finalize: false,
+			sender: None,
+		});
+
+	run_manual_seal(
+		block_import,
+		env,
+		client,
+		pool,
+		commands_stream,
+		select_chain,
+		inherent_data_providers,
+	)
+}
+
+/// Start proposing a block on top of the best block.
+fn propose<B, E, A, SC>(
+	env: &mut E,
+	pool: &Pool<A>,
+	select_chain: &SC,
+	inherent_data_providers: &InherentDataProviders,
+	create_empty: bool,
+) -> Pin<Box<dyn Future<Output = Result<B, Error>> + Send>> where
+	B: BlockT + 'static,
+	E: Environment<B>,
+	E::Error: std::fmt::Debug,
+	<E::Proposer as Proposer<B>>::Create: Send + 'static,
+	A: txpool::ChainApi<Block = B>,
+	SC: SelectChain<B>,
+{
+	let proposer = (|| {
+		if !create_empty && pool.status().ready == 0 {
+			return Err(Error::EmptyTransactionPool);
+		}
+
+		let parent = select_chain.best_chain()?;
+		let inherent_data = inherent_data_providers.create_inherent_data()
+			.map_err(|e| Error::InherentError(e.into()))?;
+		let mut proposer = env.init(&parent)
+			.map_err(|e| Error::ProposerError(format!("{:?}", e)))?;
+
+		Ok(proposer.propose(inherent_data, Default::default(), MAX_PROPOSAL_DURATION))
+	})();
+
+	match proposer {
+		Ok(proposal) => proposal
+			.map(|block| block.map_err(|e| Error::ProposerError(format!("{:?}", e))))
+			.boxed(),
+		Err(e) => future::ready(Err(e)).boxed(),
+	}
+}
+
+/// Import a sealed block, finalizing it if requested.
+fn import<B, CB, C>(
+	block_import: &mut dyn BlockImport<B, Error = consensus_common::Error>,
+	client: &C,
+	block: B,
+	finalize: bool,
+) -> Result<CreatedBlock<B::Hash>, Error> where
+	B: BlockT<Hash = H256>,
+	CB: ClientBackend<B, Blake2Hasher>,
+	C: Finalizer<B, Blake2Hasher, CB>,
+{
+	let (header, body) = block.deconstruct();
+	let hash = header.hash();
+	let import_params = BlockImportParams {
+		origin: BlockOrigin::Own,
+		header,
+		justification: None,
+		post_digests: Vec::new(),
+		body: Some(body),
+		finalized: false,
+		auxiliary: Vec::new(),
+		fork_choice: ForkChoiceStrategy::LongestChain,
+	};
+
+	match block_import.import_block(import_params, HashMap::new())? {
+		ImportResult::Imported(_) => {
+			debug!(target: "manual-seal", "Sealed block {:?}", hash);
+		},
+		other => return Err(Error::BlockImportError(other)),
+	}
+
+	if finalize {
+		finalize_block::<B, CB, C>(client, hash, None)?;
+	}
+
+	Ok(CreatedBlock { hash })
+}
+
+/// Finalize the given block.
+fn finalize_block<B, CB, C>(
+	client: &C,
+	hash: B::Hash,
+	justification: Option<Justification>,
+) -> Result<bool, Error> where
+	B: BlockT<Hash = H256>,
+	CB: ClientBackend<B, Blake2Hasher>,
+	C: Finalizer<B, Blake2Hasher, CB>,
+{
+	client.finalize_block(BlockId::Hash(hash), justification, true)
+		.map(|()| true)
+		.map_err(|e| {
+			warn!(target: "manual-seal", "Failed to finalize block {:?}: {:?}", hash, e);
+			e.into()
+		})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use basic_authorship::ProposerFactory;
+	use client::BlockchainEvents;
+	use futures::{channel::{mpsc, oneshot}, executor::block_on};
+	use test_client::{
+		AccountKeyring, DefaultTestClientBuilderExt, TestClientBuilder, TestClientBuilderExt,
+		runtime::{Extrinsic, Transfer},
+	};
+
+	fn extrinsic(nonce: u64) -> Extrinsic {
+		Transfer {
+			amount: Default::default(),
+			nonce,
+			from: AccountKeyring::Alice.into(),
+			to: Default::default(),
+		}.into_signed_tx()
+	}
+
+	#[test]
+	fn manual_seal_creates_and_finalizes_blocks() {
+		let (client, select_chain) = TestClientBuilder::new().build_with_longest_chain();
+		let client = Arc::new(client);
+		let pool = Arc::new(Pool::new(Default::default(), transaction_pool::ChainApi::new(client.clone())));
+		let env = ProposerFactory {
+			client: client.clone(),
+			transaction_pool: pool.clone(),
+			config: Default::default(),
+		};
+		let (sink, commands_stream) = mpsc::unbounded();
+
+		let engine = run_manual_seal(
+			Box::new(client.clone()),
+			env,
+			client.clone(),
+			pool,
+			commands_stream,
+			select_chain,
+			InherentDataProviders::new(),
+		);
+
+		// the pool is empty, so only an explicitly requested empty block is sealed.
+		let (sender, receiver) = oneshot::channel();
+		sink.unbounded_send(EngineCommand::SealNewBlock {
+			create_empty: false,
+			finalize: false,
+			sender: Some(sender),
+		}).unwrap();
+		let (empty_sender, empty_receiver) = oneshot::channel();
+		sink.unbounded_send(EngineCommand::SealNewBlock {
+			create_empty: true,
+			finalize: true,
+			sender: Some(empty_sender),
+		}).unwrap();
+
+		let (results, _) = match block_on(future::select(
+			Box::pin(engine),
+			future::join(receiver, empty_receiver),
+		)) {
+			future::Either::Right(results) => results,
+			future::Either::Left(_) => panic!("the engine stopped before answering"),
+		};
+
+		match results.0.unwrap() {
+			Err(Error::EmptyTransactionPool) => {},
+			other => panic!("unexpected result: {:?}", other),
+		}
+		let created = results.1.unwrap().unwrap();
+
+		let info = client.info().chain;
+		assert_eq!(info.best_number, 1);
+		assert_eq!(info.best_hash, created.hash);
+		assert_eq!(info.finalized_hash, created.hash);
+	}
+
+	#[test]
+	fn instant_seal_seals_transactions_entering_the_pool() {
+		let (client, select_chain) = TestClientBuilder::new().build_with_longest_chain();
+		let client = Arc::new(client);
+		let pool = Arc::new(Pool::new(Default::default(), transaction_pool::ChainApi::new(client.clone())));
+		let env = ProposerFactory {
+			client: client.clone(),
+			transaction_pool: pool.clone(),
+			config: Default::default(),
+		};
+		let imported_blocks = client.import_notification_stream();
+
+		let engine = run_instant_seal(
+			Box::new(client.clone()),
+			env,
+			client.clone(),
+			pool.clone(),
+			select_chain,
+			InherentDataProviders::new(),
+		);
+
+		pool.submit_one(&BlockId::number(0), extrinsic(0)).unwrap();
+
+		let notification = match block_on(future::select(
+			Box::pin(engine),
+			imported_blocks.into_future(),
+		)) {
+			future::Either::Right(((notification, _), _)) => notification.unwrap(),
+			future::Either::Left(_) => panic!("the engine stopped before sealing"),
+		};
+
+		assert_eq!(*notification.header.number(), 1);
+		assert_eq!(client.info().chain.finalized_number, 0);
+	}
+}
@@ -0,0 +1,142 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC interface for the manual sealing engine.
+
+use futures::{
+	FutureExt, TryFutureExt,
+	channel::{mpsc, oneshot},
+};
+use jsonrpc_core::{self as rpc, futures::future::{self as future01, Future as Future01}};
+use jsonrpc_derive::rpc;
+use serde::{Deserialize, Serialize};
+use sr_primitives::Justification;
+
+use crate::error::Error;
+
+pub use self::gen_client::Client as ManualSealClient;
+
+/// Future's type for the manual sealing RPC methods.
+pub type FutureResult<T> = Box<dyn Future01<Item = T, Error = rpc::Error> + Send>;
+
+/// Sender passed to the sealing engine to receive the outcome of a command.
+pub type Sender<T> = Option<oneshot::Sender<Result<T, Error>>>;
+
+/// A command for the manual sealing engine.
+pub enum EngineCommand<Hash> {
+	/// Seal a new block on top of the best block.
+	SealNewBlock {
+		/// Whether to seal a block even if the transaction pool is empty.
+		create_empty: bool,
+		/// Whether to finalize the block right after importing it.
+		finalize: bool,
+		/// Receives the outcome of the sealing.
+		sender: Sender<CreatedBlock<Hash>>,
+	},
+	/// Finalize the given block.
+	FinalizeBlock {
+		/// The block to finalize.
+		hash: Hash,
+		/// An optional justification to store with the finalized block.
+		justification: Option<Justification>,
+		/// Receives the outcome of the finalization.
+		sender: Sender<bool>,
+	},
+}
+
+/// A block sealed by the manual sealing engine.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct CreatedBlock<Hash> {
+	/// The hash of the sealed block.
+	pub hash: Hash,
+}
+
+/// Manual sealing RPC API.
+#[rpc]
+pub trait ManualSealApi<Hash> {
+	/// Seal a new block on top of the best block, finalizing it if `finalize` is set.
+	///
+	/// Fails if the transaction pool is empty, unless `create_empty` is set.
+	#[rpc(name = "engine_createBlock")]
+	fn create_block(
+		&self,
+		create_empty: bool,
+		finalize: bool,
+	) -> FutureResult<CreatedBlock<Hash>>;
+
+	/// Finalize the given block, storing the optional justification with it.
+	#[rpc(name = "engine_finalizeBlock")]
+	fn finalize_block(
+		&self,
+		hash: Hash,
+		justification: Option<Justification>,
+	) -> FutureResult<bool>;
+}
+
+/// Implementation of the manual sealing RPC API, forwarding the calls to the sealing engine.
+pub struct ManualSeal<Hash> {
+	import_block_channel: mpsc::UnboundedSender<EngineCommand<Hash>>,
+}
+
+impl<Hash> ManualSeal<Hash> {
+	/// Create a new `ManualSeal` from the sending half of the engine's command stream.
+	pub fn new(import_block_channel: mpsc::UnboundedSender<EngineCommand<Hash>>) -> Self {
+		Self { import_block_channel }
+	}
+
+	/// Send a command to the engine and wait for its outcome.
+	fn send<T: Send + 'static>(
+		&self,
+		command: impl FnOnce(Sender<T>) -> EngineCommand<Hash>,
+	) -> FutureResult<T> {
+		let (sender, receiver) = oneshot::channel();
+		if let Err(e) = self.import_block_channel.unbounded_send(command(Some(sender))) {
+			return Box::new(future01::err(Error::from(e.into_send_error()).into()));
+		}
+
+		let future = receiver
+			.map(|result| result.unwrap_or_else(|canceled| Err(canceled.into())))
+			.compat()
+			.map_err(Into::into);
+		Box::new(future)
+	}
+}
+
+impl<Hash: Send + 'static> ManualSealApi<Hash> for ManualSeal<Hash> {
+	fn create_block(
+		&self,
+		create_empty: bool,
+		finalize: bool,
+	) -> FutureResult<CreatedBlock<Hash>> {
+		self.send(|sender| EngineCommand::SealNewBlock { create_empty, finalize, sender })
+	}
+
+	fn finalize_block(
+		&self,
+		hash: Hash,
+		justification: Option<Justification>,
+	) -> FutureResult<bool> {
+		self.send(|sender| EngineCommand::FinalizeBlock { hash, justification, sender })
+	}
+}
+
+/// Send the outcome of a command back to the caller, if there is one waiting for it.
+pub fn send_result<T>(sender: Sender<T>, result: Result<T, Error>) {
+	if let Some(sender) = sender {
+		// the caller may have stopped waiting, which is fine.
+		let _ = sender.send(result);
+	}
+}
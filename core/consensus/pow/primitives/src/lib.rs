@@ -0,0 +1,42 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Primitives for Substrate Proof-of-Work (PoW) consensus.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use rstd::vec::Vec;
+use sr_primitives::ConsensusEngineId;
+use substrate_client::decl_runtime_apis;
+
+/// The `ConsensusEngineId` of PoW.
+pub const POW_ENGINE_ID: ConsensusEngineId = [b'p', b'o', b'w', b'_'];
+
+/// Type of difficulty.
+///
+/// The higher the difficulty, the more work a seal must prove.
+pub type Difficulty = primitives::U256;
+
+/// Type of seal, as pushed in the PoW seal digest.
+pub type Seal = Vec<u8>;
+
+decl_runtime_apis! {
+	/// API necessary for algorithms taking the difficulty from the runtime.
+	pub trait DifficultyApi {
+		/// Return the target difficulty of the next block.
+		fn difficulty() -> Difficulty;
+	}
+}
@@ -0,0 +1,539 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Proof of work consensus for Substrate.
+//!
+//! To use this engine, you need to have a struct that implements
+//! `PowAlgorithm`. After that, pass an instance of the struct, along
+//! with other necessary client references to `import_queue` to setup
+//! the queue, and `start_mine` to start the mining worker.
+//!
+//! This library also comes with an `HeaviestChain` chain selection, which
+//! picks the leaf with the highest total difficulty.
+//!
+//! Blocks are sealed with a `DigestItem::Seal(POW_ENGINE_ID, seal)` post
+//! digest, where the seal proves work over the hash of the header without
+//! it. The `PowBlockImport` checks the seal and tracks each block's total
+//! difficulty in the auxiliary storage.
+
+#![forbid(unsafe_code, missing_docs)]
+
+use std::{collections::HashMap, marker::PhantomData, sync::Arc, thread, time::Duration};
+use client::{backend::AuxStore, blockchain::HeaderBackend};
+use codec::{Decode, Encode};
+use consensus_common::{
+	BlockImport, BlockImportParams, BlockOrigin, Environment, Error as ConsensusError,
+	ForkChoiceStrategy, ImportResult, Proposer, SelectChain,
+	import_queue::{
+		BasicQueue, BoxBlockImport, BoxFinalityProofImport, BoxJustificationImport, Verifier,
+	},
+	well_known_cache_keys::Id as CacheKeyId,
+};
+use inherents::InherentDataProviders;
+use log::{debug, error, info};
+use primitives::H256;
+use sr_primitives::{
+	Justification,
+	generic::{BlockId, Digest, DigestItem},
+	traits::{Block as BlockT, Header as HeaderT, NumberFor},
+};
+
+pub use pow_primitives::{Difficulty, Seal, POW_ENGINE_ID};
+
+/// Auxiliary storage prefix for the PoW engine.
+pub const POW_AUX_PREFIX: [u8; 4] = *b"PoW:";
+
+/// Get the auxiliary storage key used by the engine to store the PoW data of a block.
+fn aux_key(hash: &H256) -> Vec<u8> {
+	POW_AUX_PREFIX.iter().chain(&hash[..]).cloned().collect()
+}
+
+/// Auxiliary storage data of a PoW block.
+#[derive(Encode, Decode, Clone, Debug, Default)]
+pub struct PowAux {
+	/// Difficulty of the block.
+	pub difficulty: Difficulty,
+	/// Total difficulty of the chain up to the block.
+	pub total_difficulty: Difficulty,
+}
+
+impl PowAux {
+	/// Read the PoW data of the given block, which is empty for blocks not
+	/// imported through the engine (e.g. genesis).
+	pub fn read<C: AuxStore>(client: &C, hash: &H256) -> Result<Self, String> {
+		let key = aux_key(hash);
+
+		match client.get_aux(&key).map_err(|e| format!("{:?}", e))? {
+			Some(bytes) => PowAux::decode(&mut &bytes[..]).map_err(|e| format!("{:?}", e)),
+			None => Ok(PowAux::default()),
+		}
+	}
+}
+
+/// Algorithm used for proof of work.
+pub trait PowAlgorithm<B: BlockT> {
+	/// Get the difficulty of the block to build on top of `parent`.
+	fn difficulty(&self, parent: &BlockId<B>) -> Result<Difficulty, String>;
+
+	/// Verify that the seal proves work over `pre_hash` for the given difficulty.
+	fn verify(
+		&self,
+		parent: &BlockId<B>,
+		pre_hash: &H256,
+		seal: &Seal,
+		difficulty: Difficulty,
+	) -> Result<bool, String>;
+
+	/// Try to mine a seal satisfying the given difficulty, doing at most `round`
+	/// attempts. Returns `None` if none of them succeeded.
+	fn mine(
+		&self,
+		parent: &BlockId<B>,
+		pre_hash: &H256,
+		difficulty: Difficulty,
+		round: u32,
+	) -> Result<Option<Seal>, String>;
+}
+
+/// Find the PoW seal among the given digest items.
+fn find_seal<B: BlockT>(digests: &[DigestItem<B::Hash>]) -> Result<Seal, String> {
+	match digests.last() {
+		Some(DigestItem::Seal(id, seal)) if id == &POW_ENGINE_ID => Ok(seal.clone()),
+		_ => Err("Block is not sealed with a PoW seal".into()),
+	}
+}
+
+/// A block import which checks the PoW seal of the blocks and tracks their total
+/// difficulty, making the heaviest chain the best one.
+pub struct PowBlockImport<B: BlockT, I, C, Algorithm> {
+	inner: I,
+	client: Arc<C>,
+	algorithm: Algorithm,
+	_marker: PhantomData<B>,
+}
+
+impl<B: BlockT, I, C, Algorithm> PowBlockImport<B, I, C, Algorithm> {
+	/// Create a new block import wrapping the given inner one.
+	pub fn new(inner: I, client: Arc<C>, algorithm: Algorithm) -> Self {
+		Self { inner, client, algorithm, _marker: PhantomData }
+	}
+}
+
+impl<B, I, C, Algorithm> BlockImport<B> for PowBlockImport<B, I, C, Algorithm> where
+	B: BlockT<Hash = H256>,
+	I: BlockImport<B, Error = ConsensusError>,
+	C: HeaderBackend<B> + AuxStore,
+	Algorithm: PowAlgorithm<B>,
+{
+	type Error = ConsensusError;
+
+	fn check_block(
+		&mut self,
+		hash: B::Hash,
+		parent_hash: B::Hash,
+	) -> Result<ImportResult, Self::Error> {
+		self.inner.check_block(hash, parent_hash)
+	}
+
+	fn import_block(
+		&mut self,
+		mut block: BlockImportParams<B>,
+		new_cache: HashMap<CacheKeyId, Vec<u8>>,
+	) -> Result<ImportResult, Self::Error> {
+		let parent_hash = *block.header.parent_hash();
+		let best_hash = self.client.info().best_hash;
+
+		let best_aux = PowAux::read(&*self.client, &best_hash)
+			.map_err(ConsensusError::ChainLookup)?;
+		let mut aux = PowAux::read(&*self.client, &parent_hash)
+			.map_err(ConsensusError::ChainLookup)?;
+
+		// the post digests aren't part of the header yet, its hash is the pre-hash.
+		let seal = find_seal::<B>(&block.post_digests).map_err(ConsensusError::ClientImport)?;
+		let pre_hash = block.header.hash();
+		let parent = BlockId::Hash(parent_hash);
+		let difficulty = self.algorithm.difficulty(&parent)
+			.map_err(ConsensusError::ClientImport)?;
+
+		let valid = self.algorithm.verify(&parent, &pre_hash, &seal, difficulty)
+			.map_err(ConsensusError::ClientImport)?;
+		if !valid {
+			return Err(ConsensusError::ClientImport(
+				format!("Invalid PoW seal for block {:?}", pre_hash)
+			));
+		}
+
+		aux.difficulty = difficulty;
+		aux.total_difficulty = aux.total_difficulty.saturating_add(difficulty);
+
+		let post_hash = {
+			let mut header = block.header.clone();
+			header.digest_mut().push(DigestItem::Seal(POW_ENGINE_ID, seal));
+			header.hash()
+		};
+		block.auxiliary.push((aux_key(&post_hash), Some(aux.encode())));
+		block.fork_choice = ForkChoiceStrategy::Custom(
+			aux.total_difficulty > best_aux.total_difficulty
+		);
+
+		self.inner.import_block(block, new_cache)
+	}
+}
+
+/// A verifier for PoW blocks, which moves the seal out of the header.
+///
+/// The seal itself is checked by the `PowBlockImport`.
+pub struct PowVerifier<B> {
+	_marker: PhantomData<B>,
+}
+
+impl<B: BlockT> Verifier<B> for PowVerifier<B> {
+	fn verify(
+		&mut self,
+		origin: BlockOrigin,
+		mut header: B::Header,
+		justification: Option<Justification>,
+		body: Option<Vec<B::Extrinsic>>,
+	) -> Result<(BlockImportParams<B>, Option<Vec<(CacheKeyId, Vec<u8>)>>), String> {
+		let hash = header.hash();
+		let seal = match header.digest_mut().pop() {
+			Some(DigestItem::Seal(id, seal)) if id == POW_ENGINE_ID => {
+				DigestItem::Seal(id, seal)
+			},
+			_ => return Err(format!("Header {:?} is unsealed", hash)),
+		};
+
+		let import_params = BlockImportParams {
+			origin,
+			header,
+			justification,
+			post_digests: vec![seal],
+			body,
+			finalized: false,
+			auxiliary: Vec::new(),
+			fork_choice: ForkChoiceStrategy::LongestChain,
+		};
+
+		Ok((import_params, None))
+	}
+}
+
+/// The PoW import queue type.
+pub type PowImportQueue<B> = BasicQueue<B>;
+
+/// Create a new import queue for PoW blocks. The given block import is expected
+/// to be a `PowBlockImport`.
+pub fn import_queue<B: BlockT>(
+	block_import: BoxBlockImport<B>,
+	justification_import: Option<BoxJustificationImport<B>>,
+	finality_proof_import: Option<BoxFinalityProofImport<B>>,
+) -> PowImportQueue<B> {
+	let verifier = PowVerifier { _marker: PhantomData };
+
+	BasicQueue::new(verifier, block_import, justification_import, finality_proof_import)
+}
+
+/// A chain selection which picks the leaf with the highest total difficulty as
+/// the best block, falling back to the inner selection when the leaves have no
+/// difficulty recorded.
+pub struct HeaviestChain<B, C, S> {
+	client: Arc<C>,
+	inner: S,
+	_marker: PhantomData<B>,
+}
+
+impl<B, C, S> HeaviestChain<B, C, S> {
+	/// Create a new heaviest chain selection, taking the leaves and finality
+	/// targets from the given inner selection.
+	pub fn new(client: Arc<C>, inner: S) -> Self {
+		Self { client, inner, _marker: PhantomData }
+	}
+}
+
+impl<B, C, S: Clone> Clone for HeaviestChain<B, C, S> {
+	fn clone(&self) -> Self {
+		Self::new(self.client.clone(), self.inner.clone())
+	}
+}
+
+impl<B, C, S> SelectChain<B> for HeaviestChain<B, C, S> where
+	B: BlockT<Hash = H256>,
+	C: HeaderBackend<B> + AuxStore + Send + Sync,
+	S: SelectChain<B>,
+{
+	fn leaves(&self) -> Result<Vec<B::Hash>, ConsensusError> {
+		self.inner.leaves()
+	}
+
+	fn best_chain(&self) -> Result<B::Header, ConsensusError> {
+		let mut best: Option<(Difficulty, H256)> = None;
+		for leaf in self.leaves()? {
+			let aux = PowAux::read(&*self.client, &leaf).map_err(ConsensusError::ChainLookup)?;
+			if best.map_or(true, |(difficulty, _)| aux.total_difficulty > difficulty) {
+				best = Some((aux.total_difficulty, leaf));
+			}
+		}
+
+		match best {
+			Some((difficulty, hash)) if !difficulty.is_zero() => {
+				self.client.header(BlockId::Hash(hash))
+					.map_err(|e| ConsensusError::ChainLookup(e.to_string()))?
+					.ok_or_else(|| ConsensusError::ChainLookup(format!("Leaf {:?} not found", hash)))
+			},
+			_ => self.inner.best_chain(),
+		}
+	}
+
+	fn finality_target(
+		&self,
+		target_hash: B::Hash,
+		maybe_max_number: Option<NumberFor<B>>,
+	) -> Result<Option<B::Hash>, ConsensusError> {
+		self.inner.finality_target(target_hash, maybe_max_number)
+	}
+}
+
+/// Start the background mining thread for PoW. The block import is expected to
+/// be a `PowBlockImport`.
+///
+/// `preruntime` is an optional pre-runtime digest pushed into the mined blocks,
+/// and `round` the number of attempts made before checking whether the best
+/// block changed.
+pub fn start_mine<B, C, Algorithm, E>(
+	mut block_import: BoxBlockImport<B>,
+	client: Arc<C>,
+	algorithm: Algorithm,
+	mut env: E,
+	preruntime: Option<Vec<u8>>,
+	round: u32,
+	inherent_data_providers: InherentDataProviders,
+) where
+	B: BlockT<Hash = H256> + 'static,
+	C: HeaderBackend<B> + AuxStore + Send + Sync + 'static,
+	Algorithm: PowAlgorithm<B> + Send + Sync + 'static,
+	E: Environment<B> + Send + 'static,
+	E::Error: std::fmt::Debug,
+{
+	thread::spawn(move || {
+		loop {
+			match mine_loop(
+				&mut block_import,
+				&*client,
+				&algorithm,
+				&mut env,
+				preruntime.as_ref(),
+				round,
+				&inherent_data_providers,
+			) {
+				Ok(()) => (),
+				Err(e) => {
+					error!(
+						target: "pow",
+						"Mining block failed with {:?}. Sleep for 1 second before restarting...",
+						e,
+					);
+					thread::sleep(Duration::new(1, 0));
+				},
+			}
+		}
+	});
+}
+
+/// Mine blocks on top of the best block, starting over whenever it changes.
+fn mine_loop<B, C, Algorithm, E>(
+	block_import: &mut BoxBlockImport<B>,
+	client: &C,
+	algorithm: &Algorithm,
+	env: &mut E,
+	preruntime: Option<&Vec<u8>>,
+	round: u32,
+	inherent_data_providers: &InherentDataProviders,
+) -> Result<(), String> where
+	B: BlockT<Hash = H256>,
+	C: HeaderBackend<B> + AuxStore,
+	Algorithm: PowAlgorithm<B>,
+	E: Environment<B>,
+	E::Error: std::fmt::Debug,
+{
+	'outer: loop {
+		let best_hash = client.info().best_hash;
+		let best_header = client.header(BlockId::Hash(best_hash))
+			.map_err(|e| format!("{:?}", e))?
+			.ok_or_else(|| format!("Best header {:?} not found", best_hash))?;
+
+		let mut proposer = env.init(&best_header).map_err(|e| format!("{:?}", e))?;
+
+		let inherent_data = inherent_data_providers.create_inherent_data()
+			.map_err(String::from)?;
+		let mut inherent_digest = Digest::default();
+		if let Some(preruntime) = preruntime {
+			inherent_digest.push(DigestItem::PreRuntime(POW_ENGINE_ID, preruntime.to_vec()));
+		}
+		let block = futures::executor::block_on(proposer.propose(
+			inherent_data,
+			inherent_digest,
+			Duration::from_secs(10),
+		)).map_err(|e| format!("Block proposing error: {:?}", e))?;
+
+		let (header, body) = block.deconstruct();
+		let parent = BlockId::Hash(best_hash);
+		let pre_hash = header.hash();
+		let difficulty = algorithm.difficulty(&parent)?;
+
+		let seal = loop {
+			if let Some(seal) = algorithm.mine(&parent, &pre_hash, difficulty, round)? {
+				break seal;
+			}
+
+			if best_hash != client.info().best_hash {
+				debug!(target: "pow", "Best block changed, restarting the mining of {:?}", pre_hash);
+				continue 'outer;
+			}
+		};
+
+		info!(target: "pow", "Mined block {:?} with difficulty {}", pre_hash, difficulty);
+
+		let import_block = BlockImportParams {
+			origin: BlockOrigin::Own,
+			header,
+			justification: None,
+			post_digests: vec![DigestItem::Seal(POW_ENGINE_ID, seal)],
+			body: Some(body),
+			finalized: false,
+			auxiliary: Vec::new(),
+			fork_choice: ForkChoiceStrategy::LongestChain,
+		};
+
+		block_import.import_block(import_block, HashMap::default())
+			.map_err(|e| format!("Error with block built on {:?}: {:?}", best_hash, e))?;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use primitives::U256;
+	use sha3::{Digest as _, Sha3_256};
+	use test_client::{
+		BlockBuilderExt, DefaultTestClientBuilderExt, TestClientBuilder,
+		TestClientBuilderExt,
+		runtime::Block,
+	};
+
+	/// A sha3 based algorithm with a fixed difficulty, where the seal is a nonce.
+	struct Sha3Algorithm {
+		difficulty: Difficulty,
+	}
+
+	impl Sha3Algorithm {
+		fn check(pre_hash: &H256, nonce: u64, difficulty: Difficulty) -> bool {
+			let mut hasher = Sha3_256::new();
+			hasher.input(&pre_hash[..]);
+			hasher.input(&nonce.encode());
+			let hash = U256::from(&hasher.result()[..]);
+
+			!hash.overflowing_mul(difficulty).1
+		}
+	}
+
+	impl PowAlgorithm<Block> for Sha3Algorithm {
+		fn difficulty(&self, _parent: &BlockId<Block>) -> Result<Difficulty, String> {
+			Ok(self.difficulty)
+		}
+
+		fn verify(
+			&self,
+			_parent: &BlockId<Block>,
+			pre_hash: &H256,
+			seal: &Seal,
+			difficulty: Difficulty,
+		) -> Result<bool, String> {
+			let nonce = u64::decode(&mut &seal[..]).map_err(|e| format!("{:?}", e))?;
+			Ok(Self::check(pre_hash, nonce, difficulty))
+		}
+
+		fn mine(
+			&self,
+			_parent: &BlockId<Block>,
+			pre_hash: &H256,
+			difficulty: Difficulty,
+			round: u32,
+		) -> Result<Option<Seal>, String> {
+			Ok((0..u64::from(round))
+				.find(|nonce| Self::check(pre_hash, *nonce, difficulty))
+				.map(|nonce| nonce.encode()))
+		}
+	}
+
+	fn import_params(header: <Block as BlockT>::Header, seal: Seal) -> BlockImportParams<Block> {
+		BlockImportParams {
+			origin: BlockOrigin::Own,
+			header,
+			justification: None,
+			post_digests: vec![DigestItem::Seal(POW_ENGINE_ID, seal)],
+			body: None,
+			finalized: false,
+			auxiliary: Vec::new(),
+			fork_choice: ForkChoiceStrategy::LongestChain,
+		}
+	}
+
+	#[test]
+	fn sha3_mined_seals_verify() {
+		let algorithm = Sha3Algorithm { difficulty: U256::from(16) };
+		let parent = BlockId::Number(0);
+		let pre_hash = H256::random();
+
+		let seal = algorithm.mine(&parent, &pre_hash, algorithm.difficulty, 10_000)
+			.unwrap()
+			.expect("a seal is found in 10000 attempts with difficulty 16");
+
+		assert!(algorithm.verify(&parent, &pre_hash, &seal, algorithm.difficulty).unwrap());
+		assert!(!algorithm.verify(&parent, &pre_hash, &seal, U256::max_value()).unwrap());
+	}
+
+	#[test]
+	fn block_import_checks_seals_and_tracks_total_difficulty() {
+		let (client, select_chain) = TestClientBuilder::new().build_with_longest_chain();
+		let client = Arc::new(client);
+		let algorithm = Sha3Algorithm { difficulty: U256::from(2) };
+		let mut block_import = PowBlockImport::new(client.clone(), client.clone(), algorithm);
+		let heaviest_chain = HeaviestChain::new(client.clone(), select_chain);
+
+		let block = client.new_block(Default::default()).unwrap().bake().unwrap();
+		let (header, _) = block.deconstruct();
+		let pre_hash = header.hash();
+
+		// a seal which doesn't prove enough work is rejected.
+		let bad_seal = (0..u64::max_value())
+			.find(|nonce| !Sha3Algorithm::check(&pre_hash, *nonce, U256::from(2)))
+			.unwrap()
+			.encode();
+		assert!(block_import.import_block(import_params(header.clone(), bad_seal), HashMap::new()).is_err());
+		assert_eq!(client.info().chain.best_number, 0);
+
+		let seal = block_import.algorithm.mine(&BlockId::Number(0), &pre_hash, U256::from(2), 10_000)
+			.unwrap()
+			.unwrap();
+		block_import.import_block(import_params(header, seal), HashMap::new()).unwrap();
+
+		let best = heaviest_chain.best_chain().unwrap();
+		assert_eq!(*best.number(), 1);
+		let aux = PowAux::read(&*client, &best.hash()).unwrap();
+		assert_eq!(aux.difficulty, U256::from(2));
+		assert_eq!(aux.total_difficulty, U256::from(2));
+	}
+}
@@ -37,7 +37,9 @@ use futures_timer::Delay;
 use inherents::{InherentData, InherentDataProviders};
 use log::{debug, error, info, warn};
 use sr_primitives::generic::BlockId;
-use sr_primitives::traits::{ApiRef, Block as BlockT, Header, ProvideRuntimeApi};
+use sr_primitives::traits::{
+	ApiRef, Block as BlockT, Header, One, ProvideRuntimeApi, SaturatedConversion, Saturating, SimpleArithmetic,
+};
 use std::{fmt::Debug, ops::Deref, pin::Pin, sync::Arc};
 use substrate_telemetry::{telemetry, CONSENSUS_DEBUG, CONSENSUS_WARN, CONSENSUS_INFO};
 use parking_lot::Mutex;
@@ -105,6 +107,14 @@ pub trait SimpleSlotWorker<B: BlockT> {
 	/// Whether to force authoring if offline.
 	fn force_authoring(&self) -> bool;
 
+	/// Returns whether the block production should back off on the given slot, e.g. because
+	/// finality is lagging too far behind the chain head.
+	///
+	/// By default, authoring never backs off.
+	fn should_backoff(&self, _slot_number: u64, _chain_head: &B::Header) -> bool {
+		false
+	}
+
 	/// Returns a handle to a `SyncOracle`.
 	fn sync_oracle(&mut self) -> &mut Self::SyncOracle;
 
@@ -148,6 +158,23 @@ pub trait SimpleSlotWorker<B: BlockT> {
 			return Box::pin(future::ready(Ok(())));
 		}
 
+		if self.should_backoff(slot_number, &chain_head) {
+			debug!(
+				target: self.logging_target(),
+				"Backing off proposal at slot {}. Chain head: #{}.",
+				slot_number,
+				chain_head.number(),
+			);
+			telemetry!(
+				CONSENSUS_DEBUG;
+				"slots.backing_off";
+				"slot" => slot_number,
+				"chain_head" => ?chain_head.number(),
+			);
+
+			return Box::pin(future::ready(Ok(())));
+		}
+
 		let claim = match self.claim_slot(&chain_head, slot_number, &epoch_data) {
 			None => return Box::pin(future::ready(Ok(()))),
 			Some(claim) => claim,
@@ -254,6 +281,90 @@ pub trait SimpleSlotWorker<B: BlockT> {
 	}
 }
 
+/// A strategy which decides whether the slot worker should back off authoring blocks
+/// on the current slot.
+pub trait BackoffAuthoringBlocksStrategy<N> {
+	/// Returns true if the authoring should back off on the slot `slot_now`, given the
+	/// chain head, the slot of the chain head and the last finalized block.
+	fn should_backoff(
+		&self,
+		chain_head_number: N,
+		chain_head_slot: u64,
+		finalized_number: N,
+		slot_now: u64,
+		logging_target: &str,
+	) -> bool;
+}
+
+/// Backs off authoring blocks when the distance between the chain head and the last
+/// finalized block grows too large.
+///
+/// Authoring is never skipped as long as less than `unfinalized_slack` blocks are
+/// unfinalized. Beyond that, the number of slots skipped after the chain head grows by
+/// one slot for every `authoring_bias` unfinalized blocks, up to `max_interval` slots.
+/// Since the interval is capped, the chain still progresses (slowly) when finality
+/// has stalled completely.
+#[derive(Debug, Clone)]
+pub struct BackoffAuthoringOnFinalizedHeadLagging<N> {
+	/// The maximum number of slots that authoring may be skipped for.
+	pub max_interval: N,
+	/// The number of unfinalized blocks allowed before authoring starts backing off.
+	pub unfinalized_slack: N,
+	/// The number of unfinalized blocks which extend the backoff interval by one slot.
+	pub authoring_bias: N,
+}
+
+impl<N: From<u32>> Default for BackoffAuthoringOnFinalizedHeadLagging<N> {
+	fn default() -> Self {
+		BackoffAuthoringOnFinalizedHeadLagging {
+			max_interval: 100.into(),
+			unfinalized_slack: 50.into(),
+			authoring_bias: 2.into(),
+		}
+	}
+}
+
+impl<N: SimpleArithmetic + Copy + Debug> BackoffAuthoringBlocksStrategy<N> for BackoffAuthoringOnFinalizedHeadLagging<N> {
+	fn should_backoff(
+		&self,
+		chain_head_number: N,
+		chain_head_slot: u64,
+		finalized_number: N,
+		slot_now: u64,
+		logging_target: &str,
+	) -> bool {
+		// this shouldn't happen, but if it does, don't stall the chain
+		if slot_now <= chain_head_slot {
+			return false
+		}
+
+		let unfinalized_block_length = chain_head_number.saturating_sub(finalized_number);
+		let interval = unfinalized_block_length.saturating_sub(self.unfinalized_slack)
+			/ self.authoring_bias.max(One::one());
+		let interval: u64 = interval.min(self.max_interval).saturated_into();
+
+		// the chain head slot is always authored, the backoff applies to the slots after it
+		if slot_now <= chain_head_slot.saturating_add(interval) {
+			debug!(
+				target: logging_target,
+				"Unfinalized block length is {:?}, backing off authoring until slot {}.",
+				unfinalized_block_length,
+				chain_head_slot.saturating_add(interval),
+			);
+
+			true
+		} else {
+			false
+		}
+	}
+}
+
+impl<N> BackoffAuthoringBlocksStrategy<N> for () {
+	fn should_backoff(&self, _: N, _: u64, _: N, _: u64, _: &str) -> bool {
+		false
+	}
+}
+
 /// Slot compatible inherent data.
 pub trait SlotCompatible {
 	/// Extract timestamp and slot from inherent data.
@@ -423,3 +534,51 @@ impl<T: Clone> SlotDuration<T> {
 		self.0.clone()
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn backoff_authoring_blocks_when_finality_lags() {
+		let strategy = BackoffAuthoringOnFinalizedHeadLagging {
+			max_interval: 100u64,
+			unfinalized_slack: 5,
+			authoring_bias: 2,
+		};
+
+		// the chain head is finalized
+		assert!(!strategy.should_backoff(10, 10, 10, 11, "slots"));
+
+		// finality lags within the slack
+		assert!(!strategy.should_backoff(15, 10, 10, 11, "slots"));
+
+		// 11 unfinalized blocks, so authoring backs off for (11 - 5) / 2 = 3 slots after the head
+		assert!(strategy.should_backoff(21, 10, 10, 11, "slots"));
+		assert!(strategy.should_backoff(21, 10, 10, 13, "slots"));
+		assert!(!strategy.should_backoff(21, 10, 10, 14, "slots"));
+	}
+
+	#[test]
+	fn backoff_authoring_blocks_interval_is_bounded() {
+		let strategy = BackoffAuthoringOnFinalizedHeadLagging::<u64>::default();
+
+		// finality stalled for a long time, so authoring backs off for at most `max_interval` slots
+		let head_number = 100_000;
+		let head_slot = 1_000;
+		assert!(strategy.should_backoff(head_number, head_slot, 0, head_slot + 100, "slots"));
+		assert!(!strategy.should_backoff(head_number, head_slot, 0, head_slot + 101, "slots"));
+	}
+
+	#[test]
+	fn backoff_authoring_blocks_never_stalls_on_stale_slot() {
+		let strategy = BackoffAuthoringOnFinalizedHeadLagging::<u64>::default();
+
+		// the current slot isn't after the chain head slot
+		assert!(!strategy.should_backoff(1_000, 10, 0, 10, "slots"));
+		assert!(!strategy.should_backoff(1_000, 10, 0, 9, "slots"));
+
+		// and the no-op strategy never backs off
+		assert!(!BackoffAuthoringBlocksStrategy::<u64>::should_backoff(&(), 1_000, 10, 0, 11, "slots"));
+	}
+}
@@ -69,10 +69,16 @@ impl_stubs!(
 		}
 		input.to_vec()
 	},
+	test_panic_in_batch_verify => |_| {
+		runtime_io::crypto::start_batch_verify();
+		panic!("test panic")
+	},
 	test_blake2_256 => |input| blake2_256(input).to_vec(),
 	test_blake2_128 => |input| blake2_128(input).to_vec(),
 	test_twox_256 => |input| twox_256(input).to_vec(),
 	test_twox_128 => |input| twox_128(input).to_vec(),
+	test_sha2_256 => |input| runtime_io::hashing::sha2_256(input).to_vec(),
+	test_keccak_512 => |input| runtime_io::hashing::keccak_512(input).to_vec(),
 	test_ed25519_verify => |input: &[u8]| {
 		let mut pubkey = [0; 32];
 		let mut sig = [0; 64];
@@ -38,12 +38,32 @@ fn safe_call<F, U>(f: F) -> Result<U>
 	::std::panic::catch_unwind(f).map_err(|_| Error::Runtime)
 }
 
+/// Discards the signature batch of the current thread when it is created and when it is dropped.
+///
+/// Held for the duration of a runtime call, so that a batch started by a call that never finished
+/// it (e.g. because it panicked in the middle of a block) can't accept the signatures of later calls.
+pub(crate) struct BatchVerifyGuard;
+
+impl BatchVerifyGuard {
+	pub(crate) fn new() -> Self {
+		runtime_io::clear_batch_verify();
+		BatchVerifyGuard
+	}
+}
+
+impl Drop for BatchVerifyGuard {
+	fn drop(&mut self) {
+		runtime_io::clear_batch_verify();
+	}
+}
+
 /// Set up the externalities and safe calling environment to execute calls to a native runtime.
 ///
 /// If the inner closure panics, it will be caught and return an error.
 pub fn with_native_environment<F, U>(ext: &mut dyn Externalities<Blake2Hasher>, f: F) -> Result<U>
 	where F: UnwindSafe + FnOnce() -> U
 {
+	let _batch_guard = BatchVerifyGuard::new();
 	::runtime_io::with_externalities(ext, move || safe_call(f))
 }
 
@@ -32,8 +32,11 @@ use primitives::{
 	offchain, hexdisplay::HexDisplay, sandbox as sandbox_primitives, H256, Blake2Hasher,
 };
 use trie::{TrieConfiguration, trie_types::Layout};
+use runtime_interface::host::{self as host_api, Function, FunctionContext, HostFunctions};
+use runtime_io::SubstrateHostFunctions;
 use crate::sandbox;
 use crate::allocator;
+use crate::native_executor::BatchVerifyGuard;
 use log::trace;
 
 #[cfg(feature="wasm-extern-trace")]
@@ -45,29 +48,246 @@ macro_rules! debug_trace {
 	( $( $x:tt )* ) => ()
 }
 
-struct FunctionExecutor<'e, E: Externalities<Blake2Hasher> + 'e> {
+/// Executes the host functions for a wasm instance.
+///
+/// The externalities of the call are not owned by the executor, they are set with
+/// `runtime_interface::externalities::using` for the whole call. This way, the sandbox can
+/// call back into the runtime with the executor while a host function is executed.
+struct FunctionExecutor {
 	sandbox_store: sandbox::Store,
 	heap: allocator::FreeingBumpHeapAllocator,
 	memory: MemoryRef,
 	table: Option<TableRef>,
-	ext: &'e mut E,
 	hash_lookup: HashMap<Vec<u8>, Vec<u8>>,
 }
 
-impl<'e, E: Externalities<Blake2Hasher>> FunctionExecutor<'e, E> {
-	fn new(m: MemoryRef, heap_base: u32, t: Option<TableRef>, e: &'e mut E) -> Result<Self> {
+impl FunctionExecutor {
+	fn new(m: MemoryRef, heap_base: u32, t: Option<TableRef>) -> Result<Self> {
 		Ok(FunctionExecutor {
 			sandbox_store: sandbox::Store::new(),
 			heap: allocator::FreeingBumpHeapAllocator::new(m.clone(), heap_base),
 			memory: m,
 			table: t,
-			ext: e,
 			hash_lookup: HashMap::new(),
 		})
 	}
 }
 
-impl<'e, E: Externalities<Blake2Hasher>> sandbox::SandboxCapabilities for FunctionExecutor<'e, E> {
+/// Call `f` with the externalities of the current call.
+fn with_externalities<R, F>(f: F) -> Result<R>
+	where F: FnOnce(&mut (dyn Externalities<Blake2Hasher> + 'static)) -> R
+{
+	runtime_interface::externalities::with(f).ok_or(Error::Externalities)
+}
+
+impl FunctionExecutor {
+	/// Invoke the runtime interface host function with the given index.
+	fn invoke_host_function(
+		&mut self,
+		index: usize,
+		args: &[RuntimeValue],
+	) -> std::result::Result<Option<RuntimeValue>, wasmi::Trap> {
+		let function = HOST_FUNCTIONS.get(index).expect(
+			"`index` is the index of the function in `HOST_FUNCTIONS` given by \
+			`resolve_host_function`; qed"
+		);
+		let mut args = args.iter().filter_map(|arg| match arg {
+			I32(val) => Some(host_api::Value::I32(*val)),
+			I64(val) => Some(host_api::Value::I64(*val)),
+			_ => None,
+		});
+
+		function.execute(self, &mut args)
+			.map(|result| result.map(|val| match val {
+				host_api::Value::I32(val) => I32(val),
+				host_api::Value::I64(val) => I64(val),
+			}))
+			.map_err(|msg| Error::FunctionExecution(function.name().into(), msg).into())
+	}
+}
+
+lazy_static::lazy_static! {
+	/// The host functions of the runtime interfaces, in the order of their indices.
+	static ref HOST_FUNCTIONS: Vec<&'static dyn Function> = SubstrateHostFunctions::host_functions();
+}
+
+/// Resolve the runtime interface host function with the given name.
+///
+/// The index of the function is `index_offset` plus its position in `HOST_FUNCTIONS`.
+pub(crate) fn resolve_host_function(
+	name: &str,
+	signature: &wasmi::Signature,
+	index_offset: usize,
+) -> std::result::Result<Option<wasmi::FuncRef>, wasmi::Error> {
+	let (index, function) = match HOST_FUNCTIONS.iter().enumerate().find(|(_, f)| f.name() == name) {
+		Some(function) => function,
+		None => return Ok(None),
+	};
+
+	let to_wasmi_type = |ty| match ty {
+		host_api::ValueType::I32 => wasmi::ValueType::I32,
+		host_api::ValueType::I64 => wasmi::ValueType::I64,
+	};
+	let expected = function.signature();
+	let args = expected.args.iter().cloned().map(to_wasmi_type).collect::<Vec<_>>();
+	let expected = wasmi::Signature::new(&args[..], expected.return_value.map(to_wasmi_type));
+
+	if signature != &expected {
+		return Err(wasmi::Error::Instantiation(
+			format!("Export {} has different signature {:?}", name, signature),
+		));
+	}
+
+	Ok(Some(wasmi::FuncInstance::alloc_host(expected, index_offset + index)))
+}
+
+impl FunctionContext for FunctionExecutor {
+	fn read_memory(&self, address: u32, size: u32) -> host_api::Result<Vec<u8>> {
+		self.memory.get(address, size as usize).map_err(|e| format!("{}", e))
+	}
+
+	fn write_memory(&mut self, address: u32, data: &[u8]) -> host_api::Result<()> {
+		self.memory.set(address, data).map_err(|e| format!("{}", e))
+	}
+
+	fn allocate_memory(&mut self, size: u32) -> host_api::Result<u32> {
+		self.heap.allocate(size).map_err(|e| format!("{}", e))
+	}
+
+	fn deallocate_memory(&mut self, ptr: u32) -> host_api::Result<()> {
+		self.heap.deallocate(ptr).map_err(|e| format!("{}", e))
+	}
+
+	fn sandbox(&mut self) -> &mut dyn host_api::Sandbox {
+		self
+	}
+}
+
+impl host_api::Sandbox for FunctionExecutor {
+	fn memory_get(
+		&mut self,
+		memory_id: u32,
+		offset: u32,
+		buf_ptr: u32,
+		buf_len: u32,
+	) -> host_api::Result<u32> {
+		let sandboxed_memory = self.sandbox_store.memory(memory_id).map_err(|e| format!("{}", e))?;
+
+		match MemoryInstance::transfer(
+			&sandboxed_memory,
+			offset as usize,
+			&self.memory,
+			buf_ptr as usize,
+			buf_len as usize,
+		) {
+			Ok(()) => Ok(sandbox_primitives::ERR_OK),
+			Err(_) => Ok(sandbox_primitives::ERR_OUT_OF_BOUNDS),
+		}
+	}
+
+	fn memory_set(
+		&mut self,
+		memory_id: u32,
+		offset: u32,
+		val_ptr: u32,
+		val_len: u32,
+	) -> host_api::Result<u32> {
+		let sandboxed_memory = self.sandbox_store.memory(memory_id).map_err(|e| format!("{}", e))?;
+
+		match MemoryInstance::transfer(
+			&self.memory,
+			val_ptr as usize,
+			&sandboxed_memory,
+			offset as usize,
+			val_len as usize,
+		) {
+			Ok(()) => Ok(sandbox_primitives::ERR_OK),
+			Err(_) => Ok(sandbox_primitives::ERR_OUT_OF_BOUNDS),
+		}
+	}
+
+	fn memory_teardown(&mut self, memory_id: u32) -> host_api::Result<()> {
+		self.sandbox_store.memory_teardown(memory_id).map_err(|e| format!("{}", e))
+	}
+
+	fn memory_new(&mut self, initial: u32, maximum: u32) -> host_api::Result<u32> {
+		self.sandbox_store.new_memory(initial, maximum).map_err(|e| format!("{}", e))
+	}
+
+	fn invoke(
+		&mut self,
+		instance_id: u32,
+		export_name: &str,
+		args: &[u8],
+		return_val: u32,
+		return_val_len: u32,
+		state: u32,
+	) -> host_api::Result<u32> {
+		use codec::{Decode, Encode};
+
+		trace!(target: "sr-sandbox", "invoke, instance_idx={}", instance_id);
+
+		// Deserialize arguments and convert them into wasmi types.
+		let args = Vec::<sandbox_primitives::TypedValue>::decode(&mut &args[..])
+			.map_err(|_| "Can't decode serialized arguments for the invocation")?
+			.into_iter()
+			.map(Into::into)
+			.collect::<Vec<_>>();
+
+		let instance = self.sandbox_store.instance(instance_id).map_err(|e| format!("{}", e))?;
+		let result = instance.invoke(export_name, &args, self, state);
+
+		match result {
+			Ok(None) => Ok(sandbox_primitives::ERR_OK),
+			Ok(Some(val)) => {
+				// Serialize return value and write it back into the memory.
+				sandbox_primitives::ReturnValue::Value(val.into()).using_encoded(|val| {
+					if val.len() > return_val_len as usize {
+						Err("Return value buffer is too small")?;
+					}
+					self.memory
+						.set(return_val, val)
+						.map_err(|_| "Return value buffer is OOB")?;
+					Ok(sandbox_primitives::ERR_OK)
+				})
+			}
+			Err(_) => Ok(sandbox_primitives::ERR_EXECUTION),
+		}
+	}
+
+	fn instance_teardown(&mut self, instance_id: u32) -> host_api::Result<()> {
+		self.sandbox_store.instance_teardown(instance_id).map_err(|e| format!("{}", e))
+	}
+
+	fn instance_new(
+		&mut self,
+		dispatch_thunk_id: u32,
+		wasm: &[u8],
+		raw_env_def: &[u8],
+		state: u32,
+	) -> host_api::Result<u32> {
+		// Extract a dispatch thunk from instance's table by the specified index.
+		let dispatch_thunk = {
+			let table = self.table.as_ref()
+				.ok_or_else(|| "Runtime doesn't have a table; sandbox is unavailable")?;
+			table.get(dispatch_thunk_id)
+				.map_err(|_| "dispatch_thunk_idx is out of the table bounds")?
+				.ok_or_else(|| "dispatch_thunk_idx points on an empty table entry")?
+				.clone()
+		};
+
+		let instance_idx_or_err_code =
+			match sandbox::instantiate(self, dispatch_thunk, wasm, raw_env_def, state) {
+				Ok(instance_idx) => instance_idx,
+				Err(sandbox::InstantiationError::StartTrapped) => sandbox_primitives::ERR_EXECUTION,
+				Err(_) => sandbox_primitives::ERR_MODULE,
+			};
+
+		Ok(instance_idx_or_err_code as u32)
+	}
+}
+
+impl sandbox::SandboxCapabilities for FunctionExecutor {
 	fn store(&self) -> &sandbox::Store {
 		&self.sandbox_store
 	}
@@ -121,7 +341,7 @@ fn deadline_to_timestamp(deadline: u64) -> Option<offchain::Timestamp> {
 	}
 }
 
-impl_function_executor!(this: FunctionExecutor<'e, E>,
+impl_function_executor!(this: FunctionExecutor,
 	ext_print_utf8(utf8_data: *const u8, utf8_len: u32) => {
 		if let Ok(utf8) = this.memory.get(utf8_data, utf8_len as usize) {
 			if let Ok(message) = String::from_utf8(utf8) {
@@ -172,7 +392,7 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 				HexDisplay::from(&key),
 			);
 		}
-		this.ext.set_storage(key, value);
+		with_externalities(|ext| ext.set_storage(key, value))?;
 		Ok(())
 	},
 	ext_set_child_storage(
@@ -208,7 +428,7 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 		}
 		let storage_key = ChildStorageKey::from_vec(storage_key)
 			.ok_or_else(|| "ext_set_child_storage: child storage key is invalid")?;
-		this.ext.set_child_storage(storage_key, key, value);
+		with_externalities(|ext| ext.set_child_storage(storage_key, key, value))?;
 		Ok(())
 	},
 	ext_clear_child_storage(
@@ -236,7 +456,7 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 		let storage_key = ChildStorageKey::from_vec(storage_key)
 			.ok_or_else(|| "ext_clear_child_storage: child storage key is not valid")?;
 
-		this.ext.clear_child_storage(storage_key, &key);
+		with_externalities(|ext| ext.clear_child_storage(storage_key, &key))?;
 		Ok(())
 	},
 	ext_clear_storage(key_data: *const u8, key_len: u32) => {
@@ -251,13 +471,13 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 			},
 			HexDisplay::from(&key)
 		);
-		this.ext.clear_storage(&key);
+		with_externalities(|ext| ext.clear_storage(&key))?;
 		Ok(())
 	},
 	ext_exists_storage(key_data: *const u8, key_len: u32) -> u32 => {
 		let key = this.memory.get(key_data, key_len as usize)
 			.map_err(|_| "Invalid attempt to determine key in ext_exists_storage")?;
-		Ok(if this.ext.exists_storage(&key) { 1 } else { 0 })
+		Ok(if with_externalities(|ext| ext.exists_storage(&key))? { 1 } else { 0 })
 	},
 	ext_exists_child_storage(
 		storage_key_data: *const u8,
@@ -273,12 +493,12 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 			.map_err(|_| "Invalid attempt to determine key in ext_exists_child_storage")?;
 		let storage_key = ChildStorageKey::from_vec(storage_key)
 			.ok_or_else(|| "ext_exists_child_storage: child storage key is not valid")?;
-		Ok(if this.ext.exists_child_storage(storage_key, &key) { 1 } else { 0 })
+		Ok(if with_externalities(|ext| ext.exists_child_storage(storage_key, &key))? { 1 } else { 0 })
 	},
 	ext_clear_prefix(prefix_data: *const u8, prefix_len: u32) => {
 		let prefix = this.memory.get(prefix_data, prefix_len as usize)
 			.map_err(|_| "Invalid attempt to determine prefix in ext_clear_prefix")?;
-		this.ext.clear_prefix(&prefix);
+		with_externalities(|ext| ext.clear_prefix(&prefix))?;
 		Ok(())
 	},
 	ext_clear_child_prefix(
@@ -295,7 +515,7 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 			.ok_or_else(|| "ext_clear_child_prefix: child storage key is not valid")?;
 		let prefix = this.memory.get(prefix_data, prefix_len as usize)
 			.map_err(|_| "Invalid attempt to determine prefix in ext_clear_child_prefix")?;
-		this.ext.clear_child_prefix(storage_key, &prefix);
+		with_externalities(|ext| ext.clear_child_prefix(storage_key, &prefix))?;
 		Ok(())
 	},
 	ext_kill_child_storage(storage_key_data: *const u8, storage_key_len: u32) => {
@@ -305,7 +525,7 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 		).map_err(|_| "Invalid attempt to determine storage_key in ext_kill_child_storage")?;
 		let storage_key = ChildStorageKey::from_vec(storage_key)
 			.ok_or_else(|| "ext_exists_child_storage: child storage key is not valid")?;
-		this.ext.kill_child_storage(storage_key);
+		with_externalities(|ext| ext.kill_child_storage(storage_key))?;
 		Ok(())
 	},
 	// return 0 and place u32::max_value() into written_out if no value exists for the key.
@@ -314,7 +534,7 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 			key_data,
 			key_len as usize
 		).map_err(|_| "Invalid attempt to determine key in ext_get_allocated_storage")?;
-		let maybe_value = this.ext.storage(&key);
+		let maybe_value = with_externalities(|ext| ext.storage(&key))?;
 
 		debug_trace!(
 			target: "wasm-trace", "*** Getting storage: {} == {}   [k={}]",
@@ -344,6 +564,27 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 			Ok(0)
 		}
 	},
+	// return 0 and place u32::max_value() into written_out if there is no key after the given one.
+	ext_next_key(key_data: *const u8, key_len: u32, written_out: *mut u32) -> *mut u8 => {
+		let key = this.memory.get(
+			key_data,
+			key_len as usize
+		).map_err(|_| "Invalid attempt to determine key in ext_next_key")?;
+		let maybe_next_key = with_externalities(|ext| ext.next_storage_key(&key))?;
+
+		if let Some(next_key) = maybe_next_key {
+			let offset = this.heap.allocate(next_key.len() as u32)? as u32;
+			this.memory.set(offset, &next_key)
+				.map_err(|_| "Invalid attempt to set memory in ext_next_key")?;
+			this.memory.write_primitive(written_out, next_key.len() as u32)
+				.map_err(|_| "Invalid attempt to write written_out in ext_next_key")?;
+			Ok(offset)
+		} else {
+			this.memory.write_primitive(written_out, u32::max_value())
+				.map_err(|_| "Invalid attempt to write failed written_out in ext_next_key")?;
+			Ok(0)
+		}
+	},
 	// return 0 and place u32::max_value() into written_out if no value exists for the key.
 	ext_get_allocated_child_storage(
 		storage_key_data: *const u8,
@@ -364,7 +605,7 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 		let maybe_value = {
 			let storage_key = ChildStorageKey::from_slice(&storage_key)
 				.ok_or_else(|| "ext_get_allocated_child_storage: child storage key is not valid")?;
-			this.ext.child_storage(storage_key, &key)
+			with_externalities(|ext| ext.child_storage(storage_key, &key))?
 		};
 
 		debug_trace!(
@@ -406,7 +647,7 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 	) -> u32 => {
 		let key = this.memory.get(key_data, key_len as usize)
 			.map_err(|_| "Invalid attempt to get key in ext_get_storage_into")?;
-		let maybe_value = this.ext.storage(&key);
+		let maybe_value = with_externalities(|ext| ext.storage(&key))?;
 		debug_trace!(
 			target: "wasm-trace", "*** Getting storage: {} == {}   [k={}]",
 			if let Some(_preimage) = this.hash_lookup.get(&key) {
@@ -454,7 +695,7 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 		let maybe_value = {
 			let storage_key = ChildStorageKey::from_slice(&*storage_key)
 				.ok_or_else(|| "ext_get_child_storage_into: child storage key is not valid")?;
-			this.ext.child_storage(storage_key, &key)
+			with_externalities(|ext| ext.child_storage(storage_key, &key))?
 		};
 		debug_trace!(
 			target: "wasm-trace", "*** Getting storage: {} -> {} == {}   [k={}]",
@@ -483,7 +724,7 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 		}
 	},
 	ext_storage_root(result: *mut u8) => {
-		let r = this.ext.storage_root();
+		let r = with_externalities(|ext| ext.storage_root())?;
 		this.memory.set(result, r.as_ref())
 			.map_err(|_| "Invalid attempt to set memory in ext_storage_root")?;
 		Ok(())
@@ -497,7 +738,7 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 			.map_err(|_| "Invalid attempt to determine storage_key in ext_child_storage_root")?;
 		let storage_key = ChildStorageKey::from_slice(&*storage_key)
 			.ok_or_else(|| "ext_child_storage_root: child storage key is not valid")?;
-		let value = this.ext.child_storage_root(storage_key);
+		let value = with_externalities(|ext| ext.child_storage_root(storage_key))?;
 
 		let offset = this.heap.allocate(value.len() as u32)? as u32;
 		this.memory.set(offset, &value)
@@ -514,7 +755,7 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 		let raw_parent_hash = this.memory.get(parent_hash_data, parent_hash_len as usize)
 			.map_err(|_| "Invalid attempt to get parent_hash in ext_storage_changes_root")?;
 		parent_hash.as_mut().copy_from_slice(&raw_parent_hash[..]);
-		let r = this.ext.storage_changes_root(parent_hash)
+		let r = with_externalities(|ext| ext.storage_changes_root(parent_hash))?
 			.map_err(|_| "Invaid parent_hash passed to ext_storage_changes_root")?;
 		if let Some(r) = r {
 			this.memory.set(result, &r[..])
@@ -550,7 +791,7 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 		Ok(())
 	},
 	ext_chain_id() -> u64 => {
-		Ok(this.ext.chain_id())
+		with_externalities(|ext| ext.chain_id())
 	},
 	ext_twox_64(data: *const u8, len: u32, out: *mut u8) => {
 		let result: [u8; 8] = if len == 0 {
@@ -663,8 +904,7 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 			.map_err(|_| "Invalid attempt to get id in ext_ed25519_public_keys")?;
 		let key_type = KeyTypeId(id);
 
-		let keys = this.ext
-			.keystore()
+		let keys = with_externalities(|ext| ext.keystore())?
 			.ok_or("No `keystore` associated for the current context!")?
 			.read()
 			.ed25519_public_keys(key_type)
@@ -722,8 +962,7 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 					.map_err(|_| "Seed not a valid utf8 string in ext_sr25119_generate")
 			).transpose()?;
 
-		let pubkey = this.ext
-			.keystore()
+		let pubkey = with_externalities(|ext| ext.keystore())?
 			.ok_or("No `keystore` associated for the current context!")?
 			.write()
 			.ed25519_generate_new(key_type, seed)
@@ -754,8 +993,7 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 		let pub_key = ed25519::Public::try_from(pubkey.as_ref())
 			.map_err(|_| "Invalid `ed25519` public key")?;
 
-		let signature = this.ext
-			.keystore()
+		let signature = with_externalities(|ext| ext.keystore())?
 			.ok_or("No `keystore` associated for the current context!")?
 			.read()
 			.ed25519_key_pair(key_type, &pub_key)
@@ -777,8 +1015,7 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 			.map_err(|_| "Invalid attempt to get id in ext_sr25519_public_keys")?;
 		let key_type = KeyTypeId(id);
 
-		let keys = this.ext
-			.keystore()
+		let keys = with_externalities(|ext| ext.keystore())?
 			.ok_or("No `keystore` associated for the current context!")?
 			.read()
 			.sr25519_public_keys(key_type)
@@ -836,8 +1073,7 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 			)
 			.transpose()?;
 
-		let pubkey = this.ext
-			.keystore()
+		let pubkey = with_externalities(|ext| ext.keystore())?
 			.ok_or("No `keystore` associated for the current context!")?
 			.write()
 			.sr25519_generate_new(key_type, seed)
@@ -868,8 +1104,7 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 		let pub_key = sr25519::Public::try_from(pubkey.as_ref())
 			.map_err(|_| "Invalid `sr25519` public key")?;
 
-		let signature = this.ext
-			.keystore()
+		let signature = with_externalities(|ext| ext.keystore())?
 			.ok_or("No `keystore` associated for the current context!")?
 			.read()
 			.sr25519_key_pair(key_type, &pub_key)
@@ -913,23 +1148,23 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 		Ok(0)
 	},
 	ext_is_validator() -> u32 => {
-		this.ext.offchain()
-			.map(|o| if o.is_validator() { 1 } else { 0 })
+		with_externalities(|ext| ext.offchain()
+			.map(|o| if o.is_validator() { 1 } else { 0 }))?
 			.ok_or("Calling unavailable API ext_is_validator: wasm".into())
 	},
 	ext_submit_transaction(msg_data: *const u8, len: u32) -> u32 => {
 		let extrinsic = this.memory.get(msg_data, len as usize)
 			.map_err(|_| "OOB while ext_submit_transaction: wasm")?;
 
-		let res = this.ext.offchain()
-			.map(|api| api.submit_transaction(extrinsic))
+		let res = with_externalities(|ext| ext.offchain()
+			.map(|api| api.submit_transaction(extrinsic)))?
 			.ok_or_else(|| "Calling unavailable API ext_submit_transaction: wasm")?;
 
 		Ok(if res.is_ok() { 0 } else { 1 })
 	},
 	ext_network_state(written_out: *mut u32) -> *mut u8 => {
-		let res = this.ext.offchain()
-			.map(|api| api.network_state())
+		let res = with_externalities(|ext| ext.offchain()
+			.map(|api| api.network_state()))?
 			.ok_or_else(|| "Calling unavailable API ext_network_state: wasm")?;
 
 		let encoded = res.encode();
@@ -944,21 +1179,21 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 		Ok(offset)
 	},
 	ext_timestamp() -> u64 => {
-		let timestamp = this.ext.offchain()
-			.map(|api| api.timestamp())
+		let timestamp = with_externalities(|ext| ext.offchain()
+			.map(|api| api.timestamp()))?
 			.ok_or_else(|| "Calling unavailable API ext_timestamp: wasm")?;
 		Ok(timestamp.unix_millis())
 	},
 	ext_sleep_until(deadline: u64) => {
-		this.ext.offchain()
-			.map(|api| api.sleep_until(offchain::Timestamp::from_unix_millis(deadline)))
+		with_externalities(|ext| ext.offchain()
+			.map(|api| api.sleep_until(offchain::Timestamp::from_unix_millis(deadline))))?
 			.ok_or_else(|| "Calling unavailable API ext_sleep_until: wasm")?;
 		Ok(())
 	},
 	ext_random_seed(seed_data: *mut u8) => {
 		// NOTE the runtime as assumptions about seed size.
-		let seed: [u8; 32] = this.ext.offchain()
-			.map(|api| api.random_seed())
+		let seed: [u8; 32] = with_externalities(|ext| ext.offchain()
+			.map(|api| api.random_seed()))?
 			.ok_or_else(|| "Calling unavailable API ext_random_seed: wasm")?;
 
 		this.memory.set(seed_data, &seed)
@@ -973,8 +1208,8 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 		let value = this.memory.get(value, value_len as usize)
 			.map_err(|_| "OOB while ext_local_storage_set: wasm")?;
 
-		this.ext.offchain()
-			.map(|api| api.local_storage_set(kind, &key, &value))
+		with_externalities(|ext| ext.offchain()
+			.map(|api| api.local_storage_set(kind, &key, &value)))?
 			.ok_or_else(|| "Calling unavailable API ext_local_storage_set: wasm")?;
 
 		Ok(())
@@ -985,8 +1220,8 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 		let key = this.memory.get(key, key_len as usize)
 			.map_err(|_| "OOB while ext_local_storage_get: wasm")?;
 
-		let maybe_value = this.ext.offchain()
-			.map(|api| api.local_storage_get(kind, &key))
+		let maybe_value = with_externalities(|ext| ext.offchain()
+			.map(|api| api.local_storage_get(kind, &key)))?
 			.ok_or_else(|| "Calling unavailable API ext_local_storage_get: wasm")?;
 
 		let (offset, len) = if let Some(value) = maybe_value {
@@ -1021,14 +1256,14 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 
 		let res = {
 			if old_value_len == u32::max_value() {
-				this.ext.offchain()
-					.map(|api| api.local_storage_compare_and_set(kind, &key, None, &new_value))
+				with_externalities(|ext| ext.offchain()
+					.map(|api| api.local_storage_compare_and_set(kind, &key, None, &new_value)))?
 					.ok_or_else(|| "Calling unavailable API ext_local_storage_compare_and_set: wasm")?
 			} else {
 				let v = this.memory.get(old_value, old_value_len as  usize)
 					.map_err(|_| "OOB while ext_local_storage_compare_and_set: wasm")?;
-				this.ext.offchain()
-					.map(|api| api.local_storage_compare_and_set(kind, &key, Some(v.as_slice()), &new_value))
+				with_externalities(|ext| ext.offchain()
+					.map(|api| api.local_storage_compare_and_set(kind, &key, Some(v.as_slice()), &new_value)))?
 					.ok_or_else(|| "Calling unavailable API ext_local_storage_compare_and_set: wasm")?
 			}
 		};
@@ -1055,8 +1290,8 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 		let url_str = str::from_utf8(&url)
 			.map_err(|_| "invalid str while ext_http_request_start: wasm")?;
 
-		let id = this.ext.offchain()
-			.map(|api| api.http_request_start(method_str, url_str, &*meta))
+		let id = with_externalities(|ext| ext.offchain()
+			.map(|api| api.http_request_start(method_str, url_str, &*meta)))?
 			.ok_or_else(|| "Calling unavailable API ext_http_request_start: wasm")?;
 
 		if let Ok(id) = id {
@@ -1082,12 +1317,12 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 		let value_str = str::from_utf8(&value)
 			.map_err(|_| "Invalid str while ext_http_request_add_header: wasm")?;
 
-		let res = this.ext.offchain()
+		let res = with_externalities(|ext| ext.offchain()
 			.map(|api| api.http_request_add_header(
 				offchain::HttpRequestId(request_id as u16),
 				&name_str,
 				&value_str,
-			))
+			)))?
 			.ok_or_else(|| "Calling unavailable API ext_http_request_add_header: wasm")?;
 
 		Ok(if res.is_ok() { 0 } else { 1 })
@@ -1101,12 +1336,12 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 		let chunk = this.memory.get(chunk, chunk_len as usize)
 			.map_err(|_| "OOB while ext_http_request_write_body: wasm")?;
 
-		let res = this.ext.offchain()
+		let res = with_externalities(|ext| ext.offchain()
 			.map(|api| api.http_request_write_body(
 				offchain::HttpRequestId(request_id as u16),
 				&chunk,
 				deadline_to_timestamp(deadline)
-			))
+			)))?
 			.ok_or_else(|| "Calling unavailable API ext_http_request_write_body: wasm")?;
 
 		Ok(match res {
@@ -1128,8 +1363,8 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 			)
 			.collect::<::std::result::Result<Vec<_>, _>>()?;
 
-		let res = this.ext.offchain()
-			.map(|api| api.http_response_wait(&ids, deadline_to_timestamp(deadline)))
+		let res = with_externalities(|ext| ext.offchain()
+			.map(|api| api.http_response_wait(&ids, deadline_to_timestamp(deadline))))?
 			.ok_or_else(|| "Calling unavailable API ext_http_response_wait: wasm")?
 			.into_iter()
 			.map(|status| status.into())
@@ -1150,8 +1385,8 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 	) -> *mut u8 => {
 		use codec::Encode;
 
-		let headers = this.ext.offchain()
-			.map(|api| api.http_response_headers(offchain::HttpRequestId(request_id as u16)))
+		let headers = with_externalities(|ext| ext.offchain()
+			.map(|api| api.http_response_headers(offchain::HttpRequestId(request_id as u16))))?
 			.ok_or_else(|| "Calling unavailable API ext_http_response_headers: wasm")?;
 
 		let encoded = headers.encode();
@@ -1173,12 +1408,12 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 		let mut internal_buffer = Vec::with_capacity(buffer_len as usize);
 		internal_buffer.resize(buffer_len as usize, 0);
 
-		let res = this.ext.offchain()
+		let res = with_externalities(|ext| ext.offchain()
 			.map(|api| api.http_response_read_body(
 				offchain::HttpRequestId(request_id as u16),
 				&mut internal_buffer,
 				deadline_to_timestamp(deadline),
-			))
+			)))?
 			.ok_or_else(|| "Calling unavailable API ext_http_response_read_body: wasm")?;
 
 		Ok(match res {
@@ -1206,28 +1441,11 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 		let raw_env_def = this.memory.get(imports_ptr, imports_len as usize)
 			.map_err(|_| "OOB while ext_sandbox_instantiate: imports")?;
 
-		// Extract a dispatch thunk from instance's table by the specified index.
-		let dispatch_thunk = {
-			let table = this.table.as_ref()
-				.ok_or_else(|| "Runtime doesn't have a table; sandbox is unavailable")?;
-			table.get(dispatch_thunk_idx)
-				.map_err(|_| "dispatch_thunk_idx is out of the table bounds")?
-				.ok_or_else(|| "dispatch_thunk_idx points on an empty table entry")?
-				.clone()
-		};
-
-		let instance_idx_or_err_code =
-			match sandbox::instantiate(this, dispatch_thunk, &wasm, &raw_env_def, state) {
-				Ok(instance_idx) => instance_idx,
-				Err(sandbox::InstantiationError::StartTrapped) => sandbox_primitives::ERR_EXECUTION,
-				Err(_) => sandbox_primitives::ERR_MODULE,
-			};
-
-		Ok(instance_idx_or_err_code as u32)
+		host_api::Sandbox::instance_new(this, dispatch_thunk_idx, &wasm, &raw_env_def, state)
+			.map_err(Error::ApiError)
 	},
 	ext_sandbox_instance_teardown(instance_idx: u32) => {
-		this.sandbox_store.instance_teardown(instance_idx)?;
-		Ok(())
+		host_api::Sandbox::instance_teardown(this, instance_idx).map_err(Error::ApiError)
 	},
 	ext_sandbox_invoke(
 		instance_idx: u32,
@@ -1239,82 +1457,39 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 		return_val_len: usize,
 		state: usize
 	) -> u32 => {
-		use codec::{Decode, Encode};
-
-		trace!(target: "sr-sandbox", "invoke, instance_idx={}", instance_idx);
 		let export = this.memory.get(export_ptr, export_len as usize)
 			.map_err(|_| "OOB while ext_sandbox_invoke: export")
 			.and_then(|b|
 				String::from_utf8(b)
 					.map_err(|_| "Export name should be a valid utf-8 sequence")
 			)?;
-
-		// Deserialize arguments and convert them into wasmi types.
 		let serialized_args = this.memory.get(args_ptr, args_len as usize)
 			.map_err(|_| "OOB while ext_sandbox_invoke: args")?;
-		let args = Vec::<sandbox_primitives::TypedValue>::decode(&mut &serialized_args[..])
-			.map_err(|_| "Can't decode serialized arguments for the invocation")?
-			.into_iter()
-			.map(Into::into)
-			.collect::<Vec<_>>();
-
-		let instance = this.sandbox_store.instance(instance_idx)?;
-		let result = instance.invoke(&export, &args, this, state);
 
-		match result {
-			Ok(None) => Ok(sandbox_primitives::ERR_OK),
-			Ok(Some(val)) => {
-				// Serialize return value and write it back into the memory.
-				sandbox_primitives::ReturnValue::Value(val.into()).using_encoded(|val| {
-					if val.len() > return_val_len as usize {
-						Err("Return value buffer is too small")?;
-					}
-					this.memory
-						.set(return_val_ptr, val)
-						.map_err(|_| "Return value buffer is OOB")?;
-					Ok(sandbox_primitives::ERR_OK)
-				})
-			}
-			Err(_) => Ok(sandbox_primitives::ERR_EXECUTION),
-		}
+		host_api::Sandbox::invoke(
+			this,
+			instance_idx,
+			&export,
+			&serialized_args,
+			return_val_ptr,
+			return_val_len,
+			state,
+		).map_err(Error::ApiError)
 	},
 	ext_sandbox_memory_new(initial: u32, maximum: u32) -> u32 => {
-		let mem_idx = this.sandbox_store.new_memory(initial, maximum)?;
-		Ok(mem_idx)
+		host_api::Sandbox::memory_new(this, initial, maximum).map_err(Error::ApiError)
 	},
 	ext_sandbox_memory_get(memory_idx: u32, offset: u32, buf_ptr: *mut u8, buf_len: u32) -> u32 => {
-		let sandboxed_memory = this.sandbox_store.memory(memory_idx)?;
-
-		match MemoryInstance::transfer(
-			&sandboxed_memory,
-			offset as usize,
-			&this.memory,
-			buf_ptr as usize,
-			buf_len as usize,
-		) {
-			Ok(()) => Ok(sandbox_primitives::ERR_OK),
-			Err(_) => Ok(sandbox_primitives::ERR_OUT_OF_BOUNDS),
-		}
+		host_api::Sandbox::memory_get(this, memory_idx, offset, buf_ptr, buf_len)
+			.map_err(Error::ApiError)
 	},
 	ext_sandbox_memory_set(memory_idx: u32, offset: u32, val_ptr: *const u8, val_len: u32) -> u32 => {
-		let sandboxed_memory = this.sandbox_store.memory(memory_idx)?;
-
-		match MemoryInstance::transfer(
-			&this.memory,
-			val_ptr as usize,
-			&sandboxed_memory,
-			offset as usize,
-			val_len as usize,
-		) {
-			Ok(()) => Ok(sandbox_primitives::ERR_OK),
-			Err(_) => Ok(sandbox_primitives::ERR_OUT_OF_BOUNDS),
-		}
+		host_api::Sandbox::memory_set(this, memory_idx, offset, val_ptr, val_len)
+			.map_err(Error::ApiError)
 	},
 	ext_sandbox_memory_teardown(memory_idx: u32) => {
-		this.sandbox_store.memory_teardown(memory_idx)?;
-		Ok(())
+		host_api::Sandbox::memory_teardown(this, memory_idx).map_err(Error::ApiError)
 	},
-	=> <'e, E: Externalities<Blake2Hasher> + 'e>
 );
 
 /// Wasm rust executor for contracts.
@@ -1344,7 +1519,7 @@ impl WasmExecutor {
 		data: &[u8],
 	) -> Result<Vec<u8>> {
 		let module = ::wasmi::Module::from_buffer(code)?;
-		let module = Self::instantiate_module::<E>(heap_pages, &module)?;
+		let module = Self::instantiate_module(heap_pages, &module)?;
 		self.call_in_wasm_module(ext, &module, method, data)
 	}
 
@@ -1366,7 +1541,7 @@ impl WasmExecutor {
 		filter_result: FR,
 	) -> Result<R> {
 		let module = wasmi::Module::from_buffer(code)?;
-		let module = Self::instantiate_module::<E>(heap_pages, &module)?;
+		let module = Self::instantiate_module(heap_pages, &module)?;
 		self.call_in_wasm_module_with_custom_signature(
 			ext,
 			&module,
@@ -1451,18 +1626,17 @@ impl WasmExecutor {
 			.and_then(|e| e.as_table().cloned());
 		let heap_base = Self::get_heap_base(module_instance)?;
 
-		let mut fec = FunctionExecutor::new(memory.clone(), heap_base, table, ext)?;
+		let mut fec = FunctionExecutor::new(memory.clone(), heap_base, table)?;
 		let parameters = create_parameters(&mut |data: &[u8]| {
 			let offset = fec.heap.allocate(data.len() as u32)?;
 			memory.set(offset, &data)?;
 			Ok(offset)
 		})?;
 
-		let result = module_instance.invoke_export(
-			method,
-			&parameters,
-			&mut fec
-		);
+		let _batch_guard = BatchVerifyGuard::new();
+		let result = runtime_interface::externalities::using(ext, || {
+			module_instance.invoke_export(method, &parameters, &mut fec)
+		});
 		let result = match result {
 			Ok(val) => match filter_result(val, &memory)? {
 				Some(val) => Ok(val),
@@ -1478,7 +1652,7 @@ impl WasmExecutor {
 	}
 
 	/// Prepare module instance
-	pub fn instantiate_module<E: Externalities<Blake2Hasher>>(
+	pub fn instantiate_module(
 		heap_pages: usize,
 		module: &Module,
 	) -> Result<ModuleRef> {
@@ -1486,7 +1660,7 @@ impl WasmExecutor {
 		let intermediate_instance = ModuleInstance::new(
 			module,
 			&ImportsBuilder::new()
-			.with_resolver("env", FunctionExecutor::<E>::resolver())
+			.with_resolver("env", FunctionExecutor::resolver())
 		)?;
 
 		// Verify that the module has the heap base global variable.
@@ -1520,6 +1694,19 @@ mod tests {
 
 	type TestExternalities<H> = CoreTestExternalities<H, u64>;
 
+	#[test]
+	fn runtime_interface_host_functions_are_resolved() {
+		use wasmi::ValueType;
+
+		let get_signature = wasmi::Signature::new(&[ValueType::I64][..], Some(ValueType::I64));
+		assert!(resolve_host_function("ext_storage_get_version_1", &get_signature, 100).unwrap().is_some());
+
+		let wrong_signature = wasmi::Signature::new(&[ValueType::I32][..], Some(ValueType::I64));
+		assert!(resolve_host_function("ext_storage_get_version_1", &wrong_signature, 100).is_err());
+
+		assert!(resolve_host_function("ext_unknown_version_1", &get_signature, 100).unwrap().is_none());
+	}
+
 	#[test]
 	fn returning_should_work() {
 		let mut ext = TestExternalities::default();
@@ -1544,6 +1731,26 @@ mod tests {
 		assert!(output.is_err());
 	}
 
+	#[test]
+	fn panicking_discards_the_signature_batch() {
+		let mut ext = TestExternalities::default();
+		let test_code = WASM_BINARY;
+
+		let output = WasmExecutor::new().call(&mut ext, 8, &test_code[..], "test_panic_in_batch_verify", &[]);
+		assert!(output.is_err());
+		// the batch of the panicked call doesn't accept a forged signature.
+		assert!(!runtime_io::crypto::ed25519_batch_verify(&[0; 64], b"message", &[0; 32]));
+		assert!(runtime_io::crypto::finish_batch_verify());
+
+		let output = crate::with_native_environment(&mut ext, || {
+			runtime_io::crypto::start_batch_verify();
+			panic!("test panic")
+		});
+		assert!(output.is_err());
+		assert!(!runtime_io::crypto::ed25519_batch_verify(&[0; 64], b"message", &[0; 32]));
+		assert!(runtime_io::crypto::finish_batch_verify());
+	}
+
 	#[test]
 	fn storage_should_work() {
 		let mut ext = TestExternalities::default();
@@ -1641,6 +1848,34 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn sha2_256_should_work() {
+		let mut ext = TestExternalities::default();
+		let test_code = WASM_BINARY;
+		assert_eq!(
+			WasmExecutor::new().call(&mut ext, 8, &test_code[..], "test_sha2_256", &[]).unwrap(),
+			hex!("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+		);
+		assert_eq!(
+			WasmExecutor::new().call(&mut ext, 8, &test_code[..], "test_sha2_256", b"Hello world!").unwrap(),
+			hex!("c0535e4be2b79ffd93291305436bf889314e4a3faec05ecffcbb7df31ad9e51a")
+		);
+	}
+
+	#[test]
+	fn keccak_512_should_work() {
+		let mut ext = TestExternalities::default();
+		let test_code = WASM_BINARY;
+		assert_eq!(
+			WasmExecutor::new().call(&mut ext, 8, &test_code[..], "test_keccak_512", &[]).unwrap(),
+			primitives::keccak_512(&b""[..]).to_vec()
+		);
+		assert_eq!(
+			WasmExecutor::new().call(&mut ext, 8, &test_code[..], "test_keccak_512", b"Hello world!").unwrap(),
+			primitives::keccak_512(&b"Hello world!"[..]).to_vec()
+		);
+	}
+
 	#[test]
 	fn ed25519_verify_should_work() {
 		let mut ext = TestExternalities::<Blake2Hasher>::default();
@@ -308,7 +308,7 @@ impl RuntimesCache {
 		let data_segments = extract_data_segments(&code).ok_or(CacheError::CantDeserializeWasm)?;
 
 		// Instantiate this module.
-		let instance = WasmExecutor::instantiate_module::<E>(heap_pages as usize, &module)
+		let instance = WasmExecutor::instantiate_module(heap_pages as usize, &module)
 			.map_err(CacheError::Instantiation)?;
 
 		// Take state snapshot before executing anything.
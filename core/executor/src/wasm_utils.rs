@@ -224,9 +224,9 @@ macro_rules! impl_function_executor {
 			( $( $names:ident : $params:ty ),* $(,)? )
 			$( -> $returns:ty )? => { $( $body:tt )* },
 		)*
-		=> $( $pre:tt )+
+		$( => $( $pre:tt )+ )?
 	) => (
-		impl $( $pre ) + $structname {
+		impl $( $( $pre )+ )? $structname {
 			#[allow(unused)]
 			fn resolver() -> &'static dyn $crate::wasmi::ModuleImportResolver {
 				struct Resolver;
@@ -242,6 +242,17 @@ macro_rules! impl_function_executor {
 							$( $name( $( $params ),* ) $( -> $returns )? => )*
 						);
 
+						// The host functions of the runtime interfaces are indexed after the
+						// functions of this executor.
+						let index_offset = [ $( stringify!($name) ),* ].len();
+						if let Some(func) = $crate::wasm_executor::resolve_host_function(
+							name,
+							signature,
+							index_offset,
+						)? {
+							return Ok(func);
+						}
+
 						Err($crate::wasmi::Error::Instantiation(
 							format!("Export {} not found", name),
 						))
@@ -251,12 +262,17 @@ macro_rules! impl_function_executor {
 			}
 		}
 
-		impl $( $pre ) + $crate::wasmi::Externals for $structname {
+		impl $( $( $pre )+ )? $crate::wasmi::Externals for $structname {
 			fn invoke_index(
 				&mut self,
 				index: usize,
 				args: $crate::wasmi::RuntimeArgs,
 			) -> std::result::Result<Option<$crate::wasmi::RuntimeValue>, $crate::wasmi::Trap> {
+				let index_offset = [ $( stringify!($name) ),* ].len();
+				if index >= index_offset {
+					return self.invoke_host_function(index - index_offset, args.as_ref());
+				}
+
 				let $objectname = self;
 				let mut args = args.as_ref().iter();
 				dispatch_fn! {
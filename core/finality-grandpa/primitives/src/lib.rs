@@ -148,6 +148,71 @@ impl<N: Codec> ConsensusLog<N> {
 	}
 }
 
+/// A vote of a GRANDPA authority, encoded like the messages the authorities sign.
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Clone, Eq, PartialEq, Encode, Decode)]
+pub enum Vote<H, N> {
+	/// A prevote for the given target block.
+	#[codec(index = "0")]
+	Prevote {
+		/// The target block's hash.
+		target_hash: H,
+		/// The target block's number.
+		target_number: N,
+	},
+	/// A precommit for the given target block.
+	#[codec(index = "1")]
+	Precommit {
+		/// The target block's hash.
+		target_hash: H,
+		/// The target block's number.
+		target_number: N,
+	},
+}
+
+/// Proof that an authority voted twice in the same stage of a round.
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Clone, Eq, PartialEq, Encode, Decode)]
+pub struct EquivocationProof<H, N> {
+	/// The set in which the equivocation happened.
+	pub set_id: SetId,
+	/// The round in which the equivocation happened.
+	pub round: RoundNumber,
+	/// The authority that equivocated.
+	pub offender: AuthorityId,
+	/// The first vote and its signature.
+	pub first: (Vote<H, N>, AuthoritySignature),
+	/// The second vote and its signature.
+	pub second: (Vote<H, N>, AuthoritySignature),
+}
+
+impl<H: Encode + PartialEq, N: Encode + PartialEq> EquivocationProof<H, N> {
+	/// Check that both votes are of the same stage, differ and are signed by the offender.
+	pub fn is_valid(&self) -> bool {
+		use app_crypto::RuntimeAppPublic;
+
+		let same_stage = match (&self.first.0, &self.second.0) {
+			(Vote::Prevote { .. }, Vote::Prevote { .. }) => true,
+			(Vote::Precommit { .. }, Vote::Precommit { .. }) => true,
+			_ => false,
+		};
+		if !same_stage || self.first.0 == self.second.0 {
+			return false;
+		}
+
+		[&self.first, &self.second].iter().all(|(vote, signature)| {
+			let payload = (vote, self.round, self.set_id).encode();
+			self.offender.verify(&payload, signature)
+		})
+	}
+}
+
+/// Key in the offchain persistent storage under which the node queues the equivocation proofs it
+/// observed, for the runtime's offchain worker to submit them.
+///
+/// The value is an encoded `Vec<EquivocationProof<Block::Hash, NumberFor<Block>>>`.
+pub const EQUIVOCATION_REPORTS_KEY: &[u8] = b"grandpa:equivocation_reports";
+
 /// WASM function call to check for pending changes.
 pub const PENDING_CHANGE_CALL: &str = "grandpa_pending_change";
 /// WASM function call to get current GRANDPA authorities.
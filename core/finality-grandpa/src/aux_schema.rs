@@ -39,6 +39,7 @@ const VERSION_KEY: &[u8] = b"grandpa_schema_version";
 const SET_STATE_KEY: &[u8] = b"grandpa_completed_round";
 const AUTHORITY_SET_KEY: &[u8] = b"grandpa_voters";
 const CONSENSUS_CHANGES_KEY: &[u8] = b"grandpa_consensus_changes";
+const AUTHORITY_SET_CHANGES_KEY: &[u8] = b"grandpa_authority_set_changes";
 
 const CURRENT_VERSION: u32 = 2;
 
@@ -299,6 +300,16 @@ pub(crate) fn load_persistent<Block: BlockT, B, G>(
 				backend,
 				AUTHORITY_SET_KEY,
 			)? {
+				let set_changes = load_decode::<_, AuthoritySetChanges<NumberFor<Block>>>(
+					backend,
+					AUTHORITY_SET_CHANGES_KEY,
+				)?;
+				if set_changes.is_none() && set.current().0 > 0 {
+					info!(target: "afg", "The authority set changes finalized before the upgrade of the \
+						database are unknown. Warp sync proofs will only be served from the first \
+						recorded change, resync the node to serve them from genesis.");
+				}
+
 				let set_state = match load_decode::<_, VoterSetState<Block>>(
 					backend,
 					SET_STATE_KEY,
@@ -429,6 +440,32 @@ pub(crate) fn update_consensus_changes<H, N, F, R>(
 	write_aux(&[(CONSENSUS_CHANGES_KEY, set.encode().as_slice())])
 }
 
+/// The blocks that enacted standard authority set changes, in ascending order. Each
+/// entry holds the id of the set that finalized the block and the block number.
+///
+/// The changes are recorded as they are finalized. They aren't backfilled for the databases
+/// that predate them, whose nodes must resync to serve warp sync proofs from genesis. Until
+/// then, they serve no proof to the light clients whose set precedes the first recorded change.
+pub(crate) type AuthoritySetChanges<N> = Vec<(SetId, N)>;
+
+/// Load the blocks that enacted standard authority set changes.
+pub(crate) fn load_authority_set_changes<B: AuxStore, N: Decode>(backend: &B)
+	-> ClientResult<AuthoritySetChanges<N>>
+{
+	load_decode(backend, AUTHORITY_SET_CHANGES_KEY).map(Option::unwrap_or_default)
+}
+
+/// Update the blocks that enacted standard authority set changes.
+pub(crate) fn update_authority_set_changes<N, F, R>(
+	changes: &AuthoritySetChanges<N>,
+	write_aux: F
+) -> R where
+	N: Encode,
+	F: FnOnce(&[(&'static [u8], &[u8])]) -> R,
+{
+	write_aux(&[(AUTHORITY_SET_CHANGES_KEY, changes.encode().as_slice())])
+}
+
 #[cfg(test)]
 pub(crate) fn load_authorities<B: AuxStore, H: Decode, N: Decode>(backend: &B)
 	-> Option<AuthoritySet<H, N>> {
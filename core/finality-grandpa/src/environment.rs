@@ -26,7 +26,7 @@ use tokio_timer::Delay;
 use parking_lot::RwLock;
 
 use client::{
-	backend::Backend, apply_aux, BlockchainEvents, CallExecutor,
+	backend::{Backend, OffchainStorage}, apply_aux, BlockchainEvents, CallExecutor,
 	Client, error::Error as ClientError, utils::is_descendent_of,
 	blockchain::HeaderBackend, backend::Finalizer,
 };
@@ -34,7 +34,7 @@ use grandpa::{
 	BlockNumberOps, Equivocation, Error as GrandpaError, round::State as RoundState,
 	voter, voter_set::VoterSet,
 };
-use primitives::{Blake2Hasher, H256, Pair};
+use primitives::{Blake2Hasher, H256, Pair, offchain::PERSISTENT_STORAGE_PREFIX};
 use sr_primitives::generic::BlockId;
 use sr_primitives::traits::{
 	Block as BlockT, Header as HeaderT, NumberFor, One, Zero,
@@ -52,7 +52,11 @@ use crate::authorities::{AuthoritySet, SharedAuthoritySet};
 use crate::consensus_changes::SharedConsensusChanges;
 use crate::justification::GrandpaJustification;
 use crate::until_imported::UntilVoteTargetImported;
-use fg_primitives::{AuthorityId, AuthoritySignature, SetId, RoundNumber};
+use crate::voting_rule::VotingRule;
+use fg_primitives::{
+	AuthorityId, AuthoritySignature, SetId, RoundNumber, EquivocationProof, Vote,
+	EQUIVOCATION_REPORTS_KEY,
+};
 
 type HistoricalVotes<Block> = grandpa::HistoricalVotes<
 	<Block as BlockT>::Hash,
@@ -368,7 +372,7 @@ impl<Block: BlockT> SharedVoterSetState<Block> {
 }
 
 /// The environment we run GRANDPA in.
-pub(crate) struct Environment<B, E, Block: BlockT, N: Network<Block>, RA, SC> {
+pub(crate) struct Environment<B, E, Block: BlockT, N: Network<Block>, RA, SC, VR> {
 	pub(crate) inner: Arc<Client<B, E, Block, RA>>,
 	pub(crate) select_chain: SC,
 	pub(crate) voters: Arc<VoterSet<AuthorityId>>,
@@ -378,9 +382,10 @@ pub(crate) struct Environment<B, E, Block: BlockT, N: Network<Block>, RA, SC> {
 	pub(crate) network: crate::communication::NetworkBridge<Block, N>,
 	pub(crate) set_id: SetId,
 	pub(crate) voter_set_state: SharedVoterSetState<Block>,
+	pub(crate) voting_rule: VR,
 }
 
-impl<B, E, Block: BlockT, N: Network<Block>, RA, SC> Environment<B, E, Block, N, RA, SC> {
+impl<B, E, Block: BlockT, N: Network<Block>, RA, SC, VR> Environment<B, E, Block, N, RA, SC, VR> {
 	/// Updates the voter set state using the given closure. The write lock is
 	/// held during evaluation of the closure and the environment's voter set
 	/// state is set to its result if successful.
@@ -396,16 +401,62 @@ impl<B, E, Block: BlockT, N: Network<Block>, RA, SC> Environment<B, E, Block, N,
 	}
 }
 
-impl<Block: BlockT<Hash=H256>, B, E, N, RA, SC>
+impl<B, E, Block: BlockT, N: Network<Block>, RA, SC, VR> Environment<B, E, Block, N, RA, SC, VR> where
+	B: Backend<Block, Blake2Hasher>,
+	E: CallExecutor<Block, Blake2Hasher>,
+{
+	/// Queue an equivocation proof in the offchain storage, from where the runtime's offchain
+	/// worker picks it up and reports it on-chain.
+	fn report_equivocation(&self, proof: EquivocationProof<Block::Hash, NumberFor<Block>>) {
+		#[allow(deprecated)]
+		let offchain_storage = self.inner.backend().offchain_storage();
+		let mut storage = match offchain_storage {
+			Some(storage) => storage,
+			None => {
+				warn!(target: "afg", "Offchain storage unavailable, equivocation will not be reported.");
+				return;
+			},
+		};
+
+		loop {
+			let current = storage.get(PERSISTENT_STORAGE_PREFIX, EQUIVOCATION_REPORTS_KEY);
+			let mut reports = current.as_ref()
+				.and_then(|encoded| Vec::<EquivocationProof<_, _>>::decode(&mut &encoded[..]).ok())
+				.unwrap_or_default();
+
+			if reports.contains(&proof) {
+				return;
+			}
+			reports.push(proof.clone());
+
+			if storage.compare_and_set(
+				PERSISTENT_STORAGE_PREFIX,
+				EQUIVOCATION_REPORTS_KEY,
+				current.as_ref().map(|v| &v[..]),
+				&reports.encode(),
+			) {
+				debug!(
+					target: "afg", "Queued equivocation of {:?} in round {} for reporting",
+					proof.offender, proof.round,
+				);
+				return;
+			}
+		}
+	}
+}
+
+impl<Block: BlockT<Hash=H256>, B, E, N, RA, SC, VR>
 	grandpa::Chain<Block::Hash, NumberFor<Block>>
-for Environment<B, E, Block, N, RA, SC>
+for Environment<B, E, Block, N, RA, SC, VR>
 where
 	Block: 'static,
 	B: Backend<Block, Blake2Hasher> + 'static,
-	E: CallExecutor<Block, Blake2Hasher> + 'static,
+	E: CallExecutor<Block, Blake2Hasher> + Send + Sync + 'static,
 	N: Network<Block> + 'static,
 	N::In: 'static,
+	RA: Send + Sync,
 	SC: SelectChain<Block> + 'static,
+	VR: VotingRule<Block, Client<B, E, Block, RA>>,
 	NumberFor<Block>: BlockNumberOps,
 {
 	fn ancestry(&self, base: Block::Hash, block: Block::Hash) -> Result<Vec<Block::Hash>, GrandpaError> {
@@ -429,7 +480,7 @@ where
 		debug!(target: "afg", "Finding best chain containing block {:?} with number limit {:?}", block, limit);
 
 		match self.select_chain.finality_target(block, None) {
-			Ok(Some(mut best_hash)) => {
+			Ok(Some(best_hash)) => {
 				let base_header = self.inner.header(&BlockId::Hash(block)).ok()?
 					.expect("Header known to exist after `best_containing` call; qed");
 
@@ -445,35 +496,61 @@ where
 					}
 				}
 
-				let mut best_header = self.inner.header(&BlockId::Hash(best_hash)).ok()?
+				let best_header = self.inner.header(&BlockId::Hash(best_hash)).ok()?
 					.expect("Header known to exist after `best_containing` call; qed");
 
-				// we target a vote towards 3/4 of the unfinalized chain (rounding up)
-				let target = {
-					let two = NumberFor::<Block>::one() + One::one();
-					let three = two + One::one();
-					let four = three + One::one();
-
-					let diff = *best_header.number() - *base_header.number();
-					let diff = ((diff * three) + two) / four;
+				// check if our vote is currently being limited due to a pending change
+				let limit = limit.filter(|limit| limit < best_header.number());
+				let target;
+
+				let target_header = if let Some(target_number) = limit {
+					let mut target_header = best_header.clone();
+
+					// walk backwards until we find the target block
+					loop {
+						if *target_header.number() < target_number {
+							unreachable!(
+								"we are traversing backwards from a known block; \
+								 blocks are stored contiguously; \
+								 qed"
+							);
+						}
+
+						if *target_header.number() == target_number {
+							break;
+						}
+
+						target_header = self.inner.header(&BlockId::Hash(*target_header.parent_hash())).ok()?
+							.expect("Header known to exist after `best_containing` call; qed");
+					}
 
-					*base_header.number() + diff
+					target = target_header;
+					&target
+				} else {
+					// otherwise just use the given best as the target
+					&best_header
 				};
 
-				// unless our vote is currently being limited due to a pending change
-				let target = limit.map(|limit| limit.min(target)).unwrap_or(target);
-
-				// walk backwards until we find the target block
-				loop {
-					if *best_header.number() < target { unreachable!(); }
-					if *best_header.number() == target {
-						return Some((best_hash, *best_header.number()));
-					}
-
-					best_hash = *best_header.parent_hash();
-					best_header = self.inner.header(&BlockId::Hash(best_hash)).ok()?
-						.expect("Header known to exist after `best_containing` call; qed");
-				}
+				// restrict vote according to the given voting rule, if the
+				// voting rule doesn't restrict the vote then we keep the
+				// previous target.
+				//
+				// note that we pass the original `best_header`, i.e. before the
+				// authority set limit filter, which can be considered a
+				// mandatory/implicit voting rule.
+				//
+				// we also make sure that the restricted vote is higher than the
+				// round base (i.e. last finalized), otherwise the value
+				// returned by the given voting rule is ignored and the original
+				// target is used instead.
+				self.voting_rule
+					.restrict_vote(&*self.inner, &base_header, &best_header, target_header)
+					.filter(|(_, restricted_number)| {
+						// we can only restrict votes within the interval [base, target]
+						restricted_number >= base_header.number() &&
+							restricted_number < target_header.number()
+					})
+					.or(Some((target_header.hash(), *target_header.number())))
 			},
 			Ok(None) => {
 				debug!(target: "afg", "Encountered error finding best chain containing {:?}: couldn't find target block", block);
@@ -523,9 +600,9 @@ pub(crate) fn ancestry<B, Block: BlockT<Hash=H256>, E, RA>(
 	Ok(tree_route.retracted().iter().skip(1).map(|e| e.hash).collect())
 }
 
-impl<B, E, Block: BlockT<Hash=H256>, N, RA, SC>
+impl<B, E, Block: BlockT<Hash=H256>, N, RA, SC, VR>
 	voter::Environment<Block::Hash, NumberFor<Block>>
-for Environment<B, E, Block, N, RA, SC>
+for Environment<B, E, Block, N, RA, SC, VR>
 where
 	Block: 'static,
 	B: Backend<Block, Blake2Hasher> + 'static,
@@ -534,6 +611,7 @@ where
 	N::In: 'static + Send,
 	RA: 'static + Send + Sync,
 	SC: SelectChain<Block> + 'static,
+	VR: VotingRule<Block, Client<B, E, Block, RA>>,
 	NumberFor<Block>: BlockNumberOps,
 {
 	type Timer = Box<dyn Future<Item = (), Error = Self::Error> + Send>;
@@ -556,6 +634,9 @@ where
 		&self,
 		round: RoundNumber,
 	) -> voter::RoundData<Self::Id, Self::Timer, Self::In, Self::Out> {
+		crate::metrics::ROUND.set(round as i64);
+		crate::metrics::SET_ID.set(self.set_id as i64);
+
 		let now = Instant::now();
 		let prevote_timer = Delay::new(now + self.config.gossip_duration * 2);
 		let precommit_timer = Delay::new(now + self.config.gossip_duration * 4);
@@ -824,7 +905,18 @@ where
 		equivocation: ::grandpa::Equivocation<Self::Id, Prevote<Block>, Self::Signature>
 	) {
 		warn!(target: "afg", "Detected prevote equivocation in the finality worker: {:?}", equivocation);
-		// nothing yet; this could craft misbehavior reports of some kind.
+
+		let vote = |prevote: Prevote<Block>| Vote::Prevote {
+			target_hash: prevote.target_hash,
+			target_number: prevote.target_number,
+		};
+		self.report_equivocation(EquivocationProof {
+			set_id: self.set_id,
+			round: equivocation.round_number,
+			offender: equivocation.identity,
+			first: (vote(equivocation.first.0), equivocation.first.1),
+			second: (vote(equivocation.second.0), equivocation.second.1),
+		});
 	}
 
 	fn precommit_equivocation(
@@ -833,7 +925,18 @@ where
 		equivocation: Equivocation<Self::Id, Precommit<Block>, Self::Signature>
 	) {
 		warn!(target: "afg", "Detected precommit equivocation in the finality worker: {:?}", equivocation);
-		// nothing yet
+
+		let vote = |precommit: Precommit<Block>| Vote::Precommit {
+			target_hash: precommit.target_hash,
+			target_number: precommit.target_number,
+		};
+		self.report_equivocation(EquivocationProof {
+			set_id: self.set_id,
+			round: equivocation.round_number,
+			offender: equivocation.identity,
+			first: (vote(equivocation.first.0), equivocation.first.1),
+			second: (vote(equivocation.second.0), equivocation.second.1),
+		});
 	}
 }
 
@@ -1020,6 +1123,25 @@ pub(crate) fn finalize_block<B, Block: BlockT<Hash=H256>, E, RA>(
 			}
 		}
 
+		if let Some(ref new_set) = new_authorities {
+			// remember the block that enacted the change (along with its
+			// justification stored above) to serve warp sync proofs.
+			let mut set_changes = crate::aux_schema::load_authority_set_changes(client)?;
+			set_changes.push((new_set.set_id - 1, new_set.canon_number));
+
+			let write_result = crate::aux_schema::update_authority_set_changes(
+				&set_changes,
+				|insert| apply_aux(import_op, insert, &[]),
+			);
+
+			if let Err(e) = write_result {
+				warn!(target: "finality", "Failed to write authority set changes to disk. Bailing.");
+				warn!(target: "finality", "Node is in a potentially inconsistent state.");
+
+				return Err(e.into());
+			}
+		}
+
 		Ok(new_authorities.map(VoterCommand::ChangeAuthorities))
 	});
 
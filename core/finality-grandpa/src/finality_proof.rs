@@ -174,6 +174,11 @@ impl<B, Block> network::FinalityProofProvider<Block> for FinalityProofProvider<B
 				request.last_finalized,
 				for_block,
 			),
+			FinalityProofRequest::WarpSync(request) => crate::warp_sync::prove_warp_sync(
+				&*self.backend.blockchain(),
+				&crate::aux_schema::load_authority_set_changes(&*self.backend)?,
+				request.authorities_set_id,
+			),
 		}
 	}
 }
@@ -221,6 +226,8 @@ type FinalityProof<Header> = Vec<FinalityProofFragment<Header>>;
 enum FinalityProofRequest<H: Encode + Decode> {
 	/// Original version of the request.
 	Original(OriginalFinalityProofRequest<H>),
+	/// Request for the proof of all authority set changes unknown to the caller.
+	WarpSync(WarpSyncFinalityProofRequest),
 }
 
 /// Original version of finality proof request.
@@ -234,6 +241,15 @@ struct OriginalFinalityProofRequest<H: Encode + Decode> {
 	pub last_finalized: H,
 }
 
+/// Warp sync version of finality proof request.
+#[derive(Debug, Encode, Decode)]
+struct WarpSyncFinalityProofRequest {
+	/// The latest authorities set id known to the caller.
+	///
+	/// The first justification in the proof must be signed by this authority set.
+	pub authorities_set_id: u64,
+}
+
 /// Prepare data blob associated with finality proof request.
 pub(crate) fn make_finality_proof_request<H: Encode + Decode>(last_finalized: H, authorities_set_id: u64) -> Vec<u8> {
 	FinalityProofRequest::Original(OriginalFinalityProofRequest {
@@ -242,6 +258,13 @@ pub(crate) fn make_finality_proof_request<H: Encode + Decode>(last_finalized: H,
 	}).encode()
 }
 
+/// Prepare data blob associated with warp sync proof request.
+pub(crate) fn make_warp_sync_request<H: Encode + Decode>(authorities_set_id: u64) -> Vec<u8> {
+	FinalityProofRequest::<H>::WarpSync(WarpSyncFinalityProofRequest {
+		authorities_set_id,
+	}).encode()
+}
+
 /// Prepare proof-of-finality for the best possible block in the range: (begin; end].
 ///
 /// It is assumed that the caller already have a proof-of-finality for the block 'begin'.
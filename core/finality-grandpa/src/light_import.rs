@@ -21,7 +21,7 @@ use parking_lot::RwLock;
 
 use client::{
 	CallExecutor, Client,
-	backend::{AuxStore, Backend, Finalizer},
+	backend::{AuxStore, Backend, Finalizer, RemoteBackend},
 	blockchain::HeaderBackend,
 	error::Error as ClientError,
 };
@@ -43,8 +43,11 @@ use primitives::{H256, Blake2Hasher};
 use crate::aux_schema::load_decode;
 use crate::consensus_changes::ConsensusChanges;
 use crate::environment::canonical_at_height;
-use crate::finality_proof::{AuthoritySetForFinalityChecker, ProvableJustification, make_finality_proof_request};
+use crate::finality_proof::{
+	AuthoritySetForFinalityChecker, ProvableJustification, make_finality_proof_request, make_warp_sync_request,
+};
 use crate::justification::GrandpaJustification;
+use crate::warp_sync::WarpSyncJustification;
 
 /// LightAuthoritySet is saved under this key in aux storage.
 const LIGHT_AUTHORITY_SET_KEY: &[u8] = b"grandpa_voters";
@@ -103,6 +106,7 @@ struct LightImportData<Block: BlockT<Hash=H256>> {
 	last_finalized: Block::Hash,
 	authority_set: LightAuthoritySet,
 	consensus_changes: ConsensusChanges<Block::Hash, NumberFor<Block>>,
+	warp_sync_target: Option<Block::Hash>,
 }
 
 /// Latest authority set tracker.
@@ -151,7 +155,7 @@ impl<B, E, Block: BlockT<Hash=H256>, RA> BlockImport<Block>
 impl<B, E, Block: BlockT<Hash=H256>, RA> FinalityProofImport<Block>
 	for GrandpaLightBlockImport<B, E, Block, RA> where
 		NumberFor<Block>: grandpa::BlockNumberOps,
-		B: Backend<Block, Blake2Hasher> + 'static,
+		B: RemoteBackend<Block, Blake2Hasher> + 'static,
 		E: CallExecutor<Block, Blake2Hasher> + 'static + Clone + Send + Sync,
 		DigestFor<Block>: Encode,
 		RA: Send + Sync,
@@ -179,11 +183,21 @@ impl<B, E, Block: BlockT<Hash=H256>, RA> FinalityProofImport<Block>
 		finality_proof: Vec<u8>,
 		verifier: &mut dyn Verifier<Block>,
 	) -> Result<(Block::Hash, NumberFor<Block>), Self::Error> {
+		let mut data = self.data.write();
+		if data.warp_sync_target == Some(hash) {
+			data.warp_sync_target = None;
+			return do_import_warp_sync_proof::<_, _, GrandpaJustification<Block>>(
+				&*self.backend,
+				&mut *data,
+				finality_proof,
+			);
+		}
+
 		do_import_finality_proof::<_, _, _, GrandpaJustification<Block>>(
 			&*self.client,
 			self.backend.clone(),
 			&*self.authority_set_provider,
-			&mut *self.data.write(),
+			&mut *data,
 			hash,
 			number,
 			finality_proof,
@@ -228,6 +242,13 @@ impl<B: BlockT<Hash=H256>> FinalityProofRequestBuilder<B> for GrandpaFinalityPro
 			data.authority_set.set_id(),
 		)
 	}
+
+	fn build_warp_sync_request_data(&mut self, hash: &B::Hash) -> Option<Vec<u8>> {
+		// remember the request, so that the response is imported as a warp sync proof
+		let mut data = self.0.write();
+		data.warp_sync_target = Some(*hash);
+		Some(make_warp_sync_request::<B::Hash>(data.authority_set.set_id()))
+	}
 }
 
 /// Try to import new block.
@@ -357,6 +378,53 @@ fn do_import_finality_proof<B, C, Block: BlockT<Hash=H256>, J>(
 	Ok((finalized_block_hash, finalized_block_number))
 }
 
+/// Try to import warp sync proof.
+fn do_import_warp_sync_proof<B, Block: BlockT<Hash=H256>, J>(
+	backend: &B,
+	data: &mut LightImportData<Block>,
+	warp_sync_proof: Vec<u8>,
+) -> Result<(Block::Hash, NumberFor<Block>), ConsensusError>
+	where
+		B: RemoteBackend<Block, Blake2Hasher>,
+		J: WarpSyncJustification<Block::Header>,
+{
+	let warp_sync_effects = crate::warp_sync::check_warp_sync_proof::<_, J>(
+		data.authority_set.set_id(),
+		data.authority_set.authorities(),
+		warp_sync_proof,
+	).map_err(|e| ConsensusError::ClientImport(e.to_string()))?;
+
+	let hash = warp_sync_effects.header.hash();
+	let number = *warp_sync_effects.header.number();
+	trace!(
+		target: "finality",
+		"Warp sync proof is valid. Importing block {} as finalized.",
+		hash,
+	);
+
+	// consensus changes pending before the warp are obsolete, and the new
+	// authority set is stored along with the header
+	let authority_set = LightAuthoritySet {
+		set_id: warp_sync_effects.new_set_id,
+		authorities: warp_sync_effects.new_authorities,
+	};
+	let consensus_changes = ConsensusChanges::<Block::Hash, NumberFor<Block>>::empty();
+	let aux_ops = vec![
+		(LIGHT_AUTHORITY_SET_KEY.to_vec(), Some(authority_set.encode())),
+		(LIGHT_CONSENSUS_CHANGES_KEY.to_vec(), Some(consensus_changes.encode())),
+	];
+	backend.import_warp_header(warp_sync_effects.header, aux_ops).map_err(|e| {
+		warn!(target: "finality", "Error importing warp sync block {:?}: {:?}", (hash, number), e);
+		ConsensusError::ClientImport(e.to_string())
+	})?;
+
+	data.last_finalized = hash;
+	data.authority_set = authority_set;
+	data.consensus_changes = consensus_changes;
+
+	Ok((hash, number))
+}
+
 /// Try to import justification.
 fn do_import_justification<B, C, Block: BlockT<Hash=H256>, J>(
 	client: C,
@@ -515,6 +583,7 @@ fn load_aux_import_data<B, Block: BlockT<Hash=H256>, PRA>(
 		last_finalized,
 		authority_set,
 		consensus_changes,
+		warp_sync_target: None,
 	})
 }
 
@@ -599,7 +668,7 @@ pub mod tests {
 	impl<B, E, Block: BlockT<Hash=H256>, RA> FinalityProofImport<Block>
 		for NoJustificationsImport<B, E, Block, RA> where
 			NumberFor<Block>: grandpa::BlockNumberOps,
-			B: Backend<Block, Blake2Hasher> + 'static,
+			B: RemoteBackend<Block, Blake2Hasher> + 'static,
 			E: CallExecutor<Block, Blake2Hasher> + 'static + Clone + Send + Sync,
 			DigestFor<Block>: Encode,
 			RA: Send + Sync,
@@ -647,6 +716,7 @@ pub mod tests {
 			last_finalized: Default::default(),
 			authority_set: LightAuthoritySet::genesis(vec![(AuthorityId::from_slice(&[1; 32]), 1)]),
 			consensus_changes: ConsensusChanges::empty(),
+			warp_sync_target: None,
 		};
 		let block = BlockImportParams {
 			origin: BlockOrigin::Own,
@@ -766,4 +836,39 @@ pub mod tests {
 		assert_eq!(data.authority_set.authorities(), vec![(AuthorityId::from_slice(&[42; 32]), 2)]);
 		assert_eq!(data.consensus_changes.pending_changes(), &[(42, Default::default())]);
 	}
+
+	#[test]
+	fn warp_sync_proof_import_finalizes_latest_proven_block() {
+		use crate::warp_sync::{prove_warp_sync, tests::{self as warp_sync, TestJustification as WarpJustification}};
+
+		let (client, backend) = test_client::new_light();
+		let headers = warp_sync::test_headers();
+		let blockchain = warp_sync::test_blockchain(&headers);
+		let proof = prove_warp_sync(&blockchain, &warp_sync::test_set_changes(), 0).unwrap().unwrap();
+		let mut data = LightImportData {
+			last_finalized: client.info().chain.finalized_hash,
+			authority_set: LightAuthoritySet::genesis(warp_sync::authorities(0)),
+			consensus_changes: ConsensusChanges::empty(),
+			warp_sync_target: None,
+		};
+
+		// the proof can't be verified with the authority set of another set id
+		data.authority_set.update(1, warp_sync::authorities(1));
+		do_import_warp_sync_proof::<_, Block, WarpJustification>(&*backend, &mut data, proof.clone()).unwrap_err();
+		assert_eq!(client.info().chain.finalized_number, 0);
+
+		// the latest enacting block is imported as finalized
+		data.authority_set.update(0, warp_sync::authorities(0));
+		let finalized = do_import_warp_sync_proof::<_, Block, WarpJustification>(&*backend, &mut data, proof).unwrap();
+		assert_eq!(finalized, (headers[6].hash(), 6));
+		assert_eq!(client.info().chain.finalized_hash, headers[6].hash());
+		assert_eq!(client.info().chain.best_hash, headers[6].hash());
+		assert_eq!(data.last_finalized, headers[6].hash());
+
+		// and the new authority set is persisted
+		assert_eq!(data.authority_set.set_id(), 2);
+		assert_eq!(data.authority_set.authorities(), warp_sync::authorities(2));
+		let stored: LightAuthoritySet = load_decode(&*backend, LIGHT_AUTHORITY_SET_KEY).unwrap().unwrap();
+		assert_eq!(stored.set_id(), 2);
+	}
 }
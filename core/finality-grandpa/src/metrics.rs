@@ -0,0 +1,31 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Prometheus metrics of the GRANDPA voter.
+
+use substrate_prometheus::{IntGauge, create_int_gauge, lazy_static};
+
+lazy_static! {
+	/// Number of the round the voter is currently in.
+	pub static ref ROUND: IntGauge = create_int_gauge(
+		"substrate_finality_grandpa_round", "Highest GRANDPA round started by the voter",
+	);
+
+	/// Id of the authority set the voter is currently voting in.
+	pub static ref SET_ID: IntGauge = create_int_gauge(
+		"substrate_finality_grandpa_set_id", "Id of the GRANDPA authority set of the voter",
+	);
+}
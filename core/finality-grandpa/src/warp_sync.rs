@@ -0,0 +1,454 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! GRANDPA warp sync proof generation and check.
+//!
+//! A light client that has just joined the network only knows the genesis authority set.
+//! Instead of downloading every header, it may request a warp sync proof from a full node.
+//! For every standard authority set change since the set known to the caller, the proof
+//! contains:
+//! 1) the headers sub-chain [S; F], where block S signals the change and block F enacts it;
+//! 2) the justification for the block F, signed by the authority set that is being replaced.
+//!
+//! The new authorities are read from the `ScheduledChange` digest of the header S, so checking
+//! the proof requires neither the state nor any other header. The fragments are verified
+//! in-order, set by set, and the caller ends up with the latest block F that has been proven
+//! final, along with the authority set that is active after it.
+//!
+//! Forced changes are not enacted on finality and can't be proven this way: the proof stops
+//! at the last standard change preceding a forced change.
+
+use log::trace;
+
+use client::{
+	blockchain::Backend as BlockchainBackend,
+	error::{Error as ClientError, Result as ClientResult},
+};
+use codec::{Encode, Decode};
+use grandpa::BlockNumberOps;
+use sr_primitives::generic::{BlockId, OpaqueDigestItemId};
+use sr_primitives::traits::{NumberFor, Block as BlockT, Header as HeaderT, Zero};
+use primitives::H256;
+use substrate_telemetry::{telemetry, CONSENSUS_INFO};
+use fg_primitives::{AuthorityId, ConsensusLog, ScheduledChange, GRANDPA_ENGINE_ID};
+
+use crate::aux_schema::AuthoritySetChanges;
+use crate::finality_proof::ProvableJustification;
+use crate::justification::GrandpaJustification;
+
+/// Maximum number of fragments that we want to return in a single warp sync proof.
+const MAX_FRAGMENTS_IN_PROOF: usize = 128;
+
+/// Justification that is able to prove the finality of a warp sync proof fragment.
+pub(crate) trait WarpSyncJustification<Header: HeaderT>: ProvableJustification<Header> {
+	/// The hash and the number of the block finalized by this justification.
+	fn target_block(&self) -> (Header::Hash, Header::Number);
+}
+
+impl<Block: BlockT<Hash=H256>> WarpSyncJustification<Block::Header> for GrandpaJustification<Block>
+	where
+		NumberFor<Block>: BlockNumberOps,
+{
+	fn target_block(&self) -> (Block::Hash, NumberFor<Block>) {
+		(self.commit.target_hash, self.commit.target_number)
+	}
+}
+
+/// The effects of a warp sync proof.
+#[derive(Debug, PartialEq)]
+pub(crate) struct WarpSyncEffects<Header: HeaderT> {
+	/// The header of the latest block that has been proven final.
+	pub header: Header,
+	/// Id of the authority set that is active after this block.
+	pub new_set_id: u64,
+	/// Authority set that is active after this block.
+	pub new_authorities: Vec<(AuthorityId, u64)>,
+}
+
+/// Single fragment of the warp sync proof, proving one authority set change.
+#[derive(Debug, PartialEq, Encode, Decode)]
+struct WarpSyncFragment<Header: HeaderT> {
+	/// The headers sub-chain [S; F], where S signals the change and F enacts it. Ordered.
+	pub headers: Vec<Header>,
+	/// Justification of the block F, signed by the authority set that is being replaced.
+	pub justification: Vec<u8>,
+}
+
+/// Warp sync proof is the ordered set of fragments, one for each consecutive
+/// authority set change.
+type WarpSyncProof<Header> = Vec<WarpSyncFragment<Header>>;
+
+/// Prepare a warp sync proof for the authority set changes that happened after the
+/// `authorities_set_id` set became active.
+///
+/// Returns None if there are no authority set changes unknown to the caller.
+pub(crate) fn prove_warp_sync<Block: BlockT, B: BlockchainBackend<Block>>(
+	blockchain: &B,
+	set_changes: &AuthoritySetChanges<NumberFor<Block>>,
+	authorities_set_id: u64,
+) -> ClientResult<Option<Vec<u8>>> {
+	let mut expected_set_id = authorities_set_id;
+	let mut warp_sync_proof = Vec::new();
+	for (set_id, enacting_number) in set_changes.iter().skip_while(|(set_id, _)| *set_id < authorities_set_id) {
+		// the set ids are consecutive unless there was a forced change that we can't prove
+		if *set_id != expected_set_id {
+			trace!(
+				target: "finality",
+				"Authority set #{} has been replaced by a forced change. Stopping warp sync proof.",
+				expected_set_id,
+			);
+
+			break;
+		}
+
+		let enacting_id = BlockId::Number(*enacting_number);
+		let justification = match blockchain.justification(enacting_id)? {
+			Some(justification) => justification,
+			None => {
+				trace!(
+					target: "finality",
+					"Missing justification for block #{} enacting authority set #{}. Stopping warp sync proof.",
+					enacting_number,
+					set_id + 1,
+				);
+
+				break;
+			},
+		};
+
+		// walk back to the block that signalled the change
+		let mut headers = Vec::new();
+		let mut header = blockchain.expect_header(enacting_id)?;
+		loop {
+			let is_signal = find_scheduled_change(&header)
+				.map_or(false, |change| *header.number() + change.delay <= *enacting_number);
+			let parent_hash = *header.parent_hash();
+			let is_genesis = header.number().is_zero();
+			headers.push(header);
+
+			if is_signal {
+				break;
+			}
+
+			if is_genesis {
+				return Err(ClientError::Backend(
+					format!("Missing signal for the authority set change enacted at #{}", enacting_number),
+				));
+			}
+
+			header = blockchain.expect_header(BlockId::Hash(parent_hash))?;
+		}
+		headers.reverse();
+
+		warp_sync_proof.push(WarpSyncFragment {
+			headers,
+			justification,
+		});
+
+		expected_set_id += 1;
+		if warp_sync_proof.len() == MAX_FRAGMENTS_IN_PROOF {
+			break;
+		}
+	}
+
+	if warp_sync_proof.is_empty() {
+		trace!(
+			target: "finality",
+			"No authority set changes after set #{}. Returning empty warp sync proof.",
+			authorities_set_id,
+		);
+
+		Ok(None)
+	} else {
+		trace!(
+			target: "finality",
+			"Built warp sync proof of {} fragments starting from set #{}.",
+			warp_sync_proof.len(),
+			authorities_set_id,
+		);
+
+		Ok(Some(warp_sync_proof.encode()))
+	}
+}
+
+/// Check GRANDPA warp sync proof, starting from the given authority set.
+pub(crate) fn check_warp_sync_proof<Header: HeaderT, J>(
+	current_set_id: u64,
+	current_authorities: Vec<(AuthorityId, u64)>,
+	remote_proof: Vec<u8>,
+) -> ClientResult<WarpSyncEffects<Header>>
+	where
+		J: WarpSyncJustification<Header>,
+{
+	// decode warp sync proof
+	let proof = WarpSyncProof::<Header>::decode(&mut &remote_proof[..])
+		.map_err(|_| ClientError::BadJustification("failed to decode warp sync proof".into()))?;
+
+	let mut set_id = current_set_id;
+	let mut authorities = current_authorities;
+	let mut last_enacting_number = None;
+	let mut last_header = None;
+	for fragment in proof {
+		let signal_header = fragment.headers.first()
+			.ok_or_else(|| ClientError::BadJustification("warp sync proof fragment without headers".into()))?;
+		let enacting_header = fragment.headers.last()
+			.expect("headers are non-empty since first() returned Some; qed");
+
+		// the headers must form a chain
+		let is_chain = fragment.headers.windows(2)
+			.all(|pair| *pair[1].parent_hash() == pair[0].hash());
+		if !is_chain {
+			return Err(ClientError::BadJustification("warp sync proof headers do not form a chain".into()));
+		}
+
+		// the enacting block must be justified by the current authority set
+		let justification = J::decode_and_verify(&fragment.justification, set_id, &authorities)?;
+		if justification.target_block() != (enacting_header.hash(), *enacting_header.number()) {
+			return Err(ClientError::BadJustification("warp sync proof justifies an unexpected block".into()));
+		}
+
+		// the change must have been signalled after the previous one was enacted
+		if last_enacting_number.map_or(false, |number| *signal_header.number() <= number) {
+			return Err(ClientError::BadJustification("warp sync proof signal precedes the previous change".into()));
+		}
+
+		// and it must be enacted by the justified block
+		let change = find_scheduled_change(signal_header)
+			.ok_or_else(|| ClientError::BadJustification("warp sync proof fragment without signal".into()))?;
+		if *signal_header.number() + change.delay > *enacting_header.number() {
+			return Err(ClientError::BadJustification("warp sync proof change is not enacted".into()));
+		}
+
+		set_id += 1;
+		authorities = change.next_authorities;
+		last_enacting_number = Some(*enacting_header.number());
+		last_header = fragment.headers.into_iter().last();
+	}
+
+	let header = last_header
+		.ok_or_else(|| ClientError::BadJustification("empty warp sync proof".into()))?;
+
+	telemetry!(CONSENSUS_INFO; "afg.warp_sync_proof_ok";
+		"set_id" => ?set_id, "finalized_header_hash" => ?header.hash());
+
+	Ok(WarpSyncEffects {
+		header,
+		new_set_id: set_id,
+		new_authorities: authorities,
+	})
+}
+
+/// Extract the standard authority set change signalled by the given header, if any.
+fn find_scheduled_change<Header: HeaderT>(header: &Header) -> Option<ScheduledChange<Header::Number>> {
+	let id = OpaqueDigestItemId::Consensus(&GRANDPA_ENGINE_ID);
+	header.digest().convert_first(|log| log.try_to::<ConsensusLog<Header::Number>>(id)
+		.and_then(ConsensusLog::try_into_change))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use test_client::runtime::{Block, Header, H256};
+	use test_client::client::backend::NewBlockState;
+	use test_client::client::in_mem::Blockchain as InMemoryBlockchain;
+	use sr_primitives::generic::{Digest, DigestItem};
+	use super::*;
+	use primitives::crypto::Public;
+
+	/// Justification that is valid for a single set id.
+	#[derive(Debug, PartialEq, Encode, Decode)]
+	pub struct TestJustification(pub u64, pub (H256, u64));
+
+	impl ProvableJustification<Header> for TestJustification {
+		fn verify(&self, set_id: u64, _authorities: &[(AuthorityId, u64)]) -> ClientResult<()> {
+			if self.0 == set_id {
+				Ok(())
+			} else {
+				Err(ClientError::BadJustification("test".into()))
+			}
+		}
+	}
+
+	impl WarpSyncJustification<Header> for TestJustification {
+		fn target_block(&self) -> (H256, u64) {
+			self.1
+		}
+	}
+
+	pub fn authorities(seed: u8) -> Vec<(AuthorityId, u64)> {
+		vec![(AuthorityId::from_slice(&[seed; 32]), 1)]
+	}
+
+	pub fn signal(next_authorities: Vec<(AuthorityId, u64)>, delay: u64) -> DigestItem<H256> {
+		let log = ConsensusLog::ScheduledChange(ScheduledChange { next_authorities, delay });
+		DigestItem::Consensus(GRANDPA_ENGINE_ID, log.encode())
+	}
+
+	/// Build a chain of `count` headers after genesis, with the given digests.
+	pub fn headers(count: u64, mut logs: impl FnMut(u64) -> Vec<DigestItem<H256>>) -> Vec<Header> {
+		let mut headers: Vec<Header> = Vec::new();
+		for number in 0..=count {
+			let parent_hash = headers.last().map(|header| header.hash()).unwrap_or_default();
+			let digest = Digest { logs: logs(number) };
+			headers.push(Header::new(number, Default::default(), Default::default(), parent_hash, digest));
+		}
+		headers
+	}
+
+	/// Chain where #2 signals a change to set #1 enacted at #3 and #6 signals
+	/// a change to set #2 enacted at #6.
+	pub fn test_headers() -> Vec<Header> {
+		headers(8, |number| match number {
+			2 => vec![signal(authorities(1), 1)],
+			6 => vec![signal(authorities(2), 0)],
+			_ => Vec::new(),
+		})
+	}
+
+	pub fn test_blockchain(headers: &[Header]) -> InMemoryBlockchain<Block> {
+		let blockchain = InMemoryBlockchain::<Block>::new();
+		for header in headers {
+			let justification = match *header.number() {
+				3 => Some(TestJustification(0, (header.hash(), 3)).encode()),
+				6 => Some(TestJustification(1, (header.hash(), 6)).encode()),
+				_ => None,
+			};
+			blockchain.insert(header.hash(), header.clone(), justification, None, NewBlockState::Final).unwrap();
+		}
+		blockchain
+	}
+
+	pub fn test_set_changes() -> AuthoritySetChanges<u64> {
+		vec![(0, 3), (1, 6)]
+	}
+
+	#[test]
+	fn warp_sync_proof_is_none_if_no_set_changes_are_unknown() {
+		let headers = test_headers();
+		let blockchain = test_blockchain(&headers);
+
+		assert_eq!(prove_warp_sync(&blockchain, &test_set_changes(), 2).unwrap(), None);
+		assert_eq!(prove_warp_sync(&blockchain, &Vec::new(), 0).unwrap(), None);
+	}
+
+	#[test]
+	fn warp_sync_proof_contains_all_set_changes() {
+		let headers = test_headers();
+		let blockchain = test_blockchain(&headers);
+
+		let proof: WarpSyncProof<Header> = Decode::decode(
+			&mut &prove_warp_sync(&blockchain, &test_set_changes(), 0).unwrap().unwrap()[..],
+		).unwrap();
+		assert_eq!(proof, vec![
+			WarpSyncFragment {
+				headers: headers[2..=3].to_vec(),
+				justification: TestJustification(0, (headers[3].hash(), 3)).encode(),
+			},
+			WarpSyncFragment {
+				headers: headers[6..=6].to_vec(),
+				justification: TestJustification(1, (headers[6].hash(), 6)).encode(),
+			},
+		]);
+
+		// the set changes that are known to the caller are skipped
+		let proof: WarpSyncProof<Header> = Decode::decode(
+			&mut &prove_warp_sync(&blockchain, &test_set_changes(), 1).unwrap().unwrap()[..],
+		).unwrap();
+		assert_eq!(proof.len(), 1);
+	}
+
+	#[test]
+	fn warp_sync_proof_stops_at_forced_change() {
+		let headers = test_headers();
+		let blockchain = test_blockchain(&headers);
+
+		// set #1 was replaced by a forced change, so set #2 enacted nothing
+		let set_changes = vec![(0, 3), (2, 6)];
+		let proof: WarpSyncProof<Header> = Decode::decode(
+			&mut &prove_warp_sync(&blockchain, &set_changes, 0).unwrap().unwrap()[..],
+		).unwrap();
+		assert_eq!(proof.len(), 1);
+	}
+
+	#[test]
+	fn warp_sync_proof_check_works() {
+		let headers = test_headers();
+		let blockchain = test_blockchain(&headers);
+		let proof = prove_warp_sync(&blockchain, &test_set_changes(), 0).unwrap().unwrap();
+
+		let effects = check_warp_sync_proof::<Header, TestJustification>(0, authorities(0), proof).unwrap();
+		assert_eq!(effects, WarpSyncEffects {
+			header: headers[6].clone(),
+			new_set_id: 2,
+			new_authorities: authorities(2),
+		});
+	}
+
+	#[test]
+	fn warp_sync_proof_check_fails_with_wrong_authority_set() {
+		let headers = test_headers();
+		let blockchain = test_blockchain(&headers);
+		let proof = prove_warp_sync(&blockchain, &test_set_changes(), 0).unwrap().unwrap();
+
+		check_warp_sync_proof::<Header, TestJustification>(1, authorities(1), proof).unwrap_err();
+	}
+
+	#[test]
+	fn warp_sync_proof_check_fails_if_headers_are_not_a_chain() {
+		let headers = test_headers();
+		let proof = vec![WarpSyncFragment {
+			headers: vec![headers[2].clone(), headers[4].clone()],
+			justification: TestJustification(0, (headers[4].hash(), 4)).encode(),
+		}];
+
+		check_warp_sync_proof::<Header, TestJustification>(0, authorities(0), proof.encode()).unwrap_err();
+	}
+
+	#[test]
+	fn warp_sync_proof_check_fails_if_justification_targets_other_block() {
+		let headers = test_headers();
+		let proof = vec![WarpSyncFragment {
+			headers: headers[2..=3].to_vec(),
+			justification: TestJustification(0, (headers[4].hash(), 4)).encode(),
+		}];
+
+		check_warp_sync_proof::<Header, TestJustification>(0, authorities(0), proof.encode()).unwrap_err();
+	}
+
+	#[test]
+	fn warp_sync_proof_check_fails_if_change_is_not_enacted() {
+		let headers = test_headers();
+
+		// the change signalled at #2 is only enacted at #3
+		let proof = vec![WarpSyncFragment {
+			headers: headers[2..=2].to_vec(),
+			justification: TestJustification(0, (headers[2].hash(), 2)).encode(),
+		}];
+		check_warp_sync_proof::<Header, TestJustification>(0, authorities(0), proof.encode()).unwrap_err();
+
+		// #1 doesn't signal any change
+		let proof = vec![WarpSyncFragment {
+			headers: headers[1..=3].to_vec(),
+			justification: TestJustification(0, (headers[3].hash(), 3)).encode(),
+		}];
+		check_warp_sync_proof::<Header, TestJustification>(0, authorities(0), proof.encode()).unwrap_err();
+	}
+
+	#[test]
+	fn warp_sync_proof_check_fails_if_empty() {
+		let proof: WarpSyncProof<Header> = Vec::new();
+		check_warp_sync_proof::<Header, TestJustification>(0, authorities(0), proof.encode()).unwrap_err();
+	}
+}
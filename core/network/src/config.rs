@@ -131,6 +131,15 @@ impl codec::Decode for Roles {
 pub trait FinalityProofRequestBuilder<B: BlockT>: Send {
 	/// Build data blob, associated with the request.
 	fn build_request_data(&mut self, hash: &B::Hash) -> Vec<u8>;
+
+	/// Build data blob of the warp sync request, associated with the given block.
+	///
+	/// The warp sync request asks the remote to prove all authority set changes that
+	/// happened since the authority set known to us. Returns `None` if warp sync isn't
+	/// supported by the finality engine.
+	fn build_warp_sync_request_data(&mut self, _hash: &B::Hash) -> Option<Vec<u8>> {
+		None
+	}
 }
 
 /// Implementation of `FinalityProofRequestBuilder` that builds a dummy empty request.
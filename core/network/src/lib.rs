@@ -175,6 +175,7 @@ mod chain;
 mod legacy_proto;
 mod debug_info;
 mod discovery;
+mod metrics;
 mod on_demand_layer;
 mod protocol;
 mod service;
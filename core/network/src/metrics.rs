@@ -0,0 +1,41 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Prometheus metrics of the network.
+
+use substrate_prometheus::{IntGauge, create_int_gauge, lazy_static};
+
+lazy_static! {
+	/// Number of peers we're connected to.
+	pub static ref CONNECTED_PEERS: IntGauge = create_int_gauge(
+		"substrate_sub_libp2p_peers_count", "Number of peers we're connected to",
+	);
+
+	/// Number of peers participating in syncing.
+	pub static ref SYNC_PEERS: IntGauge = create_int_gauge(
+		"substrate_sync_peers_count", "Number of peers participating in syncing",
+	);
+
+	/// Downloaded bytes per second, averaged over the past few seconds.
+	pub static ref BANDWIDTH_DOWNLOAD: IntGauge = create_int_gauge(
+		"substrate_network_download_bytes_per_second", "Downloaded bytes per second",
+	);
+
+	/// Uploaded bytes per second, averaged over the past few seconds.
+	pub static ref BANDWIDTH_UPLOAD: IntGauge = create_int_gauge(
+		"substrate_network_upload_bytes_per_second", "Uploaded bytes per second",
+	);
+}
@@ -826,6 +826,9 @@ impl<B: BlockT, S: NetworkSpecialization<B>, H: ExHashT> Protocol<B, S, H> {
 				trace!(target: "sync", "Handshake timeout {}", who);
 				aborting.push(who.clone());
 			}
+			if let Some(who) = self.sync.maintain_warp_sync() {
+				aborting.push(who);
+			}
 		}
 
 		self.specialization.maintain_peers(
@@ -1252,7 +1255,10 @@ impl<B: BlockT, S: NetworkSpecialization<B>, H: ExHashT> Protocol<B, S, H> {
 		request_block: (B::Hash, NumberFor<B>),
 		finalization_result: Result<(B::Hash, NumberFor<B>), ()>,
 	) {
-		self.sync.on_finality_proof_import(request_block, finalization_result)
+		if let Err(sync::BadPeer(id, repu)) = self.sync.on_finality_proof_import(request_block, finalization_result) {
+			self.behaviour.disconnect_peer(&id);
+			self.peerset_handle.report_peer(id, repu);
+		}
 	}
 
 	fn on_remote_call_response(
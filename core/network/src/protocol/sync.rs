@@ -44,7 +44,7 @@ use sr_primitives::{
 	generic::BlockId,
 	traits::{Block as BlockT, Header, NumberFor, Zero, One, CheckedSub, SaturatedConversion}
 };
-use std::{fmt, ops::Range, collections::{HashMap, HashSet, VecDeque}, sync::Arc};
+use std::{fmt, ops::Range, collections::{HashMap, HashSet, VecDeque}, sync::Arc, time::{Duration, Instant}};
 
 mod blocks;
 mod extra_requests;
@@ -92,6 +92,16 @@ const BAD_BLOCK_REPUTATION_CHANGE: i32 = -(1 << 29);
 /// Reputation change for peers which send us a block with bad justifications.
 const BAD_JUSTIFICATION_REPUTATION_CHANGE: i32 = -(1 << 16);
 
+/// Minimal number of blocks that a light client should be behind the peer before it
+/// tries to warp sync instead of downloading all headers.
+const WARP_SYNC_THRESHOLD: u32 = 1024;
+
+/// Time after which a warp sync proof request is given up and another peer may be asked.
+const WARP_SYNC_REQUEST_TIMEOUT: Duration = Duration::from_secs(40);
+
+/// Reputation change for peers which send us a warp sync proof that we fail to import.
+const BAD_WARP_SYNC_PROOF_REPUTATION_CHANGE: i32 = -(1 << 20);
+
 /// The main data structure which contains all the state for a chains
 /// active syncing strategy.
 pub struct ChainSync<B: BlockT> {
@@ -120,10 +130,41 @@ pub struct ChainSync<B: BlockT> {
 	/// The best block number that we are currently importing.
 	best_importing_number: NumberFor<B>,
 	request_builder: Option<BoxFinalityProofRequestBuilder<B>>,
+	/// The state of the warp sync.
+	warp_sync: WarpSyncState<B>,
+	/// Peers that didn't answer a warp sync request in time or sent us a bad proof,
+	/// which aren't asked again.
+	warp_sync_failed_peers: HashSet<PeerId>,
 	/// A flag that caches idle state with no pending requests.
 	is_idle: bool,
 }
 
+/// The state of the light client warp sync.
+///
+/// While the warp sync is in progress, no block requests are issued.
+enum WarpSyncState<B: BlockT> {
+	/// Warp sync isn't supported or is already completed.
+	Disabled,
+	/// Waiting for the peer that is far enough ahead of us.
+	Pending,
+	/// Warp sync request to the given peer is scheduled.
+	Scheduled(PeerId, B::Hash, NumberFor<B>),
+	/// Warp sync proof has been requested from the given peer at the given time.
+	Requested(PeerId, B::Hash, NumberFor<B>, Instant),
+	/// Warp sync proof of the given peer is being imported.
+	Importing(PeerId, B::Hash, NumberFor<B>),
+}
+
+impl<B: BlockT> WarpSyncState<B> {
+	/// Returns true if block requests must be paused.
+	fn is_active(&self) -> bool {
+		match *self {
+			WarpSyncState::Scheduled(..) | WarpSyncState::Requested(..) | WarpSyncState::Importing(..) => true,
+			WarpSyncState::Disabled | WarpSyncState::Pending => false,
+		}
+	}
+}
+
 /// All the data we have about a Peer that we are trying to sync with
 #[derive(Debug, Clone)]
 pub struct PeerSync<B: BlockT> {
@@ -279,6 +320,13 @@ impl<B: BlockT> ChainSync<B> {
 			required_block_attributes |= BlockAttributes::BODY
 		}
 
+		// only light clients that know nothing but genesis are warp syncing
+		let warp_sync = if role.is_light() && info.chain.finalized_number.is_zero() && request_builder.is_some() {
+			WarpSyncState::Pending
+		} else {
+			WarpSyncState::Disabled
+		};
+
 		ChainSync {
 			client,
 			peers: HashMap::new(),
@@ -292,6 +340,8 @@ impl<B: BlockT> ChainSync<B> {
 			queue_blocks: Default::default(),
 			best_importing_number: Zero::zero(),
 			request_builder,
+			warp_sync,
+			warp_sync_failed_peers: HashSet::new(),
 			is_idle: false,
 		}
 	}
@@ -369,6 +419,24 @@ impl<B: BlockT> ChainSync<B> {
 					return Ok(None)
 				}
 
+				// If the peer is far ahead of us, try to warp sync to its best block.
+				if self.try_schedule_warp_sync(&who, info.best_hash, info.best_number) {
+					debug!(
+						target:"sync",
+						"New peer with best hash {} ({}), scheduling warp sync.",
+						info.best_hash,
+						info.best_number,
+					);
+					self.peers.insert(who, PeerSync {
+						common_number: Zero::zero(),
+						best_hash: info.best_hash,
+						best_number: info.best_number,
+						state: PeerSyncState::Available,
+						recently_announced: Default::default(),
+					});
+					return Ok(None)
+				}
+
 				// If we are at genesis, just start downloading.
 				if self.best_queued_number.is_zero() {
 					debug!(target:"sync", "New peer with best hash {} ({}).", info.best_hash, info.best_number);
@@ -467,10 +535,15 @@ impl<B: BlockT> ChainSync<B> {
 
 	/// Get an iterator over all scheduled finality proof requests.
 	pub fn finality_proof_requests(&mut self) -> impl Iterator<Item = (PeerId, FinalityProofRequest<B::Hash>)> + '_ {
+		let mut warp_sync_request = self.warp_sync_request();
 		let peers = &mut self.peers;
 		let request_builder = &mut self.request_builder;
 		let mut matcher = self.extra_finality_proofs.matcher();
 		std::iter::from_fn(move || {
+			if let Some(request) = warp_sync_request.take() {
+				return Some(request)
+			}
+
 			if let Some((peer, request)) = matcher.next(&peers) {
 				peers.get_mut(&peer)
 					.expect("`Matcher::next` guarantees the `PeerId` comes from the given peers; qed")
@@ -494,6 +567,10 @@ impl<B: BlockT> ChainSync<B> {
 		if self.is_idle {
 			return Either::Left(std::iter::empty())
 		}
+		if self.warp_sync.is_active() {
+			trace!(target: "sync", "Warp sync is in progress.");
+			return Either::Left(std::iter::empty())
+		}
 		if self.queue_blocks.len() > MAX_IMPORTING_BLOCKS {
 			trace!(target: "sync", "Too many blocks in the queue.");
 			return Either::Left(std::iter::empty())
@@ -703,6 +780,22 @@ impl<B: BlockT> ChainSync<B> {
 				return Err(BadPeer(who, i32::min_value()))
 			}
 
+			if let WarpSyncState::Requested(ref peer, hash, number, _) = self.warp_sync {
+				if *peer == who && hash == resp.block {
+					return match resp.proof {
+						Some(proof) => {
+							self.warp_sync = WarpSyncState::Importing(who.clone(), hash, number);
+							Ok(OnBlockFinalityProof::Import { peer: who, hash, number, proof })
+						},
+						None => {
+							debug!(target: "sync", "Peer {} has no warp sync proof, falling back to full sync", who);
+							self.warp_sync = WarpSyncState::Disabled;
+							Ok(OnBlockFinalityProof::Nothing)
+						},
+					}
+				}
+			}
+
 			if let Some((peer, hash, number, p)) = self.extra_finality_proofs.on_response(who, resp.proof) {
 				return Ok(OnBlockFinalityProof::Import { peer, hash, number, proof: p })
 			}
@@ -824,9 +917,49 @@ impl<B: BlockT> ChainSync<B> {
 		self.is_idle = false;
 	}
 
-	pub fn on_finality_proof_import(&mut self, req: (B::Hash, NumberFor<B>), res: Result<(B::Hash, NumberFor<B>), ()>) {
+	/// Call this when a finality proof has been processed by the import queue,
+	/// with or without errors.
+	///
+	/// Returns the peer to punish if it has sent us a warp sync proof that failed to import.
+	pub fn on_finality_proof_import(
+		&mut self,
+		req: (B::Hash, NumberFor<B>),
+		res: Result<(B::Hash, NumberFor<B>), ()>,
+	) -> Result<(), BadPeer> {
+		let warp_sync_peer = match self.warp_sync {
+			WarpSyncState::Importing(ref peer, hash, number) if (hash, number) == req => Some(peer.clone()),
+			_ => None,
+		};
+		if let Some(who) = warp_sync_peer {
+			self.warp_sync = WarpSyncState::Disabled;
+			self.is_idle = false;
+			match res {
+				Ok((hash, number)) => {
+					info!("Warp sync completed at block {} ({}).", hash, number);
+					if number > self.best_queued_number {
+						self.best_queued_hash = hash;
+						self.best_queued_number = number;
+					}
+					// the warped block is final, so every peer that is ahead of it has it
+					for peer in self.peers.values_mut() {
+						if peer.best_number >= number && peer.common_number < number {
+							peer.common_number = number;
+						}
+					}
+				},
+				Err(()) => {
+					debug!(target: "sync", "Warp sync proof of {} failed to import, waiting for another peer", who);
+					self.warp_sync = WarpSyncState::Pending;
+					self.warp_sync_failed_peers.insert(who.clone());
+					return Err(BadPeer(who, BAD_WARP_SYNC_PROOF_REPUTATION_CHANGE))
+				},
+			}
+			return Ok(())
+		}
+
 		self.extra_finality_proofs.try_finalize_root(req, res, true);
 		self.is_idle = false;
+		Ok(())
 	}
 
 	/// Notify about finalization of the given block.
@@ -1007,7 +1140,109 @@ impl<B: BlockT> ChainSync<B> {
 		self.peers.remove(&who);
 		self.extra_justifications.peer_disconnected(&who);
 		self.extra_finality_proofs.peer_disconnected(&who);
+		match self.warp_sync {
+			WarpSyncState::Scheduled(ref peer, _, _) | WarpSyncState::Requested(ref peer, _, _, _) if *peer == who =>
+				self.warp_sync = WarpSyncState::Pending,
+			_ => (),
+		}
+		self.is_idle = false;
+	}
+
+	/// Give up the warp sync request if it hasn't been answered in time, and retry a pending
+	/// warp sync with the best of the connected peers.
+	///
+	/// Returns the peer that failed to answer the request in time.
+	pub fn maintain_warp_sync(&mut self) -> Option<PeerId> {
+		let timed_out = match self.warp_sync {
+			WarpSyncState::Requested(ref who, _, _, at) if at.elapsed() > WARP_SYNC_REQUEST_TIMEOUT =>
+				Some(who.clone()),
+			_ => None,
+		};
+
+		if let Some(ref who) = timed_out {
+			debug!(target: "sync", "Warp sync request to {} timed out", who);
+			if let Some(peer) = self.peers.get_mut(who) {
+				peer.state = PeerSyncState::Available;
+			}
+			self.warp_sync = WarpSyncState::Pending;
+			self.warp_sync_failed_peers.insert(who.clone());
+			self.is_idle = false;
+		}
+
+		if let WarpSyncState::Pending = self.warp_sync {
+			let failed_peers = &self.warp_sync_failed_peers;
+			let best_peer = self.peers.iter()
+				.filter(|(who, _)| !failed_peers.contains(*who))
+				.max_by_key(|(_, peer)| peer.best_number)
+				.map(|(who, peer)| (who.clone(), peer.best_hash, peer.best_number));
+			if let Some((who, hash, number)) = best_peer {
+				if self.try_schedule_warp_sync(&who, hash, number) {
+					debug!(target: "sync", "Retrying warp sync to block {} ({}) with {}", hash, number, who);
+				}
+			}
+		}
+
+		timed_out
+	}
+
+	/// Schedule warp sync to the best block of the given peer, if it is far enough ahead of us.
+	///
+	/// Returns true if the warp sync has been scheduled.
+	fn try_schedule_warp_sync(&mut self, who: &PeerId, best_hash: B::Hash, best_number: NumberFor<B>) -> bool {
+		match self.warp_sync {
+			WarpSyncState::Pending => (),
+			_ => return false,
+		}
+		if self.warp_sync_failed_peers.contains(who) {
+			return false
+		}
+		if best_number <= self.best_queued_number + WARP_SYNC_THRESHOLD.into() {
+			return false
+		}
+
+		self.warp_sync = WarpSyncState::Scheduled(who.clone(), best_hash, best_number);
 		self.is_idle = false;
+		true
+	}
+
+	/// Take the scheduled warp sync request, if any.
+	fn warp_sync_request(&mut self) -> Option<(PeerId, FinalityProofRequest<B::Hash>)> {
+		let (who, hash, number) = match self.warp_sync {
+			WarpSyncState::Scheduled(ref who, hash, number) => (who.clone(), hash, number),
+			_ => return None,
+		};
+
+		let peer = match self.peers.get_mut(&who) {
+			Some(peer) => peer,
+			None => {
+				self.warp_sync = WarpSyncState::Pending;
+				return None
+			},
+		};
+		if !peer.state.is_available() {
+			return None
+		}
+
+		let request = self.request_builder.as_mut()
+			.and_then(|builder| builder.build_warp_sync_request_data(&hash));
+		match request {
+			Some(request) => {
+				trace!(target: "sync", "Requesting warp sync proof for block {} ({}) from {}", hash, number, who);
+				peer.state = PeerSyncState::DownloadingFinalityProof(hash);
+				self.warp_sync = WarpSyncState::Requested(who.clone(), hash, number, Instant::now());
+				Some((who, message::generic::FinalityProofRequest {
+					id: 0,
+					block: hash,
+					request,
+				}))
+			},
+			None => {
+				debug!(target: "sync", "Warp sync isn't supported by the finality engine");
+				self.warp_sync = WarpSyncState::Disabled;
+				self.is_idle = false;
+				None
+			},
+		}
 	}
 
 	/// Restart the sync process.
@@ -1214,3 +1449,137 @@ fn peer_block_request<B: BlockT>(
 		None
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::config::FinalityProofRequestBuilder;
+	use test_client::runtime::{Block, Hash};
+
+	/// Builds warp sync requests, as the finality engines that support warp sync do.
+	struct WarpSyncRequestBuilder;
+
+	impl FinalityProofRequestBuilder<Block> for WarpSyncRequestBuilder {
+		fn build_request_data(&mut self, _: &Hash) -> Vec<u8> {
+			Vec::new()
+		}
+
+		fn build_warp_sync_request_data(&mut self, _: &Hash) -> Option<Vec<u8>> {
+			Some(vec![42])
+		}
+	}
+
+	fn light_sync() -> ChainSync<Block> {
+		let client = Arc::new(test_client::new());
+		let info = client.info();
+		ChainSync::new(Roles::LIGHT, client, &info, Some(Box::new(WarpSyncRequestBuilder)))
+	}
+
+	fn new_peer(sync: &mut ChainSync<Block>, best_number: u64) -> PeerId {
+		let who = PeerId::random();
+		let info = protocol::PeerInfo {
+			roles: Roles::FULL,
+			protocol_version: 0,
+			best_hash: Hash::random(),
+			best_number,
+		};
+		sync.new_peer(who.clone(), info).unwrap();
+		// answer any block request of the peer
+		sync.peers.get_mut(&who).unwrap().state = PeerSyncState::Available;
+		who
+	}
+
+	/// Takes the scheduled warp sync request and checks that it is sent to `who`.
+	fn assert_warp_sync_requested(sync: &mut ChainSync<Block>, who: &PeerId) -> (Hash, u64) {
+		let requests = sync.finality_proof_requests().collect::<Vec<_>>();
+		assert_eq!(requests.len(), 1);
+		assert_eq!(&requests[0].0, who);
+		assert_eq!(requests[0].1.request, vec![42]);
+		match sync.warp_sync {
+			WarpSyncState::Requested(ref peer, hash, number, _) if peer == who => (hash, number),
+			_ => panic!("warp sync proof should be requested from {}", who),
+		}
+	}
+
+	/// Checks that a warp sync proof is still being requested from `who`.
+	fn assert_warp_sync_requested_from(sync: &ChainSync<Block>, who: &PeerId) {
+		match sync.warp_sync {
+			WarpSyncState::Requested(ref peer, _, _, _) => assert_eq!(peer, who),
+			_ => panic!("warp sync proof should be requested from {}", who),
+		}
+	}
+
+	#[test]
+	fn warp_sync_is_scheduled_with_peers_far_ahead() {
+		let mut sync = light_sync();
+
+		let near = new_peer(&mut sync, u64::from(WARP_SYNC_THRESHOLD));
+		assert!(!sync.warp_sync.is_active());
+		let far = new_peer(&mut sync, u64::from(WARP_SYNC_THRESHOLD) + 1);
+		assert!(sync.warp_sync.is_active());
+
+		assert_warp_sync_requested(&mut sync, &far);
+		assert_eq!(sync.block_requests().count(), 0);
+		assert!(sync.peer_info(&near).is_some());
+	}
+
+	#[test]
+	fn warp_sync_is_not_scheduled_by_full_nodes() {
+		let client = Arc::new(test_client::new());
+		let info = client.info();
+		let mut sync = ChainSync::new(Roles::FULL, client, &info, Some(Box::new(WarpSyncRequestBuilder)));
+
+		new_peer(&mut sync, u64::from(WARP_SYNC_THRESHOLD) * 2);
+		assert!(!sync.warp_sync.is_active());
+		assert_eq!(sync.finality_proof_requests().count(), 0);
+	}
+
+	#[test]
+	fn warp_sync_request_times_out_and_is_retried_with_another_peer() {
+		let mut sync = light_sync();
+		let first = new_peer(&mut sync, u64::from(WARP_SYNC_THRESHOLD) * 2);
+		let second = new_peer(&mut sync, u64::from(WARP_SYNC_THRESHOLD) * 2);
+		assert_warp_sync_requested(&mut sync, &first);
+
+		// not timed out yet
+		assert_eq!(sync.maintain_warp_sync(), None);
+		assert_warp_sync_requested_from(&sync, &first);
+
+		if let WarpSyncState::Requested(_, _, _, ref mut at) = sync.warp_sync {
+			*at = Instant::now() - WARP_SYNC_REQUEST_TIMEOUT - Duration::from_secs(1);
+		}
+		assert_eq!(sync.maintain_warp_sync(), Some(first.clone()));
+		assert_warp_sync_requested(&mut sync, &second);
+
+		// the peer that timed out isn't asked again
+		sync.peer_disconnected(second.clone());
+		assert_eq!(sync.maintain_warp_sync(), None);
+		assert!(!sync.warp_sync.is_active());
+	}
+
+	#[test]
+	fn bad_warp_sync_proof_is_penalized_and_retried_with_another_peer() {
+		let mut sync = light_sync();
+		let first = new_peer(&mut sync, u64::from(WARP_SYNC_THRESHOLD) * 2);
+		let second = new_peer(&mut sync, u64::from(WARP_SYNC_THRESHOLD) * 2);
+		let (hash, number) = assert_warp_sync_requested(&mut sync, &first);
+
+		let response = message::generic::FinalityProofResponse { id: 0, block: hash, proof: Some(vec![1]) };
+		match sync.on_block_finality_proof(first.clone(), response) {
+			Ok(OnBlockFinalityProof::Import { ref peer, number: n, .. }) => {
+				assert_eq!(peer, &first);
+				assert_eq!(n, number);
+			},
+			_ => panic!("the warp sync proof should be imported"),
+		}
+
+		let BadPeer(who, reputation) = sync.on_finality_proof_import((hash, number), Err(())).unwrap_err();
+		assert_eq!(who, first);
+		assert_eq!(reputation, BAD_WARP_SYNC_PROOF_REPUTATION_CHANGE);
+		assert!(!sync.warp_sync.is_active());
+
+		// the retry happens on the next tick, with another peer
+		assert_eq!(sync.maintain_warp_sync(), None);
+		assert_warp_sync_requested(&mut sync, &second);
+	}
+}
@@ -45,6 +45,7 @@ use crate::{NetworkState, NetworkStateNotConnectedPeer, NetworkStatePeer};
 use crate::{transport, config::NodeKeyConfig, config::NonReservedPeerMode};
 use crate::config::{Params, TransportConfig};
 use crate::error::Error;
+use crate::metrics;
 use crate::protocol::{self, Protocol, Context, CustomMessageOutcome, PeerInfo};
 use crate::protocol::consensus_gossip::{ConsensusGossip, MessageRecipient as GossipMessageRecipient};
 use crate::protocol::{event::Event, light_dispatch::{AlwaysBadChecker, RequestData}};
@@ -699,7 +700,8 @@ impl<B: BlockT + 'static, S: NetworkSpecialization<B>, H: ExHashT> Future for Ne
 		}
 
 		// Update the variables shared with the `NetworkService`.
-		self.num_connected.store(self.network_service.user_protocol_mut().num_connected_peers(), Ordering::Relaxed);
+		let num_connected_peers = self.network_service.user_protocol_mut().num_connected_peers();
+		self.num_connected.store(num_connected_peers, Ordering::Relaxed);
 		{
 			let external_addresses = Swarm::<B, S, H>::external_addresses(&self.network_service).cloned().collect();
 			*self.external_addresses.lock() = external_addresses;
@@ -709,6 +711,11 @@ impl<B: BlockT + 'static, S: NetworkSpecialization<B>, H: ExHashT> Future for Ne
 			SyncState::Downloading => true,
 		}, Ordering::Relaxed);
 
+		metrics::CONNECTED_PEERS.set(num_connected_peers as i64);
+		metrics::SYNC_PEERS.set(self.network_service.user_protocol_mut().num_sync_peers() as i64);
+		metrics::BANDWIDTH_DOWNLOAD.set(self.service.bandwidth.average_download_per_sec() as i64);
+		metrics::BANDWIDTH_UPLOAD.set(self.service.bandwidth.average_upload_per_sec() as i64);
+
 		Ok(Async::NotReady)
 	}
 }
@@ -121,3 +121,21 @@ pub fn twox_256(data: &[u8]) -> [u8; 32] {
 	twox_256_into(data, &mut r);
 	r
 }
+
+/// Do a keccak 256-bit hash and return result.
+pub fn keccak_256(data: &[u8]) -> [u8; 32] {
+	tiny_keccak::keccak256(data)
+}
+
+/// Do a keccak 512-bit hash and return result.
+pub fn keccak_512(data: &[u8]) -> [u8; 64] {
+	tiny_keccak::keccak512(data)
+}
+
+/// Do a sha2 256-bit hash and return result.
+pub fn sha2_256(data: &[u8]) -> [u8; 32] {
+	use sha2::Digest;
+	let mut r = [0; 32];
+	r.copy_from_slice(sha2::Sha256::digest(data).as_slice());
+	r
+}
@@ -41,6 +41,9 @@ pub enum StorageKind {
 	LOCAL = 2,
 }
 
+/// Prefix under which the values of `StorageKind::PERSISTENT` are kept in the offchain database.
+pub const PERSISTENT_STORAGE_PREFIX: &[u8] = b"storage";
+
 impl TryFrom<u32> for StorageKind {
 	type Error = ();
 
@@ -60,7 +63,7 @@ impl From<StorageKind> for u32 {
 }
 
 /// Opaque type for offchain http requests.
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Encode, Decode)]
 #[cfg_attr(feature = "std", derive(Debug, Hash))]
 pub struct HttpRequestId(pub u16);
 
@@ -71,7 +74,7 @@ impl From<HttpRequestId> for u32 {
 }
 
 /// An error enum returned by some http methods.
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode)]
 #[cfg_attr(feature = "std", derive(Debug))]
 #[repr(C)]
 pub enum HttpError {
@@ -103,7 +106,7 @@ impl From<HttpError> for u32 {
 }
 
 /// Status of the HTTP request
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode)]
 #[cfg_attr(feature = "std", derive(Debug))]
 pub enum HttpRequestStatus {
 	/// Deadline was reached while we waited for this request to finish.
@@ -184,7 +187,7 @@ impl OpaqueMultiaddr {
 }
 
 /// Opaque timestamp type
-#[derive(Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Default)]
+#[derive(Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Default, Encode, Decode)]
 #[cfg_attr(feature = "std", derive(Debug))]
 pub struct Timestamp(u64);
 
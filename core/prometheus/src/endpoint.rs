@@ -0,0 +1,118 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! HTTP endpoint exporting the registered metrics.
+
+use futures::Future;
+use hyper::{
+	Body, Method, Request, Response, Server, StatusCode,
+	header::CONTENT_TYPE,
+	service::service_fn_ok,
+};
+use log::{info, warn};
+use prometheus::{Encoder, TextEncoder};
+use std::net::SocketAddr;
+
+/// Prometheus endpoint errors.
+#[derive(Debug, derive_more::Display, derive_more::From)]
+pub enum Error {
+	/// Failed to bind the HTTP server.
+	Hyper(hyper::Error),
+}
+
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Error::Hyper(ref err) => Some(err),
+		}
+	}
+}
+
+/// Encode all the registered metrics in the Prometheus text format.
+fn encode_metrics() -> Result<(Vec<u8>, String), prometheus::Error> {
+	let encoder = TextEncoder::new();
+	let mut buffer = Vec::new();
+	encoder.encode(&prometheus::gather(), &mut buffer)?;
+	Ok((buffer, encoder.format_type().to_owned()))
+}
+
+/// Answer a request made to the endpoint.
+fn handle_request(request: Request<Body>) -> Response<Body> {
+	let mut response = Response::builder();
+
+	if request.method() != Method::GET || request.uri().path() != "/metrics" {
+		return response.status(StatusCode::NOT_FOUND)
+			.body(Body::from("Not found."))
+			.expect("status and body are valid; qed");
+	}
+
+	match encode_metrics() {
+		Ok((buffer, format)) => response
+			.header(CONTENT_TYPE, format)
+			.body(Body::from(buffer))
+			.expect("format type is a valid header value; qed"),
+		Err(e) => {
+			warn!(target: "prometheus", "Failed to encode metrics: {:?}", e);
+			response.status(StatusCode::INTERNAL_SERVER_ERROR)
+				.body(Body::empty())
+				.expect("status and body are valid; qed")
+		},
+	}
+}
+
+/// Start an HTTP server exporting the registered metrics on `/metrics` at the given address.
+///
+/// The returned future must be polled by a tokio runtime for the server to answer requests.
+pub fn init_prometheus(addr: SocketAddr) -> Result<impl Future<Item = (), Error = ()>, Error> {
+	let server = Server::try_bind(&addr)?
+		.serve(|| service_fn_ok(handle_request))
+		.map_err(|e| warn!(target: "prometheus", "Prometheus endpoint failed: {:?}", e));
+
+	info!("Prometheus metrics served at http://{}/metrics", addr);
+	Ok(server)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::create_int_gauge;
+	use futures::Stream;
+
+	fn request(method: Method, path: &str) -> (StatusCode, String) {
+		let request = Request::builder().method(method).uri(path).body(Body::empty()).unwrap();
+		let response = handle_request(request);
+		let status = response.status();
+		let body = response.into_body().concat2().wait().unwrap();
+		(status, String::from_utf8(body.to_vec()).unwrap())
+	}
+
+	#[test]
+	fn metrics_are_exported_in_text_format() {
+		let gauge = create_int_gauge("test_gauge", "A test gauge");
+		gauge.set(42);
+
+		let (status, body) = request(Method::GET, "/metrics");
+		assert_eq!(status, StatusCode::OK);
+		assert!(body.contains("# HELP test_gauge A test gauge"));
+		assert!(body.contains("test_gauge 42"));
+	}
+
+	#[test]
+	fn other_paths_are_not_found() {
+		assert_eq!(request(Method::GET, "/").0, StatusCode::NOT_FOUND);
+		assert_eq!(request(Method::POST, "/metrics").0, StatusCode::NOT_FOUND);
+	}
+}
@@ -0,0 +1,83 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Prometheus metrics utilities.
+//!
+//! Subsystems declare their metrics as `lazy_static`s built with the `create_*` functions of this
+//! crate, which registers them in the global Prometheus registry. Calling `init_prometheus` then
+//! returns a future serving every registered metric in the Prometheus text format on the
+//! `/metrics` path of an HTTP server.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use substrate_prometheus::{lazy_static, create_int_gauge, IntGauge};
+//!
+//! lazy_static! {
+//! 	static ref BEST_BLOCK: IntGauge = create_int_gauge(
+//! 		"example_best_block", "Height of the best block",
+//! 	);
+//! }
+//!
+//! BEST_BLOCK.set(42);
+//!
+//! // The returned future must be spawned on a tokio runtime to answer the requests.
+//! let endpoint = substrate_prometheus::init_prometheus(([127, 0, 0, 1], 9615).into());
+//! ```
+
+#![warn(missing_docs)]
+
+use log::warn;
+use prometheus::core::Collector;
+
+pub use lazy_static::lazy_static;
+pub use prometheus::{Histogram, HistogramOpts, IntCounter, IntGauge};
+
+#[cfg(not(target_os = "unknown"))]
+mod endpoint;
+
+#[cfg(not(target_os = "unknown"))]
+pub use endpoint::{Error, init_prometheus};
+
+/// Register the given metric in the global registry and return it.
+///
+/// Failing to register a metric (e.g. because its name is already taken) only means it won't be
+/// exported, so the error is logged rather than returned.
+fn register<T: Collector + Clone + 'static>(metric: T) -> T {
+	if let Err(e) = prometheus::register(Box::new(metric.clone())) {
+		warn!(target: "prometheus", "Failed to register metric: {:?}", e);
+	}
+	metric
+}
+
+/// Create and register an integer gauge.
+pub fn create_int_gauge(name: &str, help: &str) -> IntGauge {
+	let gauge = IntGauge::new(name, help).expect("metric names and help are valid; qed");
+	register(gauge)
+}
+
+/// Create and register an integer counter.
+pub fn create_int_counter(name: &str, help: &str) -> IntCounter {
+	let counter = IntCounter::new(name, help).expect("metric names and help are valid; qed");
+	register(counter)
+}
+
+/// Create and register a histogram with the default buckets.
+pub fn create_histogram(name: &str, help: &str) -> Histogram {
+	let histogram = Histogram::with_opts(HistogramOpts::new(name, help))
+		.expect("metric names and help are valid; qed");
+	register(histogram)
+}
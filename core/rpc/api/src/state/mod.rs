@@ -25,10 +25,23 @@ use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId};
 use primitives::Bytes;
 use primitives::storage::{StorageKey, StorageData, StorageChangeSet};
 use runtime_version::RuntimeVersion;
+use serde::{Serialize, Deserialize};
 use self::error::FutureResult;
 
 pub use self::gen_client::Client as StateClient;
 
+/// Events deposited under a topic in a single block.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventTopicsMatch<Hash> {
+	/// Hash of the block that deposited the events.
+	pub block: Hash,
+	/// The matched topic.
+	pub topic: Hash,
+	/// Indices of the matching events in the block's `System::Events` list.
+	pub indices: Vec<u32>,
+}
+
 /// Substrate state API
 #[rpc]
 pub trait StateApi<Hash> {
@@ -91,6 +104,16 @@ pub trait StateApi<Hash> {
 		hash: Option<Hash>
 	) -> FutureResult<Option<u64>>;
 
+	/// Returns the proof of a child storage entry at a block's state, i.e. the trie nodes needed
+	/// to check the entry against the block's state root.
+	#[rpc(name = "state_getChildReadProof")]
+	fn child_read_proof(
+		&self,
+		child_storage_key: StorageKey,
+		key: StorageKey,
+		hash: Option<Hash>
+	) -> FutureResult<Vec<Bytes>>;
+
 	/// Returns the runtime metadata as an opaque blob.
 	#[rpc(name = "state_getMetadata")]
 	fn metadata(&self, hash: Option<Hash>) -> FutureResult<Bytes>;
@@ -111,6 +134,19 @@ pub trait StateApi<Hash> {
 		hash: Option<Hash>
 	) -> FutureResult<Vec<StorageChangeSet<Hash>>>;
 
+	/// Query the events deposited under any of the given topics in the blocks starting from
+	/// `from` up to `to` (or the best block if `None`).
+	///
+	/// Results are ordered by block and only include canonical blocks. The events themselves
+	/// can be read from the `System::Events` storage of the matched block.
+	#[rpc(name = "state_queryEventTopics")]
+	fn query_event_topics(
+		&self,
+		topics: Vec<Hash>,
+		from: Hash,
+		to: Option<Hash>
+	) -> FutureResult<Vec<EventTopicsMatch<Hash>>>;
+
 	/// New runtime version subscription
 	#[pubsub(
 		subscription = "state_runtimeVersion",
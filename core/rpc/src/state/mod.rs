@@ -139,6 +139,14 @@ pub trait StateBackend<B, E, Block: BlockT, RA>: Send + Sync + 'static
 			.map(|x| x.map(|x| x.0.len() as u64)))
 	}
 
+	/// Returns the proof of a child storage entry at a block's state.
+	fn child_read_proof(
+		&self,
+		block: Option<Block::Hash>,
+		child_storage_key: StorageKey,
+		key: StorageKey,
+	) -> FutureResult<Vec<Bytes>>;
+
 	/// Returns the runtime metadata as an opaque blob.
 	fn metadata(&self, block: Option<Block::Hash>) -> FutureResult<Bytes>;
 
@@ -156,6 +164,14 @@ pub trait StateBackend<B, E, Block: BlockT, RA>: Send + Sync + 'static
 		keys: Vec<StorageKey>,
 	) -> FutureResult<Vec<StorageChangeSet<Block::Hash>>>;
 
+	/// Query the events deposited under any of the given topics in a range of blocks.
+	fn query_event_topics(
+		&self,
+		from: Block::Hash,
+		to: Option<Block::Hash>,
+		topics: Vec<Block::Hash>,
+	) -> FutureResult<Vec<EventTopicsMatch<Block::Hash>>>;
+
 	/// New runtime version subscription
 	fn subscribe_runtime_version(
 		&self,
@@ -396,6 +412,15 @@ impl<B, E, Block, RA> StateApi<Block::Hash> for State<B, E, Block, RA>
 		self.backend.child_storage_size(block, child_storage_key, key)
 	}
 
+	fn child_read_proof(
+		&self,
+		child_storage_key: StorageKey,
+		key: StorageKey,
+		block: Option<Block::Hash>
+	) -> FutureResult<Vec<Bytes>> {
+		self.backend.child_read_proof(block, child_storage_key, key)
+	}
+
 	fn metadata(&self, block: Option<Block::Hash>) -> FutureResult<Bytes> {
 		self.backend.metadata(block)
 	}
@@ -409,6 +434,15 @@ impl<B, E, Block, RA> StateApi<Block::Hash> for State<B, E, Block, RA>
 		self.backend.query_storage(from, to, keys)
 	}
 
+	fn query_event_topics(
+		&self,
+		topics: Vec<Block::Hash>,
+		from: Block::Hash,
+		to: Option<Block::Hash>
+	) -> FutureResult<Vec<EventTopicsMatch<Block::Hash>>> {
+		self.backend.query_event_topics(from, to, topics)
+	}
+
 	fn subscribe_storage(
 		&self,
 		meta: Self::Metadata,
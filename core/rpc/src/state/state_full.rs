@@ -19,12 +19,13 @@
 use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use std::ops::Range;
+use codec::Encode;
 use rpc::futures::future::result;
 
 use api::Subscriptions;
 use client::{
 	Client, CallExecutor, runtime_api::Metadata,
-	backend::Backend, error::Result as ClientResult,
+	backend::Backend, error::{Error as ClientError, Result as ClientResult},
 };
 use primitives::{
 	H256, Blake2Hasher, Bytes,
@@ -37,7 +38,7 @@ use sr_primitives::{
 	traits::{Block as BlockT, Header, NumberFor, ProvideRuntimeApi, SaturatedConversion},
 };
 
-use super::{StateBackend, error::{FutureResult, Error, Result}, client_err};
+use super::{StateBackend, EventTopicsMatch, error::{FutureResult, Error, Result}, client_err};
 
 /// Ranges to query in state_queryStorage.
 struct QueryStorageRange<Block: BlockT> {
@@ -322,6 +323,19 @@ impl<B, E, Block, RA> StateBackend<B, E, Block, RA> for FullState<B, E, Block, R
 				.map_err(client_err)))
 	}
 
+	fn child_read_proof(
+		&self,
+		block: Option<Block::Hash>,
+		child_storage_key: StorageKey,
+		key: StorageKey,
+	) -> FutureResult<Vec<Bytes>> {
+		Box::new(result(
+			self.block_or_best(block)
+				.and_then(|block| self.client.read_child_proof(&BlockId::Hash(block), &child_storage_key.0, &key.0))
+				.map(|proof| proof.into_iter().map(Bytes).collect())
+				.map_err(client_err)))
+	}
+
 	fn metadata(&self, block: Option<Block::Hash>) -> FutureResult<Bytes> {
 		Box::new(result(
 			self.block_or_best(block)
@@ -352,6 +366,42 @@ impl<B, E, Block, RA> StateBackend<B, E, Block, RA> for FullState<B, E, Block, R
 		};
 		Box::new(result(call_fn()))
 	}
+
+	fn query_event_topics(
+		&self,
+		from: Block::Hash,
+		to: Option<Block::Hash>,
+		topics: Vec<Block::Hash>,
+	) -> FutureResult<Vec<EventTopicsMatch<Block::Hash>>> {
+		let call_fn = move || {
+			let to = self.block_or_best(to).map_err(client_err)?;
+			let from_hdr = self.client.header(&BlockId::hash(from)).map_err(client_err)?;
+			let to_hdr = self.client.header(&BlockId::hash(to)).map_err(client_err)?;
+			let (from_number, to_number) = match (from_hdr, to_hdr) {
+				(Some(ref from), Some(ref to)) if from.number() <= to.number() => (*from.number(), *to.number()),
+				(from, to) => return Err(
+					invalid_block_range(from.as_ref(), to.as_ref(), "Invalid range or unknown block".into())
+				),
+			};
+			#[allow(deprecated)]
+			let index = self.client.backend().event_topics_index().ok_or_else(|| client_err(
+				ClientError::Backend("Event topics are not indexed by this node".into())
+			))?;
+
+			let mut matches = Vec::new();
+			for topic in topics {
+				let events = index.events_with_topic(&topic.encode(), from_number, to_number)
+					.map_err(client_err)?;
+				for (number, block, indices) in events {
+					matches.push((number, EventTopicsMatch { block, topic, indices }));
+				}
+			}
+			// stable sort keeps the order of topics within a block.
+			matches.sort_by_key(|(number, _)| *number);
+			Ok(matches.into_iter().map(|(_, m)| m).collect())
+		};
+		Box::new(result(call_fn()))
+	}
 }
 
 /// Splits passed range into two subranges where:
@@ -45,7 +45,7 @@ use sr_primitives::{
 	traits::{Block as BlockT, Header as HeaderT},
 };
 
-use super::{StateBackend, error::{FutureResult, Error}, client_err};
+use super::{StateBackend, EventTopicsMatch, error::{FutureResult, Error}, client_err};
 
 pub struct LightState<Block: BlockT, F: Fetcher<Block>, B, E, RA> {
 	client: Arc<Client<B, E, Block, RA>>,
@@ -220,6 +220,15 @@ impl<Block, F, B, E, RA> StateBackend<B, E, Block, RA> for LightState<Block, F,
 		)
 	}
 
+	fn child_read_proof(
+		&self,
+		_block: Option<Block::Hash>,
+		_child_storage_key: StorageKey,
+		_key: StorageKey,
+	) -> FutureResult<Vec<Bytes>> {
+		Box::new(result(Err(client_err(ClientError::NotAvailableOnLightClient))))
+	}
+
 	fn metadata(&self, block: Option<Block::Hash>) -> FutureResult<Bytes> {
 		let metadata = self.call(block, "Metadata_metadata".into(), Bytes(Vec::new()))
 			.and_then(|metadata| OpaqueMetadata::decode(&mut &metadata.0[..])
@@ -250,6 +259,15 @@ impl<Block, F, B, E, RA> StateBackend<B, E, Block, RA> for LightState<Block, F,
 		Box::new(result(Err(client_err(ClientError::NotAvailableOnLightClient))))
 	}
 
+	fn query_event_topics(
+		&self,
+		_from: Block::Hash,
+		_to: Option<Block::Hash>,
+		_topics: Vec<Block::Hash>,
+	) -> FutureResult<Vec<EventTopicsMatch<Block::Hash>>> {
+		Box::new(result(Err(client_err(ClientError::NotAvailableOnLightClient))))
+	}
+
 	fn subscribe_storage(
 		&self,
 		_meta: crate::metadata::Metadata,
@@ -97,6 +97,57 @@ fn should_return_child_storage() {
 	);
 }
 
+#[test]
+fn should_return_child_storage_keys() {
+	let core = tokio::runtime::Runtime::new().unwrap();
+	let client = Arc::new(test_client::TestClientBuilder::new()
+		.add_child_storage("test", "key1", vec![1_u8])
+		.add_child_storage("test", "key2", vec![2_u8])
+		.add_child_storage("test", "other", vec![3_u8])
+		.add_child_storage("test2", "key3", vec![4_u8])
+		.build());
+	let genesis_hash = client.genesis_hash();
+	let api = new_full(client, Subscriptions::new(Arc::new(core.executor())));
+	let child_key = StorageKey(well_known_keys::CHILD_STORAGE_KEY_PREFIX.iter().chain(b"test").cloned().collect());
+
+	assert_eq!(
+		api.child_storage_keys(child_key.clone(), StorageKey(b"key".to_vec()), Some(genesis_hash).into())
+			.wait()
+			.unwrap(),
+		vec![StorageKey(b"key1".to_vec()), StorageKey(b"key2".to_vec())],
+	);
+	assert_eq!(
+		api.child_storage_keys(child_key, StorageKey(vec![]), Some(genesis_hash).into())
+			.wait()
+			.unwrap(),
+		vec![StorageKey(b"key1".to_vec()), StorageKey(b"key2".to_vec()), StorageKey(b"other".to_vec())],
+	);
+}
+
+#[test]
+fn should_return_checkable_child_read_proof() {
+	let core = tokio::runtime::Runtime::new().unwrap();
+	let client = Arc::new(test_client::TestClientBuilder::new()
+		.add_child_storage("test", "key", vec![42_u8])
+		.build());
+	let genesis_hash = client.genesis_hash();
+	let state_root = client.header(&BlockId::Hash(genesis_hash)).unwrap().unwrap().state_root;
+	let api = new_full(client, Subscriptions::new(Arc::new(core.executor())));
+	let child_key = StorageKey(well_known_keys::CHILD_STORAGE_KEY_PREFIX.iter().chain(b"test").cloned().collect());
+	let key = StorageKey(b"key".to_vec());
+
+	let proof = api.child_read_proof(child_key.clone(), key.clone(), Some(genesis_hash).into())
+		.wait()
+		.unwrap()
+		.into_iter()
+		.map(|node| node.0)
+		.collect();
+	assert_eq!(
+		state_machine::read_child_proof_check::<Blake2Hasher>(state_root, proof, &child_key.0, &key.0).unwrap(),
+		Some(vec![42_u8]),
+	);
+}
+
 #[test]
 fn should_call_contract() {
 	let core = tokio::runtime::Runtime::new().unwrap();
@@ -261,6 +312,87 @@ fn should_query_storage() {
 	run_tests(Arc::new(TestClientBuilder::new().set_support_changes_trie(true).build()));
 }
 
+#[test]
+fn should_query_event_topics() {
+	use codec::Encode;
+
+	const PREFIX: &[u8] = b"event topics";
+
+	let core = tokio::runtime::Runtime::new().unwrap();
+	let backend = Arc::new(test_client::Backend::new_test_with_event_topics_index(
+		std::u32::MAX,
+		std::u64::MAX,
+		PREFIX.to_vec(),
+	));
+	let client = Arc::new(TestClientBuilder::with_backend(backend).build());
+	let api = new_full(client.clone(), Subscriptions::new(Arc::new(core.executor())));
+
+	let topic1 = H256::repeat_byte(1);
+	let topic2 = H256::repeat_byte(2);
+	let topic_key = |topic: &H256| {
+		let mut key = PREFIX.to_vec();
+		key.extend_from_slice(&blake2_256(&topic.encode()));
+		key
+	};
+	let key1 = topic_key(&topic1);
+	let key2 = topic_key(&topic2);
+
+	let add_block = |changes: Vec<(Vec<u8>, Option<Vec<u8>>)>| {
+		let mut builder = client.new_block(Default::default()).unwrap();
+		for (key, value) in changes {
+			builder.push_storage_change(key, value).unwrap();
+		}
+		let block = builder.bake().unwrap();
+		let hash = block.header.hash();
+		client.import(BlockOrigin::Own, block).unwrap();
+		hash
+	};
+	let block1_hash = add_block(vec![
+		(key1.clone(), Some(vec![(1u64, 0u32), (1, 2)].encode())),
+	]);
+	let block2_hash = add_block(vec![
+		(key1.clone(), None),
+		(key2.clone(), Some(vec![(2u64, 1u32)].encode())),
+	]);
+	let block3_hash = add_block(vec![
+		(key1.clone(), Some(vec![(3u64, 0u32)].encode())),
+		(key2.clone(), Some(vec![(3u64, 3u32)].encode())),
+	]);
+	let genesis_hash = client.genesis_hash();
+
+	let result = api.query_event_topics(vec![topic1], genesis_hash, Some(block2_hash).into());
+	assert_eq!(result.wait().unwrap(), vec![
+		EventTopicsMatch { block: block1_hash, topic: topic1, indices: vec![0, 2] },
+	]);
+
+	let result = api.query_event_topics(vec![topic1, topic2], block2_hash, None.into());
+	assert_eq!(result.wait().unwrap(), vec![
+		EventTopicsMatch { block: block2_hash, topic: topic2, indices: vec![1] },
+		EventTopicsMatch { block: block3_hash, topic: topic1, indices: vec![0] },
+		EventTopicsMatch { block: block3_hash, topic: topic2, indices: vec![3] },
+	]);
+
+	assert_matches!(
+		api.query_event_topics(vec![topic1], block3_hash, Some(block1_hash).into()).wait(),
+		Err(Error::InvalidBlockRange { .. })
+	);
+
+	// up to the best block.
+	let result = api.query_event_topics(vec![topic1], genesis_hash, None.into());
+	assert_eq!(result.wait().unwrap(), vec![
+		EventTopicsMatch { block: block1_hash, topic: topic1, indices: vec![0, 2] },
+		EventTopicsMatch { block: block3_hash, topic: topic1, indices: vec![0] },
+	]);
+
+	// nodes that do not index the event topics refuse the query.
+	let client = Arc::new(test_client::new());
+	let api = new_full(client.clone(), Subscriptions::new(Arc::new(core.executor())));
+	assert_matches!(
+		api.query_event_topics(vec![topic1], client.genesis_hash(), None.into()).wait(),
+		Err(Error::Client(_))
+	);
+}
+
 #[test]
 fn should_split_ranges() {
 	assert_eq!(split_range(1, None), (0..1, None));
@@ -0,0 +1,171 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Generates the bare function interface for a given trait definition.
+//!
+//! The bare function interface is the public interface of the runtime interface: one function per
+//! method that calls the latest version of the method. Every version is implemented twice, with
+//! `std` by calling the trait implementation directly and without `std` by calling the host
+//! function. Wasm only interfaces have no `std` implementation.
+
+use crate::utils::{
+	RuntimeInterface, RuntimeInterfaceMethod, Receiver, generate_crate_access, doc_attributes,
+	versioned_function_name, host_function_name, externalities_type,
+};
+
+use proc_macro2::TokenStream;
+use syn::{ItemTrait, Result};
+use quote::quote;
+
+/// Generate the bare function interface for the given trait definition.
+pub fn generate(
+	trait_def: &ItemTrait,
+	methods: &RuntimeInterface,
+	is_wasm_only: bool,
+) -> Result<TokenStream> {
+	let public_functions = methods.latest_versions()
+		.map(|method| generate_public_function(method, is_wasm_only));
+	let native_functions = methods.all_versions()
+		.filter(|_| !is_wasm_only)
+		.map(|method| generate_native_function(trait_def, method));
+	let wasm_functions = methods.all_versions()
+		.map(|method| generate_wasm_function(trait_def, methods, method));
+
+	Ok(quote! {
+		#( #public_functions )*
+
+		#( #native_functions )*
+
+		#( #wasm_functions )*
+	})
+}
+
+/// Generate the public function calling the given, latest, version of a method.
+fn generate_public_function(method: &RuntimeInterfaceMethod, is_wasm_only: bool) -> TokenStream {
+	let name = method.name();
+	let versioned_name = versioned_function_name(name, method.version);
+	let docs = doc_attributes(&method.method.attrs);
+	let args = method.arguments();
+	let arg_decls = args.iter().map(|(name, ty)| quote!( #name: #ty ));
+	let arg_names = args.iter().map(|(name, _)| name);
+	let output = &method.method.sig.decl.output;
+	let cfg = if is_wasm_only {
+		Some(quote!( #[cfg(not(feature = "std"))] ))
+	} else {
+		None
+	};
+
+	quote! {
+		#( #docs )*
+		#cfg
+		pub fn #name( #( #arg_decls ),* ) #output {
+			#versioned_name( #( #arg_names ),* )
+		}
+	}
+}
+
+/// Generate the implementation of a method version that calls the trait implementation on the
+/// host side.
+fn generate_native_function(trait_def: &ItemTrait, method: &RuntimeInterfaceMethod) -> TokenStream {
+	let crate_ = generate_crate_access();
+	let trait_name = &trait_def.ident;
+	let versioned_name = versioned_function_name(method.name(), method.version);
+	let signature = method.bare_signature();
+	let arg_names = method.arguments().into_iter().map(|(name, _)| name);
+	let externalities = externalities_type(&crate_);
+
+	let call = match method.receiver() {
+		Receiver::None => quote! {
+			<#externalities as #trait_name>::#versioned_name( #( #arg_names ),* )
+		},
+		receiver => {
+			let ext_ref = if receiver == Receiver::RefMut {
+				quote!( __externalities__ )
+			} else {
+				quote!( &*__externalities__ )
+			};
+			let expect_msg = format!(
+				"`{}` called outside of an Externalities-provided environment.",
+				method.name(),
+			);
+
+			quote! {
+				#crate_::externalities::with(|__externalities__| {
+					<#externalities as #trait_name>::#versioned_name( #ext_ref, #( #arg_names ),* )
+				}).expect(#expect_msg)
+			}
+		},
+	};
+
+	quote! {
+		#[cfg(feature = "std")]
+		#signature {
+			#call
+		}
+	}
+}
+
+/// Generate the implementation of a method version that calls the host function from wasm.
+fn generate_wasm_function(
+	trait_def: &ItemTrait,
+	methods: &RuntimeInterface,
+	method: &RuntimeInterfaceMethod,
+) -> TokenStream {
+	let crate_ = generate_crate_access();
+	let signature = method.bare_signature();
+	let host_function = host_function_name(&trait_def.ident, method.name(), method.version);
+	let args = method.arguments();
+
+	let ffi_args = args.iter().map(|(name, ty)| quote! {
+		#name: <#ty as #crate_::RIType>::FFIType
+	});
+	let ffi_return = method.return_type().map(|ty| quote! {
+		-> <#ty as #crate_::RIType>::FFIType
+	});
+	let convert_args = args.iter().map(|(name, ty)| quote! {
+		let #name = <#ty as #crate_::wasm::IntoFFIValue>::into_ffi_value(&#name);
+	});
+	let arg_names = args.iter().map(|(name, _)| name).collect::<Vec<_>>();
+	let arg_names = &arg_names;
+	let call = match method.return_type() {
+		Some(ty) => quote! {
+			let result = unsafe { #host_function( #( #arg_names.get() ),* ) };
+			<#ty as #crate_::wasm::FromFFIValue>::from_ffi_value(result)
+		},
+		None => quote! {
+			unsafe { #host_function( #( #arg_names.get() ),* ) }
+		},
+	};
+	let allow_dead_code = if methods.is_latest(method) {
+		None
+	} else {
+		Some(quote!( #[allow(dead_code)] ))
+	};
+
+	quote! {
+		#[cfg(not(feature = "std"))]
+		#allow_dead_code
+		#signature {
+			extern "C" {
+				pub fn #host_function( #( #ffi_args ),* ) #ffi_return;
+			}
+
+			#( #convert_args )*
+
+			#call
+		}
+	}
+}
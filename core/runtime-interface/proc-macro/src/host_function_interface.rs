@@ -0,0 +1,156 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Generates the host functions of a runtime interface.
+//!
+//! Every version of every method gets a unit struct implementing `host::Function`, which
+//! converts the ffi arguments, calls the native implementation and converts the return value.
+//! For wasm only interfaces, the trait implementation is called with the function context.
+//! `HostFunctions` lists all of them for the executor.
+
+use crate::utils::{
+	RuntimeInterface, RuntimeInterfaceMethod, Receiver, generate_crate_access, host_function_name,
+	versioned_function_name, argument_for_call,
+};
+
+use proc_macro2::TokenStream;
+use syn::{ItemTrait, Result};
+use quote::quote;
+
+/// Generate the host functions for the given trait definition.
+pub fn generate(
+	trait_def: &ItemTrait,
+	methods: &RuntimeInterface,
+	is_wasm_only: bool,
+) -> Result<TokenStream> {
+	let crate_ = generate_crate_access();
+	let host_functions = methods.all_versions()
+		.map(|method| host_function_name(&trait_def.ident, method.name(), method.version))
+		.collect::<Vec<_>>();
+	let host_function_impls = methods.all_versions()
+		.map(|method| generate_host_function(trait_def, method, is_wasm_only));
+
+	Ok(quote! {
+		/// Provides the host functions of this runtime interface.
+		#[cfg(feature = "std")]
+		pub struct HostFunctions;
+
+		#[cfg(feature = "std")]
+		impl #crate_::host::HostFunctions for HostFunctions {
+			fn host_functions() -> Vec<&'static dyn #crate_::host::Function> {
+				vec![ #( &host_functions::#host_functions as &dyn #crate_::host::Function ),* ]
+			}
+		}
+
+		#[cfg(feature = "std")]
+		mod host_functions {
+			#[allow(unused_imports)]
+			use super::*;
+
+			#( #host_function_impls )*
+		}
+	})
+}
+
+/// Generate the host function for the given version of a method.
+fn generate_host_function(
+	trait_def: &ItemTrait,
+	method: &RuntimeInterfaceMethod,
+	is_wasm_only: bool,
+) -> TokenStream {
+	let crate_ = generate_crate_access();
+	let trait_name = &trait_def.ident;
+	let host_function = host_function_name(&trait_def.ident, method.name(), method.version);
+	let host_function_str = host_function.to_string();
+	let versioned_name = versioned_function_name(method.name(), method.version);
+	let args = method.arguments();
+
+	let arg_types = args.iter().map(|(_, ty)| quote! {
+		<<#ty as #crate_::RIType>::FFIType as #crate_::host::IntoValue>::VALUE_TYPE
+	});
+	let return_type = match method.return_type() {
+		Some(ty) => quote! {
+			Some(<<#ty as #crate_::RIType>::FFIType as #crate_::host::IntoValue>::VALUE_TYPE)
+		},
+		None => quote!( None ),
+	};
+
+	let convert_args = args.iter().map(|(name, ty)| {
+		let error = format!("Invalid argument `{}` for `{}`", name, host_function);
+
+		quote! {
+			let #name: <#ty as #crate_::RIType>::FFIType = __args__
+				.next()
+				.and_then(#crate_::host::TryFromValue::try_from_value)
+				.ok_or_else(|| String::from(#error))?;
+			let #name = <#ty as #crate_::host::FromFFIValue>::from_ffi_value(__context__, #name)?;
+		}
+	});
+	let call_args = args.iter().map(|(name, ty)| argument_for_call(name, ty));
+	let call = if is_wasm_only {
+		// Without a receiver, the implementation for the context can't be inferred.
+		let (self_type, context) = match method.receiver() {
+			Receiver::None => (quote!( dyn #crate_::host::FunctionContext ), None),
+			Receiver::Ref => (quote!( _ ), Some(quote!( &*__context__, ))),
+			Receiver::RefMut => (quote!( _ ), Some(quote!( __context__, ))),
+		};
+
+		quote!( <#self_type as super::#trait_name>::#versioned_name( #context #( #call_args ),* ) )
+	} else {
+		quote!( super::#versioned_name( #( #call_args ),* ) )
+	};
+	let convert_return = match method.return_type() {
+		Some(ty) => quote! {
+			let __result__ = <#ty as #crate_::host::IntoFFIValue>::into_ffi_value(
+				__result__,
+				__context__,
+			)?;
+			Ok(Some(#crate_::host::IntoValue::into_value(__result__)))
+		},
+		None => quote! {
+			let () = __result__;
+			Ok(None)
+		},
+	};
+
+	quote! {
+		#[allow(non_camel_case_types)]
+		pub struct #host_function;
+
+		impl #crate_::host::Function for #host_function {
+			fn name(&self) -> &str {
+				#host_function_str
+			}
+
+			fn signature(&self) -> #crate_::host::Signature {
+				let args: Vec<#crate_::host::ValueType> = vec![ #( #arg_types ),* ];
+				#crate_::host::Signature::new(args, #return_type)
+			}
+
+			fn execute(
+				&self,
+				__context__: &mut dyn #crate_::host::FunctionContext,
+				__args__: &mut dyn Iterator<Item = #crate_::host::Value>,
+			) -> #crate_::host::Result<Option<#crate_::host::Value>> {
+				#( #convert_args )*
+
+				let __result__ = #call;
+
+				#convert_return
+			}
+		}
+	}
+}
@@ -0,0 +1,125 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Macros for declaring runtime interfaces.
+
+#![recursion_limit = "512"]
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, ItemTrait};
+
+mod bare_function_interface;
+mod host_function_interface;
+mod trait_decl_impl;
+mod utils;
+
+/// Declares a runtime interface from a trait.
+///
+/// Every method of the trait needs a default implementation, which is the implementation used on
+/// the host side. Methods that take `&self` or `&mut self` are called on the
+/// `Externalities` of the current call, methods without `self` are plain functions.
+///
+/// The trait is turned into a module named after the trait in snake case, which contains:
+///
+/// - a public function for every method, calling the latest version of the method.
+/// - `HostFunctions`, a type implementing `substrate_runtime_interface::host::HostFunctions` that
+///   provides every version of every method as a host function (only with `std`).
+///
+/// A method can be declared multiple times with different versions, using the `#[version(X)]`
+/// attribute. Methods without the attribute are version `1`. The host function for a method is
+/// called `ext_{module}_{method}_version_{version}`.
+///
+/// # Example
+///
+/// ```nocompile
+/// #[runtime_interface]
+/// trait Interface {
+///     /// A function that can be called from the runtime.
+///     fn call_some_complex_code(data: &[u8]) -> Vec<u8> {
+///         // Here you could call some rather complex code that only compiles on native or
+///         // is way faster in native than executing it in wasm.
+///         Vec::new()
+///     }
+///
+///     /// A function that uses the externalities of the current call.
+///     fn set_or_clear(&mut self, key: &[u8], value: Option<Vec<u8>>) {
+///         match value {
+///             Some(value) => self.set_storage(key.to_vec(), value),
+///             None => self.clear_storage(key),
+///         }
+///     }
+///
+///     /// The new version of `call_some_complex_code`, which is now used by the runtime.
+///     #[version(2)]
+///     fn call_some_complex_code(data: &[u8]) -> Vec<u8> {
+///         data.to_vec()
+///     }
+/// }
+/// ```
+///
+/// The arguments and the return value of every method need to implement
+/// `substrate_runtime_interface::RIType`.
+///
+/// # Wasm only interfaces
+///
+/// An interface declared with `#[runtime_interface(wasm_only)]` is only available to the wasm
+/// runtime: no functions are generated for `std`. Methods that take `&self` or `&mut self` are
+/// called on the `substrate_runtime_interface::host::FunctionContext` of the wasm instance that
+/// calls the host function instead of the `Externalities`.
+#[proc_macro_attribute]
+pub fn runtime_interface(attrs: TokenStream, input: TokenStream) -> TokenStream {
+	let is_wasm_only = match attrs.to_string().trim() {
+		"" => false,
+		"wasm_only" => true,
+		_ => return syn::Error::new(
+			proc_macro2::Span::call_site(),
+			"`runtime_interface` only accepts the `wasm_only` argument",
+		).to_compile_error().into(),
+	};
+
+	let trait_def = parse_macro_input!(input as ItemTrait);
+
+	utils::unwrap_or_error(runtime_interface_impl(trait_def, is_wasm_only)).into()
+}
+
+fn runtime_interface_impl(
+	trait_def: ItemTrait,
+	is_wasm_only: bool,
+) -> syn::Result<proc_macro2::TokenStream> {
+	let methods = utils::RuntimeInterface::new(&trait_def)?;
+	let mod_name = utils::mod_name(&trait_def.ident);
+	let vis = &trait_def.vis;
+	let docs = utils::doc_attributes(&trait_def.attrs);
+
+	let bare_functions = bare_function_interface::generate(&trait_def, &methods, is_wasm_only)?;
+	let trait_decl_impl = trait_decl_impl::generate(&trait_def, &methods, is_wasm_only)?;
+	let host_functions = host_function_interface::generate(&trait_def, &methods, is_wasm_only)?;
+
+	Ok(quote::quote! {
+		#( #docs )*
+		#vis mod #mod_name {
+			#[allow(unused_imports)]
+			use super::*;
+
+			#bare_functions
+
+			#trait_decl_impl
+
+			#host_functions
+		}
+	})
+}
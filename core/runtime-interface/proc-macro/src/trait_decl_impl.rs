@@ -0,0 +1,64 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Generates the trait declaration and its implementation for the externalities.
+//!
+//! Every version of a method becomes a method of the generated trait, named
+//! `{method}_version_{version}`. The default implementations given by the user are moved into
+//! the implementation of the trait for the externalities, so that `self` is the externalities.
+//! For wasm only interfaces, the trait is implemented for the function context instead.
+
+use crate::utils::{RuntimeInterface, generate_crate_access, host_self_type};
+
+use proc_macro2::TokenStream;
+use syn::{ItemTrait, Result};
+use quote::quote;
+
+/// Generate the trait declaration and its implementation for the externalities.
+pub fn generate(
+	trait_def: &ItemTrait,
+	methods: &RuntimeInterface,
+	is_wasm_only: bool,
+) -> Result<TokenStream> {
+	let crate_ = generate_crate_access();
+	let trait_name = &trait_def.ident;
+	let self_type = host_self_type(&crate_, is_wasm_only);
+	let impl_generics = if is_wasm_only {
+		Some(quote!( <'__context__> ))
+	} else {
+		None
+	};
+
+	let declarations = methods.all_versions().map(|method| method.versioned_signature());
+	let implementations = methods.all_versions().map(|method| {
+		let signature = method.versioned_signature();
+		let body = &method.method.default;
+
+		quote!( #signature #body )
+	});
+
+	Ok(quote! {
+		#[cfg(feature = "std")]
+		trait #trait_name {
+			#( #declarations; )*
+		}
+
+		#[cfg(feature = "std")]
+		impl #impl_generics #trait_name for #self_type {
+			#( #implementations )*
+		}
+	})
+}
@@ -0,0 +1,309 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Util function used by this crate.
+
+use proc_macro2::{TokenStream, Span};
+use syn::{
+	Ident, Error, Result, ItemTrait, TraitItem, TraitItemMethod, Attribute, FnArg, Pat, Type,
+	ReturnType, Meta, NestedMeta, Lit, parse_quote, spanned::Spanned,
+};
+use quote::quote;
+use proc_macro_crate::crate_name;
+use std::{env, collections::BTreeMap};
+
+/// Unwrap the given result, if it is an error, `compile_error!` will be generated.
+pub fn unwrap_or_error(res: Result<TokenStream>) -> TokenStream {
+	res.unwrap_or_else(|e| e.to_compile_error())
+}
+
+/// Generates the access to the `substrate-runtime-interface` crate.
+pub fn generate_crate_access() -> TokenStream {
+	if env::var("CARGO_PKG_NAME").unwrap() == "substrate-runtime-interface" {
+		quote!( crate )
+	} else {
+		match crate_name("substrate-runtime-interface") {
+			Ok(name) => {
+				let name = Ident::new(&name, Span::call_site());
+				quote!( ::#name )
+			},
+			Err(e) => Error::new(Span::call_site(), &e).to_compile_error(),
+		}
+	}
+}
+
+/// Returns the name of the module generated for the given trait, the trait name in snake case.
+pub fn mod_name(trait_name: &Ident) -> Ident {
+	let mut name = String::new();
+	for (i, c) in trait_name.to_string().chars().enumerate() {
+		if c.is_uppercase() {
+			if i > 0 {
+				name.push('_');
+			}
+			name.extend(c.to_lowercase());
+		} else {
+			name.push(c);
+		}
+	}
+
+	Ident::new(&name, trait_name.span())
+}
+
+/// Returns the name of the function implementing the given version of a method.
+pub fn versioned_function_name(method: &Ident, version: u32) -> Ident {
+	Ident::new(&format!("{}_version_{}", method, version), method.span())
+}
+
+/// Returns the name of the host function for the given version of a method.
+pub fn host_function_name(trait_name: &Ident, method: &Ident, version: u32) -> Ident {
+	Ident::new(
+		&format!("ext_{}_{}_version_{}", mod_name(trait_name), method, version),
+		Span::call_site(),
+	)
+}
+
+/// Returns the doc attributes of the given attributes.
+pub fn doc_attributes(attrs: &[Attribute]) -> Vec<&Attribute> {
+	attrs.iter().filter(|a| a.path.is_ident("doc")).collect()
+}
+
+/// The receiver of a method.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Receiver {
+	/// The method does not take `self`.
+	None,
+	/// The method takes `&self`.
+	Ref,
+	/// The method takes `&mut self`.
+	RefMut,
+}
+
+/// A method of a runtime interface with its version.
+pub struct RuntimeInterfaceMethod {
+	/// The method, without the `version` attribute.
+	pub method: TraitItemMethod,
+	/// The version of the method.
+	pub version: u32,
+}
+
+impl RuntimeInterfaceMethod {
+	/// The name of the method.
+	pub fn name(&self) -> &Ident {
+		&self.method.sig.ident
+	}
+
+	/// The receiver of the method.
+	pub fn receiver(&self) -> Receiver {
+		match self.method.sig.decl.inputs.first().map(|a| a.into_value()) {
+			Some(FnArg::SelfRef(arg)) if arg.mutability.is_some() => Receiver::RefMut,
+			Some(FnArg::SelfRef(_)) => Receiver::Ref,
+			_ => Receiver::None,
+		}
+	}
+
+	/// The arguments of the method, without `self`.
+	pub fn arguments(&self) -> Vec<(Ident, Type)> {
+		self.method.sig.decl.inputs.iter().filter_map(|arg| match arg {
+			FnArg::Captured(arg) => match &arg.pat {
+				Pat::Ident(pat) => Some((pat.ident.clone(), arg.ty.clone())),
+				_ => None,
+			},
+			_ => None,
+		}).collect()
+	}
+
+	/// The return type of the method, `None` for `()`.
+	pub fn return_type(&self) -> Option<Type> {
+		match &self.method.sig.decl.output {
+			ReturnType::Default => None,
+			ReturnType::Type(_, ty) => match &**ty {
+				Type::Tuple(tuple) if tuple.elems.is_empty() => None,
+				ty => Some(ty.clone()),
+			},
+		}
+	}
+
+	/// The signature of the method with its name replaced by the versioned name and without
+	/// `self`.
+	pub fn bare_signature(&self) -> TokenStream {
+		let name = versioned_function_name(self.name(), self.version);
+		let args = self.arguments().into_iter().map(|(name, ty)| quote!( #name: #ty ));
+		let output = &self.method.sig.decl.output;
+
+		quote!( fn #name( #( #args ),* ) #output )
+	}
+
+	/// The signature of the method with its name replaced by the versioned name.
+	pub fn versioned_signature(&self) -> TokenStream {
+		let name = versioned_function_name(self.name(), self.version);
+		let inputs = &self.method.sig.decl.inputs;
+		let output = &self.method.sig.decl.output;
+
+		quote!( fn #name( #inputs ) #output )
+	}
+}
+
+/// All methods of a runtime interface.
+pub struct RuntimeInterface {
+	methods: Vec<RuntimeInterfaceMethod>,
+}
+
+impl RuntimeInterface {
+	/// Collect and check the methods of the given trait.
+	pub fn new(trait_def: &ItemTrait) -> Result<Self> {
+		if !trait_def.generics.params.is_empty() {
+			return Err(Error::new(trait_def.generics.span(), "Generic traits are not supported"));
+		}
+
+		let mut versions = BTreeMap::new();
+		let mut methods = Vec::new();
+
+		for item in &trait_def.items {
+			let method = match item {
+				TraitItem::Method(method) => method,
+				item => return Err(Error::new(item.span(), "Only methods are supported")),
+			};
+
+			check_method(method)?;
+
+			let (version, method) = extract_version(method)?;
+			if versions.insert((method.sig.ident.clone(), version), ()).is_some() {
+				return Err(Error::new(
+					method.sig.ident.span(),
+					format!("Version {} of `{}` is declared twice", version, method.sig.ident),
+				));
+			}
+
+			methods.push(RuntimeInterfaceMethod { method, version });
+		}
+
+		Ok(RuntimeInterface { methods })
+	}
+
+	/// Every version of every method.
+	pub fn all_versions(&self) -> impl Iterator<Item = &RuntimeInterfaceMethod> {
+		self.methods.iter()
+	}
+
+	/// The latest version of every method, in declaration order.
+	pub fn latest_versions(&self) -> impl Iterator<Item = &RuntimeInterfaceMethod> {
+		self.methods.iter().filter(move |method| self.is_latest(method))
+	}
+
+	/// Returns `true` if `method` is the latest version of its method.
+	pub fn is_latest(&self, method: &RuntimeInterfaceMethod) -> bool {
+		!self.methods.iter().any(|other| other.name() == method.name() && other.version > method.version)
+	}
+}
+
+/// Check that the given method is supported by the runtime interface.
+fn check_method(method: &TraitItemMethod) -> Result<()> {
+	let sig = &method.sig;
+
+	if method.default.is_none() {
+		return Err(Error::new(sig.ident.span(), "Methods require a default implementation"));
+	}
+
+	if !sig.decl.generics.params.is_empty() {
+		return Err(Error::new(sig.decl.generics.span(), "Generic methods are not supported"));
+	}
+
+	if sig.unsafety.is_some() || sig.asyncness.is_some() || sig.constness.is_some() || sig.abi.is_some() {
+		return Err(Error::new(sig.ident.span(), "Only plain `fn` methods are supported"));
+	}
+
+	for (i, arg) in sig.decl.inputs.iter().enumerate() {
+		match arg {
+			FnArg::SelfRef(_) if i == 0 => {},
+			FnArg::Captured(arg) => match &arg.pat {
+				Pat::Ident(pat) if pat.by_ref.is_none() && pat.subpat.is_none() => {},
+				pat => return Err(Error::new(pat.span(), "Only identifiers are supported as argument")),
+			},
+			arg => return Err(Error::new(
+				arg.span(),
+				"Only `&self`, `&mut self` and `name: Type` arguments are supported",
+			)),
+		}
+	}
+
+	Ok(())
+}
+
+/// Extract the `#[version(X)]` attribute of the given method.
+///
+/// Returns the version, `1` if not given, and the method without the attribute.
+fn extract_version(method: &TraitItemMethod) -> Result<(u32, TraitItemMethod)> {
+	let mut method = method.clone();
+	let mut version = None;
+
+	let mut attrs = Vec::new();
+	for attr in method.attrs.drain(..) {
+		if !attr.path.is_ident("version") {
+			attrs.push(attr);
+			continue;
+		}
+
+		if version.is_some() {
+			return Err(Error::new(attr.span(), "Duplicated `version` attribute"));
+		}
+
+		let parsed = match attr.parse_meta()? {
+			Meta::List(list) => if list.nested.len() == 1 {
+				match list.nested.first().map(|n| n.into_value()) {
+					Some(NestedMeta::Literal(Lit::Int(i))) if i.value() > 0 => Some(i.value() as u32),
+					_ => None,
+				}
+			} else {
+				None
+			},
+			_ => None,
+		};
+
+		match parsed {
+			Some(v) => version = Some(v),
+			None => return Err(Error::new(attr.span(), "Expected `#[version(X)]` with `X` > 0")),
+		}
+	}
+	method.attrs = attrs;
+
+	Ok((version.unwrap_or(1), method))
+}
+
+/// Returns `&name`, `&mut name` or `name`, depending on how the argument is passed to the
+/// function.
+pub fn argument_for_call(name: &Ident, ty: &Type) -> TokenStream {
+	match ty {
+		Type::Reference(reference) if reference.mutability.is_some() => quote!( &mut #name ),
+		Type::Reference(_) => quote!( &#name ),
+		_ => quote!( #name ),
+	}
+}
+
+/// The type used for the externalities on the host side.
+pub fn externalities_type(crate_: &TokenStream) -> Type {
+	parse_quote!( dyn #crate_::Externalities<#crate_::Blake2Hasher> )
+}
+
+/// The type `self` refers to in the methods of the runtime interface on the host side.
+///
+/// This is the externalities, or the function context for wasm only interfaces.
+pub fn host_self_type(crate_: &TokenStream, is_wasm_only: bool) -> Type {
+	if is_wasm_only {
+		parse_quote!( dyn #crate_::host::FunctionContext + '__context__ )
+	} else {
+		externalities_type(crate_)
+	}
+}
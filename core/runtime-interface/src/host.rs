@@ -0,0 +1,224 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Traits required by the runtime interface from the host side.
+//!
+//! These traits are independent of the wasm interpreter: an executor provides a
+//! [`FunctionContext`] to access the wasm memory and calls the [`Function`]s returned by
+//! [`HostFunctions::host_functions`].
+
+use crate::RIType;
+
+use std::borrow::Cow;
+
+/// Result type used by the host side of the runtime interface.
+pub type Result<T> = std::result::Result<T, String>;
+
+/// Value types supported by the runtime interface.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ValueType {
+	/// An `i32` value.
+	I32,
+	/// An `i64` value.
+	I64,
+}
+
+/// Values passed between the runtime and the host.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Value {
+	/// An `i32` value.
+	I32(i32),
+	/// An `i64` value.
+	I64(i64),
+}
+
+/// Something that can be converted into a wasm compatible `Value`.
+pub trait IntoValue {
+	/// The type of the value in wasm.
+	const VALUE_TYPE: ValueType;
+
+	/// Convert `self` into a wasm `Value`.
+	fn into_value(self) -> Value;
+}
+
+/// Something that can be created from a wasm compatible `Value`.
+pub trait TryFromValue: Sized {
+	/// Try to convert the given `Value` into `Self`.
+	fn try_from_value(val: Value) -> Option<Self>;
+}
+
+macro_rules! impl_value_conversions {
+	( $( $ty:ty, $variant:ident, $inner:ty; )* ) => {
+		$(
+			impl IntoValue for $ty {
+				const VALUE_TYPE: ValueType = ValueType::$variant;
+
+				fn into_value(self) -> Value {
+					Value::$variant(self as $inner)
+				}
+			}
+
+			impl TryFromValue for $ty {
+				fn try_from_value(val: Value) -> Option<Self> {
+					match val {
+						Value::$variant(val) => Some(val as $ty),
+						_ => None,
+					}
+				}
+			}
+		)*
+	}
+}
+
+impl_value_conversions! {
+	i32, I32, i32;
+	u32, I32, i32;
+	i64, I64, i64;
+	u64, I64, i64;
+}
+
+/// The signature of a host function.
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct Signature {
+	/// The types of the arguments.
+	pub args: Cow<'static, [ValueType]>,
+	/// The type of the return value, if any.
+	pub return_value: Option<ValueType>,
+}
+
+impl Signature {
+	/// Create a new signature.
+	pub fn new<T: Into<Cow<'static, [ValueType]>>>(args: T, return_value: Option<ValueType>) -> Self {
+		Signature {
+			args: args.into(),
+			return_value,
+		}
+	}
+}
+
+/// Access to the wasm instance a host function is called from.
+pub trait FunctionContext {
+	/// Read `size` bytes of the wasm memory starting at `address`.
+	fn read_memory(&self, address: u32, size: u32) -> Result<Vec<u8>>;
+	/// Write `data` into the wasm memory starting at `address`.
+	fn write_memory(&mut self, address: u32, data: &[u8]) -> Result<()>;
+	/// Allocate `size` bytes in the wasm memory and return the pointer to the allocation.
+	fn allocate_memory(&mut self, size: u32) -> Result<u32>;
+	/// Free the allocation at `ptr`.
+	fn deallocate_memory(&mut self, ptr: u32) -> Result<()>;
+	/// Provides access to the sandbox.
+	fn sandbox(&mut self) -> &mut dyn Sandbox;
+}
+
+/// Something that provides the sandbox to the wasm instance a host function is called from.
+///
+/// Pointers are addresses in the memory of the calling wasm instance.
+pub trait Sandbox {
+	/// Copy `buf_len` bytes of the sandbox memory `memory_id` at `offset` into the buffer at
+	/// `buf_ptr`.
+	fn memory_get(&mut self, memory_id: u32, offset: u32, buf_ptr: u32, buf_len: u32) -> Result<u32>;
+	/// Copy the `val_len` bytes at `val_ptr` into the sandbox memory `memory_id` at `offset`.
+	fn memory_set(&mut self, memory_id: u32, offset: u32, val_ptr: u32, val_len: u32) -> Result<u32>;
+	/// Delete the sandbox memory `memory_id`.
+	fn memory_teardown(&mut self, memory_id: u32) -> Result<()>;
+	/// Create a new sandbox memory and return its id.
+	fn memory_new(&mut self, initial: u32, maximum: u32) -> Result<u32>;
+	/// Invoke the function `export_name` of the sandbox instance `instance_id` with the encoded
+	/// `args`, writing the encoded return value into the buffer at `return_val`.
+	fn invoke(
+		&mut self,
+		instance_id: u32,
+		export_name: &str,
+		args: &[u8],
+		return_val: u32,
+		return_val_len: u32,
+		state: u32,
+	) -> Result<u32>;
+	/// Delete the sandbox instance `instance_id`.
+	fn instance_teardown(&mut self, instance_id: u32) -> Result<()>;
+	/// Create a new sandbox instance from the given `wasm` and return its id.
+	///
+	/// `dispatch_thunk_id` is the index in the table of the calling wasm instance of the function
+	/// that dispatches the calls of the sandbox instance to the functions in `raw_env_def`.
+	fn instance_new(
+		&mut self,
+		dispatch_thunk_id: u32,
+		wasm: &[u8],
+		raw_env_def: &[u8],
+		state: u32,
+	) -> Result<u32>;
+}
+
+/// A host function that can be called by the wasm runtime.
+pub trait Function: Send + Sync {
+	/// The name of the function, as imported by the runtime.
+	fn name(&self) -> &str;
+	/// The signature of the function.
+	fn signature(&self) -> Signature;
+	/// Execute the function with the given arguments.
+	fn execute(
+		&self,
+		context: &mut dyn FunctionContext,
+		args: &mut dyn Iterator<Item = Value>,
+	) -> Result<Option<Value>>;
+}
+
+/// Something that provides a list of host functions.
+pub trait HostFunctions {
+	/// The host functions.
+	fn host_functions() -> Vec<&'static dyn Function>;
+}
+
+macro_rules! impl_host_functions_for_tuples {
+	( $( $name:ident )+ ) => {
+		impl< $( $name: HostFunctions ),+ > HostFunctions for ( $( $name, )+ ) {
+			fn host_functions() -> Vec<&'static dyn Function> {
+				let mut functions = Vec::new();
+				$( functions.extend($name::host_functions()); )+
+				functions
+			}
+		}
+	};
+}
+
+impl_host_functions_for_tuples!(A);
+impl_host_functions_for_tuples!(A B);
+impl_host_functions_for_tuples!(A B C);
+impl_host_functions_for_tuples!(A B C D);
+impl_host_functions_for_tuples!(A B C D E);
+impl_host_functions_for_tuples!(A B C D E F);
+impl_host_functions_for_tuples!(A B C D E F G);
+impl_host_functions_for_tuples!(A B C D E F G H);
+
+/// Something that can be converted into a ffi value on the host side.
+pub trait IntoFFIValue: RIType {
+	/// Convert `self` into a ffi value, allocating in the wasm memory if required.
+	fn into_ffi_value(self, context: &mut dyn FunctionContext) -> Result<Self::FFIType>;
+}
+
+/// Something that can be created from a ffi value on the host side.
+pub trait FromFFIValue: RIType {
+	/// The owned instance that is created.
+	///
+	/// For unsized types like `[u8]` this is the owned counterpart, e.g. `Vec<u8>`.
+	type SelfInstance;
+
+	/// Create `Self::SelfInstance` from the given ffi value.
+	fn from_ffi_value(
+		context: &mut dyn FunctionContext,
+		arg: Self::FFIType,
+	) -> Result<Self::SelfInstance>;
+}
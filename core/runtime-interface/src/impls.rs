@@ -0,0 +1,480 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Provides implementations for the runtime interface traits.
+
+use crate::RIType;
+
+#[cfg(feature = "std")]
+use crate::host::{FunctionContext, IntoFFIValue, FromFFIValue, Result};
+
+#[cfg(not(feature = "std"))]
+use crate::wasm::{IntoFFIValue, FromFFIValue, WrappedFFIValue};
+
+use codec::{Encode, Decode, Codec};
+
+use rstd::vec::Vec;
+
+/// Pack a pointer and a length into an `u64`.
+pub fn pack_ptr_and_len(ptr: u32, len: u32) -> u64 {
+	(u64::from(len) << 32) | u64::from(ptr)
+}
+
+/// Unpacks an `u64` into the pointer and the length.
+///
+/// Returns `(ptr, len)`.
+pub fn unpack_ptr_and_len(val: u64) -> (u32, u32) {
+	let ptr = (val & u64::from(u32::max_value())) as u32;
+	let len = (val >> 32) as u32;
+
+	(ptr, len)
+}
+
+/// Implements the runtime interface traits for primitive types that are passed as they are.
+macro_rules! impl_traits_for_primitives {
+	(
+		$(
+			$rty:ty, $fty:ty,
+		)*
+	) => {
+		$(
+			impl RIType for $rty {
+				type FFIType = $fty;
+			}
+
+			#[cfg(not(feature = "std"))]
+			impl IntoFFIValue for $rty {
+				type Owned = ();
+
+				fn into_ffi_value(&self) -> WrappedFFIValue<$fty> {
+					(*self as $fty).into()
+				}
+			}
+
+			#[cfg(not(feature = "std"))]
+			impl FromFFIValue for $rty {
+				fn from_ffi_value(arg: $fty) -> $rty {
+					arg as $rty
+				}
+			}
+
+			#[cfg(feature = "std")]
+			impl FromFFIValue for $rty {
+				type SelfInstance = $rty;
+
+				fn from_ffi_value(_: &mut dyn FunctionContext, arg: $fty) -> Result<$rty> {
+					Ok(arg as $rty)
+				}
+			}
+
+			#[cfg(feature = "std")]
+			impl IntoFFIValue for $rty {
+				fn into_ffi_value(self, _: &mut dyn FunctionContext) -> Result<$fty> {
+					Ok(self as $fty)
+				}
+			}
+		)*
+	}
+}
+
+impl_traits_for_primitives! {
+	u8, u32,
+	u16, u32,
+	u32, u32,
+	u64, u64,
+	i8, i32,
+	i16, i32,
+	i32, i32,
+	i64, i64,
+}
+
+/// `bool` is passed as `u32`.
+///
+/// - `1`: true
+/// - `0`: false
+impl RIType for bool {
+	type FFIType = u32;
+}
+
+#[cfg(not(feature = "std"))]
+impl IntoFFIValue for bool {
+	type Owned = ();
+
+	fn into_ffi_value(&self) -> WrappedFFIValue<u32> {
+		(if *self { 1 } else { 0 }).into()
+	}
+}
+
+#[cfg(not(feature = "std"))]
+impl FromFFIValue for bool {
+	fn from_ffi_value(arg: u32) -> bool {
+		arg == 1
+	}
+}
+
+#[cfg(feature = "std")]
+impl FromFFIValue for bool {
+	type SelfInstance = bool;
+
+	fn from_ffi_value(_: &mut dyn FunctionContext, arg: u32) -> Result<bool> {
+		Ok(arg == 1)
+	}
+}
+
+#[cfg(feature = "std")]
+impl IntoFFIValue for bool {
+	fn into_ffi_value(self, _: &mut dyn FunctionContext) -> Result<u32> {
+		Ok(if self { 1 } else { 0 })
+	}
+}
+
+/// The type is passed as `u64`.
+///
+/// The `u64` value is build by `length 32bit << 32 | pointer 32bit`.
+impl RIType for [u8] {
+	type FFIType = u64;
+}
+
+#[cfg(not(feature = "std"))]
+impl IntoFFIValue for [u8] {
+	type Owned = ();
+
+	fn into_ffi_value(&self) -> WrappedFFIValue<u64> {
+		pack_ptr_and_len(self.as_ptr() as u32, self.len() as u32).into()
+	}
+}
+
+#[cfg(feature = "std")]
+impl FromFFIValue for [u8] {
+	type SelfInstance = Vec<u8>;
+
+	fn from_ffi_value(context: &mut dyn FunctionContext, arg: u64) -> Result<Vec<u8>> {
+		let (ptr, len) = unpack_ptr_and_len(arg);
+		context.read_memory(ptr, len)
+	}
+}
+
+/// The type is passed as `u64`, in the same way as `[u8]`.
+///
+/// The host rejects data that is not valid utf8.
+impl RIType for str {
+	type FFIType = u64;
+}
+
+#[cfg(not(feature = "std"))]
+impl IntoFFIValue for str {
+	type Owned = ();
+
+	fn into_ffi_value(&self) -> WrappedFFIValue<u64> {
+		self.as_bytes().into_ffi_value()
+	}
+}
+
+#[cfg(feature = "std")]
+impl FromFFIValue for str {
+	type SelfInstance = String;
+
+	fn from_ffi_value(context: &mut dyn FunctionContext, arg: u64) -> Result<String> {
+		let data = <[u8] as FromFFIValue>::from_ffi_value(context, arg)?;
+		String::from_utf8(data).map_err(|_| "Invalid utf8 data provided".into())
+	}
+}
+
+/// The type is passed as `u64`, in the same way as `[u8]`.
+///
+/// When returned from the host, the data is allocated in the wasm memory and owned by the runtime
+/// afterwards.
+impl RIType for Vec<u8> {
+	type FFIType = u64;
+}
+
+#[cfg(not(feature = "std"))]
+impl IntoFFIValue for Vec<u8> {
+	type Owned = ();
+
+	fn into_ffi_value(&self) -> WrappedFFIValue<u64> {
+		self[..].into_ffi_value()
+	}
+}
+
+#[cfg(not(feature = "std"))]
+impl FromFFIValue for Vec<u8> {
+	fn from_ffi_value(arg: u64) -> Vec<u8> {
+		let (ptr, len) = unpack_ptr_and_len(arg);
+
+		if len == 0 {
+			Vec::new()
+		} else {
+			unsafe { Vec::from_raw_parts(ptr as *mut u8, len as usize, len as usize) }
+		}
+	}
+}
+
+#[cfg(feature = "std")]
+impl FromFFIValue for Vec<u8> {
+	type SelfInstance = Vec<u8>;
+
+	fn from_ffi_value(context: &mut dyn FunctionContext, arg: u64) -> Result<Vec<u8>> {
+		<[u8] as FromFFIValue>::from_ffi_value(context, arg)
+	}
+}
+
+#[cfg(feature = "std")]
+impl IntoFFIValue for Vec<u8> {
+	fn into_ffi_value(self, context: &mut dyn FunctionContext) -> Result<u64> {
+		write_to_wasm(context, &self)
+	}
+}
+
+/// Write the given data into a new allocation in the wasm memory and return the packed pointer
+/// and length. Empty data is not allocated.
+#[cfg(feature = "std")]
+fn write_to_wasm(context: &mut dyn FunctionContext, data: &[u8]) -> Result<u64> {
+	if data.is_empty() {
+		return Ok(pack_ptr_and_len(0, 0));
+	}
+
+	let ptr = context.allocate_memory(data.len() as u32)?;
+	context.write_memory(ptr, data)?;
+	Ok(pack_ptr_and_len(ptr, data.len() as u32))
+}
+
+/// Implements the runtime interface traits for types that are passed SCALE encoded as `u64`, in
+/// the same way as `Vec<u8>`.
+macro_rules! impl_traits_for_codec {
+	(
+		$(
+			impl < $( $param:ident ),* > for $rty:ty;
+		)*
+	) => {
+		$(
+			impl< $( $param: Codec ),* > RIType for $rty {
+				type FFIType = u64;
+			}
+
+			#[cfg(not(feature = "std"))]
+			impl< $( $param: Codec ),* > IntoFFIValue for $rty {
+				type Owned = Vec<u8>;
+
+				fn into_ffi_value(&self) -> WrappedFFIValue<u64, Vec<u8>> {
+					let encoded = self.encode();
+					let ptr_len = pack_ptr_and_len(encoded.as_ptr() as u32, encoded.len() as u32);
+
+					(ptr_len, encoded).into()
+				}
+			}
+
+			#[cfg(not(feature = "std"))]
+			impl< $( $param: Codec ),* > FromFFIValue for $rty {
+				fn from_ffi_value(arg: u64) -> $rty {
+					let encoded = <Vec<u8> as FromFFIValue>::from_ffi_value(arg);
+
+					<$rty>::decode(&mut &encoded[..])
+						.expect("Host to wasm values are encoded correctly; qed")
+				}
+			}
+
+			#[cfg(feature = "std")]
+			impl< $( $param: Codec ),* > FromFFIValue for $rty {
+				type SelfInstance = $rty;
+
+				fn from_ffi_value(context: &mut dyn FunctionContext, arg: u64) -> Result<$rty> {
+					let encoded = <[u8] as FromFFIValue>::from_ffi_value(context, arg)?;
+
+					<$rty>::decode(&mut &encoded[..])
+						.map_err(|e| format!("Could not decode value from wasm: {}", e.what()))
+				}
+			}
+
+			#[cfg(feature = "std")]
+			impl< $( $param: Codec ),* > IntoFFIValue for $rty {
+				fn into_ffi_value(self, context: &mut dyn FunctionContext) -> Result<u64> {
+					write_to_wasm(context, &self.encode())
+				}
+			}
+		)*
+	}
+}
+
+impl_traits_for_codec! {
+	impl<T> for Option<T>;
+	impl<T, E> for rstd::result::Result<T, E>;
+	impl<> for Vec<[u8; 32]>;
+	impl<> for Vec<(Vec<u8>, Vec<u8>)>;
+	impl<> for primitives::crypto::KeyTypeId;
+	impl<> for primitives::offchain::StorageKind;
+	impl<> for primitives::offchain::Timestamp;
+	impl<> for primitives::offchain::HttpRequestId;
+	impl<> for Vec<primitives::offchain::HttpRequestId>;
+	impl<> for Vec<primitives::offchain::HttpRequestStatus>;
+}
+
+/// Implements the runtime interface traits for fixed size byte arrays.
+///
+/// The array is passed as a pointer (`u32`) to its first element, the length is known on both
+/// sides.
+macro_rules! impl_traits_for_arrays {
+	(
+		$(
+			$n:expr
+		),*
+		$(,)?
+	) => {
+		$(
+			impl RIType for [u8; $n] {
+				type FFIType = u32;
+			}
+
+			#[cfg(not(feature = "std"))]
+			impl IntoFFIValue for [u8; $n] {
+				type Owned = ();
+
+				fn into_ffi_value(&self) -> WrappedFFIValue<u32> {
+					(self.as_ptr() as u32).into()
+				}
+			}
+
+			#[cfg(not(feature = "std"))]
+			impl FromFFIValue for [u8; $n] {
+				fn from_ffi_value(arg: u32) -> [u8; $n] {
+					let data = unsafe { Vec::from_raw_parts(arg as *mut u8, $n, $n) };
+					let mut res = [0u8; $n];
+					res.copy_from_slice(&data);
+
+					res
+				}
+			}
+
+			#[cfg(feature = "std")]
+			impl FromFFIValue for [u8; $n] {
+				type SelfInstance = [u8; $n];
+
+				fn from_ffi_value(context: &mut dyn FunctionContext, arg: u32) -> Result<[u8; $n]> {
+					let data = context.read_memory(arg, $n)?;
+					let mut res = [0u8; $n];
+					res.copy_from_slice(&data);
+
+					Ok(res)
+				}
+			}
+
+			#[cfg(feature = "std")]
+			impl IntoFFIValue for [u8; $n] {
+				fn into_ffi_value(self, context: &mut dyn FunctionContext) -> Result<u32> {
+					let ptr = context.allocate_memory($n)?;
+					context.write_memory(ptr, &self)?;
+
+					Ok(ptr)
+				}
+			}
+		)*
+	}
+}
+
+impl_traits_for_arrays! { 8, 16, 20, 32, 33, 64, 65 }
+
+/// References are passed in the same way as the type they reference.
+impl<T: RIType + ?Sized> RIType for &T {
+	type FFIType = T::FFIType;
+}
+
+#[cfg(not(feature = "std"))]
+impl<T: IntoFFIValue + ?Sized> IntoFFIValue for &T {
+	type Owned = T::Owned;
+
+	fn into_ffi_value(&self) -> WrappedFFIValue<T::FFIType, T::Owned> {
+		(**self).into_ffi_value()
+	}
+}
+
+#[cfg(feature = "std")]
+impl<T: FromFFIValue + ?Sized> FromFFIValue for &T {
+	type SelfInstance = T::SelfInstance;
+
+	fn from_ffi_value(context: &mut dyn FunctionContext, arg: T::FFIType) -> Result<T::SelfInstance> {
+		T::from_ffi_value(context, arg)
+	}
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+	use super::*;
+
+	/// A `FunctionContext` that operates on a plain vector and allocates by appending to it.
+	struct TestContext(Vec<u8>);
+
+	impl FunctionContext for TestContext {
+		fn read_memory(&self, address: u32, size: u32) -> Result<Vec<u8>> {
+			let (start, end) = (address as usize, address as usize + size as usize);
+			self.0.get(start..end).map(|d| d.to_vec()).ok_or_else(|| "Out of bounds".into())
+		}
+
+		fn write_memory(&mut self, address: u32, data: &[u8]) -> Result<()> {
+			let (start, end) = (address as usize, address as usize + data.len());
+			self.0.get_mut(start..end)
+				.ok_or_else(|| "Out of bounds".to_string())?
+				.copy_from_slice(data);
+			Ok(())
+		}
+
+		fn allocate_memory(&mut self, size: u32) -> Result<u32> {
+			let ptr = self.0.len() as u32;
+			self.0.resize(self.0.len() + size as usize, 0);
+			Ok(ptr)
+		}
+
+		fn deallocate_memory(&mut self, _: u32) -> Result<()> {
+			Ok(())
+		}
+
+		fn sandbox(&mut self) -> &mut dyn crate::host::Sandbox {
+			unimplemented!("The sandbox is not used by these tests")
+		}
+	}
+
+	#[test]
+	fn pack_and_unpack_ptr_and_len() {
+		let packed = pack_ptr_and_len(0xdead_beef, 42);
+		assert_eq!(unpack_ptr_and_len(packed), (0xdead_beef, 42));
+	}
+
+	#[test]
+	fn codec_values_roundtrip_through_wasm_memory() {
+		let mut context = TestContext(vec![0; 8]);
+		let value = Some(vec![1u8, 2, 3]);
+
+		let ffi = value.clone().into_ffi_value(&mut context).unwrap();
+		let decoded = <Option<Vec<u8>> as FromFFIValue>::from_ffi_value(&mut context, ffi).unwrap();
+		assert_eq!(decoded, value);
+	}
+
+	#[test]
+	fn empty_vec_is_not_allocated() {
+		let mut context = TestContext(Vec::new());
+
+		let ffi = Vec::<u8>::new().into_ffi_value(&mut context).unwrap();
+		assert_eq!(ffi, 0);
+		assert!(context.0.is_empty());
+	}
+
+	#[test]
+	fn arrays_roundtrip_through_wasm_memory() {
+		let mut context = TestContext(Vec::new());
+
+		let ptr = [7u8; 32].into_ffi_value(&mut context).unwrap();
+		assert_eq!(<&[u8; 32] as FromFFIValue>::from_ffi_value(&mut context, ptr).unwrap(), [7u8; 32]);
+	}
+}
@@ -0,0 +1,91 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Types, traits and macros for declaring runtime interfaces.
+//!
+//! A runtime interface is the set of functions a Substrate node exposes to the wasm runtime. It is
+//! declared once as a Rust trait with the [`runtime_interface`] attribute macro, which generates:
+//!
+//! - the functions the runtime calls. With `std`, they call the trait implementation for the
+//!   current [`Externalities`] directly. Without `std`, they call the imported host function.
+//! - the host functions the executor registers for the wasm runtime, available through the
+//!   generated `HostFunctions` type.
+//!
+//! Every function is versioned. The name of the host function is
+//! `ext_{interface}_{function}_version_{version}`, so a new version of a function can be added
+//! without breaking runtimes that were compiled against an older version.
+//!
+//! Arguments and return values are passed between the runtime and the host as described by the
+//! [`RIType`] implementation of their type. Primitive integers are passed as they are, byte slices
+//! and vectors as a pointer and a length, and other supported types are SCALE encoded.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![warn(missing_docs)]
+
+#[doc(hidden)]
+pub use rstd;
+
+#[doc(hidden)]
+pub use codec;
+
+pub use runtime_interface_proc_macro::runtime_interface;
+
+#[cfg(feature = "std")]
+pub use state_machine::Externalities;
+
+#[cfg(feature = "std")]
+pub use primitives::Blake2Hasher;
+
+mod impls;
+
+#[cfg(feature = "std")]
+pub mod host;
+
+#[cfg(not(feature = "std"))]
+pub mod wasm;
+
+pub use impls::{pack_ptr_and_len, unpack_ptr_and_len};
+
+/// Something that can be used as an argument or return value of a runtime interface function.
+///
+/// `FFIType` is the type that is actually passed over the boundary between the runtime and the
+/// host.
+pub trait RIType {
+	/// The ffi type that is used to represent `Self`.
+	type FFIType;
+}
+
+/// Access to the [`Externalities`] of the current call on the host side.
+#[cfg(feature = "std")]
+pub mod externalities {
+	use super::{Externalities, Blake2Hasher};
+
+	environmental::environmental!(ext: trait Externalities<Blake2Hasher>);
+
+	/// Execute the given closure with the currently set externalities.
+	///
+	/// Returns `None` if no externalities are set.
+	pub fn with<R, F: FnOnce(&mut (dyn Externalities<Blake2Hasher> + 'static)) -> R>(
+		f: F,
+	) -> Option<R> {
+		ext::with(f)
+	}
+
+	/// Set the given externalities while executing the given closure.
+	pub fn using<R, F: FnOnce() -> R>(ext: &mut dyn Externalities<Blake2Hasher>, f: F) -> R {
+		ext::using(ext, f)
+	}
+}
@@ -167,6 +167,7 @@ where TGen: Serialize + DeserializeOwned + BuildStorage {
 				config.state_cache_child_ratio.map(|v| (v, 100)),
 			path: config.database_path.clone(),
 			pruning: config.pruning.clone(),
+			event_topics_key_prefix: config.event_topics_key_prefix.clone(),
 		};
 
 		let executor = NativeExecutor::<TExecDisp>::new(config.default_heap_pages);
@@ -230,6 +231,7 @@ where TGen: Serialize + DeserializeOwned + BuildStorage {
 				config.state_cache_child_ratio.map(|v| (v, 100)),
 			path: config.database_path.clone(),
 			pruning: config.pruning.clone(),
+			event_topics_key_prefix: None,
 		};
 
 		let executor = NativeExecutor::<TExecDisp>::new(config.default_heap_pages);
@@ -56,6 +56,9 @@ pub struct Configuration<C, G> {
 	pub state_cache_child_ratio: Option<usize>,
 	/// Pruning settings.
 	pub pruning: PruningMode,
+	/// Storage key prefix of the runtime's map of event topics, used to index the events of the
+	/// imported blocks by topic. `None` disables the index.
+	pub event_topics_key_prefix: Option<Vec<u8>>,
 	/// Chain configuration.
 	pub chain_spec: ChainSpec<G>,
 	/// Custom configuration.
@@ -72,6 +75,8 @@ pub struct Configuration<C, G> {
 	pub rpc_ws_max_connections: Option<usize>,
 	/// CORS settings for HTTP & WS servers. `None` if all origins are allowed.
 	pub rpc_cors: Option<Vec<String>>,
+	/// Prometheus endpoint binding address. `None` if disabled.
+	pub prometheus_endpoint: Option<SocketAddr>,
 	/// Telemetry service URL. `None` if disabled.
 	pub telemetry_endpoints: Option<TelemetryEndpoints>,
 	/// External WASM transport for the telemetry. If `Some`, when connection to a telemetry
@@ -114,11 +119,13 @@ impl<C: Default, G: Serialize + DeserializeOwned + BuildStorage> Configuration<C
 			state_cache_child_ratio: Default::default(),
 			custom: Default::default(),
 			pruning: PruningMode::default(),
+			event_topics_key_prefix: None,
 			execution_strategies: Default::default(),
 			rpc_http: None,
 			rpc_ws: None,
 			rpc_ws_max_connections: None,
 			rpc_cors: Some(vec![]),
+			prometheus_endpoint: None,
 			telemetry_endpoints: None,
 			telemetry_external_transport: None,
 			default_heap_pages: None,
@@ -357,6 +357,13 @@ macro_rules! new_impl {
 		let rpc_handlers = gen_handler();
 		let rpc = start_rpc_servers(&$config, gen_handler)?;
 
+		// Prometheus endpoint
+		if let Some(prometheus) = start_prometheus(&$config)? {
+			let _ = to_spawn_tx.unbounded_send(Box::new(prometheus
+				.select(exit.clone())
+				.then(|_| Ok(()))));
+		}
+
 		let _ = to_spawn_tx.unbounded_send(Box::new(build_network_future(
 			network_mut,
 			client.clone(),
@@ -829,6 +836,29 @@ fn start_rpc_servers<C, G, H: FnMut() -> components::RpcHandler>(
 	Ok(Box::new(()))
 }
 
+/// Starts the Prometheus endpoint if it is enabled, and returns the future serving it.
+#[cfg(not(target_os = "unknown"))]
+fn start_prometheus<C, G>(
+	config: &Configuration<C, G>,
+) -> Result<Option<Box<dyn Future<Item = (), Error = ()> + Send>>, error::Error> {
+	Ok(match config.prometheus_endpoint {
+		Some(address) => {
+			let endpoint = substrate_prometheus::init_prometheus(address)
+				.map_err(|e| format!("Failed to start the Prometheus endpoint on {}: {}", address, e))?;
+			Some(Box::new(endpoint))
+		},
+		None => None,
+	})
+}
+
+/// Starts the Prometheus endpoint if it is enabled, and returns the future serving it.
+#[cfg(target_os = "unknown")]
+fn start_prometheus<C, G>(
+	_: &Configuration<C, G>,
+) -> Result<Option<Box<dyn Future<Item = (), Error = ()> + Send>>, error::Error> {
+	Ok(None)
+}
+
 /// An RPC session. Used to perform in-memory RPC queries (ie. RPC queries that don't go through
 /// the HTTP or WebSockets server).
 pub struct RpcSession {
@@ -0,0 +1,148 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Host side of batch signature verification.
+//!
+//! A batch is started and finished by the runtime on the thread that executes it. Signatures that
+//! are pushed in between are verified in chunks by a bounded number of background threads while
+//! the runtime keeps executing.
+
+use std::{
+	cell::RefCell, mem, sync::{Arc, Mutex, mpsc},
+	thread::{self, JoinHandle},
+};
+use primitives::{ed25519, sr25519, Pair};
+
+/// Number of signatures that are verified together by a background thread.
+const CHUNK_SIZE: usize = 64;
+
+/// Maximum number of background threads of one batch.
+const MAX_WORKERS: usize = 4;
+
+/// A signature waiting for verification, together with its signer and message.
+enum Item {
+	Ed25519(ed25519::Signature, ed25519::Public, Vec<u8>),
+	Sr25519(sr25519::Signature, sr25519::Public, Vec<u8>),
+}
+
+impl Item {
+	fn verify(&self) -> bool {
+		match self {
+			Item::Ed25519(sig, pubkey, msg) => ed25519::Pair::verify(sig, msg, pubkey),
+			Item::Sr25519(sig, pubkey, msg) => sr25519::Pair::verify(sig, msg, pubkey),
+		}
+	}
+}
+
+/// Signatures of the current batch.
+struct BatchVerifier {
+	pending: Vec<Item>,
+	sender: mpsc::Sender<Vec<Item>>,
+	receiver: Arc<Mutex<mpsc::Receiver<Vec<Item>>>>,
+	workers: Vec<JoinHandle<bool>>,
+}
+
+impl Default for BatchVerifier {
+	fn default() -> Self {
+		let (sender, receiver) = mpsc::channel();
+		BatchVerifier {
+			pending: Vec::new(),
+			sender,
+			receiver: Arc::new(Mutex::new(receiver)),
+			workers: Vec::new(),
+		}
+	}
+}
+
+impl BatchVerifier {
+	fn push(&mut self, item: Item) {
+		self.pending.push(item);
+		if self.pending.len() >= CHUNK_SIZE {
+			let chunk = mem::replace(&mut self.pending, Vec::with_capacity(CHUNK_SIZE));
+			// the receiver outlives the sender, so sending can't fail.
+			let _ = self.sender.send(chunk);
+			if self.workers.len() < MAX_WORKERS {
+				let receiver = self.receiver.clone();
+				self.workers.push(thread::spawn(move || verify_chunks(&receiver)));
+			}
+		}
+	}
+
+	fn verify(self) -> bool {
+		let BatchVerifier { pending, sender, workers, .. } = self;
+		// let the workers finish once all queued chunks are verified.
+		drop(sender);
+		let pending_valid = pending.iter().all(Item::verify);
+		// always join every worker, so that no thread outlives the batch.
+		workers.into_iter().fold(pending_valid, |valid, worker| worker.join().unwrap_or(false) && valid)
+	}
+}
+
+/// Verify the chunks of `receiver` until its sender is dropped.
+fn verify_chunks(receiver: &Mutex<mpsc::Receiver<Vec<Item>>>) -> bool {
+	let mut valid = true;
+	loop {
+		let chunk = match receiver.lock() {
+			Ok(receiver) => receiver.recv(),
+			Err(_) => return false,
+		};
+		match chunk {
+			Ok(chunk) => valid = chunk.iter().all(Item::verify) && valid,
+			Err(_) => return valid,
+		}
+	}
+}
+
+thread_local! {
+	static BATCH: RefCell<Option<BatchVerifier>> = RefCell::new(None);
+}
+
+/// Start a new batch, discarding any batch that was not finished.
+pub fn start() {
+	BATCH.with(|batch| *batch.borrow_mut() = Some(BatchVerifier::default()));
+}
+
+/// Verify all signatures of the current batch and end it.
+///
+/// Returns `true` if there is no current batch.
+pub fn finish() -> bool {
+	BATCH.with(|batch| batch.borrow_mut().take()).map_or(true, BatchVerifier::verify)
+}
+
+/// Discard the current batch without verifying it.
+pub fn clear() {
+	BATCH.with(|batch| batch.borrow_mut().take());
+}
+
+/// Add an ed25519 signature to the current batch or verify it right away if there is none.
+pub fn push_ed25519(sig: ed25519::Signature, pubkey: ed25519::Public, msg: Vec<u8>) -> bool {
+	push(Item::Ed25519(sig, pubkey, msg))
+}
+
+/// Add an sr25519 signature to the current batch or verify it right away if there is none.
+pub fn push_sr25519(sig: sr25519::Signature, pubkey: sr25519::Public, msg: Vec<u8>) -> bool {
+	push(Item::Sr25519(sig, pubkey, msg))
+}
+
+fn push(item: Item) -> bool {
+	BATCH.with(|batch| match batch.borrow_mut().as_mut() {
+		Some(batch) => {
+			batch.push(item);
+			true
+		},
+		None => item.verify(),
+	})
+}
@@ -33,14 +33,18 @@ use rstd::vec::Vec;
 pub use codec;
 
 pub use primitives::Blake2Hasher;
+use runtime_interface::runtime_interface;
 use primitives::{
 	crypto::KeyTypeId, ed25519, sr25519,
 	offchain::{
 		Timestamp, HttpRequestId, HttpRequestStatus, HttpError, StorageKind, OpaqueNetworkState,
 	},
 };
+#[cfg(feature = "std")]
+use substrate_state_machine::ChildStorageKey;
 
 /// Error verifying ECDSA signature
+#[derive(codec::Encode, codec::Decode)]
 pub enum EcdsaVerifyError {
 	/// Incorrect value of R or S
 	BadRS,
@@ -52,6 +56,22 @@ pub enum EcdsaVerifyError {
 
 pub mod offchain;
 
+#[cfg(feature = "std")]
+mod batch_verifier;
+
+/// Returns a `ChildStorageKey` if the given `storage_key` slice is a valid storage
+/// key or panics otherwise.
+///
+/// Panicking here is aligned with what the `without_std` environment would do
+/// in the case of an invalid child storage key.
+#[cfg(feature = "std")]
+fn child_storage_key_or_panic(storage_key: &[u8]) -> ChildStorageKey<Blake2Hasher> {
+	match ChildStorageKey::from_slice(storage_key) {
+		Some(storage_key) => storage_key,
+		None => panic!("child storage key is invalid"),
+	}
+}
+
 /// Trait for things which can be printed.
 pub trait Printable {
 	/// Print the object.
@@ -144,6 +164,9 @@ export_api! {
 		/// Clear the child storage entries with a key that starts with the given prefix.
 		fn clear_child_prefix(storage_key: &[u8], prefix: &[u8]);
 
+		/// Get the next key in storage after the given one, in lexicographic order.
+		fn next_key(key: &[u8]) -> Option<Vec<u8>>;
+
 		/// "Commit" all existing operations and compute the resultant storage root.
 		fn storage_root() -> [u8; 32];
 
@@ -384,6 +407,587 @@ export_api! {
 	}
 }
 
+/// Interface for accessing the storage from within the runtime.
+#[runtime_interface]
+pub trait Storage {
+	/// Returns the data for `key` in the storage or `None` if the key can not be found.
+	fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+		self.storage(key)
+	}
+
+	/// Set `key` to `value` in the storage.
+	fn set(&mut self, key: &[u8], value: &[u8]) {
+		self.set_storage(key.to_vec(), value.to_vec());
+	}
+
+	/// Clear the storage of the given `key` and its value.
+	fn clear(&mut self, key: &[u8]) {
+		self.clear_storage(key)
+	}
+
+	/// Check whether the given `key` exists in storage.
+	fn exists(&self, key: &[u8]) -> bool {
+		self.exists_storage(key)
+	}
+
+	/// Clear the storage of each key-value pair where the key starts with the given `prefix`.
+	fn clear_prefix(&mut self, prefix: &[u8]) {
+		self.clear_prefix(prefix)
+	}
+
+	/// Get the next key in storage after the given one, in lexicographic order.
+	fn next_key(&self, key: &[u8]) -> Option<Vec<u8>> {
+		self.next_storage_key(key)
+	}
+
+	/// The current storage root.
+	fn root(&mut self) -> [u8; 32] {
+		self.storage_root().into()
+	}
+
+	/// The current storage change root, `None` if changes tries are disabled.
+	fn changes_root(&mut self, parent_hash: [u8; 32]) -> Option<[u8; 32]> {
+		self.storage_changes_root(parent_hash.into())
+			.expect("Invalid parent hash passed to storage_changes_root")
+			.map(Into::into)
+	}
+}
+
+/// Interface for accessing the child storage from within the runtime.
+///
+/// Every function panics if `storage_key` is not a valid child storage key.
+#[runtime_interface]
+pub trait ChildStorage {
+	/// Returns the data for `key` in the child storage `storage_key` or `None` if the key can not
+	/// be found.
+	fn get(&self, storage_key: &[u8], key: &[u8]) -> Option<Vec<u8>> {
+		self.child_storage(child_storage_key_or_panic(storage_key), key)
+	}
+
+	/// Set `key` to `value` in the child storage `storage_key`.
+	fn set(&mut self, storage_key: &[u8], key: &[u8], value: &[u8]) {
+		self.set_child_storage(child_storage_key_or_panic(storage_key), key.to_vec(), value.to_vec());
+	}
+
+	/// Clear the given `key` and its value in the child storage `storage_key`.
+	fn clear(&mut self, storage_key: &[u8], key: &[u8]) {
+		self.clear_child_storage(child_storage_key_or_panic(storage_key), key)
+	}
+
+	/// Clear the entire child storage `storage_key`.
+	fn kill(&mut self, storage_key: &[u8]) {
+		self.kill_child_storage(child_storage_key_or_panic(storage_key))
+	}
+
+	/// Check whether the given `key` exists in the child storage `storage_key`.
+	fn exists(&self, storage_key: &[u8], key: &[u8]) -> bool {
+		self.exists_child_storage(child_storage_key_or_panic(storage_key), key)
+	}
+
+	/// Clear the child storage `storage_key` of each key-value pair where the key starts with the
+	/// given `prefix`.
+	fn clear_prefix(&mut self, storage_key: &[u8], prefix: &[u8]) {
+		self.clear_child_prefix(child_storage_key_or_panic(storage_key), prefix)
+	}
+
+	/// The current root of the child storage `storage_key`.
+	fn root(&mut self, storage_key: &[u8]) -> Vec<u8> {
+		self.child_storage_root(child_storage_key_or_panic(storage_key))
+	}
+}
+
+/// Interface that provides functions for hashing with different algorithms.
+#[runtime_interface]
+pub trait Hashing {
+	/// Conduct a 256-bit Keccak hash.
+	fn keccak_256(data: &[u8]) -> [u8; 32] {
+		primitives::keccak_256(data)
+	}
+
+	/// Conduct a 512-bit Keccak hash.
+	fn keccak_512(data: &[u8]) -> [u8; 64] {
+		primitives::keccak_512(data)
+	}
+
+	/// Conduct a 256-bit Sha2 hash.
+	fn sha2_256(data: &[u8]) -> [u8; 32] {
+		primitives::sha2_256(data)
+	}
+
+	/// Conduct a 128-bit Blake2 hash.
+	fn blake2_128(data: &[u8]) -> [u8; 16] {
+		primitives::blake2_128(data)
+	}
+
+	/// Conduct a 256-bit Blake2 hash.
+	fn blake2_256(data: &[u8]) -> [u8; 32] {
+		primitives::blake2_256(data)
+	}
+
+	/// Conduct four XX hashes to give a 256-bit result.
+	fn twox_256(data: &[u8]) -> [u8; 32] {
+		primitives::twox_256(data)
+	}
+
+	/// Conduct two XX hashes to give a 128-bit result.
+	fn twox_128(data: &[u8]) -> [u8; 16] {
+		primitives::twox_128(data)
+	}
+
+	/// Conduct two XX hashes to give a 64-bit result.
+	fn twox_64(data: &[u8]) -> [u8; 8] {
+		primitives::twox_64(data)
+	}
+}
+
+/// Interface that provides miscellaneous functions for communicating between the runtime and the
+/// node.
+#[runtime_interface]
+pub trait Misc {
+	/// Print a number.
+	fn print_num(val: u64) {
+		println!("Runtime: {}", val);
+	}
+
+	/// Print any valid `utf8` buffer.
+	fn print_utf8(utf8: &[u8]) {
+		if let Ok(data) = std::str::from_utf8(utf8) {
+			println!("Runtime: {}", data)
+		}
+	}
+
+	/// Print any `u8` slice as hex.
+	fn print_hex(data: &[u8]) {
+		println!("Runtime: {}", primitives::hexdisplay::HexDisplay::from(&data));
+	}
+}
+
+/// Interface that provides functions for the keystore, signature verification and batch
+/// verification of signatures.
+///
+/// Between `start_batch_verify` and `finish_batch_verify` the `*_batch_verify` functions only queue
+/// the given signature and return `true`. The queued signatures are verified together, possibly in
+/// parallel, and `finish_batch_verify` returns whether all of them were valid. Without a started
+/// batch, the `*_batch_verify` functions verify the signature right away.
+#[runtime_interface]
+pub trait Crypto {
+	/// Returns all ed25519 public keys for the given key id from the keystore.
+	fn ed25519_public_keys(&self, id: KeyTypeId) -> Vec<[u8; 32]> {
+		self.keystore()
+			.expect("No `keystore` associated for the current context!")
+			.read()
+			.ed25519_public_keys(id)
+			.into_iter()
+			.map(Into::into)
+			.collect()
+	}
+
+	/// Generate an ed25519 key for the given key type, using the given utf8 `seed`, and store it
+	/// in the keystore.
+	///
+	/// Returns the raw public key.
+	fn ed25519_generate(&self, id: KeyTypeId, seed: Option<Vec<u8>>) -> [u8; 32] {
+		let seed = seed.map(|s| String::from_utf8(s).expect("Seed is valid utf8!"));
+		self.keystore()
+			.expect("No `keystore` associated for the current context!")
+			.write()
+			.ed25519_generate_new(id, seed.as_ref().map(|s| s.as_str()))
+			.expect("`ed25519_generate` failed")
+			.into()
+	}
+
+	/// Sign the given `msg` with the ed25519 key that corresponds to the given public key and key
+	/// type in the keystore.
+	///
+	/// Returns the raw signature.
+	fn ed25519_sign(&self, id: KeyTypeId, pubkey: &[u8; 32], msg: &[u8]) -> Option<[u8; 64]> {
+		self.keystore()
+			.expect("No `keystore` associated for the current context!")
+			.read()
+			.ed25519_key_pair(id, &ed25519::Public::from_raw(*pubkey))
+			.map(|k| primitives::Pair::sign(&k, msg).into())
+	}
+
+	/// Verify an ed25519 signature.
+	///
+	/// Returns `true` when the verification is successful.
+	fn ed25519_verify(sig: &[u8; 64], msg: &[u8], pubkey: &[u8; 32]) -> bool {
+		<ed25519::Pair as primitives::Pair>::verify(
+			&ed25519::Signature::from_raw(*sig),
+			msg,
+			&ed25519::Public::from_raw(*pubkey),
+		)
+	}
+
+	/// Returns all sr25519 public keys for the given key id from the keystore.
+	fn sr25519_public_keys(&self, id: KeyTypeId) -> Vec<[u8; 32]> {
+		self.keystore()
+			.expect("No `keystore` associated for the current context!")
+			.read()
+			.sr25519_public_keys(id)
+			.into_iter()
+			.map(Into::into)
+			.collect()
+	}
+
+	/// Generate an sr25519 key for the given key type, using the given utf8 `seed`, and store it
+	/// in the keystore.
+	///
+	/// Returns the raw public key.
+	fn sr25519_generate(&self, id: KeyTypeId, seed: Option<Vec<u8>>) -> [u8; 32] {
+		let seed = seed.map(|s| String::from_utf8(s).expect("Seed is valid utf8!"));
+		self.keystore()
+			.expect("No `keystore` associated for the current context!")
+			.write()
+			.sr25519_generate_new(id, seed.as_ref().map(|s| s.as_str()))
+			.expect("`sr25519_generate` failed")
+			.into()
+	}
+
+	/// Sign the given `msg` with the sr25519 key that corresponds to the given public key and key
+	/// type in the keystore.
+	///
+	/// Returns the raw signature.
+	fn sr25519_sign(&self, id: KeyTypeId, pubkey: &[u8; 32], msg: &[u8]) -> Option<[u8; 64]> {
+		self.keystore()
+			.expect("No `keystore` associated for the current context!")
+			.read()
+			.sr25519_key_pair(id, &sr25519::Public::from_raw(*pubkey))
+			.map(|k| primitives::Pair::sign(&k, msg).into())
+	}
+
+	/// Verify an sr25519 signature.
+	///
+	/// Returns `true` when the verification is successful.
+	fn sr25519_verify(sig: &[u8; 64], msg: &[u8], pubkey: &[u8; 32]) -> bool {
+		<sr25519::Pair as primitives::Pair>::verify(
+			&sr25519::Signature::from_raw(*sig),
+			msg,
+			&sr25519::Public::from_raw(*pubkey),
+		)
+	}
+
+	/// Verify and recover a SECP256k1 ECDSA signature.
+	///
+	/// - `sig` is passed in RSV format. V should be either 0/1 or 27/28.
+	/// - returns `Err` if the signature is bad, otherwise the 64-byte pubkey (doesn't include the
+	///   0x04 prefix).
+	fn secp256k1_ecdsa_recover(sig: &[u8; 65], msg: &[u8; 32]) -> Result<[u8; 64], EcdsaVerifyError> {
+		let rs = secp256k1::Signature::parse_slice(&sig[0..64])
+			.map_err(|_| EcdsaVerifyError::BadRS)?;
+		let v = secp256k1::RecoveryId::parse(if sig[64] > 26 { sig[64] - 27 } else { sig[64] } as u8)
+			.map_err(|_| EcdsaVerifyError::BadV)?;
+		let pubkey = secp256k1::recover(&secp256k1::Message::parse(msg), &rs, &v)
+			.map_err(|_| EcdsaVerifyError::BadSignature)?;
+		let mut res = [0u8; 64];
+		res.copy_from_slice(&pubkey.serialize()[1..65]);
+		Ok(res)
+	}
+
+	/// Start a new batch of signature verifications.
+	///
+	/// A batch that was started before and not finished is discarded.
+	fn start_batch_verify() {
+		batch_verifier::start()
+	}
+
+	/// Queue an ed25519 signature for verification in the current batch.
+	///
+	/// Returns the result of the verification if there is no current batch and `true` otherwise.
+	fn ed25519_batch_verify(sig: &[u8; 64], msg: &[u8], pubkey: &[u8; 32]) -> bool {
+		batch_verifier::push_ed25519(
+			ed25519::Signature::from_raw(*sig),
+			ed25519::Public::from_raw(*pubkey),
+			msg.to_vec(),
+		)
+	}
+
+	/// Queue an sr25519 signature for verification in the current batch.
+	///
+	/// Returns the result of the verification if there is no current batch and `true` otherwise.
+	fn sr25519_batch_verify(sig: &[u8; 64], msg: &[u8], pubkey: &[u8; 32]) -> bool {
+		batch_verifier::push_sr25519(
+			sr25519::Signature::from_raw(*sig),
+			sr25519::Public::from_raw(*pubkey),
+			msg.to_vec(),
+		)
+	}
+
+	/// Verify all signatures of the current batch and end it.
+	///
+	/// Returns `true` if all signatures are valid or if no batch was started.
+	fn finish_batch_verify() -> bool {
+		batch_verifier::finish()
+	}
+}
+
+/// Interface that provides functions to access the offchain functionality.
+///
+/// These functions can only be called from an offchain worker, they panic otherwise.
+#[runtime_interface]
+pub trait OffchainWorker {
+	/// Returns if the local node is a potential validator.
+	///
+	/// Even if this function returns `true`, it does not mean that any keys are configured
+	/// and that the validator is registered in the chain.
+	fn is_validator(&mut self) -> bool {
+		self.offchain()
+			.expect("is_validator can be called only in the offchain worker context")
+			.is_validator()
+	}
+
+	/// Submit an encoded transaction to the pool.
+	///
+	/// The transaction will end up in the pool.
+	fn submit_transaction(&mut self, data: Vec<u8>) -> Result<(), ()> {
+		self.offchain()
+			.expect("submit_transaction can be called only in the offchain worker context")
+			.submit_transaction(data)
+	}
+
+	/// Returns information about the local node's network state.
+	fn network_state(&mut self) -> Result<OpaqueNetworkState, ()> {
+		self.offchain()
+			.expect("network_state can be called only in the offchain worker context")
+			.network_state()
+	}
+
+	/// Returns current UNIX timestamp (in millis)
+	fn timestamp(&mut self) -> Timestamp {
+		self.offchain()
+			.expect("timestamp can be called only in the offchain worker context")
+			.timestamp()
+	}
+
+	/// Pause the execution until `deadline` is reached.
+	fn sleep_until(&mut self, deadline: Timestamp) {
+		self.offchain()
+			.expect("sleep_until can be called only in the offchain worker context")
+			.sleep_until(deadline)
+	}
+
+	/// Returns a random seed.
+	///
+	/// This is a truly random non deterministic seed generated by host environment.
+	/// Obviously fine in the off-chain worker context.
+	fn random_seed(&mut self) -> [u8; 32] {
+		self.offchain()
+			.expect("random_seed can be called only in the offchain worker context")
+			.random_seed()
+	}
+
+	/// Sets a value in the local storage.
+	///
+	/// Note this storage is not part of the consensus, it's only accessible by
+	/// offchain worker tasks running on the same machine. It IS persisted between runs.
+	fn local_storage_set(&mut self, kind: StorageKind, key: &[u8], value: &[u8]) {
+		self.offchain()
+			.expect("local_storage_set can be called only in the offchain worker context")
+			.local_storage_set(kind, key, value)
+	}
+
+	/// Sets a value in the local storage if it matches current value.
+	///
+	/// Returns `true` if the value has been set, `false` otherwise.
+	fn local_storage_compare_and_set(
+		&mut self,
+		kind: StorageKind,
+		key: &[u8],
+		old_value: Option<Vec<u8>>,
+		new_value: &[u8],
+	) -> bool {
+		self.offchain()
+			.expect("local_storage_compare_and_set can be called only in the offchain worker context")
+			.local_storage_compare_and_set(kind, key, old_value.as_ref().map(|v| &v[..]), new_value)
+	}
+
+	/// Gets a value from the local storage.
+	///
+	/// If the value does not exist in the storage `None` will be returned.
+	fn local_storage_get(&mut self, kind: StorageKind, key: &[u8]) -> Option<Vec<u8>> {
+		self.offchain()
+			.expect("local_storage_get can be called only in the offchain worker context")
+			.local_storage_get(kind, key)
+	}
+
+	/// Initiates a http request given HTTP verb and the URL.
+	///
+	/// Meta is a future-reserved field containing additional, parity-scale-codec encoded parameters.
+	/// Returns the id of newly started request.
+	fn http_request_start(
+		&mut self,
+		method: &str,
+		uri: &str,
+		meta: &[u8],
+	) -> Result<HttpRequestId, ()> {
+		self.offchain()
+			.expect("http_request_start can be called only in the offchain worker context")
+			.http_request_start(method, uri, meta)
+	}
+
+	/// Append header to the request.
+	fn http_request_add_header(
+		&mut self,
+		request_id: HttpRequestId,
+		name: &str,
+		value: &str,
+	) -> Result<(), ()> {
+		self.offchain()
+			.expect("http_request_add_header can be called only in the offchain worker context")
+			.http_request_add_header(request_id, name, value)
+	}
+
+	/// Write a chunk of request body.
+	///
+	/// Writing an empty chunks finalises the request.
+	/// Passing `None` as deadline blocks forever.
+	fn http_request_write_body(
+		&mut self,
+		request_id: HttpRequestId,
+		chunk: &[u8],
+		deadline: Option<Timestamp>,
+	) -> Result<(), HttpError> {
+		self.offchain()
+			.expect("http_request_write_body can be called only in the offchain worker context")
+			.http_request_write_body(request_id, chunk, deadline)
+	}
+
+	/// Block and wait for the responses for given requests.
+	///
+	/// Returns a vector of request statuses (the len is the same as ids).
+	/// Passing `None` as deadline blocks forever.
+	fn http_response_wait(
+		&mut self,
+		ids: Vec<HttpRequestId>,
+		deadline: Option<Timestamp>,
+	) -> Vec<HttpRequestStatus> {
+		self.offchain()
+			.expect("http_response_wait can be called only in the offchain worker context")
+			.http_response_wait(&ids, deadline)
+	}
+
+	/// Read all response headers.
+	///
+	/// Returns a vector of pairs `(HeaderKey, HeaderValue)`.
+	fn http_response_headers(&mut self, request_id: HttpRequestId) -> Vec<(Vec<u8>, Vec<u8>)> {
+		self.offchain()
+			.expect("http_response_headers can be called only in the offchain worker context")
+			.http_response_headers(request_id)
+	}
+
+	/// Read a chunk of at most `max_len` bytes of the response body.
+	///
+	/// An empty chunk means that the response has been fully consumed and the `request_id` is
+	/// now invalid. Passing `None` as a deadline blocks forever.
+	fn http_response_read_body(
+		&mut self,
+		request_id: HttpRequestId,
+		max_len: u32,
+		deadline: Option<Timestamp>,
+	) -> Result<Vec<u8>, HttpError> {
+		let mut buffer = vec![0; max_len as usize];
+		self.offchain()
+			.expect("http_response_read_body can be called only in the offchain worker context")
+			.http_response_read_body(request_id, &mut buffer, deadline)
+			.map(|read| {
+				buffer.truncate(read);
+				buffer
+			})
+	}
+}
+
+/// Interface that provides functions for instantiating and executing wasm modules in a sandbox.
+///
+/// Pointers are addresses in the memory of the runtime. Errors in the sandboxed module are
+/// returned as the error codes of `primitives::sandbox`.
+#[runtime_interface(wasm_only)]
+pub trait Sandbox {
+	/// Instantiate a new sandbox instance with the given `wasm_code` and return its index.
+	///
+	/// `dispatch_thunk` is the index in the table of the runtime of the function that dispatches
+	/// the calls of the instance to the host functions defined in the encoded `env_def`.
+	fn instantiate(&mut self, dispatch_thunk: u32, wasm_code: &[u8], env_def: &[u8], state: u32) -> u32 {
+		self.sandbox()
+			.instance_new(dispatch_thunk, wasm_code, env_def, state)
+			.expect("Failed to instantiate a new sandbox")
+	}
+
+	/// Invoke `function` of the sandbox instance `instance_idx` with the encoded `args`.
+	///
+	/// The encoded return value is written into the buffer at `return_val`.
+	fn invoke(
+		&mut self,
+		instance_idx: u32,
+		function: &str,
+		args: &[u8],
+		return_val: u32,
+		return_val_len: u32,
+		state: u32,
+	) -> u32 {
+		self.sandbox()
+			.invoke(instance_idx, function, args, return_val, return_val_len, state)
+			.expect("Failed to invoke a function of a sandbox instance")
+	}
+
+	/// Create a new sandbox memory and return its index.
+	fn memory_new(&mut self, initial: u32, maximum: u32) -> u32 {
+		self.sandbox()
+			.memory_new(initial, maximum)
+			.expect("Failed to create a new sandbox memory")
+	}
+
+	/// Copy `buf_len` bytes of the sandbox memory `memory_idx` at `offset` into the buffer at
+	/// `buf`.
+	fn memory_get(&mut self, memory_idx: u32, offset: u32, buf: u32, buf_len: u32) -> u32 {
+		self.sandbox()
+			.memory_get(memory_idx, offset, buf, buf_len)
+			.expect("Failed to read a sandbox memory")
+	}
+
+	/// Copy the `val_len` bytes at `val` into the sandbox memory `memory_idx` at `offset`.
+	fn memory_set(&mut self, memory_idx: u32, offset: u32, val: u32, val_len: u32) -> u32 {
+		self.sandbox()
+			.memory_set(memory_idx, offset, val, val_len)
+			.expect("Failed to write a sandbox memory")
+	}
+
+	/// Delete the sandbox memory `memory_idx`.
+	fn memory_teardown(&mut self, memory_idx: u32) {
+		self.sandbox()
+			.memory_teardown(memory_idx)
+			.expect("Failed to delete a sandbox memory")
+	}
+
+	/// Delete the sandbox instance `instance_idx`.
+	fn instance_teardown(&mut self, instance_idx: u32) {
+		self.sandbox()
+			.instance_teardown(instance_idx)
+			.expect("Failed to delete a sandbox instance")
+	}
+}
+
+/// Discard the batch of signature verifications of the current thread, if there is one.
+///
+/// A runtime call that panics between `start_batch_verify` and `finish_batch_verify` leaves its
+/// batch behind, so the executor calls this around every runtime call. Otherwise the batch would
+/// accept any signature of the next call.
+#[cfg(feature = "std")]
+pub fn clear_batch_verify() {
+	batch_verifier::clear()
+}
+
+/// The host functions Substrate provides for the runtime interfaces declared in this crate.
+#[cfg(feature = "std")]
+pub type SubstrateHostFunctions = (
+	storage::HostFunctions,
+	child_storage::HostFunctions,
+	hashing::HostFunctions,
+	misc::HostFunctions,
+	crypto::HostFunctions,
+	offchain_worker::HostFunctions,
+	sandbox::HostFunctions,
+);
+
 /// API trait that should cover all other APIs.
 ///
 /// Implement this to make sure you implement all APIs.
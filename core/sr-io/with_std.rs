@@ -23,30 +23,16 @@ pub use substrate_state_machine::{
 	Externalities, BasicExternalities, TestExternalities, ChildStorageKey,
 };
 
-use environmental::environmental;
+use runtime_interface::externalities as ext;
 use primitives::{offchain, hexdisplay::HexDisplay, H256};
 use trie::{TrieConfiguration, trie_types::Layout};
 
 use std::{collections::HashMap, convert::TryFrom};
 
-environmental!(ext: trait Externalities<Blake2Hasher>);
-
 /// Additional bounds for `Hasher` trait for with_std.
 pub trait HasherBounds {}
 impl<T: Hasher> HasherBounds for T {}
 
-/// Returns a `ChildStorageKey` if the given `storage_key` slice is a valid storage
-/// key or panics otherwise.
-///
-/// Panicking here is aligned with what the `without_std` environment would do
-/// in the case of an invalid child storage key.
-fn child_storage_key_or_panic(storage_key: &[u8]) -> ChildStorageKey<Blake2Hasher> {
-	match ChildStorageKey::from_slice(storage_key) {
-		Some(storage_key) => storage_key,
-		None => panic!("child storage key is invalid"),
-	}
-}
-
 impl StorageApi for () {
 	fn storage(key: &[u8]) -> Option<Vec<u8>> {
 		ext::with(|ext| ext.storage(key).map(|s| s.to_vec()))
@@ -148,6 +134,12 @@ impl StorageApi for () {
 		});
 	}
 
+	fn next_key(key: &[u8]) -> Option<Vec<u8>> {
+		ext::with(|ext|
+			ext.next_storage_key(key)
+		).expect("next_key cannot be called outside of an Externalities-provided environment.")
+	}
+
 	fn storage_root() -> [u8; 32] {
 		ext::with(|ext|
 			ext.storage_root()
@@ -556,4 +548,75 @@ mod std_tests {
 			assert!(storage(b":abc").is_none());
 		});
 	}
+
+	#[test]
+	fn storage_interface_uses_the_set_externalities() {
+		let mut t = BasicExternalities::default();
+
+		with_externalities(&mut t, || {
+			crate::storage::set(b"hello", b"world");
+			assert_eq!(storage(b"hello"), Some(b"world".to_vec()));
+			assert!(crate::storage::exists(b"hello"));
+			crate::storage::clear(b"hello");
+			assert_eq!(crate::storage::get(b"hello"), None);
+		});
+	}
+
+	#[test]
+	fn next_key_works() {
+		let mut t = BasicExternalities::new(map![
+			b":a".to_vec() => b"\x0b\0\0\0Hello world".to_vec(),
+			b":abc".to_vec() => b"\x0b\0\0\0Hello world".to_vec(),
+			b":abdd".to_vec() => b"\x0b\0\0\0Hello world".to_vec()
+		], map![]);
+
+		with_externalities(&mut t, || {
+			assert_eq!(next_key(b":"), Some(b":a".to_vec()));
+			assert_eq!(next_key(b":a"), Some(b":abc".to_vec()));
+			assert_eq!(next_key(b":abcd"), Some(b":abdd".to_vec()));
+			assert_eq!(next_key(b":abdd"), None);
+		});
+	}
+
+	#[test]
+	fn batch_verify_works() {
+		use crate::crypto::{start_batch_verify, ed25519_batch_verify, sr25519_batch_verify, finish_batch_verify};
+
+		let ed_pair = ed25519::Pair::from_seed(&[1; 32]);
+		let sr_pair = sr25519::Pair::from_seed(&[2; 32]);
+		let ed_sig = ed_pair.sign(b"message").0;
+		let sr_sig = sr_pair.sign(b"message").0;
+
+		// without a batch signatures are verified right away.
+		assert!(ed25519_batch_verify(&ed_sig, b"message", &ed_pair.public().0));
+		assert!(!ed25519_batch_verify(&ed_sig, b"other", &ed_pair.public().0));
+		assert!(finish_batch_verify());
+
+		// enough signatures to be verified on multiple threads.
+		start_batch_verify();
+		for _ in 0..150 {
+			assert!(ed25519_batch_verify(&ed_sig, b"message", &ed_pair.public().0));
+			assert!(sr25519_batch_verify(&sr_sig, b"message", &sr_pair.public().0));
+		}
+		assert!(finish_batch_verify());
+
+		start_batch_verify();
+		for i in 0..150 {
+			let msg: &[u8] = if i == 100 { b"other" } else { b"message" };
+			assert!(sr25519_batch_verify(&sr_sig, msg, &sr_pair.public().0));
+		}
+		assert!(!finish_batch_verify());
+
+		// an unfinished batch is discarded by the next one.
+		start_batch_verify();
+		assert!(ed25519_batch_verify(&ed_sig, b"other", &ed_pair.public().0));
+		start_batch_verify();
+		assert!(finish_batch_verify());
+
+		// a cleared batch doesn't accept signatures anymore.
+		start_batch_verify();
+		crate::clear_batch_verify();
+		assert!(!ed25519_batch_verify(&ed_sig, b"other", &ed_pair.public().0));
+		assert!(finish_batch_verify());
+	}
 }
@@ -19,9 +19,8 @@ pub use rstd;
 pub use rstd::{mem, slice};
 
 use core::{intrinsics, panic::PanicInfo};
-use rstd::{vec::Vec, cell::Cell, convert::TryInto};
+use rstd::{vec::Vec, cell::Cell};
 use primitives::{offchain, Blake2Hasher};
-use codec::Decode;
 
 #[cfg(not(feature = "no_panic_handler"))]
 #[panic_handler]
@@ -239,6 +238,15 @@ pub mod ext {
 		/// - `0` if no value exists to the given key. `written_out` is set to `u32::max_value()`.
 		/// - Otherwise, pointer to the value in memory. `written_out` contains the length of the value.
 		fn ext_get_allocated_storage(key_data: *const u8, key_len: u32, written_out: *mut u32) -> *mut u8;
+		/// Gets the next key in storage after the given one, in lexicographic order.
+		///
+		/// The host allocates the memory for storing the key.
+		///
+		/// # Returns
+		///
+		/// - `0` if there is no next key. `written_out` is set to `u32::max_value()`.
+		/// - Otherwise, pointer to the key in memory. `written_out` contains the length of the key.
+		fn ext_next_key(key_data: *const u8, key_len: u32, written_out: *mut u32) -> *mut u8;
 		/// Gets the value of the given key from storage.
 		///
 		/// The value is written into `value` starting at `value_offset`.
@@ -614,25 +622,11 @@ pub use self::ext::*;
 
 impl StorageApi for () {
 	fn storage(key: &[u8]) -> Option<Vec<u8>> {
-		let mut length: u32 = 0;
-		unsafe {
-			let ptr = ext_get_allocated_storage.get()(key.as_ptr(), key.len() as u32, &mut length);
-			from_raw_parts(ptr, length)
-		}
+		storage::get(key)
 	}
 
 	fn child_storage(storage_key: &[u8], key: &[u8]) -> Option<Vec<u8>> {
-		let mut length: u32 = 0;
-		unsafe {
-			let ptr = ext_get_allocated_child_storage.get()(
-				storage_key.as_ptr(),
-				storage_key.len() as u32,
-				key.as_ptr(),
-				key.len() as u32,
-				&mut length
-			);
-			from_raw_parts(ptr, length)
-		}
+		child_storage::get(storage_key, key)
 	}
 
 	fn read_storage(key: &[u8], value_out: &mut [u8], value_offset: usize) -> Option<usize> {
@@ -665,116 +659,55 @@ impl StorageApi for () {
 	}
 
 	fn set_storage(key: &[u8], value: &[u8]) {
-		unsafe {
-			ext_set_storage.get()(
-				key.as_ptr(), key.len() as u32,
-				value.as_ptr(), value.len() as u32
-			);
-		}
+		storage::set(key, value)
 	}
 
 	fn set_child_storage(storage_key: &[u8], key: &[u8], value: &[u8]) {
-		unsafe {
-			ext_set_child_storage.get()(
-				storage_key.as_ptr(), storage_key.len() as u32,
-				key.as_ptr(), key.len() as u32,
-				value.as_ptr(), value.len() as u32
-			);
-		}
+		child_storage::set(storage_key, key, value)
 	}
 
 	fn clear_storage(key: &[u8]) {
-		unsafe {
-			ext_clear_storage.get()(
-				key.as_ptr(), key.len() as u32
-			);
-		}
+		storage::clear(key)
 	}
 
 	fn clear_child_storage(storage_key: &[u8], key: &[u8]) {
-		unsafe {
-			ext_clear_child_storage.get()(
-				storage_key.as_ptr(), storage_key.len() as u32,
-				key.as_ptr(), key.len() as u32
-			);
-		}
+		child_storage::clear(storage_key, key)
 	}
 
 	fn exists_storage(key: &[u8]) -> bool {
-		unsafe {
-			ext_exists_storage.get()(
-				key.as_ptr(), key.len() as u32
-			) != 0
-		}
+		storage::exists(key)
 	}
 
 	fn exists_child_storage(storage_key: &[u8], key: &[u8]) -> bool {
-		unsafe {
-			ext_exists_child_storage.get()(
-				storage_key.as_ptr(), storage_key.len() as u32,
-				key.as_ptr(), key.len() as u32
-			) != 0
-		}
+		child_storage::exists(storage_key, key)
 	}
 
 	fn clear_prefix(prefix: &[u8]) {
-		unsafe {
-			ext_clear_prefix.get()(
-				prefix.as_ptr(),
-				prefix.len() as u32
-			);
-		}
+		storage::clear_prefix(prefix)
 	}
 
 	fn clear_child_prefix(storage_key: &[u8], prefix: &[u8]) {
-		unsafe {
-			ext_clear_child_prefix.get()(
-				storage_key.as_ptr(), storage_key.len() as u32,
-				prefix.as_ptr(), prefix.len() as u32
-			);
-		}
+		child_storage::clear_prefix(storage_key, prefix)
+	}
+
+	fn next_key(key: &[u8]) -> Option<Vec<u8>> {
+		storage::next_key(key)
 	}
 
 	fn kill_child_storage(storage_key: &[u8]) {
-		unsafe {
-			ext_kill_child_storage.get()(
-				storage_key.as_ptr(),
-				storage_key.len() as u32
-			);
-		}
+		child_storage::kill(storage_key)
 	}
 
 	fn storage_root() -> [u8; 32] {
-		let mut result: [u8; 32] = Default::default();
-		unsafe {
-			ext_storage_root.get()(result.as_mut_ptr());
-		}
-		result
+		storage::root()
 	}
 
 	fn child_storage_root(storage_key: &[u8]) -> Vec<u8> {
-		let mut length: u32 = 0;
-		unsafe {
-			let ptr = ext_child_storage_root.get()(
-				storage_key.as_ptr(),
-				storage_key.len() as u32,
-				&mut length
-			);
-			from_raw_parts(ptr, length).expect("ext_child_storage_root never returns u32::max_value; qed")
-		}
+		child_storage::root(storage_key)
 	}
 
 	fn storage_changes_root(parent_hash: [u8; 32]) -> Option<[u8; 32]> {
-		let mut result: [u8; 32] = Default::default();
-		let is_set = unsafe {
-			ext_storage_changes_root.get()(parent_hash.as_ptr(), parent_hash.len() as u32, result.as_mut_ptr())
-		};
-
-		if is_set != 0 {
-			Some(result)
-		} else {
-			None
-		}
+		storage::changes_root(parent_hash)
 	}
 
 	fn trie_root<
@@ -810,70 +743,37 @@ impl OtherApi for () {
 
 impl HashingApi for () {
 	fn keccak_256(data: &[u8]) -> [u8; 32] {
-		let mut result: [u8; 32] = Default::default();
-		unsafe {
-			ext_keccak_256.get()(data.as_ptr(), data.len() as u32, result.as_mut_ptr());
-		}
-		result
+		hashing::keccak_256(data)
 	}
 
 	fn blake2_128(data: &[u8]) -> [u8; 16] {
-		let mut result: [u8; 16] = Default::default();
-		unsafe {
-			ext_blake2_128.get()(data.as_ptr(), data.len() as u32, result.as_mut_ptr());
-		}
-		result
+		hashing::blake2_128(data)
 	}
 
 	fn blake2_256(data: &[u8]) -> [u8; 32] {
-		let mut result: [u8; 32] = Default::default();
-		unsafe {
-			ext_blake2_256.get()(data.as_ptr(), data.len() as u32, result.as_mut_ptr());
-		}
-		result
+		hashing::blake2_256(data)
 	}
 
 	fn twox_256(data: &[u8]) -> [u8; 32] {
-		let mut result: [u8; 32] = Default::default();
-		unsafe {
-			ext_twox_256.get()(data.as_ptr(), data.len() as u32, result.as_mut_ptr());
-		}
-		result
+		hashing::twox_256(data)
 	}
 
 	fn twox_128(data: &[u8]) -> [u8; 16] {
-		let mut result: [u8; 16] = Default::default();
-		unsafe {
-			ext_twox_128.get()(data.as_ptr(), data.len() as u32, result.as_mut_ptr());
-		}
-		result
+		hashing::twox_128(data)
 	}
 
 	fn twox_64(data: &[u8]) -> [u8; 8] {
-		let mut result: [u8; 8] = Default::default();
-		unsafe {
-			ext_twox_64.get()(data.as_ptr(), data.len() as u32, result.as_mut_ptr());
-		}
-		result
+		hashing::twox_64(data)
 	}
 }
 
 impl CryptoApi for () {
 	fn ed25519_public_keys(id: KeyTypeId) -> Vec<ed25519::Public> {
-		let mut res_len = 0u32;
-		unsafe {
-			let res_ptr = ext_ed25519_public_keys.get()(id.0.as_ptr(), &mut res_len);
-			Vec::decode(&mut rstd::slice::from_raw_parts(res_ptr, res_len as usize)).unwrap_or_default()
-		}
+		crypto::ed25519_public_keys(id).into_iter().map(ed25519::Public).collect()
 	}
 
 	fn ed25519_generate(id: KeyTypeId, seed: Option<&str>) -> ed25519::Public {
-		let mut res = [0u8; 32];
-		let seed = seed.as_ref().map(|s| s.as_bytes()).unwrap_or(&[]);
-		unsafe {
-			ext_ed25519_generate.get()(id.0.as_ptr(), seed.as_ptr(), seed.len() as u32, res.as_mut_ptr())
-		};
-		ed25519::Public(res)
+		ed25519::Public(crypto::ed25519_generate(id, seed.map(|s| s.as_bytes().to_vec())))
 	}
 
 	fn ed25519_sign<M: AsRef<[u8]>>(
@@ -881,50 +781,19 @@ impl CryptoApi for () {
 		pubkey: &ed25519::Public,
 		msg: &M,
 	) -> Option<ed25519::Signature> {
-		let mut res = [0u8; 64];
-		let success = unsafe {
-			ext_ed25519_sign.get()(
-				id.0.as_ptr(),
-				pubkey.0.as_ptr(),
-				msg.as_ref().as_ptr(),
-				msg.as_ref().len() as u32,
-				res.as_mut_ptr(),
-			) == 0
-		};
-
-		if success {
-			Some(ed25519::Signature(res))
-		} else {
-			None
-		}
+		crypto::ed25519_sign(id, &pubkey.0, msg.as_ref()).map(ed25519::Signature)
 	}
 
 	fn ed25519_verify(sig: &ed25519::Signature, msg: &[u8], pubkey: &ed25519::Public) -> bool {
-		unsafe {
-			ext_ed25519_verify.get()(
-				msg.as_ptr(),
-				msg.len() as u32,
-				sig.0.as_ptr(),
-				pubkey.0.as_ptr(),
-			) == 0
-		}
+		crypto::ed25519_verify(&sig.0, msg, &pubkey.0)
 	}
 
 	fn sr25519_public_keys(id: KeyTypeId) -> Vec<sr25519::Public> {
-		let mut res_len = 0u32;
-		unsafe {
-			let res_ptr = ext_sr25519_public_keys.get()(id.0.as_ptr(), &mut res_len);
-			Vec::decode(&mut rstd::slice::from_raw_parts(res_ptr, res_len as usize)).unwrap_or_default()
-		}
+		crypto::sr25519_public_keys(id).into_iter().map(sr25519::Public).collect()
 	}
 
 	fn sr25519_generate(id: KeyTypeId, seed: Option<&str>) -> sr25519::Public {
-		let mut res = [0u8;32];
-		let seed = seed.as_ref().map(|s| s.as_bytes()).unwrap_or(&[]);
-		unsafe {
-			ext_sr25519_generate.get()(id.0.as_ptr(), seed.as_ptr(), seed.len() as u32, res.as_mut_ptr())
-		};
-		sr25519::Public(res)
+		sr25519::Public(crypto::sr25519_generate(id, seed.map(|s| s.as_bytes().to_vec())))
 	}
 
 	fn sr25519_sign<M: AsRef<[u8]>>(
@@ -932,111 +801,45 @@ impl CryptoApi for () {
 		pubkey: &sr25519::Public,
 		msg: &M,
 	) -> Option<sr25519::Signature> {
-		let mut res = [0u8; 64];
-		let success = unsafe {
-			ext_sr25519_sign.get()(
-				id.0.as_ptr(),
-				pubkey.0.as_ptr(),
-				msg.as_ref().as_ptr(),
-				msg.as_ref().len() as u32,
-				res.as_mut_ptr(),
-			) == 0
-		};
-
-		if success {
-			Some(sr25519::Signature(res))
-		} else {
-			None
-		}
+		crypto::sr25519_sign(id, &pubkey.0, msg.as_ref()).map(sr25519::Signature)
 	}
 
 	fn sr25519_verify(sig: &sr25519::Signature, msg: &[u8], pubkey: &sr25519::Public) -> bool {
-		unsafe {
-			ext_sr25519_verify.get()(
-				msg.as_ptr(),
-				msg.len() as u32,
-				sig.0.as_ptr(),
-				pubkey.0.as_ptr(),
-			) == 0
-		}
+		crypto::sr25519_verify(&sig.0, msg, &pubkey.0)
 	}
 
 	fn secp256k1_ecdsa_recover(sig: &[u8; 65], msg: &[u8; 32]) -> Result<[u8; 64], EcdsaVerifyError> {
-		let mut pubkey = [0u8; 64];
-		match unsafe {
-			ext_secp256k1_ecdsa_recover.get()(msg.as_ptr(), sig.as_ptr(), pubkey.as_mut_ptr())
-		} {
-			0 => Ok(pubkey),
-			1 => Err(EcdsaVerifyError::BadRS),
-			2 => Err(EcdsaVerifyError::BadV),
-			3 => Err(EcdsaVerifyError::BadSignature),
-			_ => unreachable!("`ext_secp256k1_ecdsa_recover` only returns 0, 1, 2 or 3; qed"),
-		}
+		crypto::secp256k1_ecdsa_recover(sig, msg)
 	}
 }
 
 impl OffchainApi for () {
 	fn is_validator() -> bool {
-		unsafe { ext_is_validator.get()() == 1 }
+		offchain_worker::is_validator()
 	}
 
 	fn submit_transaction<T: codec::Encode>(data: &T) -> Result<(), ()> {
-		let encoded_data = codec::Encode::encode(data);
-		let ret = unsafe {
-			ext_submit_transaction.get()(encoded_data.as_ptr(), encoded_data.len() as u32)
-		};
-
-		if ret == 0 {
-			Ok(())
-		} else {
-			Err(())
-		}
+		offchain_worker::submit_transaction(codec::Encode::encode(data))
 	}
 
 	fn network_state() -> Result<offchain::OpaqueNetworkState, ()> {
-		let mut len = 0_u32;
-		let raw_result = unsafe {
-			let ptr = ext_network_state.get()(&mut len);
-
-			from_raw_parts(ptr, len)
-		};
-
-		match raw_result {
-			Some(raw_result) => codec::Decode::decode(&mut &*raw_result).unwrap_or(Err(())),
-			None => Err(())
-		}
+		offchain_worker::network_state()
 	}
 
 	fn timestamp() -> offchain::Timestamp {
-		offchain::Timestamp::from_unix_millis(unsafe {
-			ext_timestamp.get()()
-		})
+		offchain_worker::timestamp()
 	}
 
 	fn sleep_until(deadline: offchain::Timestamp) {
-		unsafe {
-			ext_sleep_until.get()(deadline.unix_millis())
-		}
+		offchain_worker::sleep_until(deadline)
 	}
 
 	fn random_seed() -> [u8; 32] {
-		let mut result = [0_u8; 32];
-		unsafe {
-			ext_random_seed.get()(result.as_mut_ptr())
-		}
-		result
+		offchain_worker::random_seed()
 	}
 
 	fn local_storage_set(kind: offchain::StorageKind, key: &[u8], value: &[u8]) {
-		unsafe {
-			ext_local_storage_set.get()(
-				kind.into(),
-				key.as_ptr(),
-				key.len() as u32,
-				value.as_ptr(),
-				value.len() as u32,
-			)
-		}
+		offchain_worker::local_storage_set(kind, key, value)
 	}
 
 	fn local_storage_compare_and_set(
@@ -1045,82 +848,19 @@ impl OffchainApi for () {
 		old_value: Option<&[u8]>,
 		new_value: &[u8],
 	) -> bool {
-		let (ptr, len) = match old_value {
-			Some(old_value) => (
-				old_value.as_ptr(),
-				old_value.len() as u32,
-			),
-			None => (0 as *const u8, u32::max_value()),
-		};
-
-		unsafe {
-			ext_local_storage_compare_and_set.get()(
-				kind.into(),
-				key.as_ptr(),
-				key.len() as u32,
-				ptr,
-				len,
-				new_value.as_ptr(),
-				new_value.len() as u32,
-			) == 0
-		}
+		offchain_worker::local_storage_compare_and_set(kind, key, old_value.map(|v| v.to_vec()), new_value)
 	}
 
 	fn local_storage_get(kind: offchain::StorageKind, key: &[u8]) -> Option<Vec<u8>> {
-		let mut len = 0u32;
-		unsafe {
-			let ptr = ext_local_storage_get.get()(
-				kind.into(),
-				key.as_ptr(),
-				key.len() as u32,
-				&mut len,
-			);
-
-			from_raw_parts(ptr, len)
-		}
+		offchain_worker::local_storage_get(kind, key)
 	}
 
 	fn http_request_start(method: &str, url: &str, meta: &[u8]) -> Result<offchain::HttpRequestId, ()> {
-		let method = method.as_bytes();
-		let url = url.as_bytes();
-
-		let result = unsafe {
-			ext_http_request_start.get()(
-				method.as_ptr(),
-				method.len() as u32,
-				url.as_ptr(),
-				url.len() as u32,
-				meta.as_ptr(),
-				meta.len() as u32,
-			)
-		};
-
-		if result > u16::max_value() as u32 {
-			Err(())
-		} else {
-			Ok(offchain::HttpRequestId(result as u16))
-		}
+		offchain_worker::http_request_start(method, url, meta)
 	}
 
 	fn http_request_add_header(request_id: offchain::HttpRequestId, name: &str, value: &str) -> Result<(), ()> {
-		let name = name.as_bytes();
-		let value = value.as_bytes();
-
-		let result = unsafe {
-			ext_http_request_add_header.get()(
-				request_id.into(),
-				name.as_ptr(),
-				name.len() as u32,
-				value.as_ptr(),
-				value.len() as u32,
-			)
-		};
-
-		if result == 0 {
-			Ok(())
-		} else {
-			Err(())
-		}
+		offchain_worker::http_request_add_header(request_id, name, value)
 	}
 
 	fn http_request_write_body(
@@ -1128,59 +868,20 @@ impl OffchainApi for () {
 		chunk: &[u8],
 		deadline: Option<offchain::Timestamp>
 	) -> Result<(), offchain::HttpError> {
-		let res = unsafe {
-			ext_http_request_write_body.get()(
-				request_id.into(),
-				chunk.as_ptr(),
-				chunk.len() as u32,
-				deadline.map_or(0, |x| x.unix_millis()),
-			)
-		};
-
-		if res == 0 {
-			Ok(())
-		} else {
-			Err(res.try_into().unwrap_or(offchain::HttpError::IoError))
-		}
+		offchain_worker::http_request_write_body(request_id, chunk, deadline)
 	}
 
 	fn http_response_wait(
 		ids: &[offchain::HttpRequestId],
 		deadline: Option<offchain::Timestamp>
 	) -> Vec<offchain::HttpRequestStatus> {
-		let ids = ids.iter().map(|x| x.0 as u32).collect::<Vec<_>>();
-		let mut statuses = Vec::new();
-		statuses.resize(ids.len(), 0u32);
-
-		unsafe {
-			ext_http_response_wait.get()(
-				ids.as_ptr(),
-				ids.len() as u32,
-				statuses.as_mut_ptr(),
-				deadline.map_or(0, |x| x.unix_millis()),
-			)
-		}
-
-		statuses
-			.into_iter()
-			.map(|status| status.try_into().unwrap_or(offchain::HttpRequestStatus::Invalid))
-			.collect()
+		offchain_worker::http_response_wait(ids.to_vec(), deadline)
 	}
 
 	fn http_response_headers(
 		request_id: offchain::HttpRequestId,
 	) -> Vec<(Vec<u8>, Vec<u8>)> {
-		let mut len = 0u32;
-		let raw_result = unsafe {
-			let ptr = ext_http_response_headers.get()(
-				request_id.into(),
-				&mut len,
-			);
-
-			from_raw_parts(ptr, len).expect("ext_http_response_headers never return u32::max_value; qed")
-		};
-
-		codec::Decode::decode(&mut &*raw_result).unwrap_or_default()
+		offchain_worker::http_response_headers(request_id)
 	}
 
 	fn http_response_read_body(
@@ -1188,32 +889,9 @@ impl OffchainApi for () {
 		buffer: &mut [u8],
 		deadline: Option<offchain::Timestamp>,
 	) -> Result<usize, offchain::HttpError> {
-		let res = unsafe {
-			ext_http_response_read_body.get()(
-				request_id.into(),
-				buffer.as_mut_ptr(),
-				buffer.len() as u32,
-				deadline.map_or(0, |x| x.unix_millis()),
-			)
-		};
-
-		if res >= u32::max_value() - 255 {
-			let code = (u32::max_value() - res) + 1;
-			code.try_into().map_err(|_| offchain::HttpError::IoError)
-		} else {
-			Ok(res as usize)
-		}
-	}
-}
-
-unsafe fn from_raw_parts(ptr: *mut u8, len: u32) -> Option<Vec<u8>> {
-	if len == u32::max_value() {
-		None
-	} else {
-		// Invariants required by Vec::from_raw_parts are not formally fulfilled.
-		// We don't allocate via String/Vec<T>, but use a custom allocator instead.
-		// See #300 for more details.
-		Some(<Vec<u8>>::from_raw_parts(ptr, len as usize, len as usize))
+		let chunk = offchain_worker::http_response_read_body(request_id, buffer.len() as u32, deadline)?;
+		buffer[..chunk.len()].copy_from_slice(&chunk);
+		Ok(chunk.len())
 	}
 }
 
@@ -1221,22 +899,18 @@ impl Api for () {}
 
 impl<'a> Printable for &'a [u8] {
 	fn print(self) {
-		unsafe {
-			ext_print_hex.get()(self.as_ptr(), self.len() as u32);
-		}
+		misc::print_hex(self)
 	}
 }
 
 impl<'a> Printable for &'a str {
 	fn print(self) {
-		unsafe {
-			ext_print_utf8.get()(self.as_ptr() as *const u8, self.len() as u32);
-		}
+		misc::print_utf8(self.as_bytes())
 	}
 }
 
 impl Printable for u64 {
 	fn print(self) {
-		unsafe { ext_print_num.get()(self); }
+		misc::print_num(self)
 	}
 }
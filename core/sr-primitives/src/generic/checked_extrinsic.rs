@@ -76,7 +76,7 @@ where
 	fn dispatch(self,
 		info: DispatchInfo,
 		len: usize,
-	) -> Result<DispatchResult, DispatchError> {
+	) -> Result<DispatchResult<crate::DispatchError>, DispatchError> {
 		let (maybe_who, pre) = if let Some((id, extra)) = self.signed {
 			let pre = Extra::pre_dispatch(extra, &id, &self.function, info, len)?;
 			(Some(id), pre)
@@ -84,7 +84,7 @@ where
 			let pre = Extra::pre_dispatch_unsigned(&self.function, info, len)?;
 			(None, pre)
 		};
-		let res = self.function.dispatch(Origin::from(maybe_who));
+		let res = self.function.dispatch(Origin::from(maybe_who)).map_err(Into::into);
 		Extra::post_dispatch(pre, info, len);
 		Ok(res)
 	}
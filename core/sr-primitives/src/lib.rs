@@ -604,6 +604,13 @@ impl Verify for MultiSignature {
 			_ => false,
 		}
 	}
+	fn batch_verify<L: Lazy<[u8]>>(&self, msg: L, signer: &Self::Signer) -> bool {
+		match (self, signer) {
+			(MultiSignature::Ed25519(ref sig), &MultiSigner::Ed25519(ref who)) => sig.batch_verify(msg, who),
+			(MultiSignature::Sr25519(ref sig), &MultiSigner::Sr25519(ref who)) => sig.batch_verify(msg, who),
+			_ => false,
+		}
+	}
 }
 
 /// Signature verify that can work with any known signature types..
@@ -683,6 +690,60 @@ impl codec::EncodeLike for ApplyError {}
 /// Result from attempt to apply an extrinsic.
 pub type ApplyResult = Result<ApplyOutcome, ApplyError>;
 
+/// Reason why a dispatch call failed.
+///
+/// `module` is the index of the module in the runtime metadata (`None` if the error did not come
+/// from a module) and `error` is the index of the error variant within that module's error type.
+/// Together they can be resolved against the runtime metadata to find the name and documentation
+/// of the error.
+#[derive(Eq, PartialEq, Clone, Copy, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize))]
+pub struct DispatchError {
+	/// Module index, matching the metadata module index.
+	pub module: Option<u8>,
+	/// Module specific error value.
+	pub error: u8,
+	/// Optional error message.
+	#[codec(skip)]
+	pub message: Option<&'static str>,
+}
+
+impl DispatchError {
+	/// Create a new instance of `DispatchError`.
+	pub fn new(module: Option<u8>, error: u8, message: Option<&'static str>) -> Self {
+		DispatchError {
+			module,
+			error,
+			message,
+		}
+	}
+}
+
+impl<T: traits::ModuleDispatchError> From<T> for DispatchError {
+	fn from(err: T) -> DispatchError {
+		DispatchError::new(None, err.as_u8(), Some(err.as_str()))
+	}
+}
+
+impl From<DispatchError> for &'static str {
+	fn from(err: DispatchError) -> &'static str {
+		err.message.unwrap_or("Unknown Error")
+	}
+}
+
+impl runtime_io::Printable for DispatchError {
+	fn print(self) {
+		runtime_io::print("DispatchError");
+		if let Some(module) = self.module {
+			runtime_io::print(module as u64);
+		}
+		runtime_io::print(self.error as u64);
+		if let Some(msg) = self.message {
+			runtime_io::print(msg);
+		}
+	}
+}
+
 /// Verify a signature on an encoded value in a lazy manner. This can be
 /// an optimization if the signature scheme has an "unsigned" escape hash.
 pub fn verify_encoded_lazy<V: Verify, T: codec::Encode>(sig: &V, item: &T, signer: &V::Signer) -> bool {
@@ -309,7 +309,7 @@ impl<Origin, Call, Extra> Applyable for TestXt<Call, Extra> where
 	fn dispatch(self,
 		info: DispatchInfo,
 		len: usize,
-	) -> Result<DispatchResult, DispatchError> {
+	) -> Result<DispatchResult<crate::DispatchError>, DispatchError> {
 		let maybe_who = if let Some((who, extra)) = self.0 {
 			Extra::pre_dispatch(extra, &who, &self.1, info, len)?;
 			Some(who)
@@ -317,7 +317,7 @@ impl<Origin, Call, Extra> Applyable for TestXt<Call, Extra> where
 			Extra::pre_dispatch_unsigned(&self.1, info, len)?;
 			None
 		};
-		Ok(self.1.dispatch(maybe_who.into()))
+		Ok(self.1.dispatch(maybe_who.into()).map_err(Into::into))
 	}
 }
 
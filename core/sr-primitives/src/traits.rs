@@ -25,7 +25,7 @@ use primitives::{self, Hasher, Blake2Hasher};
 use crate::codec::{Codec, Encode, Decode, HasCompact};
 use crate::transaction_validity::{ValidTransaction, TransactionValidity};
 use crate::generic::{Digest, DigestItem};
-use crate::weights::DispatchInfo;
+use crate::weights::{DispatchInfo, Weight};
 pub use integer_sqrt::IntegerSquareRoot;
 pub use num_traits::{
 	Zero, One, Bounded, CheckedAdd, CheckedSub, CheckedMul, CheckedDiv,
@@ -55,6 +55,14 @@ pub trait Verify {
 	type Signer;
 	/// Verify a signature. Return `true` if signature is valid for the value.
 	fn verify<L: Lazy<[u8]>>(&self, msg: L, signer: &Self::Signer) -> bool;
+	/// Verify a signature as part of the current signature batch.
+	///
+	/// If a batch was started with `runtime_io::crypto::start_batch_verify`, the signature may
+	/// only be queued and `true` returned; the result of the whole batch is then returned by
+	/// `runtime_io::crypto::finish_batch_verify`. Otherwise this is the same as `verify`.
+	fn batch_verify<L: Lazy<[u8]>>(&self, msg: L, signer: &Self::Signer) -> bool {
+		self.verify(msg, signer)
+	}
 }
 
 impl Verify for primitives::ed25519::Signature {
@@ -62,6 +70,9 @@ impl Verify for primitives::ed25519::Signature {
 	fn verify<L: Lazy<[u8]>>(&self, mut msg: L, signer: &Self::Signer) -> bool {
 		runtime_io::ed25519_verify(self, msg.get(), signer)
 	}
+	fn batch_verify<L: Lazy<[u8]>>(&self, mut msg: L, signer: &Self::Signer) -> bool {
+		runtime_io::crypto::ed25519_batch_verify(&self.0, msg.get(), &signer.0)
+	}
 }
 
 impl Verify for primitives::sr25519::Signature {
@@ -69,6 +80,9 @@ impl Verify for primitives::sr25519::Signature {
 	fn verify<L: Lazy<[u8]>>(&self, mut msg: L, signer: &Self::Signer) -> bool {
 		runtime_io::sr25519_verify(self, msg.get(), signer)
 	}
+	fn batch_verify<L: Lazy<[u8]>>(&self, mut msg: L, signer: &Self::Signer) -> bool {
+		runtime_io::crypto::sr25519_batch_verify(&self.0, msg.get(), &signer.0)
+	}
 }
 
 /// Means of signature verification of an application key.
@@ -409,6 +423,16 @@ pub trait OffchainWorker<BlockNumber> {
 
 impl<N> OffchainWorker<N> for () {}
 
+/// The runtime upgrade trait. Implementing this lets you express what should happen
+/// for your module when the runtime has been upgraded, e.g. migrating its storage.
+pub trait OnRuntimeUpgrade {
+	/// The runtime has been upgraded. Called once, at the beginning of the first block
+	/// with the new spec version. Returns the weight consumed by the upgrade.
+	fn on_runtime_upgrade() -> Weight { 0 }
+}
+
+impl OnRuntimeUpgrade for () {}
+
 macro_rules! tuple_impl {
 	($first:ident, $($rest:ident,)+) => {
 		tuple_impl!([$first] [$first] [$($rest)+]);
@@ -438,6 +462,15 @@ macro_rules! tuple_impl {
 				$($direct::generate_extrinsics(n);)+
 			}
 		}
+		impl<
+			$($direct: OnRuntimeUpgrade),+
+		> OnRuntimeUpgrade for ($($direct),+,) {
+			fn on_runtime_upgrade() -> Weight {
+				let mut weight: Weight = 0;
+				$( weight = weight.saturating_add($direct::on_runtime_upgrade()); )+
+				weight
+			}
+		}
 	};
 	([$($direct:ident)+] [$($reverse:ident)+] [$first:ident $($rest:ident)*]) => {
 		tuple_impl!([$($direct)+] [$($reverse)+] []);
@@ -851,8 +884,30 @@ impl From<DispatchError> for i8 {
 }
 
 /// Result of a module function call; either nothing (functions are only called for "side effects")
-/// or an error message.
-pub type DispatchResult = result::Result<(), &'static str>;
+/// or an error.
+pub type DispatchResult<Error = &'static str> = result::Result<(), Error>;
+
+/// An error that can be returned by a module dispatchable and later resolved against the module's
+/// error metadata.
+pub trait ModuleDispatchError {
+	/// Convert this error to an `u8`.
+	///
+	/// The `u8` corresponds to the index of the variant in the error enum.
+	fn as_u8(&self) -> u8;
+
+	/// Convert the error to a `&'static str`.
+	fn as_str(&self) -> &'static str;
+}
+
+impl ModuleDispatchError for &'static str {
+	fn as_u8(&self) -> u8 {
+		0
+	}
+
+	fn as_str(&self) -> &'static str {
+		self
+	}
+}
 
 /// A lazy call (module function and argument values) that can be executed via its `dispatch`
 /// method.
@@ -863,8 +918,10 @@ pub trait Dispatchable {
 	type Origin;
 	/// ...
 	type Trait;
+	/// The error type returned by this dispatchable.
+	type Error: Into<crate::DispatchError>;
 	/// Actually dispatch this call and result the result of it.
-	fn dispatch(self, origin: Self::Origin) -> DispatchResult;
+	fn dispatch(self, origin: Self::Origin) -> DispatchResult<Self::Error>;
 }
 
 /// Means by which a transaction may be extended. This type embodies both the data and the logic
@@ -1082,7 +1139,7 @@ pub trait Applyable: Sized + Send + Sync {
 	fn dispatch(self,
 		info: DispatchInfo,
 		len: usize,
-	) -> Result<DispatchResult, DispatchError>;
+	) -> Result<DispatchResult<crate::DispatchError>, DispatchError>;
 }
 
 /// Auxiliary wrapper that holds an api instance and binds it to the given lifetime.
@@ -220,6 +220,12 @@ where
 		self.backend.child_storage_hash(storage_key.as_ref(), key).expect(EXT_NOT_ALLOWED_TO_FAIL)
 	}
 
+	fn next_storage_key(&self, key: &[u8]) -> Option<Vec<u8>> {
+		let _guard = panic_handler::AbortGuard::force_abort();
+		let backend = &self.backend;
+		self.overlay.next_storage_key(key, |key| backend.next_storage_key(key).expect(EXT_NOT_ALLOWED_TO_FAIL))
+	}
+
 	fn exists_storage(&self, key: &[u8]) -> bool {
 		let _guard = panic_handler::AbortGuard::force_abort();
 		match self.overlay.storage(key) {
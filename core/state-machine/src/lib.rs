@@ -216,6 +216,10 @@ pub trait Externalities<H: Hasher> {
 		self.child_storage(storage_key, key).is_some()
 	}
 
+	/// Return the next key in storage after `key` in lexicographic order, or `None` if there is
+	/// no such key.
+	fn next_storage_key(&self, key: &[u8]) -> Option<Vec<u8>>;
+
 	/// Clear an entire child storage.
 	fn kill_child_storage(&mut self, storage_key: ChildStorageKey<H>);
 
@@ -1186,6 +1190,46 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn next_storage_key_in_ext_works() {
+		let initial: HashMap<_, _> = map![
+			b"aaa".to_vec() => b"0".to_vec(),
+			b"abb".to_vec() => b"1".to_vec(),
+			b"abc".to_vec() => b"2".to_vec(),
+			b"bbb".to_vec() => b"3".to_vec()
+		];
+		let mut state = InMemory::<Blake2Hasher>::from(initial);
+		let backend = state.as_trie_backend().unwrap();
+		let mut overlay = OverlayedChanges {
+			committed: map![
+				b"aba".to_vec() => OverlayedValue::from(Some(b"1312".to_vec())),
+				b"abc".to_vec() => OverlayedValue::from(None)
+			],
+			prospective: map![
+				b"abb".to_vec() => OverlayedValue::from(None),
+				b"abd".to_vec() => OverlayedValue::from(Some(b"69".to_vec()))
+			],
+			..Default::default()
+		};
+
+		let changes_trie_storage = InMemoryChangesTrieStorage::<Blake2Hasher, u64>::new();
+		let ext = Ext::new(
+			&mut overlay,
+			backend,
+			Some(&changes_trie_storage),
+			NeverOffchainExt::new(),
+			None,
+		);
+
+		assert_eq!(ext.next_storage_key(b"a"), Some(b"aaa".to_vec()));
+		// Only in the overlay.
+		assert_eq!(ext.next_storage_key(b"aaa"), Some(b"aba".to_vec()));
+		// Deleted in the overlay, both prospective and committed.
+		assert_eq!(ext.next_storage_key(b"aba"), Some(b"abd".to_vec()));
+		assert_eq!(ext.next_storage_key(b"abd"), Some(b"bbb".to_vec()));
+		assert_eq!(ext.next_storage_key(b"bbb"), None);
+	}
+
 	#[test]
 	fn set_child_storage_works() {
 		let mut state = InMemory::<Blake2Hasher>::default();
@@ -17,7 +17,8 @@
 //! The overlayed changes to state.
 
 #[cfg(test)] use std::iter::FromIterator;
-use std::collections::{HashMap, BTreeSet};
+use std::collections::{HashMap, BTreeMap, BTreeSet};
+use std::ops::Bound;
 use codec::Decode;
 use crate::changes_trie::{NO_EXTRINSIC_INDEX, Configuration as ChangesTrieConfig};
 use primitives::storage::well_known_keys::EXTRINSIC_INDEX;
@@ -52,10 +53,10 @@ pub struct OverlayedValue {
 #[derive(Debug, Default, Clone)]
 #[cfg_attr(test, derive(PartialEq))]
 pub struct OverlayedChangeSet {
-	/// Top level storage changes.
-	pub top: HashMap<Vec<u8>, OverlayedValue>,
+	/// Top level storage changes, ordered by key.
+	pub top: BTreeMap<Vec<u8>, OverlayedValue>,
 	/// Child storage changes.
-	pub children: HashMap<Vec<u8>, HashMap<Vec<u8>, OverlayedValue>>,
+	pub children: HashMap<Vec<u8>, BTreeMap<Vec<u8>, OverlayedValue>>,
 }
 
 #[cfg(test)]
@@ -112,6 +113,54 @@ impl OverlayedChanges {
 			.map(|x| x.value.as_ref().map(AsRef::as_ref))
 	}
 
+	/// Returns the next (in lexicographic order) key after `key` that has been changed in the
+	/// overlay, along with its value (`None` if it has been deleted).
+	pub fn next_storage_key_change(&self, key: &[u8]) -> Option<(&[u8], &OverlayedValue)> {
+		let next = |changes: &OverlayedChangeSet| changes.top
+			.range::<[u8], _>((Bound::Excluded(key), Bound::Unbounded))
+			.next()
+			.map(|(k, v)| (&k[..], v));
+
+		match (next(&self.prospective), next(&self.committed)) {
+			(Some(prospective), Some(committed)) => if committed.0 < prospective.0 {
+				Some(committed)
+			} else {
+				// The prospective change shadows the committed one for the same key.
+				Some(prospective)
+			},
+			(prospective, committed) => prospective.or(committed),
+		}
+	}
+
+	/// Returns the next (in lexicographic order) key after `key` that exists once the overlay
+	/// is applied on top of the backend, where `next_backend_key` gives the next key after a
+	/// given one in the backend.
+	pub fn next_storage_key<F>(&self, key: &[u8], mut next_backend_key: F) -> Option<Vec<u8>>
+		where F: FnMut(&[u8]) -> Option<Vec<u8>>
+	{
+		let mut next_backend = next_backend_key(key);
+		let mut next_overlay_change = self.next_storage_key_change(key);
+
+		loop {
+			let overlay_key = match next_overlay_change {
+				Some(overlay_key) => overlay_key,
+				None => return next_backend,
+			};
+			if next_backend.as_ref().map_or(false, |k| &k[..] < overlay_key.0) {
+				return next_backend;
+			}
+			if overlay_key.1.value.is_some() {
+				return Some(overlay_key.0.to_vec());
+			}
+
+			// The key has been deleted in the overlay, look for the one after it.
+			if next_backend.as_ref().map_or(false, |k| &k[..] == overlay_key.0) {
+				next_backend = next_backend_key(overlay_key.0);
+			}
+			next_overlay_change = self.next_storage_key_change(overlay_key.0);
+		}
+	}
+
 	/// Returns a double-Option: None if the key is unknown (i.e. and the query should be refered
 	/// to the backend); Some(None) if the key has been deleted. Some(Some(...)) for a key whose
 	/// value has been set.
@@ -275,7 +324,7 @@ impl OverlayedChanges {
 		if self.committed.is_empty() {
 			::std::mem::swap(&mut self.prospective, &mut self.committed);
 		} else {
-			for (key, val) in self.prospective.top.drain() {
+			for (key, val) in ::std::mem::replace(&mut self.prospective.top, Default::default()) {
 				let entry = self.committed.top.entry(key).or_default();
 				entry.value = val.value;
 
@@ -284,9 +333,9 @@ impl OverlayedChanges {
 						.extend(prospective_extrinsics);
 				}
 			}
-			for (storage_key, mut map) in self.prospective.children.drain() {
+			for (storage_key, map) in self.prospective.children.drain() {
 				let map_dest = self.committed.children.entry(storage_key).or_default();
-				for (key, val) in map.drain() {
+				for (key, val) in map {
 					let entry = map_dest.entry(key).or_default();
 					entry.value = val.value;
 
@@ -358,7 +407,7 @@ mod tests {
 	use crate::Externalities;
 	use super::*;
 
-	fn strip_extrinsic_index(map: &HashMap<Vec<u8>, OverlayedValue>) -> HashMap<Vec<u8>, OverlayedValue> {
+	fn strip_extrinsic_index(map: &BTreeMap<Vec<u8>, OverlayedValue>) -> BTreeMap<Vec<u8>, OverlayedValue> {
 		let mut clone = map.clone();
 		clone.remove(&EXTRINSIC_INDEX.to_vec());
 		clone
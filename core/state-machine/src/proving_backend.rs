@@ -58,6 +58,17 @@ impl<'a, S, H> ProvingBackendEssence<'a, S, H>
 		).map_err(map_e)
 	}
 
+	pub fn next_storage_key(&mut self, key: &[u8]) -> Result<Option<Vec<u8>>, String> {
+		// Seeking `key` and stepping to the following key only touches the nodes on the lookup
+		// paths of both keys, so recording these two lookups is enough for the proof.
+		self.storage(key)?;
+		let next_key = self.backend.next_storage_key(key)?;
+		if let Some(ref next_key) = next_key {
+			self.storage(next_key)?;
+		}
+		Ok(next_key)
+	}
+
 	pub fn child_storage(
 		&mut self,
 		storage_key: &[u8],
@@ -166,6 +177,14 @@ impl<'a, S, H> Backend<H> for ProvingBackend<'a, S, H>
 		}.child_storage(storage_key, key)
 	}
 
+	fn next_storage_key(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
+		ProvingBackendEssence {
+			backend: self.backend.essence(),
+			proof_recorder: &mut *self.proof_recorder.try_borrow_mut()
+				.expect("only fails when already borrowed; next_storage_key() is non-reentrant; qed"),
+		}.next_storage_key(key)
+	}
+
 	fn for_keys_in_child_storage<F: FnMut(&[u8])>(&self, storage_key: &[u8], f: F) {
 		self.backend.for_keys_in_child_storage(storage_key, f)
 	}
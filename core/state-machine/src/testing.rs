@@ -173,6 +173,11 @@ impl<H, N> Externalities<H> for TestExternalities<H, N>
 			.expect(EXT_NOT_ALLOWED_TO_FAIL)
 	}
 
+	fn next_storage_key(&self, key: &[u8]) -> Option<Vec<u8>> {
+		let backend = &self.backend;
+		self.overlay.next_storage_key(key, |key| backend.next_storage_key(key).expect(EXT_NOT_ALLOWED_TO_FAIL))
+	}
+
 	fn place_storage(&mut self, key: Vec<u8>, maybe_value: Option<Vec<u8>>) {
 		if is_child_storage_key(&key) {
 			panic!("Refuse to directly set child storage key");
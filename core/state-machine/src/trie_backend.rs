@@ -74,6 +74,10 @@ impl<S: TrieBackendStorage<H>, H: Hasher> Backend<H> for TrieBackend<S, H> where
 		self.essence.child_storage(storage_key, key)
 	}
 
+	fn next_storage_key(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
+		self.essence.next_storage_key(key)
+	}
+
 	fn for_keys_with_prefix<F: FnMut(&[u8])>(&self, prefix: &[u8], f: F) {
 		self.essence.for_keys_with_prefix(prefix, f)
 	}
@@ -246,6 +250,16 @@ pub mod tests {
 		assert_eq!(test_trie().storage(b"non-existing-key").unwrap(), None);
 	}
 
+	#[test]
+	fn next_storage_key_works() {
+		let trie = test_trie();
+		assert_eq!(trie.next_storage_key(b"").unwrap(), Some(b":child_storage:default:sub1".to_vec()));
+		assert_eq!(trie.next_storage_key(b"key").unwrap(), Some(b"value1".to_vec()));
+		assert_eq!(trie.next_storage_key(b"value").unwrap(), Some(b"value1".to_vec()));
+		assert_eq!(trie.next_storage_key(b"value2").unwrap(), Some(vec![128]));
+		assert_eq!(trie.next_storage_key(&[254]).unwrap(), None);
+	}
+
 	#[test]
 	fn pairs_are_not_empty_on_non_empty_storage() {
 		assert!(!test_trie().pairs().is_empty());
@@ -76,6 +76,33 @@ impl<S: TrieBackendStorage<H>, H: Hasher> TrieBackendEssence<S, H> {
 		read_trie_value::<Layout<H>, _>(&eph, &self.root, key).map_err(map_e)
 	}
 
+	/// Return the next key in the trie i.e. the minimum key that is strictly superior to `key` in
+	/// lexicographic order.
+	pub fn next_storage_key(&self, key: &[u8]) -> Result<Option<Vec<u8>>, String> {
+		let mut read_overlay = S::Overlay::default();
+		let eph = Ephemeral {
+			storage: &self.storage,
+			overlay: &mut read_overlay,
+		};
+
+		let map_e = |e| format!("Trie lookup error: {}", e);
+
+		let trie = TrieDB::<H>::new(&eph, &self.root).map_err(map_e)?;
+		let mut iter = trie.iter().map_err(map_e)?;
+
+		// The iterator is positioned at the first key that is greater or equal to `key`.
+		iter.seek(key).map_err(map_e)?;
+
+		for x in iter {
+			let (next_key, _) = x.map_err(map_e)?;
+			if &next_key[..] != key {
+				return Ok(Some(next_key));
+			}
+		}
+
+		Ok(None)
+	}
+
 	/// Get the value of child storage at given key.
 	pub fn child_storage(&self, storage_key: &[u8], key: &[u8]) -> Result<Option<Vec<u8>>, String> {
 		let root = self.storage(storage_key)?
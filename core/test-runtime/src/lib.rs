@@ -37,7 +37,7 @@ use substrate_client::{
 	impl_runtime_apis,
 };
 use sr_primitives::{
-	ApplyResult, create_runtime_str, Perbill, impl_opaque_keys,
+	ApplyResult, create_runtime_str, Perbill, impl_opaque_keys, weights::Weight,
 	transaction_validity::{TransactionValidity, ValidTransaction},
 	traits::{
 		BlindCheckable, BlakeTwo256, Block as BlockT, Extrinsic as ExtrinsicT,
@@ -389,9 +389,20 @@ parameter_types! {
 	pub const ExpectedBlockTime: u64 = 10_000;
 }
 
+impl From<srml_babe::Call<Runtime>> for Extrinsic {
+	fn from(_call: srml_babe::Call<Runtime>) -> Self {
+		unimplemented!("Not required in tests!")
+	}
+}
+
 impl srml_babe::Trait for Runtime {
 	type EpochDuration = EpochDuration;
 	type ExpectedBlockTime = ExpectedBlockTime;
+	type Call = Extrinsic;
+	type SubmitTransaction = srml_system::offchain::TransactionSubmitter<(), Runtime, Extrinsic>;
+	type KeyOwnerProof = ();
+	type KeyOwnerSystem = ();
+	type ReportEquivocation = ();
 }
 
 /// Adds one to the given input and returns the final result.
@@ -511,6 +522,11 @@ cfg_if! {
 				fn random_seed() -> <Block as BlockT>::Hash {
 					unimplemented!()
 				}
+
+				fn block_weight() -> Weight {
+					// the test runtime doesn't weigh its extrinsics
+					0
+				}
 			}
 
 			impl self::TestAPI<Block> for Runtime {
@@ -695,6 +711,11 @@ cfg_if! {
 				fn random_seed() -> <Block as BlockT>::Hash {
 					unimplemented!()
 				}
+
+				fn block_weight() -> Weight {
+					// the test runtime doesn't weigh its extrinsics
+					0
+				}
 			}
 
 			impl self::TestAPI<Block> for Runtime {
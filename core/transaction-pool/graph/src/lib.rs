@@ -26,6 +26,7 @@
 
 mod future;
 mod listener;
+mod metrics;
 mod pool;
 mod ready;
 mod rotator;
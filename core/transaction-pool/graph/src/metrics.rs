@@ -0,0 +1,31 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Prometheus metrics of the transaction pool.
+
+use substrate_prometheus::{IntGauge, create_int_gauge, lazy_static};
+
+lazy_static! {
+	/// Number of transactions in the ready queue.
+	pub static ref READY_TRANSACTIONS: IntGauge = create_int_gauge(
+		"substrate_ready_transactions_number", "Number of transactions in the ready queue",
+	);
+
+	/// Number of transactions in the future queue.
+	pub static ref FUTURE_TRANSACTIONS: IntGauge = create_int_gauge(
+		"substrate_future_transactions_number", "Number of transactions in the future queue",
+	);
+}
@@ -24,6 +24,7 @@ use std::{
 use crate::base_pool as base;
 use crate::error;
 use crate::listener::Listener;
+use crate::metrics;
 use crate::rotator::PoolRotator;
 use crate::watcher::Watcher;
 use log::debug;
@@ -192,6 +193,7 @@ impl<B: ChainApi> Pool<B> {
             .collect::<Vec<_>>();
 
         let removed = self.enforce_limits();
+        self.update_metrics();
 
         Ok(results
             .into_iter()
@@ -510,6 +512,7 @@ impl<B: ChainApi> Pool<B> {
         for tx in &invalid {
             listener.invalid(&tx.hash);
         }
+        self.update_metrics();
 
         invalid
     }
@@ -534,6 +537,13 @@ impl<B: ChainApi> Pool<B> {
         self.pool2.read().status()
     }
 
+    /// Export the sizes of both pools to the metrics.
+    fn update_metrics(&self) {
+        let (status, status2) = (self.status(), self.status_2());
+        metrics::READY_TRANSACTIONS.set((status.ready + status2.ready) as i64);
+        metrics::FUTURE_TRANSACTIONS.set((status.future + status2.future) as i64);
+    }
+
     /// Returns transaction hash
     pub fn hash_of(&self, xt: &ExtrinsicFor<B>) -> ExHash<B> {
         self.api.hash_and_length(xt).0
@@ -34,6 +34,7 @@ pub use timestamp::Call as TimestampCall;
 pub use balances::Call as BalancesCall;
 pub use sr_primitives::{Permill, Perbill};
 pub use support::{StorageValue, construct_runtime, parameter_types};
+use support::traits::Randomness;
 
 /// An index to a block.
 pub type BlockNumber = u32;
@@ -189,10 +190,22 @@ parameter_types! {
 impl babe::Trait for Runtime {
 	type EpochDuration = EpochDuration;
 	type ExpectedBlockTime = ExpectedBlockTime;
+	type Call = Call;
+	type SubmitTransaction = system::offchain::TransactionSubmitter<(), Runtime, UncheckedExtrinsic>;
+	// there are no staked validators to identify and slash, so equivocations can't be reported.
+	type KeyOwnerProof = ();
+	type KeyOwnerSystem = ();
+	type ReportEquivocation = ();
 }
 
 impl grandpa::Trait for Runtime {
 	type Event = Event;
+	type Call = Call;
+	type SubmitTransaction = system::offchain::TransactionSubmitter<(), Runtime, UncheckedExtrinsic>;
+	// there are no staked validators to identify and slash, so equivocations can't be reported.
+	type KeyOwnerProof = ();
+	type KeyOwnerSystem = ();
+	type ReportEquivocation = ();
 }
 
 impl indices::Trait for Runtime {
@@ -340,7 +353,11 @@ impl_runtime_apis! {
 		}
 
 		fn random_seed() -> <Block as BlockT>::Hash {
-			System::random_seed()
+			Babe::random_seed()
+		}
+
+		fn block_weight() -> Weight {
+			System::all_extrinsics_weight()
 		}
 	}
 
@@ -112,6 +112,7 @@ pub fn new_full<C: Send + Default + 'static>(config: Configuration<C, GenesisCon
 		let proposer = basic_authorship::ProposerFactory {
 			client: service.client(),
 			transaction_pool: service.transaction_pool(),
+			config: Default::default(),
 		};
 
 		let client = service.client();
@@ -128,6 +129,7 @@ pub fn new_full<C: Send + Default + 'static>(config: Configuration<C, GenesisCon
 			sync_oracle: service.network(),
 			inherent_data_providers: inherent_data_providers.clone(),
 			force_authoring: force_authoring,
+			backoff_authoring_blocks: Some(babe::BackoffAuthoringOnFinalizedHeadLagging::default()),
 			time_source: babe_link,
 		};
 
@@ -166,6 +168,7 @@ pub fn new_full<C: Send + Default + 'static>(config: Configuration<C, GenesisCon
 				inherent_data_providers: inherent_data_providers.clone(),
 				on_exit: service.on_exit(),
 				telemetry_on_connect: Some(service.telemetry_on_connect_stream()),
+				voting_rule: grandpa::VotingRulesBuilder::default().build(),
 			};
 
 			// the GRANDPA voter task is considered infallible, i.e.
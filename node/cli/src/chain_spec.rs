@@ -21,7 +21,7 @@ pub use node_primitives::{AccountId, Balance};
 use node_runtime::{
 	AuthorityDiscoveryConfig, BabeConfig, BalancesConfig, ContractsConfig, CouncilConfig, DemocracyConfig,
 	ElectionsConfig, GrandpaConfig, ImOnlineConfig, IndicesConfig, SessionConfig, SessionKeys, StakerStatus,
-	StakingConfig, SudoConfig, SystemConfig, TechnicalCommitteeConfig, WASM_BINARY,
+	StakingConfig, SudoConfig, SystemConfig, TechnicalCommitteeConfig, VestingConfig, WASM_BINARY,
 };
 use node_runtime::constants::{time::*, currency::*};
 pub use node_runtime::GenesisConfig;
@@ -119,6 +119,8 @@ fn staging_testnet_config_genesis() -> GenesisConfig {
 				.map(|k| (k, ENDOWMENT))
 				.chain(initial_authorities.iter().map(|x| (x.0.clone(), STASH)))
 				.collect(),
+		}),
+		vesting: Some(VestingConfig {
 			vesting: vec![],
 		}),
 		indices: Some(IndicesConfig {
@@ -253,6 +255,8 @@ pub fn testnet_genesis(
 		}),
 		balances: Some(BalancesConfig {
 			balances: endowed_accounts.iter().map(|k| (k.clone(), ENDOWMENT)).collect(),
+		}),
+		vesting: Some(VestingConfig {
 			vesting: vec![],
 		}),
 		session: Some(SessionConfig {
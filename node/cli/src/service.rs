@@ -48,9 +48,14 @@ macro_rules! new_full_start {
 		let inherent_data_providers = inherents::InherentDataProviders::new();
 		let mut tasks_to_spawn = Vec::new();
 
+		let mut config = $config;
+		// Index the events of the imported blocks by topic for `state_queryEventTopics`.
+		config.event_topics_key_prefix =
+			Some(system::Module::<node_runtime::Runtime>::event_topics_key_prefix());
+
 		let builder = substrate_service::ServiceBuilder::new_full::<
 			node_primitives::Block, node_runtime::RuntimeApi, node_executor::Executor
-		>($config)?
+		>(config)?
 			.with_select_chain(|_config, backend| {
 				Ok(client::LongestChain::new(backend.clone()))
 			})?
@@ -140,6 +145,7 @@ macro_rules! new_full {
 			let proposer = substrate_basic_authorship::ProposerFactory {
 				client: service.client(),
 				transaction_pool: service.transaction_pool(),
+				config: Default::default(),
 			};
 
 			let client = service.client();
@@ -156,6 +162,7 @@ macro_rules! new_full {
 				sync_oracle: service.network(),
 				inherent_data_providers: inherent_data_providers.clone(),
 				force_authoring: force_authoring,
+				backoff_authoring_blocks: Some(babe::BackoffAuthoringOnFinalizedHeadLagging::default()),
 				time_source: babe_link,
 			};
 
@@ -191,6 +198,7 @@ macro_rules! new_full {
 					inherent_data_providers: inherent_data_providers.clone(),
 					on_exit: service.on_exit(),
 					telemetry_on_connect: Some(service.telemetry_on_connect_stream()),
+					voting_rule: grandpa::VotingRulesBuilder::default().build(),
 				};
 				service.spawn_task(Box::new(grandpa::run_grandpa_voter(grandpa_config)?));
 			},
@@ -401,6 +409,7 @@ mod tests {
 				let mut proposer_factory = substrate_basic_authorship::ProposerFactory {
 					client: service.client(),
 					transaction_pool: service.transaction_pool(),
+					config: Default::default(),
 				};
 
 				let mut digest = Digest::<H256>::default();
@@ -20,6 +20,7 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use rstd::prelude::*;
 use sr_primitives::{
 	generic, traits::{Verify, BlakeTwo256}, OpaqueExtrinsic, AnySignature
 };
@@ -73,4 +74,10 @@ client::decl_runtime_apis! {
 		/// Get current account nonce of given `AccountId`.
 		fn account_nonce(account: AccountId) -> Index;
 	}
+
+	/// The API to look up the on-chain names of accounts.
+	pub trait NicksApi {
+		/// Get the names of the given `accounts`, in the same order; `None` for unnamed accounts.
+		fn names(accounts: Vec<AccountId>) -> Vec<Option<Vec<u8>>>;
+	}
 }
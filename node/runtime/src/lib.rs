@@ -22,8 +22,9 @@
 
 use rstd::prelude::*;
 use support::{
-	construct_runtime, parameter_types, traits::{SplitTwoWays, Currency}
+	construct_runtime, parameter_types, traits::{SplitTwoWays, Currency, InstanceFilter, Randomness}
 };
+use codec::{Encode, Decode};
 use primitives::u32_trait::{_1, _2, _3, _4};
 use node_primitives::{
 	AccountId, AccountIndex, Balance, BlockNumber, Hash, Index,
@@ -40,6 +41,7 @@ use sr_primitives::transaction_validity::TransactionValidity;
 use sr_primitives::weights::Weight;
 use sr_primitives::traits::{
 	self, BlakeTwo256, Block as BlockT, DigestFor, NumberFor, StaticLookup, SaturatedConversion,
+	ConvertInto,
 };
 use version::RuntimeVersion;
 use elections::VoteIndex;
@@ -48,7 +50,7 @@ use version::NativeVersion;
 use primitives::OpaqueMetadata;
 use grandpa::{AuthorityId as GrandpaId, AuthorityWeight as GrandpaWeight};
 use im_online::sr25519::{AuthorityId as ImOnlineId};
-use system::offchain::TransactionSubmitter;
+use system::{EnsureRoot, offchain::TransactionSubmitter};
 
 #[cfg(any(feature = "std", test))]
 pub use sr_primitives::BuildStorage;
@@ -80,8 +82,8 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	// and set impl_version to equal spec_version. If only runtime
 	// implementation changes and behavior does not, then leave spec_version as
 	// is and increment impl_version.
-	spec_version: 154,
-	impl_version: 159,
+	spec_version: 183,
+	impl_version: 183,
 	apis: RUNTIME_API_VERSIONS,
 };
 
@@ -138,6 +140,11 @@ parameter_types! {
 impl babe::Trait for Runtime {
 	type EpochDuration = EpochDuration;
 	type ExpectedBlockTime = ExpectedBlockTime;
+	type Call = Call;
+	type SubmitTransaction = SubmitTransaction;
+	type KeyOwnerProof = session::historical::Proof;
+	type KeyOwnerSystem = session::historical::Module<Runtime>;
+	type ReportEquivocation = Offences;
 }
 
 impl indices::Trait for Runtime {
@@ -157,7 +164,7 @@ parameter_types! {
 
 impl balances::Trait for Runtime {
 	type Balance = Balance;
-	type OnFreeBalanceZero = ((Staking, Contracts), Session);
+	type OnFreeBalanceZero = (((Staking, Contracts), Session), Vesting);
 	type OnNewAccount = Indices;
 	type Event = Event;
 	type TransactionPayment = DealWithFees;
@@ -171,6 +178,17 @@ impl balances::Trait for Runtime {
 	type WeightToFee = WeightToFee;
 }
 
+parameter_types! {
+	pub const MinVestedTransfer: Balance = 100 * DOLLARS;
+}
+
+impl vesting::Trait for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type BlockNumberToBalance = ConvertInto;
+	type MinVestedTransfer = MinVestedTransfer;
+}
+
 parameter_types! {
 	pub const MinimumPeriod: Moment = SLOT_DURATION / 2;
 }
@@ -278,6 +296,7 @@ impl democracy::Trait for Runtime {
 	// only do it once and it lasts only for the cooloff period.
 	type VetoOrigin = collective::EnsureMember<AccountId, TechnicalCollective>;
 	type CooloffPeriod = CooloffPeriod;
+	type Scheduler = Scheduler;
 }
 
 type CouncilCollective = collective::Instance1;
@@ -364,6 +383,7 @@ parameter_types! {
 
 impl contracts::Trait for Runtime {
 	type Currency = Balances;
+	type Randomness = Babe;
 	type Call = Call;
 	type Event = Event;
 	type DetermineContractAddress = contracts::SimpleAddressDeterminator<Runtime>;
@@ -386,6 +406,7 @@ impl contracts::Trait for Runtime {
 	type MaxDepth = contracts::DefaultMaxDepth;
 	type MaxValueSize = contracts::DefaultMaxValueSize;
 	type BlockGasLimit = contracts::DefaultBlockGasLimit;
+	type ChainExtension = ();
 }
 
 impl sudo::Trait for Runtime {
@@ -393,6 +414,129 @@ impl sudo::Trait for Runtime {
 	type Proposal = Call;
 }
 
+parameter_types! {
+	// One storage item; key size 32, value size 16 for the deposit plus the list length.
+	pub const ProxyDepositBase: Balance = 1 * DOLLARS;
+	// Additional 33 bytes (account and proxy type) for each proxy in the list.
+	pub const ProxyDepositFactor: Balance = 50 * CENTS;
+	pub const MaxProxies: u16 = 32;
+}
+
+/// The type used to represent the kinds of proxying allowed.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum ProxyType {
+	/// Any call may be dispatched.
+	Any,
+	/// Any call that cannot move funds out of the account or change its proxies.
+	NonTransfer,
+	/// Only calls to the governance modules.
+	Governance,
+	/// Only calls to the staking and session modules.
+	Staking,
+}
+
+impl Default for ProxyType {
+	fn default() -> Self { ProxyType::Any }
+}
+
+impl InstanceFilter<Call> for ProxyType {
+	fn filter(&self, c: &Call) -> bool {
+		match self {
+			ProxyType::Any => true,
+			// Only the calls listed here are allowed, so that calls of newly added modules are
+			// rejected until they are explicitly reviewed.
+			ProxyType::NonTransfer => match c {
+				Call::System(..) | Call::Babe(..) | Call::Timestamp(..) | Call::Authorship(..) |
+				Call::Indices(..) | Call::Staking(..) | Call::Session(..) | Call::Democracy(..) |
+				Call::Council(..) | Call::TechnicalCommittee(..) | Call::Elections(..) |
+				Call::TechnicalMembership(..) | Call::FinalityTracker(..) | Call::Grandpa(..) |
+				Call::Treasury(..) | Call::ImOnline(..) | Call::AuthorityDiscovery(..) |
+				Call::Offences(..) | Call::Nicks(..) => true,
+				Call::Vesting(vesting::Call::vest(..)) | Call::Vesting(vesting::Call::vest_other(..)) => true,
+				_ => false,
+			},
+			ProxyType::Governance => match c {
+				Call::Democracy(..) | Call::Council(..) | Call::TechnicalCommittee(..) |
+				Call::Elections(..) | Call::Treasury(..) => true,
+				_ => false,
+			},
+			ProxyType::Staking => match c {
+				Call::Staking(..) | Call::Session(..) => true,
+				_ => false,
+			},
+		}
+	}
+
+	fn is_superset(&self, o: &Self) -> bool {
+		match (self, o) {
+			(x, y) if x == y => true,
+			(ProxyType::Any, _) => true,
+			(_, ProxyType::Any) => false,
+			(ProxyType::NonTransfer, _) => true,
+			_ => false,
+		}
+	}
+}
+
+parameter_types! {
+	// 80% of the maximum block weight.
+	pub const MaximumSchedulerWeight: Weight = 800_000_000;
+}
+
+impl scheduler::Trait for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type MaximumWeight = MaximumSchedulerWeight;
+	type ScheduleOrigin = EnsureRoot<AccountId>;
+}
+
+parameter_types! {
+	// One storage item; key size 32, value size 8 + 16 + 2 for the delay, deposit and threshold.
+	pub const ConfigDepositBase: Balance = 5 * DOLLARS;
+	// Additional 32 bytes for each friend in the list.
+	pub const FriendDepositFactor: Balance = 50 * CENTS;
+	pub const MaxFriends: u16 = 9;
+	pub const RecoveryDeposit: Balance = 5 * DOLLARS;
+}
+
+impl recovery::Trait for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type Currency = Balances;
+	type ConfigDepositBase = ConfigDepositBase;
+	type FriendDepositFactor = FriendDepositFactor;
+	type MaxFriends = MaxFriends;
+	type RecoveryDeposit = RecoveryDeposit;
+}
+
+parameter_types! {
+	pub const ReservationFee: Balance = 1 * DOLLARS;
+	pub const MinNickLength: usize = 3;
+	pub const MaxNickLength: usize = 32;
+}
+
+impl nicks::Trait for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type ReservationFee = ReservationFee;
+	type Slashed = Treasury;
+	/// Two thirds of the council may forcibly set or remove a name.
+	type ForceOrigin = collective::EnsureProportionAtLeast<_2, _3, AccountId, CouncilCollective>;
+	type MinLength = MinNickLength;
+	type MaxLength = MaxNickLength;
+}
+
+impl proxy::Trait for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type Currency = Balances;
+	type ProxyType = ProxyType;
+	type ProxyDepositBase = ProxyDepositBase;
+	type ProxyDepositFactor = ProxyDepositFactor;
+	type MaxProxies = MaxProxies;
+}
+
 type SubmitTransaction = TransactionSubmitter<ImOnlineId, Runtime, UncheckedExtrinsic>;
 
 impl im_online::Trait for Runtime {
@@ -414,6 +558,11 @@ impl authority_discovery::Trait for Runtime {}
 
 impl grandpa::Trait for Runtime {
 	type Event = Event;
+	type Call = Call;
+	type SubmitTransaction = SubmitTransaction;
+	type KeyOwnerProof = session::historical::Proof;
+	type KeyOwnerSystem = session::historical::Module<Runtime>;
+	type ReportEquivocation = Offences;
 }
 
 parameter_types! {
@@ -461,11 +610,12 @@ construct_runtime!(
 		UncheckedExtrinsic = UncheckedExtrinsic
 	{
 		System: system::{Module, Call, Storage, Config, Event},
-		Babe: babe::{Module, Call, Storage, Config, Inherent(Timestamp)},
+		Babe: babe::{Module, Call, Storage, Config, Inherent(Timestamp), ValidateUnsigned},
 		Timestamp: timestamp::{Module, Call, Storage, Inherent},
 		Authorship: authorship::{Module, Call, Storage, Inherent},
 		Indices: indices,
 		Balances: balances,
+		Vesting: vesting::{Module, Call, Storage, Event<T>, Config<T>},
 		Staking: staking::{default, OfflineWorker},
 		Session: session::{Module, Call, Storage, Event, Config<T>},
 		Democracy: democracy::{Module, Call, Storage, Config, Event<T>},
@@ -474,13 +624,18 @@ construct_runtime!(
 		Elections: elections::{Module, Call, Storage, Event<T>, Config<T>},
 		TechnicalMembership: membership::<Instance1>::{Module, Call, Storage, Event<T>, Config<T>},
 		FinalityTracker: finality_tracker::{Module, Call, Inherent},
-		Grandpa: grandpa::{Module, Call, Storage, Config, Event},
+		Grandpa: grandpa::{Module, Call, Storage, Config, Event, ValidateUnsigned},
 		Treasury: treasury::{Module, Call, Storage, Event<T>},
 		Contracts: contracts,
 		Sudo: sudo,
 		ImOnline: im_online::{Module, Call, Storage, Event<T>, ValidateUnsigned, Config<T>},
 		AuthorityDiscovery: authority_discovery::{Module, Call, Config<T>},
 		Offences: offences::{Module, Call, Storage, Event},
+		Proxy: proxy::{Module, Call, Storage, Event},
+		Scheduler: scheduler::{Module, Call, Storage, Event<T>},
+		Recovery: recovery::{Module, Call, Storage, Event<T>},
+		Nicks: nicks::{Module, Call, Storage, Event<T>},
+		RandomnessCollectiveFlip: randomness_collective_flip::{Module, Call, Storage},
 	}
 );
 
@@ -551,7 +706,11 @@ impl_runtime_apis! {
 		}
 
 		fn random_seed() -> <Block as BlockT>::Hash {
-			System::random_seed()
+			RandomnessCollectiveFlip::random_seed()
+		}
+
+		fn block_weight() -> Weight {
+			System::all_extrinsics_weight()
 		}
 	}
 
@@ -634,6 +793,12 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl node_primitives::NicksApi<Block> for Runtime {
+		fn names(accounts: Vec<AccountId>) -> Vec<Option<Vec<u8>>> {
+			Nicks::names(&accounts)
+		}
+	}
+
 	impl substrate_session::SessionKeys<Block> for Runtime {
 		fn generate_session_keys(seed: Option<Vec<u8>>) -> Vec<u8> {
 			let seed = seed.as_ref().map(|s| rstd::str::from_utf8(&s).expect("Seed is an utf8 string"));
@@ -20,7 +20,7 @@ use crate::keyring::*;
 use keyring::{Ed25519Keyring, Sr25519Keyring};
 use node_runtime::{
 	GenesisConfig, BalancesConfig, SessionConfig, StakingConfig, SystemConfig,
-	GrandpaConfig, IndicesConfig, ContractsConfig, WASM_BINARY,
+	GrandpaConfig, IndicesConfig, ContractsConfig, VestingConfig, WASM_BINARY,
 };
 use node_runtime::constants::currency::*;
 use primitives::ChangesTrieConfiguration;
@@ -49,6 +49,8 @@ pub fn config(support_changes_trie: bool, code: Option<&[u8]>) -> GenesisConfig
 				(eve(), 101 * DOLLARS),
 				(ferdie(), 100 * DOLLARS),
 			],
+		}),
+		vesting: Some(VestingConfig {
 			vesting: vec![],
 		}),
 		session: Some(SessionConfig {
@@ -16,23 +16,30 @@
 
 //! Consensus extension module for BABE consensus. Collects on-chain randomness
 //! from VRF outputs and manages epoch transitions.
+//!
+//! Slot equivocations observed by the BABE block import are reported on-chain by
+//! this module's offchain worker and forwarded as offences.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![forbid(unused_must_use, unsafe_code, unused_variables)]
 
-// TODO: @marcio uncomment this when BabeEquivocation is integrated.
-// #![forbid(dead_code)]
-
 pub use timestamp;
 
 use rstd::{result, prelude::*};
-use srml_support::{decl_storage, decl_module, StorageValue, StorageMap, traits::FindAuthor, traits::Get};
+use srml_support::{
+	decl_storage, decl_module, StorageValue, StorageMap, print, ensure, Parameter,
+	traits::{FindAuthor, Get, KeyOwnerProofSystem, Randomness as RandomnessT},
+};
 use timestamp::{OnTimestampSet};
-use sr_primitives::{generic::DigestItem, ConsensusEngineId, Perbill};
-use sr_primitives::traits::{IsMember, SaturatedConversion, Saturating, RandomnessBeacon};
+use primitives::{crypto::{KeyTypeId, key_types}, offchain::StorageKind};
+use sr_primitives::{
+	generic::DigestItem, ConsensusEngineId, Perbill, ApplyError,
+	transaction_validity::{TransactionValidity, TransactionLongevity, ValidTransaction},
+};
+use sr_primitives::traits::{Hash, IsMember, SaturatedConversion, Saturating, RandomnessBeacon};
 use sr_staking_primitives::{
 	SessionIndex,
-	offence::{Offence, Kind},
+	offence::{Offence, Kind, ReportOffence},
 };
 use sr_primitives::weights::SimpleDispatchInfo;
 #[cfg(feature = "std")]
@@ -41,9 +48,16 @@ use codec::{Encode, Decode};
 use inherents::{RuntimeString, InherentIdentifier, InherentData, ProvideInherent, MakeFatalError};
 #[cfg(feature = "std")]
 use inherents::{InherentDataProviders, ProvideInherentData};
-use babe_primitives::{BABE_ENGINE_ID, ConsensusLog, BabeAuthorityWeight, Epoch, RawBabePreDigest};
-pub use babe_primitives::{AuthorityId, VRF_OUTPUT_LENGTH, PUBLIC_KEY_LENGTH};
-use system::ensure_root;
+use babe_primitives::{
+	BABE_ENGINE_ID, EQUIVOCATION_REPORTS_KEY, ConsensusLog, BabeAuthorityWeight, Epoch,
+	RawBabePreDigest,
+};
+pub use babe_primitives::{AuthorityId, EquivocationProof, VRF_OUTPUT_LENGTH, PUBLIC_KEY_LENGTH};
+use system::{ensure_root, offchain::SubmitUnsignedTransaction};
+use session::GetSessionNumber;
+
+mod mock;
+mod tests;
 
 /// The BABE inherent identifier.
 pub const INHERENT_IDENTIFIER: InherentIdentifier = *b"babeslot";
@@ -120,8 +134,33 @@ impl ProvideInherentData for InherentDataProvider {
 pub trait Trait: timestamp::Trait {
 	type EpochDuration: Get<u64>;
 	type ExpectedBlockTime: Get<Self::Moment>;
+
+	/// A dispatchable call type.
+	type Call: From<Call<Self>>;
+
+	/// A transaction submitter, used to submit the equivocation reports.
+	type SubmitTransaction: SubmitUnsignedTransaction<Self, <Self as Trait>::Call>;
+
+	/// The proof that an authority key was owned by a validator.
+	type KeyOwnerProof: Parameter + GetSessionNumber;
+
+	/// A system for proving ownership of the authority keys, used to identify the offenders.
+	type KeyOwnerSystem: KeyOwnerProofSystem<(KeyTypeId, AuthorityId), Proof = Self::KeyOwnerProof>;
+
+	/// A type that gives us the ability to submit equivocation offence reports.
+	type ReportEquivocation:
+		ReportOffence<
+			Self::AccountId,
+			IdentificationTuple<Self>,
+			BabeEquivocationOffence<IdentificationTuple<Self>>,
+		>;
 }
 
+/// The full identification of an offender, as returned by the key owner proof system.
+pub type IdentificationTuple<T> = <<T as Trait>::KeyOwnerSystem as KeyOwnerProofSystem<
+	(KeyTypeId, AuthorityId),
+>>::IdentificationTuple;
+
 /// The length of the BABE randomness
 pub const RANDOMNESS_LENGTH: usize = 32;
 
@@ -166,6 +205,9 @@ decl_storage! {
 		// variable to its underlying value.
 		pub Randomness get(randomness): [u8; 32 /* RANDOMNESS_LENGTH */];
 
+		/// The epoch randomness for the *previous* epoch.
+		pub PreviousRandomness get(previous_randomness): [u8; 32 /* RANDOMNESS_LENGTH */];
+
 		/// Next epoch randomness.
 		NextRandomness: [u8; 32 /* RANDOMNESS_LENGTH */];
 
@@ -184,6 +226,11 @@ decl_storage! {
 		/// Temporary value (cleared at block finalization) which is true
 		/// if per-block initialization has already been called for current block.
 		Initialized get(initialized): Option<bool>;
+
+		/// Slot at which each session started, i.e. the start slot of its epoch.
+		///
+		/// Used to check that an equivocation and its key ownership proof are of the same session.
+		SessionStartSlot get(session_start_slot): map SessionIndex => Option<u64>;
 	}
 	add_extra_genesis {
 		config(authorities): Vec<(AuthorityId, BabeAuthorityWeight)>;
@@ -228,6 +275,34 @@ decl_module! {
 				},
 			}
 		}
+
+		/// Report a slot equivocation of a BABE authority, along with the proof that the
+		/// offender owned its key at the time.
+		///
+		/// The report may be submitted unsigned, in which case the offence has no reporter.
+		fn report_equivocation(
+			origin,
+			equivocation_proof: EquivocationProof<T::Header>,
+			key_owner_proof: T::KeyOwnerProof
+		) {
+			let origin: result::Result<system::RawOrigin<T::AccountId>, T::Origin> = origin.into();
+			let reporters = match origin {
+				Ok(system::RawOrigin::Signed(who)) => vec![who],
+				Ok(system::RawOrigin::None) => vec![],
+				_ => return Err("bad origin: expected to be a signed or unsigned origin"),
+			};
+
+			let offence = Self::check_equivocation_report(&equivocation_proof, key_owner_proof)?;
+			T::ReportEquivocation::report_offence(reporters, offence);
+		}
+
+		// Runs after every block.
+		fn offchain_worker(_now: T::BlockNumber) {
+			// Only validators import blocks as authorities and could have queued equivocations.
+			if runtime_io::is_validator() {
+				Self::submit_equivocation_reports();
+			}
+		}
 	}
 }
 
@@ -237,6 +312,24 @@ impl<T: Trait> RandomnessBeacon for Module<T> {
 	}
 }
 
+impl<T: Trait> RandomnessT<<T as system::Trait>::Hash> for Module<T> {
+	/// Get a "random" value derived from the epoch randomness.
+	///
+	/// The epoch randomness of the current and of the previous epoch are fixed when the
+	/// current epoch starts and are accumulated from the VRF outputs of earlier epochs, so
+	/// neither the author of this block nor the caller can influence the result. Mixing in
+	/// the previous epoch's randomness means that the authors of a single epoch can't bias
+	/// the result on their own. It changes only once per epoch.
+	fn random(subject: &[u8]) -> T::Hash {
+		let mut subject = subject.to_vec();
+		subject.reserve(2 * RANDOMNESS_LENGTH);
+		subject.extend_from_slice(&Self::previous_randomness()[..]);
+		subject.extend_from_slice(&Self::randomness()[..]);
+
+		<T as system::Trait>::Hashing::hash(&subject[..])
+	}
+}
+
 /// A BABE public key
 pub type BabeKey = [u8; PUBLIC_KEY_LENGTH];
 
@@ -281,12 +374,10 @@ impl<T: Trait> session::ShouldEndSession<T::BlockNumber> for Module<T> {
 	}
 }
 
-// TODO [slashing]: @marcio use this, remove the dead_code annotation.
 /// A BABE equivocation offence report.
 ///
 /// When a validator released two or more blocks at the same slot.
-#[allow(dead_code)]
-struct BabeEquivocationOffence<FullIdentification> {
+pub struct BabeEquivocationOffence<FullIdentification> {
 	/// A babe slot number in which this incident happened.
 	slot: u64,
 	/// The session index in which the incident happened.
@@ -412,6 +503,104 @@ impl<T: Trait> Module<T> {
 		this_randomness
 	}
 
+	/// Check an equivocation report and build the offence it proves.
+	fn check_equivocation_report(
+		equivocation_proof: &EquivocationProof<T::Header>,
+		key_owner_proof: T::KeyOwnerProof,
+	) -> result::Result<BabeEquivocationOffence<IdentificationTuple<T>>, &'static str> {
+		let authority_index = equivocation_proof.check().ok_or("Invalid equivocation proof.")?;
+
+		// only the authorities of the current epoch are kept, so the claimed authority index can
+		// only be checked for its equivocations. The key ownership proof identifies the offender
+		// of any epoch.
+		let authorities = Self::authorities();
+		if equivocation_proof.slot_number >= EpochStartSlot::get() {
+			ensure!(
+				authorities.get(authority_index as usize).map(|a| &a.0) ==
+					Some(&equivocation_proof.offender),
+				"Equivocation reported for an unknown authority."
+			);
+		}
+
+		let session_index = key_owner_proof.session();
+		ensure!(
+			Self::is_slot_of_session(equivocation_proof.slot_number, session_index),
+			"Key ownership proof for another session than the equivocation's.",
+		);
+		let offender = T::KeyOwnerSystem::check_proof(
+			(key_types::BABE, equivocation_proof.offender.clone()),
+			key_owner_proof,
+		).ok_or("Invalid key ownership proof.")?;
+
+		Ok(BabeEquivocationOffence {
+			slot: equivocation_proof.slot_number,
+			session_index,
+			// the size of past sets isn't kept, the current one is a close enough approximation.
+			validator_set_count: authorities.len() as u32,
+			offender,
+		})
+	}
+
+	/// Whether `slot` belongs to the session `session_index`.
+	fn is_slot_of_session(slot: u64, session_index: SessionIndex) -> bool {
+		let start = match Self::session_start_slot(session_index) {
+			Some(start) => start,
+			None => return false,
+		};
+		let end = session_index.checked_add(1).and_then(|next| Self::session_start_slot(next));
+		slot >= start && end.map_or(true, |end| slot < end)
+	}
+
+	/// Submit the equivocations queued by the block import in the offchain storage.
+	fn submit_equivocation_reports() {
+		let kind = StorageKind::PERSISTENT;
+		let encoded = match runtime_io::local_storage_get(kind, EQUIVOCATION_REPORTS_KEY) {
+			Some(encoded) => encoded,
+			None => return,
+		};
+		let reports = <Vec<EquivocationProof<T::Header>>>::decode(&mut &encoded[..])
+			.unwrap_or_default();
+		if reports.is_empty() {
+			return;
+		}
+
+		// take the reports first, so that concurrent workers don't submit them twice.
+		let empty = <Vec<EquivocationProof<T::Header>>>::new().encode();
+		let taken = runtime_io::local_storage_compare_and_set(
+			kind,
+			EQUIVOCATION_REPORTS_KEY,
+			Some(&encoded[..]),
+			&empty,
+		);
+		if !taken {
+			return;
+		}
+
+		for equivocation_proof in reports {
+			let key_owner_proof = match T::KeyOwnerSystem::prove(
+				(key_types::BABE, equivocation_proof.offender.clone()),
+			) {
+				Some(proof) => proof,
+				None => {
+					print("Skipping BABE equivocation report of an unknown authority.");
+					continue;
+				},
+			};
+
+			// key ownership can only be proven during the session itself, only the root of the
+			// session's keys is kept afterwards.
+			if !Self::is_slot_of_session(equivocation_proof.slot_number, key_owner_proof.session()) {
+				print("Skipping BABE equivocation report of a past session.");
+				continue;
+			}
+
+			let call = Call::report_equivocation(equivocation_proof, key_owner_proof);
+			if T::SubmitTransaction::submit_unsigned(call).is_err() {
+				print("Failed to submit BABE equivocation report.");
+			}
+		}
+	}
+
 	fn initialize_authorities(authorities: &[(AuthorityId, BabeAuthorityWeight)]) {
 		if !authorities.is_empty() {
 			assert!(Authorities::get().is_empty(), "Authorities are already initialized!");
@@ -424,7 +613,9 @@ impl<T: Trait> OnTimestampSet<T::Moment> for Module<T> {
 	fn on_timestamp_set(_moment: T::Moment) { }
 }
 
-impl<T: Trait> session::OneSessionHandler<T::AccountId> for Module<T> {
+impl<T: Trait> session::OneSessionHandler<T::AccountId> for Module<T>
+	where T: session::Trait
+{
 	type Key = AuthorityId;
 
 	fn on_genesis_session<'a, I: 'a>(validators: I)
@@ -432,6 +623,9 @@ impl<T: Trait> session::OneSessionHandler<T::AccountId> for Module<T> {
 	{
 		let authorities = validators.map(|(_, k)| (k, 1)).collect::<Vec<_>>();
 		Self::initialize_authorities(&authorities);
+		// the first slot isn't known yet, the genesis session includes all the slots before the
+		// next session.
+		SessionStartSlot::insert(<session::Module<T>>::current_index(), &0);
 	}
 
 	fn on_new_session<'a, I: 'a>(_changed: bool, validators: I, queued_validators: I)
@@ -467,6 +661,7 @@ impl<T: Trait> session::OneSessionHandler<T::AccountId> for Module<T> {
 				*previous = previous.saturating_add(T::EpochDuration::get());
 			}
 		});
+		SessionStartSlot::insert(<session::Module<T>>::current_index(), &EpochStartSlot::get());
 
 		// Update epoch randomness.
 		let next_epoch_index = epoch_index
@@ -476,6 +671,7 @@ impl<T: Trait> session::OneSessionHandler<T::AccountId> for Module<T> {
 		// Returns randomness for the current epoch and computes the *next*
 		// epoch randomness.
 		let randomness = Self::randomness_change_epoch(next_epoch_index);
+		PreviousRandomness::put(Randomness::get());
 		Randomness::put(randomness);
 
 		// After we update the current epoch, we signal the *next* epoch change
@@ -543,6 +739,31 @@ fn compute_randomness(
 	runtime_io::blake2_256(&s)
 }
 
+impl<T: Trait> srml_support::unsigned::ValidateUnsigned for Module<T> {
+	type Call = Call<T>;
+
+	fn validate_unsigned(call: &Self::Call) -> TransactionValidity {
+		if let Call::report_equivocation(equivocation_proof, key_owner_proof) = call {
+			if Self::check_equivocation_report(equivocation_proof, key_owner_proof.clone()).is_err() {
+				return TransactionValidity::Invalid(ApplyError::BadSignature as i8);
+			}
+
+			return TransactionValidity::Valid(ValidTransaction {
+				priority: 0,
+				requires: vec![],
+				provides: vec![(
+					equivocation_proof.slot_number,
+					&equivocation_proof.offender,
+				).encode()],
+				longevity: TransactionLongevity::max_value(),
+				propagate: true,
+			})
+		}
+
+		TransactionValidity::Invalid(0)
+	}
+}
+
 impl<T: Trait> ProvideInherent for Module<T> {
 	type Call = timestamp::Call<T>;
 	type Error = MakeFatalError<RuntimeString>;
@@ -0,0 +1,156 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Test utilities
+
+#![cfg(test)]
+
+use std::cell::RefCell;
+use sr_primitives::{
+	Perbill, traits::{IdentityLookup, ConvertInto}, testing::{Header, TestXt, UintAuthorityId},
+};
+use sr_staking_primitives::{SessionIndex, offence::ReportOffence};
+use srml_support::{impl_outer_origin, parameter_types, traits::KeyOwnerProofSystem};
+use runtime_io;
+use primitives::{H256, Blake2Hasher, Pair, crypto::KeyTypeId};
+use codec::{Encode, Decode};
+use session::GetSessionNumber;
+use babe_primitives::AuthorityPair;
+use crate::{AuthorityId, GenesisConfig, Trait, Module, Call, BabeEquivocationOffence};
+
+impl_outer_origin!{
+	pub enum Origin for Test {}
+}
+
+// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+#[derive(Clone, PartialEq, Eq, Debug, Decode, Encode)]
+pub struct Test;
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: u32 = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+	pub const MinimumPeriod: u64 = 1;
+	pub const EpochDuration: u64 = 10;
+	pub const ExpectedBlockTime: u64 = 2;
+}
+
+impl system::Trait for Test {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Call = ();
+	type Hash = H256;
+	type Hashing = ::sr_primitives::traits::BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type WeightMultiplierUpdate = ();
+	type Event = ();
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type MaximumBlockLength = MaximumBlockLength;
+	type Version = ();
+}
+
+impl timestamp::Trait for Test {
+	type Moment = u64;
+	type OnTimestampSet = Babe;
+	type MinimumPeriod = MinimumPeriod;
+}
+
+impl session::Trait for Test {
+	type Event = ();
+	type ValidatorId = u64;
+	type ValidatorIdOf = ConvertInto;
+	type ShouldEndSession = Babe;
+	type OnSessionEnding = ();
+	type SessionHandler = ();
+	type Keys = UintAuthorityId;
+	type SelectInitialValidators = ();
+}
+
+impl Trait for Test {
+	type EpochDuration = EpochDuration;
+	type ExpectedBlockTime = ExpectedBlockTime;
+	type Call = Call<Test>;
+	type SubmitTransaction = SubmitTransaction;
+	type KeyOwnerProof = TestProof;
+	type KeyOwnerSystem = TestKeyOwnerSystem;
+	type ReportEquivocation = OffenceHandler;
+}
+
+pub struct SubmitTransaction;
+impl system::offchain::SubmitUnsignedTransaction<Test, Call<Test>> for SubmitTransaction {
+	type Extrinsic = TestXt<Call<Test>, ()>;
+}
+
+/// A key ownership proof for the given session.
+#[derive(Clone, PartialEq, Eq, Debug, Decode, Encode)]
+pub struct TestProof(pub SessionIndex);
+
+impl GetSessionNumber for TestProof {
+	fn session(&self) -> SessionIndex {
+		self.0
+	}
+}
+
+/// Identifies the offenders by their authority key, whatever the proof.
+///
+/// Keys are proven for session 0.
+pub struct TestKeyOwnerSystem;
+impl KeyOwnerProofSystem<(KeyTypeId, AuthorityId)> for TestKeyOwnerSystem {
+	type Proof = TestProof;
+	type IdentificationTuple = AuthorityId;
+
+	fn prove(_key: (KeyTypeId, AuthorityId)) -> Option<TestProof> {
+		Some(TestProof(0))
+	}
+
+	fn check_proof(key: (KeyTypeId, AuthorityId), _proof: TestProof) -> Option<AuthorityId> {
+		Some(key.1)
+	}
+}
+
+thread_local! {
+	pub static OFFENCES: RefCell<Vec<(Vec<u64>, BabeEquivocationOffence<AuthorityId>)>> =
+		RefCell::new(vec![]);
+}
+
+/// Keeps the reported offences in `OFFENCES`.
+pub struct OffenceHandler;
+impl ReportOffence<u64, AuthorityId, BabeEquivocationOffence<AuthorityId>> for OffenceHandler {
+	fn report_offence(reporters: Vec<u64>, offence: BabeEquivocationOffence<AuthorityId>) {
+		OFFENCES.with(|l| l.borrow_mut().push((reporters, offence)));
+	}
+}
+
+/// The key pair of the authority `i`.
+pub fn authority_pair(i: u8) -> AuthorityPair {
+	AuthorityPair::from_seed(&[i; 32])
+}
+
+pub fn new_test_ext(authorities: Vec<u8>) -> runtime_io::TestExternalities<Blake2Hasher> {
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	GenesisConfig {
+		authorities: authorities.into_iter().map(|i| (authority_pair(i).public(), 1)).collect(),
+	}.assimilate_storage::<Test>(&mut t).unwrap();
+	t.into()
+}
+
+pub type Babe = Module<Test>;
@@ -0,0 +1,205 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tests for the module.
+
+#![cfg(test)]
+
+use sr_primitives::testing::{Digest, Header};
+use sr_primitives::traits::Header as _;
+use runtime_io::with_externalities;
+use crate::mock::*;
+use babe_primitives::AuthorityPair;
+use primitives::{H256, Pair};
+use session::OneSessionHandler;
+use srml_support::{assert_ok, assert_noop};
+use super::*;
+
+/// A header of block `number` claimed by `authority_index` for `slot_number` and sealed by `pair`.
+fn sealed_header(number: u64, slot_number: u64, authority_index: u32, pair: &AuthorityPair) -> Header {
+	let pre_digest = RawBabePreDigest::Secondary { authority_index, slot_number, weight: 0 };
+	let mut header = Header::new(
+		number,
+		Default::default(),
+		Default::default(),
+		Default::default(),
+		Digest { logs: vec![DigestItem::PreRuntime(BABE_ENGINE_ID, pre_digest.encode())] },
+	);
+	let signature = pair.sign(header.hash().as_ref());
+	header.digest_mut().push(DigestItem::Seal(BABE_ENGINE_ID, signature.encode()));
+	header
+}
+
+/// An equivocation of `pair`, claiming to be the authority `authority_index`, in `slot_number`.
+fn equivocation_proof(pair: &AuthorityPair, slot_number: u64, authority_index: u32) -> EquivocationProof<Header> {
+	EquivocationProof {
+		offender: pair.public(),
+		slot_number,
+		first_header: sealed_header(1, slot_number, authority_index, pair),
+		second_header: sealed_header(2, slot_number, authority_index, pair),
+	}
+}
+
+#[test]
+fn equivocation_proof_check_works() {
+	with_externalities(&mut new_test_ext(vec![1]), || {
+		let pair = authority_pair(1);
+		assert_eq!(equivocation_proof(&pair, 3, 0).check(), Some(0));
+
+		// the same header twice isn't an equivocation.
+		let mut proof = equivocation_proof(&pair, 3, 0);
+		proof.second_header = proof.first_header.clone();
+		assert_eq!(proof.check(), None);
+
+		// both headers must be claimed for the proof's slot.
+		let mut proof = equivocation_proof(&pair, 3, 0);
+		proof.second_header = sealed_header(2, 4, 0, &pair);
+		assert_eq!(proof.check(), None);
+
+		// and sealed by the offender.
+		let mut proof = equivocation_proof(&pair, 3, 0);
+		proof.second_header = sealed_header(2, 3, 0, &authority_pair(2));
+		assert_eq!(proof.check(), None);
+		let mut proof = equivocation_proof(&authority_pair(2), 3, 0);
+		proof.offender = pair.public();
+		assert_eq!(proof.check(), None);
+	});
+}
+
+#[test]
+fn report_equivocation_reports_offence() {
+	with_externalities(&mut new_test_ext(vec![1, 2, 3]), || {
+		SessionStartSlot::insert(5, &0);
+		let pair = authority_pair(2);
+		let proof = equivocation_proof(&pair, 3, 1);
+
+		assert_ok!(Babe::report_equivocation(system::RawOrigin::Signed(7).into(), proof, TestProof(5)));
+
+		OFFENCES.with(|offences| {
+			let offences = offences.borrow();
+			assert_eq!(offences.len(), 1);
+			let (reporters, offence) = &offences[0];
+			assert_eq!(reporters, &vec![7]);
+			assert_eq!(offence.offenders(), vec![pair.public()]);
+			assert_eq!(offence.time_slot(), 3);
+			assert_eq!(offence.session_index(), 5);
+			assert_eq!(offence.validator_set_count(), 3);
+		});
+	});
+}
+
+#[test]
+fn report_equivocation_checks_authority_of_current_epoch_only() {
+	with_externalities(&mut new_test_ext(vec![1, 2, 3]), || {
+		EpochStartSlot::put(10);
+		SessionStartSlot::insert(1, &0);
+		SessionStartSlot::insert(2, &10);
+		let pair = authority_pair(4);
+
+		// the authorities of the current epoch are known.
+		assert_noop!(
+			Babe::report_equivocation(system::RawOrigin::None.into(), equivocation_proof(&pair, 12, 0), TestProof(2)),
+			"Equivocation reported for an unknown authority."
+		);
+
+		// those of past epochs aren't, the offence is reported for the session of the proof.
+		assert_ok!(
+			Babe::report_equivocation(system::RawOrigin::None.into(), equivocation_proof(&pair, 3, 0), TestProof(1))
+		);
+		OFFENCES.with(|offences| {
+			let offences = offences.borrow();
+			assert_eq!(offences.len(), 1);
+			assert_eq!(offences[0].0, Vec::<u64>::new());
+			assert_eq!(offences[0].1.offenders(), vec![pair.public()]);
+			assert_eq!(offences[0].1.session_index(), 1);
+		});
+	});
+}
+
+#[test]
+fn report_equivocation_rejects_invalid_proofs() {
+	with_externalities(&mut new_test_ext(vec![1]), || {
+		let mut proof = equivocation_proof(&authority_pair(1), 3, 0);
+		proof.second_header = proof.first_header.clone();
+		assert_noop!(
+			Babe::report_equivocation(system::RawOrigin::None.into(), proof, TestProof(0)),
+			"Invalid equivocation proof."
+		);
+
+		assert!(OFFENCES.with(|offences| offences.borrow().is_empty()));
+	});
+}
+
+#[test]
+fn report_equivocation_rejects_key_ownership_proofs_of_another_session() {
+	with_externalities(&mut new_test_ext(vec![]), || {
+		let account = 0u64;
+		let validators = || vec![(&account, authority_pair(1).public())].into_iter();
+		let report = |slot_number, session_index| Babe::report_equivocation(
+			system::RawOrigin::None.into(),
+			equivocation_proof(&authority_pair(1), slot_number, 0),
+			TestProof(session_index),
+		);
+
+		// the genesis session lasts until the first epoch change, at slot 20.
+		Babe::on_genesis_session(validators());
+		CurrentSlot::put(25);
+		session::Module::<Test>::rotate_session();
+		Babe::on_new_session(true, validators(), validators());
+		assert_eq!(Babe::session_start_slot(0), Some(0));
+		assert_eq!(Babe::session_start_slot(1), Some(20));
+
+		let error = "Key ownership proof for another session than the equivocation's.";
+		assert_noop!(report(3, 1), error);
+		assert_noop!(report(22, 0), error);
+		assert_noop!(report(22, 2), error);
+		assert!(OFFENCES.with(|offences| offences.borrow().is_empty()));
+
+		assert_ok!(report(3, 0));
+		assert_ok!(report(22, 1));
+		OFFENCES.with(|offences| {
+			let offences = offences.borrow();
+			assert_eq!(offences.len(), 2);
+			assert_eq!(offences[0].1.session_index(), 0);
+			assert_eq!(offences[1].1.session_index(), 1);
+		});
+	});
+}
+
+#[test]
+fn random_mixes_current_and_previous_epoch_randomness() {
+	with_externalities(&mut new_test_ext(vec![1]), || {
+		let random = || <Babe as RandomnessT<H256>>::random(&b"subject"[..]);
+		let initial = random();
+
+		Randomness::put([1; 32]);
+		let with_current = random();
+		assert_ne!(with_current, initial);
+
+		PreviousRandomness::put([2; 32]);
+		assert_ne!(random(), with_current);
+
+		// on epoch change, the current epoch randomness becomes the previous one.
+		NextRandomness::put([3; 32]);
+		let account = 0u64;
+		let validators = || vec![(&account, authority_pair(1).public())].into_iter();
+		Babe::on_new_session(true, validators(), validators());
+
+		assert_eq!(Babe::previous_randomness(), [1; 32]);
+		assert_eq!(Babe::randomness(), [3; 32]);
+		assert_ne!(random(), with_current);
+	});
+}
@@ -22,7 +22,7 @@ use crate::rent;
 
 use rstd::prelude::*;
 use sr_primitives::traits::{Bounded, CheckedAdd, CheckedSub, Zero};
-use srml_support::traits::{WithdrawReason, Currency};
+use srml_support::traits::{WithdrawReason, Currency, Randomness};
 use timestamp;
 
 pub type AccountIdOf<T> = <T as system::Trait>::AccountId;
@@ -30,6 +30,7 @@ pub type CallOf<T> = <T as Trait>::Call;
 pub type MomentOf<T> = <T as timestamp::Trait>::Moment;
 pub type SeedOf<T> = <T as system::Trait>::Hash;
 pub type BlockNumberOf<T> = <T as system::Trait>::BlockNumber;
+pub type HashOf<T> = <T as system::Trait>::Hash;
 
 /// A type that represents a topic of an event. At the moment a hash is used.
 pub type TopicOf<T> = <T as system::Trait>::Hash;
@@ -125,6 +126,16 @@ pub trait Ext {
 		input_data: Vec<u8>,
 	) -> ExecResult;
 
+	/// Transfer all funds to `beneficiary` and delete the current contract.
+	///
+	/// Returns an Err if the current contract is still live further up in the call stack or if
+	/// the transfer fails.
+	fn terminate(
+		&mut self,
+		beneficiary: &AccountIdOf<Self::T>,
+		gas_meter: &mut GasMeter<Self::T>,
+	) -> Result<(), &'static str>;
+
 	/// Notes a call dispatch.
 	fn note_dispatch_call(&mut self, call: CallOf<Self::T>);
 
@@ -174,8 +185,18 @@ pub trait Ext {
 	/// Returns the current block number.
 	fn block_number(&self) -> BlockNumberOf<Self::T>;
 
+	/// Returns the hash of the block with the given number.
+	///
+	/// Only the hashes of recent blocks are known, the default hash is returned for the others.
+	fn block_hash(&self, block_number: BlockNumberOf<Self::T>) -> HashOf<Self::T>;
+
 	/// Returns the maximum allowed size of a storage item.
 	fn max_value_size(&self) -> u32;
+
+	/// Returns the value of the runtime storage under the given raw key.
+	///
+	/// Returns `None` if there is no entry under the given key.
+	fn get_runtime_storage(&self, key: &[u8]) -> Option<Vec<u8>>;
 }
 
 /// Loader is a companion of the `Vm` trait. It loads an appropriate abstract
@@ -579,6 +600,7 @@ impl<T: Trait> Token<T> for TransferFeeToken<BalanceOf<T>> {
 enum TransferCause {
 	Call,
 	Instantiate,
+	Terminate,
 }
 
 /// Transfer some funds from `transactor` to `dest`.
@@ -625,7 +647,7 @@ fn transfer<'a, T: Trait, V: Vm<T>, L: Loader<T>>(
 
 			// Otherwise the fee depends on whether we create a new account or transfer
 			// to an existing one.
-			Call => if would_create {
+			Call | Terminate => if would_create {
 				TransferFeeKind::AccountCreate
 			} else {
 				TransferFeeKind::Transfer
@@ -722,6 +744,32 @@ where
 		self.ctx.call(to.clone(), value, gas_meter, input_data)
 	}
 
+	fn terminate(
+		&mut self,
+		beneficiary: &AccountIdOf<Self::T>,
+		gas_meter: &mut GasMeter<Self::T>,
+	) -> Result<(), &'static str> {
+		let self_id = self.ctx.self_account.clone();
+		let value = self.ctx.overlay.get_balance(&self_id);
+		if let Some(parent) = self.ctx.parent {
+			if parent.is_live(&self_id) {
+				return Err("contract cannot be destroyed during recursive execution");
+			}
+		}
+
+		// Destroy first: `destroy_contract` resets the whole change entry, which would
+		// otherwise forget the zero balance written by the transfer below.
+		self.ctx.overlay.destroy_contract(&self_id);
+		transfer(
+			gas_meter,
+			TransferCause::Terminate,
+			&self_id,
+			beneficiary,
+			value,
+			self.ctx,
+		)
+	}
+
 	fn note_dispatch_call(&mut self, call: CallOf<Self::T>) {
 		self.ctx.deferred.push(DeferredAction::DispatchRuntimeCall {
 			origin: self.ctx.self_account.clone(),
@@ -762,7 +810,7 @@ where
 	}
 
 	fn random(&self, subject: &[u8]) -> SeedOf<T> {
-		system::Module::<T>::random(subject)
+		T::Randomness::random(subject)
 	}
 
 	fn now(&self) -> &T::Moment {
@@ -791,9 +839,17 @@ where
 
 	fn block_number(&self) -> T::BlockNumber { self.block_number }
 
+	fn block_hash(&self, block_number: T::BlockNumber) -> T::Hash {
+		<system::Module<T>>::block_hash(block_number)
+	}
+
 	fn max_value_size(&self) -> u32 {
 		self.ctx.config.max_value_size
 	}
+
+	fn get_runtime_storage(&self, key: &[u8]) -> Option<Vec<u8>> {
+		runtime_io::storage(key)
+	}
 }
 
 /// These tests exercise the executive layer.
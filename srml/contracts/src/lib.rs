@@ -103,12 +103,12 @@ use runtime_io::blake2_256;
 use sr_primitives::traits::{
 	Hash, StaticLookup, Zero, MaybeSerializeDebug, Member
 };
-use srml_support::dispatch::{Result, Dispatchable};
+use srml_support::dispatch::{Result, Dispatchable, Weight};
 use srml_support::{
-	Parameter, StorageMap, StorageValue, decl_module, decl_event, decl_storage, storage::child,
-	parameter_types,
+	Parameter, StorageMap, StorageValue, decl_module, decl_event, decl_storage,
+	storage::{child, unhashed}, parameter_types,
 };
-use srml_support::traits::{OnFreeBalanceZero, OnUnbalanced, Currency, Get};
+use srml_support::traits::{OnFreeBalanceZero, OnUnbalanced, Currency, Get, Randomness};
 use system::{ensure_signed, RawOrigin, ensure_root};
 use primitives::storage::well_known_keys::CHILD_STORAGE_KEY_PREFIX;
 use timestamp;
@@ -126,6 +126,37 @@ pub trait ComputeDispatchFee<Call, Balance> {
 	fn compute_dispatch_fee(call: &Call) -> Balance;
 }
 
+/// Functionality of the runtime that contracts can call synchronously with
+/// `ext_call_chain_extension`.
+///
+/// A function of the extension is identified by a `func_id` chosen by the implementation. Its
+/// input and output are opaque byte buffers whose encoding is agreed on by the extension and the
+/// contracts using it.
+///
+/// Changes made to the runtime storage by an extension are not reverted if the calling contract
+/// fails later on, so extensions should only query the state of the runtime.
+pub trait ChainExtension {
+	/// The gas charged before calling the function `func_id` with the given `input`.
+	fn gas_cost(func_id: u32, input: &[u8]) -> Gas;
+
+	/// Call the function `func_id` with the given `input`.
+	///
+	/// The returned data is put into the scratch buffer of the calling contract. Returning an
+	/// error traps the calling contract.
+	fn call(func_id: u32, input: Vec<u8>) -> rstd::result::Result<Vec<u8>, &'static str>;
+}
+
+/// No chain extension, every call traps.
+impl ChainExtension for () {
+	fn gas_cost(_func_id: u32, _input: &[u8]) -> Gas {
+		0
+	}
+
+	fn call(_func_id: u32, _input: Vec<u8>) -> rstd::result::Result<Vec<u8>, &'static str> {
+		Err("no chain extension is available")
+	}
+}
+
 /// Information for managing an acocunt and its sub trie abstraction.
 /// This is the required info to cache for an account
 #[derive(Encode, Decode)]
@@ -320,6 +351,9 @@ parameter_types! {
 pub trait Trait: timestamp::Trait {
 	type Currency: Currency<Self::AccountId>;
 
+	/// The source of randomness exposed to contracts through `ext_random`.
+	type Randomness: Randomness<Self::Hash>;
+
 	/// The outer call dispatch type.
 	type Call: Parameter + Dispatchable<Origin=<Self as system::Trait>::Origin>;
 
@@ -399,6 +433,9 @@ pub trait Trait: timestamp::Trait {
 
 	/// The maximum amount of gas that could be expended per block.
 	type BlockGasLimit: Get<Gas>;
+
+	/// The chain extension contracts can call into with `ext_call_chain_extension`.
+	type ChainExtension: ChainExtension;
 }
 
 /// Simple contract address determiner.
@@ -623,6 +660,21 @@ decl_module! {
 			}
 		}
 
+		/// Migrate a `CurrentSchedule` that is still encoded in the layout of `ScheduleV0`.
+		fn on_runtime_upgrade() -> Weight {
+			let key = CurrentSchedule::hashed_key();
+			match unhashed::get_raw(&key) {
+				Some(raw) if Schedule::decode(&mut &raw[..]).is_err() => {
+					match ScheduleV0::decode(&mut &raw[..]) {
+						Ok(old) => CurrentSchedule::put(Schedule::from(old)),
+						Err(_) => CurrentSchedule::kill(),
+					}
+					10_000
+				},
+				_ => 0,
+			}
+		}
+
 		fn on_finalize() {
 			GasSpent::kill();
 		}
@@ -911,12 +963,82 @@ pub struct Schedule {
 
 	/// The maximum length of a subject used for PRNG generation.
 	pub max_subject_len: u32,
+
+	/// Gas cost to terminate a contract; the base. The transfer of the remaining balance is
+	/// charged separately.
+	pub terminate_base_cost: Gas,
+
+	/// Gas cost to hash a buffer; the base.
+	pub hash_base_cost: Gas,
+
+	/// Gas cost to hash a buffer; the per-byte portion.
+	pub hash_per_byte_cost: Gas,
+
+	/// Gas cost to read the hash of a block.
+	pub block_hash_cost: Gas,
+
+	/// Gas cost to read a value from the runtime storage; the base.
+	pub runtime_storage_read_base_cost: Gas,
+
+	/// Gas cost to read a value from the runtime storage; the per-byte portion.
+	pub runtime_storage_read_per_byte_cost: Gas,
+}
+
+/// The layout of `Schedule` before the costs of terminating, hashing, reading block hashes and
+/// reading the runtime storage were appended. Only used to migrate the `CurrentSchedule`.
+#[derive(Decode)]
+struct ScheduleV0 {
+	version: u32,
+	put_code_per_byte_cost: Gas,
+	grow_mem_cost: Gas,
+	regular_op_cost: Gas,
+	return_data_per_byte_cost: Gas,
+	event_data_per_byte_cost: Gas,
+	event_per_topic_cost: Gas,
+	event_base_cost: Gas,
+	call_base_cost: Gas,
+	instantiate_base_cost: Gas,
+	sandbox_data_read_cost: Gas,
+	sandbox_data_write_cost: Gas,
+	max_event_topics: u32,
+	max_stack_height: u32,
+	max_memory_pages: u32,
+	max_table_size: u32,
+	enable_println: bool,
+	max_subject_len: u32,
+}
+
+impl From<ScheduleV0> for Schedule {
+	fn from(old: ScheduleV0) -> Schedule {
+		let defaults = Schedule::default();
+		Schedule {
+			version: old.version + 1,
+			put_code_per_byte_cost: old.put_code_per_byte_cost,
+			grow_mem_cost: old.grow_mem_cost,
+			regular_op_cost: old.regular_op_cost,
+			return_data_per_byte_cost: old.return_data_per_byte_cost,
+			event_data_per_byte_cost: old.event_data_per_byte_cost,
+			event_per_topic_cost: old.event_per_topic_cost,
+			event_base_cost: old.event_base_cost,
+			call_base_cost: old.call_base_cost,
+			instantiate_base_cost: old.instantiate_base_cost,
+			sandbox_data_read_cost: old.sandbox_data_read_cost,
+			sandbox_data_write_cost: old.sandbox_data_write_cost,
+			max_event_topics: old.max_event_topics,
+			max_stack_height: old.max_stack_height,
+			max_memory_pages: old.max_memory_pages,
+			max_table_size: old.max_table_size,
+			enable_println: old.enable_println,
+			max_subject_len: old.max_subject_len,
+			..defaults
+		}
+	}
 }
 
 impl Default for Schedule {
 	fn default() -> Schedule {
 		Schedule {
-			version: 0,
+			version: 1,
 			put_code_per_byte_cost: 1,
 			grow_mem_cost: 1,
 			regular_op_cost: 1,
@@ -934,6 +1056,12 @@ impl Default for Schedule {
 			max_table_size: 16 * 1024,
 			enable_println: false,
 			max_subject_len: 32,
+			terminate_base_cost: 1,
+			hash_base_cost: 1,
+			hash_per_byte_cost: 1,
+			block_hash_cost: 1,
+			runtime_storage_read_base_cost: 1,
+			runtime_storage_read_per_byte_cost: 1,
 		}
 	}
 }
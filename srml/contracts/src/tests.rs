@@ -23,7 +23,7 @@ use crate::account_db::{AccountDb, DirectAccountDb, OverlayAccountDb};
 use crate::{
 	BalanceOf, ComputeDispatchFee, ContractAddressFor, ContractInfo, ContractInfoOf, GenesisConfig,
 	Module, RawAliveContractInfo, RawEvent, Trait, TrieId, TrieIdFromParentCounter,
-	TrieIdGenerator, Schedule,
+	TrieIdGenerator, Schedule, ChainExtension, Gas,
 };
 use assert_matches::assert_matches;
 use hex_literal::*;
@@ -31,11 +31,11 @@ use codec::{Decode, Encode, KeyedVec};
 use runtime_io;
 use runtime_io::with_externalities;
 use sr_primitives::testing::{Digest, DigestItem, Header, UintAuthorityId, H256};
-use sr_primitives::traits::{BlakeTwo256, Hash, IdentityLookup};
+use sr_primitives::traits::{BlakeTwo256, Hash, IdentityLookup, OnRuntimeUpgrade};
 use sr_primitives::{Perbill, BuildStorage};
 use srml_support::{
 	assert_ok, assert_err, impl_outer_dispatch, impl_outer_event, impl_outer_origin, parameter_types,
-	storage::child, StorageMap, StorageValue, traits::{Currency, Get},
+	storage::{child, unhashed}, StorageMap, StorageValue, traits::{Currency, Get},
 };
 use std::cell::RefCell;
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -161,6 +161,7 @@ parameter_types! {
 }
 impl Trait for Test {
 	type Currency = Balances;
+	type Randomness = ();
 	type Call = Call;
 	type DetermineContractAddress = DummyContractAddressFor;
 	type Event = MetaEvent;
@@ -183,6 +184,7 @@ impl Trait for Test {
 	type MaxDepth = MaxDepth;
 	type MaxValueSize = MaxValueSize;
 	type BlockGasLimit = BlockGasLimit;
+	type ChainExtension = TestChainExtension;
 }
 
 type Balances = balances::Module<Test>;
@@ -216,6 +218,25 @@ impl TrieIdGenerator<u64> for DummyTrieIdGenerator {
 	}
 }
 
+/// Chain extension with a single function, `0`, which returns the free balance of the account
+/// given as input.
+pub struct TestChainExtension;
+impl ChainExtension for TestChainExtension {
+	fn gas_cost(_func_id: u32, input: &[u8]) -> Gas {
+		100 + input.len() as Gas
+	}
+
+	fn call(func_id: u32, input: Vec<u8>) -> Result<Vec<u8>, &'static str> {
+		match func_id {
+			0 => {
+				let who = u64::decode(&mut &input[..]).map_err(|_| "invalid account")?;
+				Ok(Balances::free_balance(&who).encode())
+			},
+			_ => Err("unknown function"),
+		}
+	}
+}
+
 pub struct DummyComputeDispatchFee;
 impl ComputeDispatchFee<Call, u64> for DummyComputeDispatchFee {
 	fn compute_dispatch_fee(call: &Call) -> u64 {
@@ -278,7 +299,6 @@ impl ExtBuilder {
 		let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
 		balances::GenesisConfig::<Test> {
 			balances: vec![],
-			vesting: vec![],
 		}.assimilate_storage(&mut t).unwrap();
 		GenesisConfig::<Test> {
 			current_schedule: Schedule {
@@ -2390,3 +2410,504 @@ fn cannot_self_destruct_in_constructor() {
 		}
 	);
 }
+
+const CODE_TERMINATE: &str = r#"
+(module
+	(import "env" "ext_terminate" (func $ext_terminate (param i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $ext_terminate
+			(i32.const 0)	;; Pointer to the address of the beneficiary
+			(i32.const 8)	;; Length of the address of the beneficiary
+		)
+		(unreachable)
+	)
+
+	;; Address of the beneficiary, DJANGO.
+	(data (i32.const 0) "\04\00\00\00\00\00\00\00")
+)
+"#;
+
+#[test]
+fn terminate_removes_contract_and_transfers_balance() {
+	let (wasm, code_hash) = compile_module::<Test>(CODE_TERMINATE).unwrap();
+	with_externalities(
+		// Gas is free, so the total issuance can only change if the termination is unbalanced.
+		&mut ExtBuilder::default().existential_deposit(50).gas_price(0).build(),
+		|| {
+			Balances::deposit_creating(&ALICE, 1_000_000);
+			assert_ok!(Contract::put_code(Origin::signed(ALICE), 100_000, wasm));
+
+			// Instantiate the BOB contract.
+			assert_ok!(Contract::create(
+				Origin::signed(ALICE),
+				100_000,
+				100_000,
+				code_hash.into(),
+				vec![],
+			));
+			assert_matches!(
+				ContractInfoOf::<Test>::get(BOB),
+				Some(ContractInfo::Alive(_))
+			);
+
+			let total_issuance = Balances::total_issuance();
+
+			// Call BOB, which terminates itself in favor of DJANGO.
+			assert_ok!(Contract::call(
+				Origin::signed(ALICE),
+				BOB,
+				0,
+				100_000,
+				vec![],
+			));
+
+			// BOB is gone and DJANGO received its whole balance.
+			assert!(ContractInfoOf::<Test>::get(BOB).is_none());
+			assert_eq!(Balances::free_balance(&BOB), 0);
+			assert_eq!(Balances::free_balance(&DJANGO), 100_000);
+			assert_eq!(Balances::total_issuance(), total_issuance);
+		}
+	);
+}
+
+const CODE_HASH: &str = r#"
+(module
+	(import "env" "ext_scratch_size" (func $ext_scratch_size (result i32)))
+	(import "env" "ext_scratch_read" (func $ext_scratch_read (param i32 i32 i32)))
+	(import "env" "ext_hash_sha2_256" (func $ext_hash_sha2_256 (param i32 i32 i32)))
+	(import "env" "ext_hash_keccak_256" (func $ext_hash_keccak_256 (param i32 i32 i32)))
+	(import "env" "ext_hash_blake2_256" (func $ext_hash_blake2_256 (param i32 i32 i32)))
+	(import "env" "ext_hash_blake2_128" (func $ext_hash_blake2_128 (param i32 i32 i32)))
+	(import "env" "ext_deposit_event" (func $ext_deposit_event (param i32 i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(func (export "deploy"))
+
+	;; The first byte of the input data selects the hash function, the rest of the input data is
+	;; hashed. The hash is deposited as the data of an event.
+	(func (export "call")
+		(local $input_len i32)
+		(local $output_len i32)
+
+		;; Copy the input data into memory.
+		(set_local $input_len (call $ext_scratch_size))
+		(call $ext_scratch_read
+			(i32.const 0)			;; Pointer to write the input data to
+			(i32.const 0)			;; Offset into the scratch buffer
+			(get_local $input_len)	;; Length of the input data
+		)
+
+		(set_local $output_len (i32.const 32))
+		(block $done
+			(block $blake2_128
+				(block $blake2_256
+					(block $keccak_256
+						(block $sha2_256
+							(br_table $sha2_256 $keccak_256 $blake2_256 $blake2_128
+								(i32.load8_u (i32.const 0))
+							)
+						)
+						(call $ext_hash_sha2_256
+							(i32.const 1)	;; Pointer to the data to hash
+							(i32.sub (get_local $input_len) (i32.const 1))
+							(i32.const 256)	;; Pointer to write the hash to
+						)
+						(br $done)
+					)
+					(call $ext_hash_keccak_256
+						(i32.const 1)
+						(i32.sub (get_local $input_len) (i32.const 1))
+						(i32.const 256)
+					)
+					(br $done)
+				)
+				(call $ext_hash_blake2_256
+					(i32.const 1)
+					(i32.sub (get_local $input_len) (i32.const 1))
+					(i32.const 256)
+				)
+				(br $done)
+			)
+			(call $ext_hash_blake2_128
+				(i32.const 1)
+				(i32.sub (get_local $input_len) (i32.const 1))
+				(i32.const 256)
+			)
+			(set_local $output_len (i32.const 16))
+		)
+
+		(call $ext_deposit_event
+			(i32.const 0)			;; The topics buffer
+			(i32.const 0)			;; The topics buffer's length
+			(i32.const 256)			;; The data buffer
+			(get_local $output_len)	;; The data buffer's length
+		)
+	)
+)
+"#;
+
+#[test]
+fn hash_functions_work() {
+	use sha2::Digest;
+
+	let (wasm, code_hash) = compile_module::<Test>(CODE_HASH).unwrap();
+	with_externalities(
+		&mut ExtBuilder::default().existential_deposit(50).build(),
+		|| {
+			Balances::deposit_creating(&ALICE, 1_000_000);
+			assert_ok!(Contract::put_code(Origin::signed(ALICE), 100_000, wasm));
+			assert_ok!(Contract::create(
+				Origin::signed(ALICE),
+				100_000,
+				100_000,
+				code_hash.into(),
+				vec![],
+			));
+
+			let input = b"_DEAD_BEEF";
+			let expected = vec![
+				(0u8, sha2::Sha256::digest(input).to_vec()),
+				(1, runtime_io::keccak_256(input).to_vec()),
+				(2, runtime_io::blake2_256(input).to_vec()),
+				(3, runtime_io::blake2_128(input).to_vec()),
+			];
+
+			for (selector, hash) in expected {
+				let mut input_data = vec![selector];
+				input_data.extend_from_slice(input);
+
+				assert_ok!(Contract::call(
+					Origin::signed(ALICE),
+					BOB,
+					0,
+					100_000,
+					input_data,
+				));
+				assert_eq!(
+					System::events().last().unwrap().event,
+					MetaEvent::contract(RawEvent::Contract(BOB, hash)),
+				);
+			}
+		}
+	);
+}
+
+const CODE_BLOCK_HASH: &str = r#"
+(module
+	(import "env" "ext_scratch_size" (func $ext_scratch_size (result i32)))
+	(import "env" "ext_scratch_read" (func $ext_scratch_read (param i32 i32 i32)))
+	(import "env" "ext_block_hash" (func $ext_block_hash (param i32 i32)))
+	(import "env" "ext_deposit_event" (func $ext_deposit_event (param i32 i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+
+	(func (export "deploy"))
+
+	;; The input data is the encoded block number. The hash of the block is deposited as the data
+	;; of an event.
+	(func (export "call")
+		;; Block number should be encoded as a u64.
+		(call $assert
+			(i32.eq
+				(call $ext_scratch_size)
+				(i32.const 8)
+			)
+		)
+		(call $ext_scratch_read
+			(i32.const 0)	;; Pointer to write the block number to
+			(i32.const 0)	;; Offset into the scratch buffer
+			(i32.const 8)	;; Length of the encoded block number
+		)
+
+		(call $ext_block_hash
+			(i32.const 0)	;; Pointer to the block number
+			(i32.const 8)	;; Length of the block number
+		)
+
+		;; Block hash should be encoded as a H256.
+		(call $assert
+			(i32.eq
+				(call $ext_scratch_size)
+				(i32.const 32)
+			)
+		)
+		(call $ext_scratch_read
+			(i32.const 8)	;; Pointer to write the block hash to
+			(i32.const 0)	;; Offset into the scratch buffer
+			(i32.const 32)	;; Length of the encoded block hash
+		)
+
+		(call $ext_deposit_event
+			(i32.const 0)	;; The topics buffer
+			(i32.const 0)	;; The topics buffer's length
+			(i32.const 8)	;; The data buffer
+			(i32.const 32)	;; The data buffer's length
+		)
+	)
+)
+"#;
+
+#[test]
+fn block_hash_works() {
+	let (wasm, code_hash) = compile_module::<Test>(CODE_BLOCK_HASH).unwrap();
+	with_externalities(
+		&mut ExtBuilder::default().existential_deposit(50).build(),
+		|| {
+			Balances::deposit_creating(&ALICE, 1_000_000);
+			assert_ok!(Contract::put_code(Origin::signed(ALICE), 100_000, wasm));
+			assert_ok!(Contract::create(
+				Origin::signed(ALICE),
+				100_000,
+				100_000,
+				code_hash.into(),
+				vec![],
+			));
+
+			// The hash of the genesis block is set up by the system module.
+			assert_ok!(Contract::call(Origin::signed(ALICE), BOB, 0, 100_000, 0u64.encode()));
+			assert_eq!(
+				System::events().last().unwrap().event,
+				MetaEvent::contract(RawEvent::Contract(BOB, vec![69; 32])),
+			);
+
+			// The hash of an unknown block is the default hash.
+			assert_ok!(Contract::call(Origin::signed(ALICE), BOB, 0, 100_000, 5u64.encode()));
+			assert_eq!(
+				System::events().last().unwrap().event,
+				MetaEvent::contract(RawEvent::Contract(BOB, vec![0; 32])),
+			);
+		}
+	);
+}
+
+const CODE_GET_RUNTIME_STORAGE: &str = r#"
+(module
+	(import "env" "ext_scratch_size" (func $ext_scratch_size (result i32)))
+	(import "env" "ext_scratch_read" (func $ext_scratch_read (param i32 i32 i32)))
+	(import "env" "ext_get_runtime_storage" (func $ext_get_runtime_storage (param i32 i32) (result i32)))
+	(import "env" "ext_deposit_event" (func $ext_deposit_event (param i32 i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+
+	(func (export "deploy"))
+
+	;; The input data is the key in the runtime storage. The value under the key is deposited as
+	;; the data of an event. Traps if there is no value under the key.
+	(func (export "call")
+		(local $len i32)
+
+		(set_local $len (call $ext_scratch_size))
+		(call $ext_scratch_read
+			(i32.const 0)		;; Pointer to write the key to
+			(i32.const 0)		;; Offset into the scratch buffer
+			(get_local $len)	;; Length of the key
+		)
+
+		(call $assert
+			(i32.eq
+				(call $ext_get_runtime_storage
+					(i32.const 0)		;; Pointer to the key
+					(get_local $len)	;; Length of the key
+				)
+				(i32.const 0)
+			)
+		)
+
+		(set_local $len (call $ext_scratch_size))
+		(call $ext_scratch_read
+			(i32.const 256)		;; Pointer to write the value to
+			(i32.const 0)		;; Offset into the scratch buffer
+			(get_local $len)	;; Length of the value
+		)
+
+		(call $ext_deposit_event
+			(i32.const 0)		;; The topics buffer
+			(i32.const 0)		;; The topics buffer's length
+			(i32.const 256)		;; The data buffer
+			(get_local $len)	;; The data buffer's length
+		)
+	)
+)
+"#;
+
+#[test]
+fn get_runtime_storage_works() {
+	let (wasm, code_hash) = compile_module::<Test>(CODE_GET_RUNTIME_STORAGE).unwrap();
+	with_externalities(
+		&mut ExtBuilder::default().existential_deposit(50).build(),
+		|| {
+			Balances::deposit_creating(&ALICE, 1_000_000);
+			assert_ok!(Contract::put_code(Origin::signed(ALICE), 100_000, wasm));
+			assert_ok!(Contract::create(
+				Origin::signed(ALICE),
+				100_000,
+				100_000,
+				code_hash.into(),
+				vec![],
+			));
+
+			runtime_io::set_storage(b"runtime_key", b"runtime_value");
+
+			assert_ok!(Contract::call(
+				Origin::signed(ALICE),
+				BOB,
+				0,
+				100_000,
+				b"runtime_key".to_vec(),
+			));
+			assert_eq!(
+				System::events().last().unwrap().event,
+				MetaEvent::contract(RawEvent::Contract(BOB, b"runtime_value".to_vec())),
+			);
+
+			// There is no value under this key, so the contract traps.
+			assert_err!(
+				Contract::call(
+					Origin::signed(ALICE),
+					BOB,
+					0,
+					100_000,
+					b"unknown_key".to_vec(),
+				),
+				"during execution"
+			);
+		}
+	);
+}
+
+#[test]
+fn runtime_upgrade_migrates_the_old_schedule_layout() {
+	with_externalities(&mut ExtBuilder::default().build(), || {
+		let key = crate::CurrentSchedule::hashed_key();
+		let schedule = Contract::current_schedule();
+
+		// The old layout lacks the six costs appended at the end of the schedule.
+		let mut raw = schedule.encode();
+		raw.truncate(raw.len() - 6 * 8);
+		unhashed::put_raw(&key, &raw);
+
+		assert_eq!(<Contract as OnRuntimeUpgrade>::on_runtime_upgrade(), 10_000);
+		assert_eq!(
+			Contract::current_schedule(),
+			Schedule {
+				version: schedule.version + 1,
+				..schedule.clone()
+			},
+		);
+
+		// Nothing is left to migrate.
+		assert_eq!(<Contract as OnRuntimeUpgrade>::on_runtime_upgrade(), 0);
+		assert_eq!(Contract::current_schedule().version, schedule.version + 1);
+	});
+}
+
+const CODE_CHAIN_EXTENSION: &str = r#"
+(module
+	(import "env" "ext_scratch_size" (func $ext_scratch_size (result i32)))
+	(import "env" "ext_scratch_read" (func $ext_scratch_read (param i32 i32 i32)))
+	(import "env" "ext_call_chain_extension" (func $ext_call_chain_extension (param i32 i32 i32)))
+	(import "env" "ext_deposit_event" (func $ext_deposit_event (param i32 i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(func (export "deploy"))
+
+	;; The first 4 bytes of the input data are the id of the chain extension function, the rest of
+	;; the input data is the input of the function. The output of the function is deposited as the
+	;; data of an event.
+	(func (export "call")
+		(local $len i32)
+
+		(set_local $len (call $ext_scratch_size))
+		(call $ext_scratch_read
+			(i32.const 0)		;; Pointer to write the input data to
+			(i32.const 0)		;; Offset into the scratch buffer
+			(get_local $len)	;; Length of the input data
+		)
+
+		(call $ext_call_chain_extension
+			(i32.load (i32.const 0))					;; The function id
+			(i32.const 4)								;; Pointer to the input of the function
+			(i32.sub (get_local $len) (i32.const 4))	;; Length of the input of the function
+		)
+
+		(set_local $len (call $ext_scratch_size))
+		(call $ext_scratch_read
+			(i32.const 256)		;; Pointer to write the output to
+			(i32.const 0)		;; Offset into the scratch buffer
+			(get_local $len)	;; Length of the output
+		)
+
+		(call $ext_deposit_event
+			(i32.const 0)		;; The topics buffer
+			(i32.const 0)		;; The topics buffer's length
+			(i32.const 256)		;; The data buffer
+			(get_local $len)	;; The data buffer's length
+		)
+	)
+)
+"#;
+
+#[test]
+fn chain_extension_works() {
+	let (wasm, code_hash) = compile_module::<Test>(CODE_CHAIN_EXTENSION).unwrap();
+	with_externalities(
+		&mut ExtBuilder::default().existential_deposit(50).build(),
+		|| {
+			Balances::deposit_creating(&ALICE, 1_000_000);
+			Balances::deposit_creating(&DJANGO, 1_234);
+			assert_ok!(Contract::put_code(Origin::signed(ALICE), 100_000, wasm));
+			assert_ok!(Contract::create(
+				Origin::signed(ALICE),
+				100_000,
+				100_000,
+				code_hash.into(),
+				vec![],
+			));
+
+			// Query the balance of DJANGO through function 0 of the extension.
+			assert_ok!(Contract::call(
+				Origin::signed(ALICE),
+				BOB,
+				0,
+				100_000,
+				(0u32, DJANGO).encode(),
+			));
+			assert_eq!(
+				System::events().last().unwrap().event,
+				MetaEvent::contract(RawEvent::Contract(BOB, 1_234u64.encode())),
+			);
+
+			// An error of the extension traps the contract.
+			assert_err!(
+				Contract::call(
+					Origin::signed(ALICE),
+					BOB,
+					0,
+					100_000,
+					(1u32, DJANGO).encode(),
+				),
+				"during execution"
+			);
+		}
+	);
+}
@@ -188,12 +188,19 @@ mod tests {
 		gas_left: u64,
 	}
 
+	#[derive(Debug, PartialEq, Eq)]
+	struct TerminationEntry {
+		beneficiary: u64,
+		gas_left: u64,
+	}
+
 	#[derive(Default)]
 	pub struct MockExt {
 		storage: HashMap<StorageKey, Vec<u8>>,
 		rent_allowance: u64,
 		creates: Vec<CreateEntry>,
 		transfers: Vec<TransferEntry>,
+		terminations: Vec<TerminationEntry>,
 		dispatches: Vec<DispatchEntry>,
 		restores: Vec<RestoreEntry>,
 		// (topics, data)
@@ -248,6 +255,17 @@ mod tests {
 			// TODO: Add tests for different call outcomes.
 			Ok(ExecReturnValue { status: STATUS_SUCCESS, data: Vec::new() })
 		}
+		fn terminate(
+			&mut self,
+			beneficiary: &u64,
+			gas_meter: &mut GasMeter<Test>,
+		) -> Result<(), &'static str> {
+			self.terminations.push(TerminationEntry {
+				beneficiary: *beneficiary,
+				gas_left: gas_meter.gas_left(),
+			});
+			Ok(())
+		}
 		fn note_dispatch_call(&mut self, call: Call) {
 			self.dispatches.push(DispatchEntry(call));
 		}
@@ -304,7 +322,19 @@ mod tests {
 
 		fn block_number(&self) -> u64 { 121 }
 
+		fn block_hash(&self, block_number: u64) -> H256 {
+			H256::from_low_u64_be(block_number)
+		}
+
 		fn max_value_size(&self) -> u32 { 16_384 }
+
+		fn get_runtime_storage(&self, key: &[u8]) -> Option<Vec<u8>> {
+			if key == b"runtime_key" {
+				Some(b"runtime_value".to_vec())
+			} else {
+				None
+			}
+		}
 	}
 
 	impl Ext for &mut MockExt {
@@ -336,6 +366,13 @@ mod tests {
 		) -> ExecResult {
 			(**self).call(to, value, gas_meter, input_data)
 		}
+		fn terminate(
+			&mut self,
+			beneficiary: &u64,
+			gas_meter: &mut GasMeter<Test>,
+		) -> Result<(), &'static str> {
+			(**self).terminate(beneficiary, gas_meter)
+		}
 		fn note_dispatch_call(&mut self, call: Call) {
 			(**self).note_dispatch_call(call)
 		}
@@ -386,9 +423,15 @@ mod tests {
 		fn block_number(&self) -> u64 {
 			(**self).block_number()
 		}
+		fn block_hash(&self, block_number: u64) -> H256 {
+			(**self).block_hash(block_number)
+		}
 		fn max_value_size(&self) -> u32 {
 			(**self).max_value_size()
 		}
+		fn get_runtime_storage(&self, key: &[u8]) -> Option<Vec<u8>> {
+			(**self).get_runtime_storage(key)
+		}
 	}
 
 	fn execute<E: Ext>(
@@ -1513,6 +1556,46 @@ mod tests {
 		).unwrap();
 	}
 
+	const CODE_TERMINATE: &str = r#"
+(module
+	;; ext_terminate(
+	;;     beneficiary_ptr: u32,
+	;;     beneficiary_len: u32,
+	;; )
+	(import "env" "ext_terminate" (func $ext_terminate (param i32 i32)))
+	(import "env" "memory" (memory 1 1))
+	(func (export "call")
+		(call $ext_terminate
+			(i32.const 4)  ;; Pointer to "beneficiary" address.
+			(i32.const 8)  ;; Length of "beneficiary" address.
+		)
+		;; ext_terminate never returns.
+		(unreachable)
+	)
+	(func (export "deploy"))
+
+	;; Beneficiary AccountId to transfer the funds.
+	(data (i32.const 4) "\09\00\00\00\00\00\00\00")
+)
+"#;
+
+	#[test]
+	fn contract_terminate() {
+		let mut mock_ext = MockExt::default();
+		let output = execute(
+			CODE_TERMINATE,
+			vec![],
+			&mut mock_ext,
+			&mut GasMeter::with_limit(50_000, 1),
+		).unwrap();
+
+		assert_eq!(output, ExecReturnValue { status: STATUS_SUCCESS, data: Vec::new() });
+		assert_matches!(
+			&mock_ext.terminations[..],
+			[TerminationEntry { beneficiary: 9, .. }]
+		);
+	}
+
 	// asserts that the size of the input data is 4.
 	const CODE_SIMPLE_ASSERT: &str = r#"
 (module
@@ -713,6 +713,46 @@ mod tests {
 			let r = prepare_contract::<TestEnv>(wasm.as_ref(), &schedule);
 			assert_matches!(r, Ok(_));
 		}
+
+		#[test]
+		fn can_import_terminate_hash_and_runtime_storage_functions() {
+			let wasm = wabt::Wat2Wasm::new().validate(false).convert(
+				r#"
+				(module
+					(import "env" "ext_terminate" (func (param i32 i32)))
+					(import "env" "ext_hash_sha2_256" (func (param i32 i32 i32)))
+					(import "env" "ext_hash_keccak_256" (func (param i32 i32 i32)))
+					(import "env" "ext_hash_blake2_256" (func (param i32 i32 i32)))
+					(import "env" "ext_hash_blake2_128" (func (param i32 i32 i32)))
+					(import "env" "ext_block_hash" (func (param i32 i32)))
+					(import "env" "ext_get_runtime_storage" (func (param i32 i32) (result i32)))
+
+					(func (export "call"))
+					(func (export "deploy"))
+				)
+				"#
+			).unwrap();
+			let schedule = Schedule::default();
+			let r = prepare_contract::<crate::wasm::runtime::Env>(wasm.as_ref(), &schedule);
+			assert_matches!(r, Ok(_));
+		}
+
+		#[test]
+		fn hash_function_with_wrong_signature() {
+			let wasm = wabt::Wat2Wasm::new().validate(false).convert(
+				r#"
+				(module
+					(import "env" "ext_hash_sha2_256" (func (param i32 i32) (result i32)))
+
+					(func (export "call"))
+					(func (export "deploy"))
+				)
+				"#
+			).unwrap();
+			let schedule = Schedule::default();
+			let r = prepare_contract::<crate::wasm::runtime::Env>(wasm.as_ref(), &schedule);
+			assert_matches!(r, Err("module imports a non-existent function"));
+		}
 	}
 
 	mod entrypoints {
@@ -16,7 +16,7 @@
 
 //! Environment definition of the wasm smart-contract runtime.
 
-use crate::{Schedule, Trait, CodeHash, ComputeDispatchFee, BalanceOf};
+use crate::{Schedule, Trait, CodeHash, ComputeDispatchFee, ChainExtension, BalanceOf};
 use crate::exec::{
 	Ext, ExecResult, ExecError, ExecReturnValue, StorageKey, TopicOf, STATUS_SUCCESS,
 };
@@ -41,6 +41,9 @@ const TRAP_RETURN_CODE: u32 = 0x0100;
 enum SpecialTrap {
 	/// Signals that trap was generated in response to call `ext_return` host function.
 	Return(Vec<u8>),
+	/// Signals that trap was generated because the contract terminated itself by calling
+	/// `ext_terminate` host function.
+	Termination,
 }
 
 /// Can only be used for one call.
@@ -77,8 +80,13 @@ pub(crate) fn to_execution_result<E: Ext>(
 	sandbox_result: Result<sandbox::ReturnValue, sandbox::Error>,
 ) -> ExecResult {
 	// Special case. The trap was the result of the execution `return` host function.
-	if let Some(SpecialTrap::Return(data)) = runtime.special_trap {
-		return Ok(ExecReturnValue { status: STATUS_SUCCESS, data });
+	match runtime.special_trap {
+		Some(SpecialTrap::Return(data)) =>
+			return Ok(ExecReturnValue { status: STATUS_SUCCESS, data }),
+		// Special case. The contract terminated itself, which succeeds without output.
+		Some(SpecialTrap::Termination) =>
+			return Ok(ExecReturnValue { status: STATUS_SUCCESS, data: Vec::new() }),
+		None => (),
 	}
 
 	// Check the exact type of the error.
@@ -131,6 +139,18 @@ pub enum RuntimeToken {
 	/// (topic_count, data_bytes): A buffer of the given size is posted as an event indexed with the
 	/// given number of topics.
 	DepositEvent(u32, u32),
+	/// The contract terminates itself.
+	Terminate,
+	/// A buffer of the given size is hashed.
+	Hash(u32),
+	/// The hash of a block is read.
+	BlockHash,
+	/// A value is read from the runtime storage under a key of the given size.
+	GetRuntimeStorage(u32),
+	/// A value of the given size was read from the runtime storage.
+	RuntimeStorageValue(u32),
+	/// Gas cost of calling the chain extension, calculated by `T::ChainExtension`.
+	ChainExtension(Gas),
 }
 
 impl<T: Trait> Token<T> for RuntimeToken {
@@ -169,6 +189,20 @@ impl<T: Trait> Token<T> for RuntimeToken {
 					)
 			},
 			ComputedDispatchFee(gas) => Some(gas),
+			Terminate => Some(metadata.terminate_base_cost),
+			Hash(byte_count) => metadata
+				.hash_per_byte_cost
+				.checked_mul(byte_count.into())
+				.and_then(|cost| cost.checked_add(metadata.hash_base_cost)),
+			BlockHash => Some(metadata.block_hash_cost),
+			ChainExtension(gas) => Some(gas),
+			GetRuntimeStorage(byte_count) => metadata
+				.runtime_storage_read_per_byte_cost
+				.checked_mul(byte_count.into())
+				.and_then(|cost| cost.checked_add(metadata.runtime_storage_read_base_cost)),
+			RuntimeStorageValue(byte_count) => metadata
+				.runtime_storage_read_per_byte_cost
+				.checked_mul(byte_count.into()),
 		};
 
 		value.unwrap_or_else(|| Bounded::max_value())
@@ -287,6 +321,28 @@ fn write_sandbox_memory<T: Trait>(
 	Ok(())
 }
 
+/// Hash the buffer at the given location in the sandbox memory with `hash_fn` and write the
+/// result to `output_ptr`, consuming an appropriate amount of gas.
+///
+/// Returns `Err` if one of the following conditions occurs:
+///
+/// - calculating the gas cost resulted in overflow.
+/// - out of gas
+/// - the input buffer or the output area is not within the bounds of the sandbox memory.
+fn hash_sandbox_memory<E: Ext, R: AsRef<[u8]>>(
+	ctx: &mut Runtime<E>,
+	hash_fn: fn(&[u8]) -> R,
+	input_ptr: u32,
+	input_len: u32,
+	output_ptr: u32,
+) -> Result<(), sandbox::HostError> {
+	charge_gas(ctx.gas_meter, ctx.schedule, RuntimeToken::Hash(input_len))?;
+
+	let input = read_sandbox_memory(ctx, input_ptr, input_len)?;
+	let hash = hash_fn(&input);
+	write_sandbox_memory(ctx.schedule, ctx.gas_meter, &ctx.memory, output_ptr, hash.as_ref())
+}
+
 // ***********************************************************
 // * AFTER MAKING A CHANGE MAKE SURE TO UPDATE COMPLEXITY.MD *
 // ***********************************************************
@@ -833,6 +889,137 @@ define_env!(Env, <E: Ext>,
 		ctx.ext.block_number().encode_to(&mut ctx.scratch_buf);
 		Ok(())
 	},
+
+	// Stores the hash of the block with the given number into the scratch buffer.
+	//
+	// Only the hashes of recent blocks are kept by the runtime. For any other block number the
+	// default hash, i.e. all zeros, is stored.
+	//
+	// - block_number_ptr: a pointer to the buffer with the block number.
+	//   Should be decodable as a `T::BlockNumber`. Traps otherwise.
+	// - block_number_len: length of the block number buffer.
+	//
+	// The data is encoded as T::Hash. The current contents of the scratch buffer are overwritten.
+	ext_block_hash(ctx, block_number_ptr: u32, block_number_len: u32) => {
+		let block_number: <<E as Ext>::T as system::Trait>::BlockNumber =
+			read_sandbox_memory_as(ctx, block_number_ptr, block_number_len)?;
+
+		charge_gas(ctx.gas_meter, ctx.schedule, RuntimeToken::BlockHash)?;
+
+		ctx.scratch_buf.clear();
+		ctx.ext.block_hash(block_number).encode_to(&mut ctx.scratch_buf);
+		Ok(())
+	},
+
+	// Remove the calling contract and transfer its remaining balance to the beneficiary.
+	//
+	// This function never returns. Either the termination was successful and the execution of
+	// the contract ends with a successful result, or the termination failed and the execution
+	// traps. The termination fails if the contract is live further up in the call stack or if
+	// the transfer of the balance fails.
+	//
+	// - beneficiary_ptr: a pointer to the address of the beneficiary account.
+	//   Should be decodable as an `T::AccountId`. Traps otherwise.
+	// - beneficiary_len: length of the address buffer.
+	ext_terminate(ctx, beneficiary_ptr: u32, beneficiary_len: u32) => {
+		let beneficiary: <<E as Ext>::T as system::Trait>::AccountId =
+			read_sandbox_memory_as(ctx, beneficiary_ptr, beneficiary_len)?;
+
+		charge_gas(ctx.gas_meter, ctx.schedule, RuntimeToken::Terminate)?;
+
+		if ctx.ext.terminate(&beneficiary, ctx.gas_meter).is_ok() {
+			ctx.special_trap = Some(SpecialTrap::Termination);
+		}
+
+		// The trap mechanism is used to immediately terminate the execution.
+		// This trap should be handled appropriately before returning the result
+		// to the user of this crate.
+		Err(sandbox::HostError)
+	},
+
+	// Computes the SHA2 256-bit hash of the given input and writes it to the output buffer.
+	//
+	// - input_ptr: a pointer to the buffer to hash.
+	// - input_len: length of the buffer to hash.
+	// - output_ptr: a pointer to the 32 bytes of memory the hash is written to.
+	ext_hash_sha2_256(ctx, input_ptr: u32, input_len: u32, output_ptr: u32) => {
+		hash_sandbox_memory(ctx, runtime_io::hashing::sha2_256, input_ptr, input_len, output_ptr)
+	},
+
+	// Computes the KECCAK 256-bit hash of the given input and writes it to the output buffer.
+	//
+	// - input_ptr: a pointer to the buffer to hash.
+	// - input_len: length of the buffer to hash.
+	// - output_ptr: a pointer to the 32 bytes of memory the hash is written to.
+	ext_hash_keccak_256(ctx, input_ptr: u32, input_len: u32, output_ptr: u32) => {
+		hash_sandbox_memory(ctx, runtime_io::keccak_256, input_ptr, input_len, output_ptr)
+	},
+
+	// Computes the BLAKE2 256-bit hash of the given input and writes it to the output buffer.
+	//
+	// - input_ptr: a pointer to the buffer to hash.
+	// - input_len: length of the buffer to hash.
+	// - output_ptr: a pointer to the 32 bytes of memory the hash is written to.
+	ext_hash_blake2_256(ctx, input_ptr: u32, input_len: u32, output_ptr: u32) => {
+		hash_sandbox_memory(ctx, runtime_io::blake2_256, input_ptr, input_len, output_ptr)
+	},
+
+	// Computes the BLAKE2 128-bit hash of the given input and writes it to the output buffer.
+	//
+	// - input_ptr: a pointer to the buffer to hash.
+	// - input_len: length of the buffer to hash.
+	// - output_ptr: a pointer to the 16 bytes of memory the hash is written to.
+	ext_hash_blake2_128(ctx, input_ptr: u32, input_len: u32, output_ptr: u32) => {
+		hash_sandbox_memory(ctx, runtime_io::blake2_128, input_ptr, input_len, output_ptr)
+	},
+
+	// Call the function `func_id` of the chain extension of the runtime with the given input.
+	//
+	// The extension charges gas on its own before it is called. On success the output of the
+	// function is stored into the scratch buffer. Traps if the extension returns an error.
+	//
+	// - func_id: the identifier of the function of the chain extension.
+	// - input_ptr: a pointer to the buffer with the input of the function.
+	// - input_len: length of the input buffer.
+	ext_call_chain_extension(ctx, func_id: u32, input_ptr: u32, input_len: u32) => {
+		let input = read_sandbox_memory(ctx, input_ptr, input_len)?;
+
+		let gas = <<E as Ext>::T as Trait>::ChainExtension::gas_cost(func_id, &input);
+		charge_gas(ctx.gas_meter, ctx.schedule, RuntimeToken::ChainExtension(gas))?;
+
+		ctx.scratch_buf = <<E as Ext>::T as Trait>::ChainExtension::call(func_id, input)
+			.map_err(|_| sandbox::HostError)?;
+
+		Ok(())
+	},
+
+	// Retrieve the value under the given key from the runtime storage and return 0.
+	// If there is no entry under the given key then this function will return 1 and
+	// clear the scratch buffer.
+	//
+	// Unlike `ext_get_storage` the key is a raw key of the runtime storage, not a key of the
+	// storage of the contract. The base cost and the bytes of the key are charged before the
+	// read, the bytes of the value after it.
+	//
+	// - key_ptr: pointer into the linear memory where the key is placed.
+	// - key_len: length of the key.
+	ext_get_runtime_storage(ctx, key_ptr: u32, key_len: u32) -> u32 => {
+		let key = read_sandbox_memory(ctx, key_ptr, key_len)?;
+		charge_gas(ctx.gas_meter, ctx.schedule, RuntimeToken::GetRuntimeStorage(key_len))?;
+
+		let value = ctx.ext.get_runtime_storage(&key);
+
+		let value_len = value.as_ref().map_or(0, |value| value.len() as u32);
+		charge_gas(ctx.gas_meter, ctx.schedule, RuntimeToken::RuntimeStorageValue(value_len))?;
+
+		if let Some(value) = value {
+			ctx.scratch_buf = value;
+			Ok(0)
+		} else {
+			ctx.scratch_buf.clear();
+			Ok(1)
+		}
+	},
 );
 
 /// Finds duplicates in a given vector.
@@ -28,7 +28,7 @@ use srml_support::{
 	Parameter, Dispatchable,
 	traits::{
 		Currency, ReservableCurrency, LockableCurrency, WithdrawReason, LockIdentifier, Get,
-		OnFreeBalanceZero
+		OnFreeBalanceZero, schedule
 	}
 };
 use srml_support::dispatch::Result;
@@ -175,7 +175,7 @@ impl Decode for Vote {
 type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
 
 pub trait Trait: system::Trait + Sized {
-	type Proposal: Parameter + Dispatchable<Origin=Self::Origin>;
+	type Proposal: Parameter + Dispatchable<Origin=Self::Origin> + From<Call<Self>>;
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
 
 	/// Currency type for this module.
@@ -226,6 +226,9 @@ pub trait Trait: system::Trait + Sized {
 
 	/// Period in blocks where an external proposal may not be re-submitted after being vetoed.
 	type CooloffPeriod: Get<Self::BlockNumber>;
+
+	/// The scheduler through which approved proposals are enacted.
+	type Scheduler: schedule::Named<Self::BlockNumber, Self::Proposal, system::Origin<Self>>;
 }
 
 /// Info regarding an ongoing referendum.
@@ -270,7 +273,9 @@ decl_storage! {
 		/// Information concerning any given referendum.
 		pub ReferendumInfoOf get(referendum_info):
 			map ReferendumIndex => Option<(ReferendumInfo<T::BlockNumber, T::Proposal>)>;
-		/// Queue of successful referenda to be dispatched.
+		/// Queue of successful referenda to be dispatched, as filled before enactment was handed
+		/// over to the `Scheduler`. Nothing is added to it anymore; the remaining entries are
+		/// still dispatched at the block they were queued for.
 		pub DispatchQueue get(dispatch_queue):
 			map T::BlockNumber => Vec<Option<(T::Proposal, ReferendumIndex)>>;
 
@@ -419,6 +424,9 @@ decl_module! {
 		/// Vote in a referendum on behalf of a stash. If `vote.is_aye()`, the vote is to enact
 		/// the proposal;  otherwise it is a vote to keep the status quo.
 		///
+		/// NOTE: Deprecated. Register the proxy through the Proxy module and dispatch `vote`
+		/// through its `proxy` call instead; this will be removed in a future release.
+		///
 		/// # <weight>
 		/// - O(1).
 		/// - One DB change, one DB entry.
@@ -540,6 +548,10 @@ decl_module! {
 		}
 
 		/// Cancel a proposal queued for enactment.
+		///
+		/// `when` and `which` locate the proposal of referendum `what` in the dispatch queue, for
+		/// the proposals that were queued before enactment was handed over to the `Scheduler`.
+		/// Proposals scheduled since then are found from `what` alone.
 		#[weight = SimpleDispatchInfo::FixedOperational(10_000)]
 		fn cancel_queued(
 			origin,
@@ -554,7 +566,8 @@ decl_module! {
 				items[which] = None;
 				<DispatchQueue<T>>::insert(when, items);
 			} else {
-				Err("proposal not found")?
+				T::Scheduler::cancel_named((DEMOCRACY_ID, what).encode())
+					.map_err(|_| "proposal not found")?;
 			}
 		}
 
@@ -566,6 +579,8 @@ decl_module! {
 
 		/// Specify a proxy. Called by the stash.
 		///
+		/// NOTE: Deprecated in favour of `add_proxy` of the Proxy module.
+		///
 		/// # <weight>
 		/// - One extra DB entry.
 		/// # </weight>
@@ -578,6 +593,8 @@ decl_module! {
 
 		/// Clear the proxy. Called by the proxy.
 		///
+		/// NOTE: Deprecated along with `set_proxy`.
+		///
 		/// # <weight>
 		/// - One DB clear.
 		/// # </weight>
@@ -589,6 +606,8 @@ decl_module! {
 
 		/// Clear the proxy. Called by the stash.
 		///
+		/// NOTE: Deprecated in favour of `remove_proxy` of the Proxy module.
+		///
 		/// # <weight>
 		/// - One DB clear.
 		/// # </weight>
@@ -641,6 +660,13 @@ decl_module! {
 			);
 			Self::deposit_event(RawEvent::Undelegated(who));
 		}
+
+		/// Enact a proposal from a referendum. Scheduled when the referendum passed.
+		#[weight = SimpleDispatchInfo::FixedOperational(10_000)]
+		fn enact_proposal(origin, proposal: Box<T::Proposal>, index: ReferendumIndex) {
+			ensure_root(origin)?;
+			Self::do_enact_proposal(*proposal, index);
+		}
 	}
 }
 
@@ -826,7 +852,7 @@ impl<T: Trait> Module<T> {
 	}
 
 	/// Enact a proposal from a referendum.
-	fn enact_proposal(proposal: T::Proposal, index: ReferendumIndex) {
+	fn do_enact_proposal(proposal: T::Proposal, index: ReferendumIndex) {
 		let ok = proposal.dispatch(system::RawOrigin::Root.into()).is_ok();
 		Self::deposit_event(RawEvent::Executed(index, ok));
 	}
@@ -923,12 +949,16 @@ impl<T: Trait> Module<T> {
 		if approved {
 			Self::deposit_event(RawEvent::Passed(index));
 			if info.delay.is_zero() {
-				Self::enact_proposal(info.proposal, index);
-			} else {
-				<DispatchQueue<T>>::append_or_insert(
-					now + info.delay,
-					[Some((info.proposal, index))].into_iter()
-				);
+				Self::do_enact_proposal(info.proposal, index);
+			} else if T::Scheduler::schedule_named(
+				(DEMOCRACY_ID, index).encode(),
+				now + info.delay,
+				None,
+				schedule::HARD_DEADLINE,
+				system::RawOrigin::Root,
+				Call::<T>::enact_proposal(Box::new(info.proposal), index).into(),
+			).is_err() {
+				runtime_io::print("LOGIC ERROR: bake_referendum/schedule_named failed");
 			}
 		} else {
 			Self::deposit_event(RawEvent::NotPassed(index));
@@ -953,9 +983,11 @@ impl<T: Trait> Module<T> {
 			Self::bake_referendum(now, index, info)?;
 		}
 
+		// enact the proposals that were queued before the scheduler took over.
 		for (proposal, index) in <DispatchQueue<T>>::take(now).into_iter().filter_map(|x| x) {
-			Self::enact_proposal(proposal, index);
+			Self::do_enact_proposal(proposal, index);
 		}
+
 		Ok(())
 	}
 }
@@ -971,14 +1003,14 @@ mod tests {
 	use super::*;
 	use runtime_io::with_externalities;
 	use srml_support::{
-		impl_outer_origin, impl_outer_dispatch, assert_noop, assert_ok, parameter_types,
-		traits::Contains
+		impl_outer_origin, impl_outer_dispatch, impl_outer_event, assert_noop, assert_ok,
+		parameter_types, traits::Contains
 	};
 	use primitives::{H256, Blake2Hasher};
-	use sr_primitives::{traits::{BlakeTwo256, IdentityLookup, Bounded}, testing::Header};
+	use sr_primitives::{traits::{BlakeTwo256, IdentityLookup, Bounded, OnInitialize}, testing::Header};
 	use sr_primitives::Perbill;
 	use balances::BalanceLock;
-	use system::EnsureSignedBy;
+	use system::{EnsureSignedBy, EnsureRoot};
 
 	const AYE: Vote = Vote{ aye: true, conviction: Conviction::None };
 	const NAY: Vote = Vote{ aye: false, conviction: Conviction::None };
@@ -996,6 +1028,16 @@ mod tests {
 		}
 	}
 
+	mod democracy {
+		pub use crate::Event;
+	}
+
+	impl_outer_event! {
+		pub enum TestEvent for Test {
+			democracy<T>,
+		}
+	}
+
 	// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
 	#[derive(Clone, Eq, PartialEq, Debug)]
 	pub struct Test;
@@ -1016,7 +1058,7 @@ mod tests {
 		type Lookup = IdentityLookup<Self::AccountId>;
 		type Header = Header;
 		type WeightMultiplierUpdate = ();
-		type Event = ();
+		type Event = TestEvent;
 		type BlockHashCount = BlockHashCount;
 		type MaximumBlockWeight = MaximumBlockWeight;
 		type MaximumBlockLength = MaximumBlockLength;
@@ -1045,6 +1087,15 @@ mod tests {
 		type TransactionByteFee = TransactionByteFee;
 		type WeightToFee = ();
 	}
+	parameter_types! {
+		pub const MaximumWeight: u32 = 1_000_000;
+	}
+	impl scheduler::Trait for Test {
+		type Event = ();
+		type Call = Call;
+		type MaximumWeight = MaximumWeight;
+		type ScheduleOrigin = EnsureRoot<u64>;
+	}
 	parameter_types! {
 		pub const LaunchPeriod: u64 = 2;
 		pub const VotingPeriod: u64 = 2;
@@ -1066,7 +1117,7 @@ mod tests {
 	}
 	impl super::Trait for Test {
 		type Proposal = Call;
-		type Event = ();
+		type Event = TestEvent;
 		type Currency = balances::Module<Self>;
 		type EnactmentPeriod = EnactmentPeriod;
 		type LaunchPeriod = LaunchPeriod;
@@ -1080,13 +1131,13 @@ mod tests {
 		type CancellationOrigin = EnsureSignedBy<Four, u64>;
 		type VetoOrigin = EnsureSignedBy<OneToFive, u64>;
 		type CooloffPeriod = CooloffPeriod;
+		type Scheduler = Scheduler;
 	}
 
 	fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
 		let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
 		balances::GenesisConfig::<Test>{
 			balances: vec![(1, 10), (2, 20), (3, 30), (4, 40), (5, 50), (6, 60)],
-			vesting: vec![],
 		}.assimilate_storage(&mut t).unwrap();
 		GenesisConfig::default().assimilate_storage(&mut t).unwrap();
 		runtime_io::TestExternalities::new(t)
@@ -1094,6 +1145,7 @@ mod tests {
 
 	type System = system::Module<Test>;
 	type Balances = balances::Module<Test>;
+	type Scheduler = scheduler::Module<Test>;
 	type Democracy = Module<Test>;
 
 	#[test]
@@ -1118,6 +1170,7 @@ mod tests {
 	}
 
 	fn next_block() {
+		Scheduler::on_initialize(System::block_number());
 		assert_eq!(Democracy::end_block(System::block_number()), Ok(()));
 		System::set_block_number(System::block_number() + 1);
 	}
@@ -1498,11 +1551,13 @@ mod tests {
 			fast_forward_to(3);
 
 			assert!(Democracy::referendum_info(0).is_none());
-			assert_eq!(Democracy::dispatch_queue(4), vec![
-				Some((set_balance_proposal(2), 0))
-			]);
+			assert_eq!(Scheduler::agenda(4).len(), 1);
 
 			// referendum passes and wait another two blocks for enactment.
+			fast_forward_to(4);
+
+			assert_eq!(Balances::free_balance(&42), 0);
+
 			fast_forward_to(5);
 
 			assert_eq!(Balances::free_balance(&42), 2);
@@ -1522,14 +1577,14 @@ mod tests {
 
 			fast_forward_to(3);
 
-			assert_eq!(Democracy::dispatch_queue(4), vec![
-				Some((set_balance_proposal(2), 0))
-			]);
+			assert_eq!(Scheduler::agenda(4).len(), 1);
 
-			assert_noop!(Democracy::cancel_queued(Origin::ROOT, 3, 0, 0), "proposal not found");
-			assert_noop!(Democracy::cancel_queued(Origin::ROOT, 4, 1, 0), "proposal not found");
+			assert_noop!(Democracy::cancel_queued(Origin::ROOT, 4, 0, 1), "proposal not found");
 			assert_ok!(Democracy::cancel_queued(Origin::ROOT, 4, 0, 0));
-			assert_eq!(Democracy::dispatch_queue(4), vec![None]);
+			assert_noop!(Democracy::cancel_queued(Origin::ROOT, 4, 0, 0), "proposal not found");
+
+			fast_forward_to(5);
+			assert_eq!(Balances::free_balance(&42), 0);
 		});
 	}
 
@@ -1885,6 +1940,36 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn dispatch_queue_from_before_scheduler_is_enacted() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(0);
+			<DispatchQueue<Test>>::insert(2, vec![Some((set_balance_proposal(2), 0)), None]);
+
+			fast_forward_to(2);
+			assert_eq!(Balances::free_balance(&42), 0);
+
+			fast_forward_to(3);
+			assert_eq!(Balances::free_balance(&42), 2);
+			assert!(!<DispatchQueue<Test>>::exists(2));
+		});
+	}
+
+	#[test]
+	fn cancel_queued_from_before_scheduler_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(0);
+			<DispatchQueue<Test>>::insert(2, vec![Some((set_balance_proposal(2), 0))]);
+
+			assert_noop!(Democracy::cancel_queued(Origin::ROOT, 2, 0, 1), "proposal not found");
+			assert_ok!(Democracy::cancel_queued(Origin::ROOT, 2, 0, 0));
+			assert_eq!(Democracy::dispatch_queue(2), vec![None]);
+
+			fast_forward_to(3);
+			assert_eq!(Balances::free_balance(&42), 0);
+		});
+	}
+
 	#[test]
 	fn delayed_enactment_should_work() {
 		with_externalities(&mut new_test_ext(), || {
@@ -1910,6 +1995,9 @@ mod tests {
 			next_block();
 
 			assert_eq!(Balances::free_balance(&42), 2);
+			assert!(System::events().iter().any(|record|
+				record.event == TestEvent::democracy(RawEvent::Executed(r, true))
+			));
 		});
 	}
 
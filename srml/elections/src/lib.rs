@@ -331,6 +331,9 @@ decl_module! {
 		/// Set candidate approvals from a proxy. Approval slots stay valid as long as candidates in those slots
 		/// are registered.
 		///
+		/// NOTE: Deprecated. Dispatch `set_approvals` through the Proxy module's `proxy` call
+		/// instead; this will be removed in a future release.
+		///
 		/// # <weight>
 		/// - Same as `set_approvals` with one additional storage read.
 		/// # </weight>
@@ -1301,7 +1304,6 @@ mod tests {
 						(5, 50 * self.balance_factor),
 						(6, 60 * self.balance_factor)
 					],
-					vesting: vec![],
 				}),
 				elections: Some(elections::GenesisConfig::<Test>{
 					members: vec![],
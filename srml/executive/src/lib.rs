@@ -79,7 +79,7 @@ use rstd::marker::PhantomData;
 use rstd::result;
 use sr_primitives::{generic::Digest, traits::{
 	self, Header, Zero, One, Checkable, Applyable, CheckEqual, OnFinalize,
-	OnInitialize, NumberFor, Block as BlockT, OffchainWorker, ValidateUnsigned
+	OnInitialize, NumberFor, Block as BlockT, OffchainWorker, ValidateUnsigned, OnRuntimeUpgrade
 }};
 use srml_support::Dispatchable;
 use codec::{Codec, Encode};
@@ -101,7 +101,7 @@ mod internal {
 
 	pub enum ApplyOutcome {
 		Success,
-		Fail(&'static str),
+		Fail(sr_primitives::DispatchError),
 	}
 
 	impl From<DispatchError> for ApplyError {
@@ -138,7 +138,11 @@ impl<
 	Block: traits::Block<Header=System::Header, Hash=System::Hash>,
 	Context: Default,
 	UnsignedValidator,
-	AllModules: OnInitialize<System::BlockNumber> + OnFinalize<System::BlockNumber> + OffchainWorker<System::BlockNumber>,
+	AllModules:
+		OnRuntimeUpgrade +
+		OnInitialize<System::BlockNumber> +
+		OnFinalize<System::BlockNumber> +
+		OffchainWorker<System::BlockNumber>,
 > ExecuteBlock<Block> for Executive<System, Block, Context, UnsignedValidator, AllModules>
 where
 	Block::Extrinsic: Checkable<Context> + Codec,
@@ -157,7 +161,11 @@ impl<
 	Block: traits::Block<Header=System::Header, Hash=System::Hash>,
 	Context: Default,
 	UnsignedValidator,
-	AllModules: OnInitialize<System::BlockNumber> + OnFinalize<System::BlockNumber> + OffchainWorker<System::BlockNumber>,
+	AllModules:
+		OnRuntimeUpgrade +
+		OnInitialize<System::BlockNumber> +
+		OnFinalize<System::BlockNumber> +
+		OffchainWorker<System::BlockNumber>,
 > Executive<System, Block, Context, UnsignedValidator, AllModules>
 where
 	Block::Extrinsic: Checkable<Context> + Codec,
@@ -180,6 +188,10 @@ where
 		digest: &Digest<System::Hash>,
 	) {
 		<system::Module<System>>::initialize(block_number, parent_hash, extrinsics_root, digest);
+		if <system::Module<System>>::note_runtime_upgrade() {
+			let weight = <AllModules as OnRuntimeUpgrade>::on_runtime_upgrade();
+			<system::Module<System>>::register_extra_weight_unchecked(weight);
+		}
 		<AllModules as OnInitialize<System::BlockNumber>>::on_initialize(*block_number);
 	}
 
@@ -207,9 +219,14 @@ where
 		// any initial checks
 		Self::initial_checks(&block);
 
-		// execute extrinsics
+		// execute extrinsics, verifying their signatures in one batch
 		let (header, extrinsics) = block.deconstruct();
+		runtime_io::crypto::start_batch_verify();
 		Self::execute_extrinsics_with_book_keeping(extrinsics, *header.number());
+		assert!(
+			runtime_io::crypto::finish_batch_verify(),
+			"All signatures of the block's extrinsics should be valid",
+		);
 
 		// any final checks
 		Self::final_checks(&header);
@@ -291,9 +308,9 @@ where
 
 		<system::Module<System>>::note_applied_extrinsic(&r, encoded_len as u32);
 
-		r.map(|_| internal::ApplyOutcome::Success).or_else(|e| match e {
-			sr_primitives::BLOCK_FULL => Err(internal::ApplyError::FullBlock),
-			e => Ok(internal::ApplyOutcome::Fail(e))
+		r.map(|_| internal::ApplyOutcome::Success).or_else(|e| match e.message {
+			Some(sr_primitives::BLOCK_FULL) => Err(internal::ApplyError::FullBlock),
+			_ => Ok(internal::ApplyOutcome::Fail(e))
 		})
 	}
 
@@ -356,16 +373,17 @@ mod tests {
 	use super::*;
 	use balances::Call;
 	use runtime_io::with_externalities;
-	use primitives::{H256, Blake2Hasher};
-	use sr_primitives::generic::Era;
+	use primitives::{H256, Blake2Hasher, Pair, sr25519};
+	use sr_primitives::generic::{self, Era};
 	use sr_primitives::Perbill;
 	use sr_primitives::weights::Weight;
-	use sr_primitives::traits::{Header as HeaderT, BlakeTwo256, IdentityLookup, ConvertInto};
+	use sr_primitives::traits::{Header as HeaderT, BlakeTwo256, IdentityLookup, ConvertInto, Lazy, Verify};
 	use sr_primitives::testing::{Digest, Header, Block};
-	use srml_support::{impl_outer_event, impl_outer_origin, parameter_types};
-	use srml_support::traits::{Currency, LockIdentifier, LockableCurrency, WithdrawReasons, WithdrawReason};
+	use srml_support::{impl_outer_event, impl_outer_origin, parameter_types, storage::unhashed};
+	use srml_support::traits::{Currency, Get, LockIdentifier, LockableCurrency, WithdrawReasons, WithdrawReason};
 	use system;
 	use hex_literal::hex;
+	use std::cell::RefCell;
 
 	impl_outer_origin! {
 		pub enum Origin for Runtime { }
@@ -377,6 +395,36 @@ mod tests {
 		}
 	}
 
+	thread_local! {
+		static RUNTIME_VERSION: RefCell<sr_version::RuntimeVersion> = RefCell::new(Default::default());
+	}
+
+	pub struct RuntimeVersion;
+	impl Get<sr_version::RuntimeVersion> for RuntimeVersion {
+		fn get() -> sr_version::RuntimeVersion {
+			RUNTIME_VERSION.with(|v| v.borrow().clone())
+		}
+	}
+
+	fn set_spec_version(spec_version: u32) {
+		RUNTIME_VERSION.with(|v| v.borrow_mut().spec_version = spec_version);
+	}
+
+	const ON_RUNTIME_UPGRADE_KEY: &[u8] = b":test:on_runtime_upgrade";
+
+	/// Stands in for the runtime's modules; records every call of its runtime upgrade hook.
+	pub struct CustomOnRuntimeUpgrade;
+	impl OnRuntimeUpgrade for CustomOnRuntimeUpgrade {
+		fn on_runtime_upgrade() -> Weight {
+			let calls = unhashed::get_or_default::<u32>(ON_RUNTIME_UPGRADE_KEY);
+			unhashed::put(ON_RUNTIME_UPGRADE_KEY, &(calls + 1));
+			100
+		}
+	}
+	impl OnInitialize<u64> for CustomOnRuntimeUpgrade {}
+	impl OnFinalize<u64> for CustomOnRuntimeUpgrade {}
+	impl OffchainWorker<u64> for CustomOnRuntimeUpgrade {}
+
 	// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
 	#[derive(Clone, Eq, PartialEq)]
 	pub struct Runtime;
@@ -402,7 +450,7 @@ mod tests {
 		type MaximumBlockWeight = MaximumBlockWeight;
 		type AvailableBlockRatio = AvailableBlockRatio;
 		type MaximumBlockLength = MaximumBlockLength;
-		type Version = ();
+		type Version = RuntimeVersion;
 	}
 	parameter_types! {
 		pub const ExistentialDeposit: u64 = 0;
@@ -445,7 +493,13 @@ mod tests {
 		balances::TakeFees<Runtime>
 	);
 	type TestXt = sr_primitives::testing::TestXt<Call<Runtime>, SignedExtra>;
-	type Executive = super::Executive<Runtime, Block<TestXt>, system::ChainContext<Runtime>, Runtime, ()>;
+	type Executive = super::Executive<
+		Runtime,
+		Block<TestXt>,
+		system::ChainContext<Runtime>,
+		Runtime,
+		CustomOnRuntimeUpgrade
+	>;
 
 	fn extra(nonce: u64, fee: u64) -> SignedExtra {
 		(
@@ -465,7 +519,6 @@ mod tests {
 		let mut t = system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
 		balances::GenesisConfig::<Runtime> {
 			balances: vec![(1, 211)],
-			vesting: vec![],
 		}.assimilate_storage(&mut t).unwrap();
 		let xt = sr_primitives::testing::TestXt(sign_extra(1, 0, 0), Call::transfer(2, 69));
 		let weight = xt.get_dispatch_info().weight as u64;
@@ -489,7 +542,6 @@ mod tests {
 		let mut t = system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
 		balances::GenesisConfig::<Runtime> {
 			balances: vec![(1, 111 * balance_factor)],
-			vesting: vec![],
 		}.assimilate_storage(&mut t).unwrap();
 		t.into()
 	}
@@ -527,6 +579,59 @@ mod tests {
 		});
 	}
 
+	/// An sr25519 signature of the account whose key is derived from its index.
+	#[derive(Clone, PartialEq, Eq, Debug, Encode, codec::Decode)]
+	struct TestBatchSignature(sr25519::Signature);
+
+	fn test_pair(who: u64) -> sr25519::Pair {
+		sr25519::Pair::from_seed(&[who as u8; 32])
+	}
+
+	impl Verify for TestBatchSignature {
+		type Signer = u64;
+		fn verify<L: Lazy<[u8]>>(&self, msg: L, signer: &u64) -> bool {
+			self.0.verify(msg, &test_pair(*signer).public())
+		}
+		fn batch_verify<L: Lazy<[u8]>>(&self, msg: L, signer: &u64) -> bool {
+			self.0.batch_verify(msg, &test_pair(*signer).public())
+		}
+	}
+
+	type SignedXt = generic::UncheckedExtrinsic<u64, Call<Runtime>, TestBatchSignature, SignedExtra>;
+
+	#[test]
+	#[should_panic(expected = "All signatures of the block's extrinsics should be valid")]
+	fn block_import_with_bad_signature_fails_when_finishing_the_batch() {
+		type Executive = super::Executive<
+			Runtime,
+			Block<SignedXt>,
+			system::ChainContext<Runtime>,
+			Runtime,
+			(),
+		>;
+
+		// The signature is only queued while checking the extrinsic, so the extrinsic is applied
+		// and the block is rejected by `finish_batch_verify`.
+		let xt = SignedXt::new_signed(
+			Call::transfer(2, 69),
+			1,
+			TestBatchSignature(test_pair(1).sign(b"not the signed payload")),
+			extra(0, 0),
+		);
+		with_externalities(&mut new_test_ext(10), || {
+			Executive::execute_block(Block {
+				header: Header {
+					parent_hash: [69u8; 32].into(),
+					number: 1,
+					state_root: [0u8; 32].into(),
+					extrinsics_root: extrinsics_root::<BlakeTwo256, _>(&[xt.clone()]),
+					digest: Digest { logs: vec![], },
+				},
+				extrinsics: vec![xt],
+			});
+		});
+	}
+
 	#[test]
 	#[should_panic]
 	fn block_import_of_bad_extrinsic_root_fails() {
@@ -670,4 +775,55 @@ mod tests {
 		execute_with_lock(WithdrawReasons::all());
 		execute_with_lock(WithdrawReasons::except(WithdrawReason::TransactionPayment));
 	}
+
+	#[test]
+	fn runtime_upgrade_hook_runs_once_per_spec_version() {
+		with_externalities(&mut new_test_ext(1), || {
+			let initialize_block = |n| Executive::initialize_block(&Header::new(
+				n,
+				H256::default(),
+				H256::default(),
+				[69u8; 32].into(),
+				Digest::default(),
+			));
+			let hook_calls = || unhashed::get_or_default::<u32>(ON_RUNTIME_UPGRADE_KEY);
+
+			// Same spec version as the one at genesis: nothing to do.
+			initialize_block(1);
+			assert_eq!(hook_calls(), 0);
+			assert_eq!(<system::Module<Runtime>>::all_extrinsics_weight(), 0);
+
+			set_spec_version(1);
+			initialize_block(1);
+			assert_eq!(hook_calls(), 1);
+			assert_eq!(<system::Module<Runtime>>::last_runtime_upgrade(), 1);
+			assert_eq!(<system::Module<Runtime>>::all_extrinsics_weight(), 100);
+			<system::Module<Runtime>>::finalize();
+
+			// Only the first block with the new spec version runs the hook.
+			initialize_block(2);
+			assert_eq!(hook_calls(), 1);
+			assert_eq!(<system::Module<Runtime>>::all_extrinsics_weight(), 0);
+		});
+	}
+
+	#[test]
+	fn runtime_upgrade_hook_does_not_run_for_the_genesis_runtime() {
+		set_spec_version(5);
+		let mut t = new_test_ext(1);
+		with_externalities(&mut t, || {
+			assert_eq!(<system::Module<Runtime>>::last_runtime_upgrade(), 5);
+
+			Executive::initialize_block(&Header::new(
+				1,
+				H256::default(),
+				H256::default(),
+				[69u8; 32].into(),
+				Digest::default(),
+			));
+			assert_eq!(unhashed::get_or_default::<u32>(ON_RUNTIME_UPGRADE_KEY), 0);
+			assert_eq!(<system::Module<Runtime>>::all_extrinsics_weight(), 0);
+		});
+		set_spec_version(0);
+	}
 }
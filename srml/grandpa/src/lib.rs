@@ -19,8 +19,10 @@
 //! This manages the GRANDPA authority set ready for the native code.
 //! These authorities are only for GRANDPA finality, not for consensus overall.
 //!
-//! In the future, it will also handle misbehavior reports, and on-chain
-//! finality notifications.
+//! Equivocations observed by the GRANDPA voters are reported on-chain by this module's offchain
+//! worker, together with a proof that the offender owned its key, and forwarded as offences.
+//!
+//! In the future, it will also handle on-chain finality notifications.
 //!
 //! For full integration with GRANDPA, the `GrandpaApi` should be implemented.
 //! The necessary items are re-exported via the `fg_primitives` crate.
@@ -32,21 +34,26 @@ pub use substrate_finality_grandpa_primitives as fg_primitives;
 
 use rstd::prelude::*;
 use codec::{self as codec, Encode, Decode, Error};
+use primitives::{crypto::{KeyTypeId, key_types}, offchain::StorageKind};
 use srml_support::{
-	decl_event, decl_storage, decl_module, dispatch::Result,
-	storage::StorageValue, storage::StorageMap,
+	decl_event, decl_storage, decl_module, dispatch::Result, print, ensure, Parameter,
+	storage::StorageValue, storage::StorageMap, traits::KeyOwnerProofSystem,
 };
 use sr_primitives::{
 	generic::{DigestItem, OpaqueDigestItemId}, traits::Zero,
-	Perbill,
+	Perbill, ApplyError,
+	transaction_validity::{TransactionValidity, TransactionLongevity, ValidTransaction},
 };
 use sr_staking_primitives::{
 	SessionIndex,
-	offence::{Offence, Kind},
+	offence::{Offence, Kind, ReportOffence},
+};
+use fg_primitives::{
+	GRANDPA_ENGINE_ID, EQUIVOCATION_REPORTS_KEY, ScheduledChange, ConsensusLog, SetId, RoundNumber,
 };
-use fg_primitives::{GRANDPA_ENGINE_ID, ScheduledChange, ConsensusLog, SetId, RoundNumber};
-pub use fg_primitives::{AuthorityId, AuthorityWeight};
-use system::{ensure_signed, DigestOf};
+pub use fg_primitives::{AuthorityId, AuthorityWeight, EquivocationProof};
+use system::{DigestOf, offchain::SubmitUnsignedTransaction};
+use session::GetSessionNumber;
 
 mod mock;
 mod tests;
@@ -54,8 +61,33 @@ mod tests;
 pub trait Trait: system::Trait {
 	/// The event type of this module.
 	type Event: From<Event> + Into<<Self as system::Trait>::Event>;
+
+	/// A dispatchable call type.
+	type Call: From<Call<Self>>;
+
+	/// A transaction submitter, used to submit the equivocation reports.
+	type SubmitTransaction: SubmitUnsignedTransaction<Self, <Self as Trait>::Call>;
+
+	/// The proof that an authority key was owned by a validator.
+	type KeyOwnerProof: Parameter + GetSessionNumber;
+
+	/// A system for proving ownership of the authority keys, used to identify the offenders.
+	type KeyOwnerSystem: KeyOwnerProofSystem<(KeyTypeId, AuthorityId), Proof = Self::KeyOwnerProof>;
+
+	/// A type that gives us the ability to submit equivocation offence reports.
+	type ReportEquivocation:
+		ReportOffence<
+			Self::AccountId,
+			IdentificationTuple<Self>,
+			GrandpaEquivocationOffence<IdentificationTuple<Self>>,
+		>;
 }
 
+/// The full identification of an offender, as returned by the key owner proof system.
+pub type IdentificationTuple<T> = <<T as Trait>::KeyOwnerSystem as KeyOwnerProofSystem<
+	(KeyTypeId, AuthorityId),
+>>::IdentificationTuple;
+
 /// A stored pending change, old format.
 // TODO: remove shim
 // https://github.com/paritytech/substrate/issues/1614
@@ -170,10 +202,32 @@ decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
 		fn deposit_event() = default;
 
-		/// Report some misbehavior.
-		fn report_misbehavior(origin, _report: Vec<u8>) {
-			ensure_signed(origin)?;
-			// FIXME: https://github.com/paritytech/substrate/issues/1112
+		/// Report an equivocation of a GRANDPA authority, along with the proof that the offender
+		/// owned its key at the time.
+		///
+		/// The report may be submitted unsigned, in which case the offence has no reporter.
+		fn report_equivocation(
+			origin,
+			equivocation_proof: EquivocationProof<T::Hash, T::BlockNumber>,
+			key_owner_proof: T::KeyOwnerProof
+		) {
+			let origin: rstd::result::Result<system::RawOrigin<T::AccountId>, T::Origin> = origin.into();
+			let reporters = match origin {
+				Ok(system::RawOrigin::Signed(who)) => vec![who],
+				Ok(system::RawOrigin::None) => vec![],
+				_ => return Err("bad origin: expected to be a signed or unsigned origin"),
+			};
+
+			let offence = Self::check_equivocation_report(&equivocation_proof, key_owner_proof)?;
+			T::ReportEquivocation::report_offence(reporters, offence);
+		}
+
+		// Runs after every block.
+		fn offchain_worker(_now: T::BlockNumber) {
+			// Only validators run a voter that could have observed equivocations.
+			if runtime_io::is_validator() {
+				Self::submit_equivocation_reports();
+			}
 		}
 
 		fn on_finalize(block_number: T::BlockNumber) {
@@ -328,6 +382,86 @@ impl<T: Trait> Module<T> {
 		<system::Module<T>>::deposit_log(log.into());
 	}
 
+	/// Check an equivocation report and build the offence it proves.
+	fn check_equivocation_report(
+		equivocation_proof: &EquivocationProof<T::Hash, T::BlockNumber>,
+		key_owner_proof: T::KeyOwnerProof,
+	) -> rstd::result::Result<GrandpaEquivocationOffence<IdentificationTuple<T>>, &'static str> {
+		ensure!(equivocation_proof.is_valid(), "Invalid equivocation proof.");
+
+		let session_index = Self::session_for_set(equivocation_proof.set_id)
+			.ok_or("Equivocation reported for an unknown set.")?;
+		ensure!(
+			key_owner_proof.session() == session_index,
+			"Key ownership proof for another session than the equivocation's.",
+		);
+		let offender = T::KeyOwnerSystem::check_proof(
+			(key_types::GRANDPA, equivocation_proof.offender.clone()),
+			key_owner_proof,
+		).ok_or("Invalid key ownership proof.")?;
+
+		Ok(GrandpaEquivocationOffence {
+			time_slot: GrandpaTimeSlot {
+				set_id: equivocation_proof.set_id,
+				round: equivocation_proof.round,
+			},
+			session_index,
+			// the size of past sets isn't kept, the current one is a close enough approximation.
+			validator_set_count: Self::grandpa_authorities().len() as u32,
+			offender,
+		})
+	}
+
+	/// Submit the equivocations queued by the voter in the offchain storage.
+	fn submit_equivocation_reports() {
+		let kind = StorageKind::PERSISTENT;
+		let encoded = match runtime_io::local_storage_get(kind, EQUIVOCATION_REPORTS_KEY) {
+			Some(encoded) => encoded,
+			None => return,
+		};
+		let reports = <Vec<EquivocationProof<T::Hash, T::BlockNumber>>>::decode(&mut &encoded[..])
+			.unwrap_or_default();
+		if reports.is_empty() {
+			return;
+		}
+
+		// take the reports first, so that concurrent workers don't submit them twice.
+		let empty = <Vec<EquivocationProof<T::Hash, T::BlockNumber>>>::new().encode();
+		let taken = runtime_io::local_storage_compare_and_set(
+			kind,
+			EQUIVOCATION_REPORTS_KEY,
+			Some(&encoded[..]),
+			&empty,
+		);
+		if !taken {
+			return;
+		}
+
+		for equivocation_proof in reports {
+			let key_owner_proof = match T::KeyOwnerSystem::prove(
+				(key_types::GRANDPA, equivocation_proof.offender.clone()),
+			) {
+				Some(proof) => proof,
+				None => {
+					print("Skipping GRANDPA equivocation report of an unknown authority.");
+					continue;
+				},
+			};
+
+			// key ownership can only be proven during the session itself, only the root of the
+			// session's keys is kept afterwards.
+			if Self::session_for_set(equivocation_proof.set_id) != Some(key_owner_proof.session()) {
+				print("Skipping GRANDPA equivocation report of a past session.");
+				continue;
+			}
+
+			let call = Call::report_equivocation(equivocation_proof, key_owner_proof);
+			if T::SubmitTransaction::submit_unsigned(call).is_err() {
+				print("Failed to submit GRANDPA equivocation report.");
+			}
+		}
+	}
+
 	fn initialize_authorities(authorities: &[(AuthorityId, AuthorityWeight)]) {
 		if !authorities.is_empty() {
 			assert!(Authorities::get().is_empty(), "Authorities are already initialized!");
@@ -382,6 +516,7 @@ impl<T: Trait> session::OneSessionHandler<T::AccountId> for Module<T>
 	{
 		let authorities = validators.map(|(_, k)| (k, 1)).collect::<Vec<_>>();
 		Self::initialize_authorities(&authorities);
+		SetIdSession::insert(Self::current_set_id(), &<session::Module<T>>::current_index());
 	}
 
 	fn on_new_session<'a, I: 'a>(changed: bool, validators: I, _queued_validators: I)
@@ -424,18 +559,45 @@ impl<T: Trait> finality_tracker::OnFinalizationStalled<T::BlockNumber> for Modul
 	}
 }
 
+impl<T: Trait> srml_support::unsigned::ValidateUnsigned for Module<T> {
+	type Call = Call<T>;
+
+	fn validate_unsigned(call: &Self::Call) -> TransactionValidity {
+		if let Call::report_equivocation(equivocation_proof, key_owner_proof) = call {
+			if Self::check_equivocation_report(equivocation_proof, key_owner_proof.clone()).is_err() {
+				return TransactionValidity::Invalid(ApplyError::BadSignature as i8);
+			}
+
+			return TransactionValidity::Valid(ValidTransaction {
+				priority: 0,
+				requires: vec![],
+				provides: vec![(
+					equivocation_proof.set_id,
+					equivocation_proof.round,
+					&equivocation_proof.offender,
+				).encode()],
+				longevity: TransactionLongevity::max_value(),
+				propagate: true,
+			})
+		}
+
+		TransactionValidity::Invalid(0)
+	}
+}
+
 /// A round number and set id which point on the time of an offence.
 #[derive(Copy, Clone, PartialOrd, Ord, Eq, PartialEq, Encode, Decode)]
-struct GrandpaTimeSlot {
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct GrandpaTimeSlot {
 	// The order of these matters for `derive(Ord)`.
-	set_id: SetId,
-	round: RoundNumber,
+	/// The set in which the offence happened.
+	pub set_id: SetId,
+	/// The round in which the offence happened.
+	pub round: RoundNumber,
 }
 
-// TODO [slashing]: Integrate this.
 /// A grandpa equivocation offence report.
-#[allow(dead_code)]
-struct GrandpaEquivocationOffence<FullIdentification> {
+pub struct GrandpaEquivocationOffence<FullIdentification> {
 	/// Time slot at which this incident happened.
 	time_slot: GrandpaTimeSlot,
 	/// The session index in which the incident happened.
@@ -18,12 +18,17 @@
 
 #![cfg(test)]
 
-use sr_primitives::{Perbill, DigestItem, traits::IdentityLookup, testing::{Header, UintAuthorityId}};
+use std::cell::RefCell;
+use sr_primitives::{
+	Perbill, DigestItem, traits::IdentityLookup, testing::{Header, UintAuthorityId, TestXt},
+};
+use sr_staking_primitives::{SessionIndex, offence::ReportOffence};
+use session::GetSessionNumber;
 use runtime_io;
-use srml_support::{impl_outer_origin, impl_outer_event, parameter_types};
-use primitives::{H256, Blake2Hasher};
+use srml_support::{impl_outer_origin, impl_outer_event, parameter_types, traits::KeyOwnerProofSystem};
+use primitives::{H256, Blake2Hasher, crypto::KeyTypeId};
 use codec::{Encode, Decode};
-use crate::{AuthorityId, GenesisConfig, Trait, Module, ConsensusLog};
+use crate::{AuthorityId, GenesisConfig, Trait, Module, ConsensusLog, Call, GrandpaEquivocationOffence};
 use substrate_finality_grandpa_primitives::GRANDPA_ENGINE_ID;
 
 impl_outer_origin!{
@@ -39,7 +44,56 @@ pub fn grandpa_log(log: ConsensusLog<u64>) -> DigestItem<H256> {
 pub struct Test;
 impl Trait for Test {
 	type Event = TestEvent;
+	type Call = Call<Test>;
+	type SubmitTransaction = SubmitTransaction;
+	type KeyOwnerProof = TestProof;
+	type KeyOwnerSystem = TestKeyOwnerSystem;
+	type ReportEquivocation = OffenceHandler;
+}
+
+pub struct SubmitTransaction;
+impl system::offchain::SubmitUnsignedTransaction<Test, Call<Test>> for SubmitTransaction {
+	type Extrinsic = TestXt<Call<Test>, ()>;
+}
+
+/// A key ownership proof for the given session.
+#[derive(Clone, PartialEq, Eq, Debug, Decode, Encode)]
+pub struct TestProof(pub SessionIndex);
+
+impl GetSessionNumber for TestProof {
+	fn session(&self) -> SessionIndex {
+		self.0
+	}
+}
+
+/// Identifies the offenders by their authority key, whatever the proof.
+///
+/// Keys are proven for session 0.
+pub struct TestKeyOwnerSystem;
+impl KeyOwnerProofSystem<(KeyTypeId, AuthorityId)> for TestKeyOwnerSystem {
+	type Proof = TestProof;
+	type IdentificationTuple = AuthorityId;
 
+	fn prove(_key: (KeyTypeId, AuthorityId)) -> Option<TestProof> {
+		Some(TestProof(0))
+	}
+
+	fn check_proof(key: (KeyTypeId, AuthorityId), _proof: TestProof) -> Option<AuthorityId> {
+		Some(key.1)
+	}
+}
+
+thread_local! {
+	pub static OFFENCES: RefCell<Vec<(Vec<u64>, GrandpaEquivocationOffence<AuthorityId>)>> =
+		RefCell::new(vec![]);
+}
+
+/// Keeps the reported offences in `OFFENCES`.
+pub struct OffenceHandler;
+impl ReportOffence<u64, AuthorityId, GrandpaEquivocationOffence<AuthorityId>> for OffenceHandler {
+	fn report_offence(reporters: Vec<u64>, offence: GrandpaEquivocationOffence<AuthorityId>) {
+		OFFENCES.with(|l| l.borrow_mut().push((reporters, offence)));
+	}
 }
 parameter_types! {
 	pub const BlockHashCount: u64 = 250;
@@ -24,7 +24,9 @@ use runtime_io::with_externalities;
 use crate::mock::*;
 use system::{EventRecord, Phase};
 use codec::{Decode, Encode};
-use fg_primitives::ScheduledChange;
+use fg_primitives::{ScheduledChange, Vote, AuthoritySignature};
+use primitives::{H256, Pair, ed25519};
+use srml_support::{assert_ok, assert_noop, unsigned::ValidateUnsigned};
 use super::*;
 
 #[test]
@@ -310,3 +312,115 @@ fn time_slot_have_sane_ord() {
 	];
 	assert!(FIXTURE.windows(2).all(|f| f[0] < f[1]));
 }
+
+fn prevote(target: u8) -> Vote<H256, u64> {
+	Vote::Prevote { target_hash: H256::repeat_byte(target), target_number: target as u64 }
+}
+
+fn equivocation_proof(
+	pair: &ed25519::Pair,
+	set_id: SetId,
+	round: RoundNumber,
+	first: Vote<H256, u64>,
+	second: Vote<H256, u64>,
+) -> EquivocationProof<H256, u64> {
+	let sign = |vote: &Vote<H256, u64>| {
+		AuthoritySignature::from(pair.sign(&(vote, round, set_id).encode()))
+	};
+	EquivocationProof {
+		set_id,
+		round,
+		offender: pair.public().into(),
+		first: (first.clone(), sign(&first)),
+		second: (second.clone(), sign(&second)),
+	}
+}
+
+#[test]
+fn report_equivocation_reports_offence() {
+	with_externalities(&mut new_test_ext(vec![(1, 1), (2, 1), (3, 1)]), || {
+		SetIdSession::insert(0, &5);
+		let pair = ed25519::Pair::from_seed(&[1; 32]);
+		let proof = equivocation_proof(&pair, 0, 2, prevote(1), prevote(2));
+
+		assert_ok!(Grandpa::report_equivocation(system::RawOrigin::Signed(7).into(), proof, TestProof(5)));
+
+		OFFENCES.with(|offences| {
+			let offences = offences.borrow();
+			assert_eq!(offences.len(), 1);
+			let (reporters, offence) = &offences[0];
+			assert_eq!(reporters, &vec![7]);
+			assert_eq!(offence.offenders(), vec![AuthorityId::from(pair.public())]);
+			assert_eq!(offence.time_slot(), GrandpaTimeSlot { set_id: 0, round: 2 });
+			assert_eq!(offence.session_index(), 5);
+			assert_eq!(offence.validator_set_count(), 3);
+		});
+	});
+}
+
+#[test]
+fn report_equivocation_rejects_invalid_reports() {
+	with_externalities(&mut new_test_ext(vec![(1, 1)]), || {
+		SetIdSession::insert(0, &0);
+		let pair = ed25519::Pair::from_seed(&[1; 32]);
+
+		// the same vote twice isn't an equivocation.
+		let proof = equivocation_proof(&pair, 0, 2, prevote(1), prevote(1));
+		assert_noop!(
+			Grandpa::report_equivocation(system::RawOrigin::None.into(), proof, TestProof(0)),
+			"Invalid equivocation proof."
+		);
+
+		// votes of different stages aren't an equivocation either.
+		let precommit = Vote::Precommit { target_hash: H256::repeat_byte(2), target_number: 2 };
+		let proof = equivocation_proof(&pair, 0, 2, prevote(1), precommit);
+		assert_noop!(
+			Grandpa::report_equivocation(system::RawOrigin::None.into(), proof, TestProof(0)),
+			"Invalid equivocation proof."
+		);
+
+		// the signatures must be for the given round.
+		let mut proof = equivocation_proof(&pair, 0, 2, prevote(1), prevote(2));
+		proof.round = 3;
+		assert_noop!(
+			Grandpa::report_equivocation(system::RawOrigin::None.into(), proof, TestProof(0)),
+			"Invalid equivocation proof."
+		);
+
+		// the set must be known.
+		let proof = equivocation_proof(&pair, 1, 2, prevote(1), prevote(2));
+		assert_noop!(
+			Grandpa::report_equivocation(system::RawOrigin::None.into(), proof, TestProof(0)),
+			"Equivocation reported for an unknown set."
+		);
+
+		// the key ownership must be proven for the session of the set.
+		let proof = equivocation_proof(&pair, 0, 2, prevote(1), prevote(2));
+		assert_noop!(
+			Grandpa::report_equivocation(system::RawOrigin::None.into(), proof, TestProof(1)),
+			"Key ownership proof for another session than the equivocation's."
+		);
+
+		assert!(OFFENCES.with(|offences| offences.borrow().is_empty()));
+	});
+}
+
+#[test]
+fn validate_unsigned_accepts_only_valid_reports() {
+	with_externalities(&mut new_test_ext(vec![(1, 1)]), || {
+		SetIdSession::insert(0, &0);
+		let pair = ed25519::Pair::from_seed(&[1; 32]);
+
+		let valid = equivocation_proof(&pair, 0, 2, prevote(1), prevote(2));
+		assert!(match Grandpa::validate_unsigned(&Call::report_equivocation(valid, TestProof(0))) {
+			TransactionValidity::Valid(_) => true,
+			_ => false,
+		});
+
+		let invalid = equivocation_proof(&pair, 0, 2, prevote(1), prevote(1));
+		assert_eq!(
+			Grandpa::validate_unsigned(&Call::report_equivocation(invalid, TestProof(0))),
+			TransactionValidity::Invalid(ApplyError::BadSignature as i8),
+		);
+	});
+}
@@ -0,0 +1,549 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Proxy Module
+//!
+//! - [`proxy::Trait`](./trait.Trait.html)
+//! - [`Call`](./enum.Call.html)
+//!
+//! ## Overview
+//!
+//! A module allowing accounts to give permission to other accounts to dispatch types of calls from
+//! their signed origin.
+//!
+//! Each delegation is registered together with a *proxy type*, which is a runtime-defined filter
+//! over the calls that the delegate may dispatch on behalf of the delegator (e.g. anything but
+//! balance transfers, or only governance calls). This allows a "hot" key to act for a "cold"
+//! stash within strictly bounded limits.
+//!
+//! Registering proxies requires a deposit, reserved from the delegating account, made of a base
+//! amount plus a factor for each proxy registered. It is returned once the proxies are removed.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `proxy` - Dispatch a call on behalf of an account for which the sender is a proxy.
+//! - `add_proxy` - Register an account as a proxy of the sender for a given proxy type.
+//! - `remove_proxy` - Unregister a proxy of the sender for a given proxy type.
+//! - `remove_proxies` - Unregister all proxies of the sender.
+//!
+//! [`Call`]: ./enum.Call.html
+//! [`Trait`]: ./trait.Trait.html
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use rstd::prelude::*;
+use sr_primitives::traits::{Member, Zero};
+use sr_primitives::weights::{
+	SimpleDispatchInfo, GetDispatchInfo, WeighData, ClassifyDispatch, DispatchClass, Weight,
+};
+use srml_support::{
+	StorageMap, Parameter, Dispatchable, IsSubType, decl_module, decl_event, decl_storage, ensure,
+	dispatch::DispatchError,
+};
+use srml_support::traits::{Currency, ReservableCurrency, Get, InstanceFilter};
+use system::ensure_signed;
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+pub trait Trait: system::Trait {
+	/// The overarching event type.
+	type Event: From<Event> + Into<<Self as system::Trait>::Event>;
+
+	/// The overarching call type.
+	type Call: Parameter + Dispatchable<Origin=Self::Origin> + GetDispatchInfo
+		+ IsSubType<Module<Self>, Self>;
+
+	/// The currency mechanism.
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// A kind of proxy; specified with the proxy and passed in to the `InstanceFilter` which
+	/// decides whether a given call may be dispatched through a proxy of that kind.
+	type ProxyType: Parameter + Member + Ord + PartialOrd + InstanceFilter<<Self as Trait>::Call>
+		+ Default;
+
+	/// The base amount of currency needed to reserve for creating a proxy list.
+	///
+	/// This is held for an additional storage item whose value size is
+	/// `sizeof(Balance)` bytes and whose key size is `sizeof(AccountId)` bytes.
+	type ProxyDepositBase: Get<BalanceOf<Self>>;
+
+	/// The amount of currency needed per proxy added.
+	///
+	/// This is held for adding 32 bytes plus an instance of `ProxyType` more into a pre-existing
+	/// storage value.
+	type ProxyDepositFactor: Get<BalanceOf<Self>>;
+
+	/// The maximum amount of proxies allowed for a single account.
+	type MaxProxies: Get<u16>;
+}
+
+/// The weight of a `proxy` call: a fixed overhead on top of the weight of the proxied call,
+/// whose dispatch class is kept.
+pub struct WeightForProxy(Weight);
+
+impl<AccountId, ProxyType, Call: GetDispatchInfo> WeighData<(&AccountId, &Option<ProxyType>, &Box<Call>)>
+	for WeightForProxy
+{
+	fn weigh_data(&self, (_, _, call): (&AccountId, &Option<ProxyType>, &Box<Call>)) -> Weight {
+		call.get_dispatch_info().weight.saturating_add(self.0)
+	}
+}
+
+impl<AccountId, ProxyType, Call: GetDispatchInfo> ClassifyDispatch<(&AccountId, &Option<ProxyType>, &Box<Call>)>
+	for WeightForProxy
+{
+	fn classify_dispatch(&self, (_, _, call): (&AccountId, &Option<ProxyType>, &Box<Call>)) -> DispatchClass {
+		call.get_dispatch_info().class
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Proxy {
+		/// The set of account proxies. Maps the account which has delegated to the accounts
+		/// which are being delegated to, together with the amount held on deposit.
+		pub Proxies get(proxies):
+			map T::AccountId => (Vec<(T::AccountId, T::ProxyType)>, BalanceOf<T>);
+	}
+}
+
+decl_event!(
+	pub enum Event {
+		/// A proxy was executed correctly, with the given result.
+		ProxyExecuted(bool),
+	}
+);
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		/// The base amount of currency needed to reserve for creating a proxy list.
+		const ProxyDepositBase: BalanceOf<T> = T::ProxyDepositBase::get();
+
+		/// The amount of currency needed per proxy added.
+		const ProxyDepositFactor: BalanceOf<T> = T::ProxyDepositFactor::get();
+
+		/// The maximum amount of proxies allowed for a single account.
+		const MaxProxies: u16 = T::MaxProxies::get();
+
+		fn deposit_event() = default;
+
+		/// Dispatch the given `call` from an account that the sender is authorised for through
+		/// `add_proxy`.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// Parameters:
+		/// - `real`: The account that the proxy will make a call on behalf of.
+		/// - `force_proxy_type`: Specify the exact proxy type to be used and checked for this call.
+		/// - `call`: The call to be made by the `real` account.
+		///
+		/// A proxy may only register further proxies for `real` whose proxy type it is a superset
+		/// of.
+		///
+		/// Emits `ProxyExecuted` with the outcome of the inner call.
+		///
+		/// # <weight>
+		/// - O(P) where P is the number of proxies registered by `real`, bounded by `MaxProxies`.
+		/// - One storage read.
+		/// - The weight of the `call` plus a fixed overhead, in the dispatch class of the `call`.
+		/// # </weight>
+		#[weight = WeightForProxy(1_000_000)]
+		fn proxy(origin,
+			real: T::AccountId,
+			force_proxy_type: Option<T::ProxyType>,
+			call: Box<<T as Trait>::Call>
+		) {
+			let who = ensure_signed(origin)?;
+			let (_, proxy_type) = Self::proxies(&real).0.into_iter()
+				.find(|(delegate, proxy_type)| &who == delegate &&
+					force_proxy_type.as_ref().map_or(true, |forced| forced == proxy_type)
+				)
+				.ok_or("not a proxy")?;
+			ensure!(proxy_type.filter(&call), "call not permitted by proxy type");
+			if let Some(Call::add_proxy(_, added_type)) = call.is_sub_type() {
+				ensure!(proxy_type.is_superset(added_type), "proxy type is not a superset of the added one");
+			}
+
+			let res = match call.dispatch(system::RawOrigin::Signed(real).into()) {
+				Ok(_) => true,
+				Err(e) => {
+					let e: DispatchError = e.into();
+					sr_io::print(e);
+					false
+				}
+			};
+
+			Self::deposit_event(Event::ProxyExecuted(res));
+		}
+
+		/// Register a proxy account for the sender that is able to make calls on its behalf.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// Parameters:
+		/// - `proxy`: The account that the `caller` would like to make a proxy.
+		/// - `proxy_type`: The permissions allowed for this proxy account.
+		///
+		/// # <weight>
+		/// - O(P) where P is the number of proxies the user has, bounded by `MaxProxies`.
+		/// - One storage read and write.
+		/// - One balance-reserve operation.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(500_000)]
+		fn add_proxy(origin, proxy: T::AccountId, proxy_type: T::ProxyType) {
+			let who = ensure_signed(origin)?;
+			ensure!(who != proxy, "cannot be a proxy of oneself");
+
+			let (mut proxies, old_deposit) = Self::proxies(&who);
+			ensure!(proxies.len() < T::MaxProxies::get() as usize, "too many proxies");
+			let typed_proxy = (proxy, proxy_type);
+			let i = proxies.binary_search(&typed_proxy).err().ok_or("proxy already registered")?;
+			proxies.insert(i, typed_proxy);
+
+			let new_deposit = Self::deposit(proxies.len());
+			if new_deposit > old_deposit {
+				T::Currency::reserve(&who, new_deposit - old_deposit)?;
+			} else if new_deposit < old_deposit {
+				T::Currency::unreserve(&who, old_deposit - new_deposit);
+			}
+			<Proxies<T>>::insert(&who, (proxies, new_deposit));
+		}
+
+		/// Unregister a proxy account for the sender.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// Parameters:
+		/// - `proxy`: The account that the `caller` would like to remove as a proxy.
+		/// - `proxy_type`: The permissions currently enabled for the removed proxy account.
+		///
+		/// # <weight>
+		/// - O(P) where P is the number of proxies the user has, bounded by `MaxProxies`.
+		/// - One storage read and write.
+		/// - One balance-unreserve operation.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(500_000)]
+		fn remove_proxy(origin, proxy: T::AccountId, proxy_type: T::ProxyType) {
+			let who = ensure_signed(origin)?;
+
+			let (mut proxies, old_deposit) = Self::proxies(&who);
+			let typed_proxy = (proxy, proxy_type);
+			let i = proxies.binary_search(&typed_proxy).ok().ok_or("proxy not registered")?;
+			proxies.remove(i);
+
+			if proxies.is_empty() {
+				T::Currency::unreserve(&who, old_deposit);
+				<Proxies<T>>::remove(&who);
+			} else {
+				let new_deposit = Self::deposit(proxies.len());
+				if new_deposit < old_deposit {
+					T::Currency::unreserve(&who, old_deposit - new_deposit);
+				}
+				<Proxies<T>>::insert(&who, (proxies, new_deposit));
+			}
+		}
+
+		/// Unregister all proxy accounts for the sender and return the deposit.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - One storage removal.
+		/// - One balance-unreserve operation.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(100_000)]
+		fn remove_proxies(origin) {
+			let who = ensure_signed(origin)?;
+			let (_, old_deposit) = <Proxies<T>>::take(&who);
+			if !old_deposit.is_zero() {
+				T::Currency::unreserve(&who, old_deposit);
+			}
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// The deposit that must be held for an account that has `proxy_count` proxies registered.
+	fn deposit(proxy_count: usize) -> BalanceOf<T> {
+		if proxy_count == 0 {
+			Zero::zero()
+		} else {
+			T::ProxyDepositBase::get() + T::ProxyDepositFactor::get() * (proxy_count as u32).into()
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use codec::{Encode, Decode};
+	use srml_support::{assert_ok, assert_noop, impl_outer_origin, impl_outer_dispatch, parameter_types};
+	use sr_io::with_externalities;
+	use primitives::{H256, Blake2Hasher};
+	use sr_primitives::{Perbill, traits::{BlakeTwo256, IdentityLookup}, testing::Header, weights::DispatchInfo};
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	impl_outer_dispatch! {
+		pub enum Call for Test where origin: Origin {
+			balances::Balances,
+			proxy::Proxy,
+		}
+	}
+
+	// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+	#[derive(Clone, Eq, PartialEq, Debug)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: u32 = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::one();
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Call = Call;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type WeightMultiplierUpdate = ();
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+	}
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 0;
+		pub const TransferFee: u64 = 0;
+		pub const CreationFee: u64 = 0;
+		pub const TransactionBaseFee: u64 = 0;
+		pub const TransactionByteFee: u64 = 0;
+	}
+	impl balances::Trait for Test {
+		type Balance = u64;
+		type OnFreeBalanceZero = ();
+		type OnNewAccount = ();
+		type Event = ();
+		type TransactionPayment = ();
+		type TransferPayment = ();
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type TransferFee = TransferFee;
+		type CreationFee = CreationFee;
+		type TransactionBaseFee = TransactionBaseFee;
+		type TransactionByteFee = TransactionByteFee;
+		type WeightToFee = ();
+	}
+	parameter_types! {
+		pub const ProxyDepositBase: u64 = 1;
+		pub const ProxyDepositFactor: u64 = 1;
+		pub const MaxProxies: u16 = 4;
+	}
+	#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, Debug)]
+	pub enum ProxyType {
+		Any,
+		NoTransfer,
+		JustTransfer,
+	}
+	impl Default for ProxyType {
+		fn default() -> Self { ProxyType::Any }
+	}
+	impl InstanceFilter<Call> for ProxyType {
+		fn filter(&self, c: &Call) -> bool {
+			match self {
+				ProxyType::Any => true,
+				ProxyType::NoTransfer => match c {
+					Call::Balances(_) => false,
+					_ => true,
+				},
+				ProxyType::JustTransfer => match c {
+					Call::Balances(balances::Call::transfer(..)) => true,
+					_ => false,
+				},
+			}
+		}
+		fn is_superset(&self, o: &Self) -> bool {
+			self == &ProxyType::Any || self == o
+		}
+	}
+	impl Trait for Test {
+		type Event = ();
+		type Call = Call;
+		type Currency = Balances;
+		type ProxyType = ProxyType;
+		type ProxyDepositBase = ProxyDepositBase;
+		type ProxyDepositFactor = ProxyDepositFactor;
+		type MaxProxies = MaxProxies;
+	}
+	type Balances = balances::Module<Test>;
+	type Proxy = Module<Test>;
+
+	fn new_test_ext() -> sr_io::TestExternalities<Blake2Hasher> {
+		let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		balances::GenesisConfig::<Test> {
+			balances: vec![(1, 10), (2, 10), (3, 10), (4, 10), (5, 2)],
+		}.assimilate_storage(&mut t).unwrap();
+		t.into()
+	}
+
+	#[test]
+	fn add_remove_proxies_works() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::Any));
+			assert_noop!(
+				Proxy::add_proxy(Origin::signed(1), 2, ProxyType::Any),
+				"proxy already registered"
+			);
+			assert_eq!(Balances::reserved_balance(&1), 2);
+			assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::JustTransfer));
+			assert_eq!(Balances::reserved_balance(&1), 3);
+			assert_ok!(Proxy::add_proxy(Origin::signed(1), 3, ProxyType::Any));
+			assert_eq!(Balances::reserved_balance(&1), 4);
+			assert_ok!(Proxy::add_proxy(Origin::signed(1), 4, ProxyType::JustTransfer));
+			assert_eq!(Balances::reserved_balance(&1), 5);
+			assert_noop!(
+				Proxy::add_proxy(Origin::signed(1), 4, ProxyType::NoTransfer),
+				"too many proxies"
+			);
+			assert_noop!(
+				Proxy::remove_proxy(Origin::signed(1), 3, ProxyType::JustTransfer),
+				"proxy not registered"
+			);
+			assert_ok!(Proxy::remove_proxy(Origin::signed(1), 4, ProxyType::JustTransfer));
+			assert_eq!(Balances::reserved_balance(&1), 4);
+			assert_ok!(Proxy::remove_proxy(Origin::signed(1), 3, ProxyType::Any));
+			assert_eq!(Balances::reserved_balance(&1), 3);
+			assert_ok!(Proxy::remove_proxy(Origin::signed(1), 2, ProxyType::Any));
+			assert_eq!(Balances::reserved_balance(&1), 2);
+			assert_ok!(Proxy::remove_proxy(Origin::signed(1), 2, ProxyType::JustTransfer));
+			assert_eq!(Balances::reserved_balance(&1), 0);
+			assert_eq!(Proxy::proxies(&1), (vec![], 0));
+		});
+	}
+
+	#[test]
+	fn cannot_add_self_or_without_deposit() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_noop!(
+				Proxy::add_proxy(Origin::signed(1), 1, ProxyType::Any),
+				"cannot be a proxy of oneself"
+			);
+			assert_ok!(Proxy::add_proxy(Origin::signed(5), 1, ProxyType::Any));
+			assert_noop!(
+				Proxy::add_proxy(Origin::signed(5), 2, ProxyType::Any),
+				"not enough free funds"
+			);
+		});
+	}
+
+	#[test]
+	fn remove_proxies_returns_deposit() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::Any));
+			assert_ok!(Proxy::add_proxy(Origin::signed(1), 3, ProxyType::JustTransfer));
+			assert_eq!(Balances::reserved_balance(&1), 3);
+			assert_ok!(Proxy::remove_proxies(Origin::signed(1)));
+			assert_eq!(Balances::reserved_balance(&1), 0);
+			assert_eq!(Balances::free_balance(&1), 10);
+			assert_noop!(
+				Proxy::proxy(Origin::signed(2), 1, None, Box::new(Call::Balances(balances::Call::transfer(6, 1)))),
+				"not a proxy"
+			);
+		});
+	}
+
+	#[test]
+	fn filtering_works() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::Any));
+			assert_ok!(Proxy::add_proxy(Origin::signed(1), 3, ProxyType::JustTransfer));
+			assert_ok!(Proxy::add_proxy(Origin::signed(1), 4, ProxyType::NoTransfer));
+
+			let call = Box::new(Call::Balances(balances::Call::transfer(6, 1)));
+			assert_ok!(Proxy::proxy(Origin::signed(2), 1, None, call.clone()));
+			assert_eq!(Balances::free_balance(&6), 1);
+			assert_ok!(Proxy::proxy(Origin::signed(3), 1, None, call.clone()));
+			assert_eq!(Balances::free_balance(&6), 2);
+			assert_noop!(
+				Proxy::proxy(Origin::signed(4), 1, None, call.clone()),
+				"call not permitted by proxy type"
+			);
+
+			// A transfer-only proxy cannot register more proxies for the real account.
+			let call = Box::new(Call::Proxy(super::Call::add_proxy(5, ProxyType::Any)));
+			assert_noop!(
+				Proxy::proxy(Origin::signed(3), 1, None, call.clone()),
+				"call not permitted by proxy type"
+			);
+			// Neither can a proxy register one with more permissions than it has itself.
+			assert_noop!(
+				Proxy::proxy(Origin::signed(4), 1, None, call.clone()),
+				"proxy type is not a superset of the added one"
+			);
+			assert_ok!(Proxy::proxy(Origin::signed(2), 1, None, call));
+			assert_eq!(Proxy::proxies(&1).0.len(), 4);
+
+			assert_ok!(Proxy::remove_proxy(Origin::signed(1), 5, ProxyType::Any));
+			let call = Box::new(Call::Proxy(super::Call::add_proxy(5, ProxyType::NoTransfer)));
+			assert_ok!(Proxy::proxy(Origin::signed(4), 1, None, call));
+			assert_eq!(Proxy::proxies(&1).0.len(), 4);
+		});
+	}
+
+	#[test]
+	fn proxy_weight_includes_the_call() {
+		let transfer = Call::Balances(balances::Call::transfer(6, 1));
+		let proxy = Call::Proxy(super::Call::proxy(1, None, Box::new(transfer.clone())));
+		assert_eq!(proxy.get_dispatch_info(), DispatchInfo {
+			weight: transfer.get_dispatch_info().weight + 1_000_000,
+			class: DispatchClass::Normal,
+		});
+
+		let set_balance = Call::Balances(balances::Call::set_balance(6, 1, 0));
+		let proxy = Call::Proxy(super::Call::proxy(1, None, Box::new(set_balance.clone())));
+		assert_eq!(proxy.get_dispatch_info(), DispatchInfo {
+			weight: set_balance.get_dispatch_info().weight + 1_000_000,
+			class: DispatchClass::Operational,
+		});
+	}
+
+	#[test]
+	fn forced_proxy_type_is_respected() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::JustTransfer));
+			assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::Any));
+
+			let call = Box::new(Call::Balances(balances::Call::transfer(6, 1)));
+			assert_noop!(
+				Proxy::proxy(Origin::signed(2), 1, Some(ProxyType::NoTransfer), call.clone()),
+				"not a proxy"
+			);
+			assert_ok!(Proxy::proxy(Origin::signed(2), 1, Some(ProxyType::JustTransfer), call));
+			assert_eq!(Balances::free_balance(&6), 1);
+		});
+	}
+}
@@ -0,0 +1,276 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Randomness Collective Flip Module
+//!
+//! The Randomness Collective Flip module provides a [`Randomness`](../srml_support/traits/trait.Randomness.html)
+//! source that generates low-influence random values based on the block hashes from the previous
+//! `81` blocks. Low-influence randomness can be useful when defending against relatively weak
+//! adversaries. Chains with a VRF-based block production (e.g. BABE) should prefer the randomness
+//! provided by their consensus module; this module is meant for chains that have none, like Aura
+//! chains, and for testing.
+//!
+//! ## Usage
+//!
+//! ### Prerequisites
+//!
+//! Import the Randomness Collective Flip module and derive your module's configuration trait from
+//! the system trait. The module has no configuration of its own, but it must be included in the
+//! runtime, so that its `on_initialize` hook collects the block hashes.
+//!
+//! ### Example - Get random seed for the current block
+//!
+//! ```
+//! use srml_support::{decl_module, dispatch::Result, traits::Randomness};
+//!
+//! pub trait Trait: system::Trait {}
+//!
+//! decl_module! {
+//! 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+//! 		pub fn random_module_example(origin) -> Result {
+//! 			let _random_seed = <srml_randomness_collective_flip::Module<T>>::random_seed();
+//! 			Ok(())
+//! 		}
+//! 	}
+//! }
+//! # fn main() { }
+//! ```
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use rstd::prelude::*;
+use codec::Encode;
+use safe_mix::TripletMix;
+use sr_primitives::traits::Hash;
+use srml_support::{
+	decl_module, decl_storage, StorageValue, Twox128, dispatch::Weight,
+	storage::{unhashed, hashed::StorageHasher}, traits::Randomness,
+};
+use system::Trait;
+
+/// The number of block hashes the random material is drawn from.
+const RANDOM_MATERIAL_LEN: usize = 81;
+
+/// Unhashed storage key of the random material, as it was stored by the System module.
+const SYSTEM_RANDOM_MATERIAL_KEY: &[u8] = b"System RandomMaterial";
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		/// Take over the random material that the System module collected before this module
+		/// existed, so that the randomness keeps drawing upon the previous block hashes and the
+		/// stale System entry is removed from the state.
+		fn on_runtime_upgrade() -> Weight {
+			let key = Twox128::hash(SYSTEM_RANDOM_MATERIAL_KEY);
+			match unhashed::take::<(i8, Vec<T::Hash>)>(&key) {
+				Some(random_material) => {
+					if !<RandomMaterial<T>>::exists() {
+						<RandomMaterial<T>>::put(random_material);
+					}
+					10_000
+				},
+				None => 0,
+			}
+		}
+
+		fn on_initialize() {
+			let parent_hash = <system::Module<T>>::parent_hash();
+
+			<RandomMaterial<T>>::mutate(|&mut (ref mut index, ref mut values)| if values.len() < RANDOM_MATERIAL_LEN {
+				values.push(parent_hash)
+			} else {
+				values[*index as usize] = parent_hash;
+				*index = (*index + 1) % RANDOM_MATERIAL_LEN as i8;
+			});
+		}
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as RandomnessCollectiveFlip {
+		/// Series of block headers from the last 81 blocks that acts as random seed material. This is arranged as a
+		/// ring buffer with the `i8` prefix being the index into the `Vec` of the oldest hash.
+		RandomMaterial get(random_material): (i8, Vec<T::Hash>);
+	}
+}
+
+impl<T: Trait> Randomness<T::Hash> for Module<T> {
+	/// Get a low-influence "random" value.
+	///
+	/// This is implemented through a low-influence "triplet mix" convolution of previous block
+	/// hash values.
+	///
+	/// ### Security Notes
+	///
+	/// This randomness uses a low-influence function, drawing upon the block hashes from the
+	/// previous 81 blocks. Its result for any given subject will be known in advance by the block
+	/// producer of this block (and, indeed, anyone who knows the block's `parent_hash`). However,
+	/// it is mostly impossible for the producer of this block *alone* to influence the value of
+	/// this hash. A sizable minority of dishonest and coordinating block producers would be
+	/// required in order to affect this value. If that is an insufficient security guarantee then
+	/// two things can be used to improve this randomness:
+	///
+	/// - Name, in advance, the block number whose random value will be used; ensure your module
+	///   retains a buffer of previous random values for its subject and then index into these in
+	///   order to obviate the ability of your user to look up the parent hash and choose when to
+	///   transact based upon it.
+	/// - Require your user to first commit to an additional value by first posting its hash.
+	///   Require them to reveal the value to determine the final result, hashing it with the
+	///   output of this random function. This reduces the ability of a cabal of block producers
+	///   from conspiring against individuals.
+	///
+	/// WARNING: Hashing the result of this function will remove any low-influence properties it
+	/// has and mean that all bits of the resulting value are entirely manipulatable by the author
+	/// of the parent block, who can determine the value of `parent_hash`.
+	fn random(subject: &[u8]) -> T::Hash {
+		let (index, hash_series) = <RandomMaterial<T>>::get();
+		if hash_series.len() > 0 {
+			// Always the case after block 1 is initialised.
+			hash_series.iter()
+				.cycle()
+				.skip(index as usize)
+				.take(RANDOM_MATERIAL_LEN)
+				.enumerate()
+				.map(|(i, h)| (i as i8, subject, h).using_encoded(T::Hashing::hash))
+				.triplet_mix()
+		} else {
+			T::Hash::default()
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use primitives::{H256, Blake2Hasher};
+	use sr_io::with_externalities;
+	use sr_primitives::{
+		Perbill, testing::Header,
+		traits::{BlakeTwo256, Header as _, IdentityLookup, OnInitialize, OnRuntimeUpgrade},
+	};
+	use srml_support::{impl_outer_origin, parameter_types};
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	#[derive(Clone, PartialEq, Eq, Debug)]
+	pub struct Test;
+
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: u32 = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::one();
+	}
+
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Call = ();
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type WeightMultiplierUpdate = ();
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+	}
+
+	type System = system::Module<Test>;
+	type CollectiveFlip = Module<Test>;
+
+	fn new_test_ext() -> sr_io::TestExternalities<Blake2Hasher> {
+		system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	}
+
+	fn setup_blocks(blocks: u64) {
+		for _ in 0..blocks {
+			let number = System::block_number();
+			let parent_hash = System::parent_hash();
+			System::initialize(&number, &parent_hash, &Default::default(), &Default::default());
+			<CollectiveFlip as OnInitialize<u64>>::on_initialize(number);
+
+			let header = System::finalize();
+			System::set_parent_hash(header.hash());
+			System::set_block_number(*header.number() + 1);
+		}
+	}
+
+	#[test]
+	fn random_material_is_a_ring_buffer() {
+		with_externalities(&mut new_test_ext(), || {
+			setup_blocks(38);
+
+			let (index, random_material) = CollectiveFlip::random_material();
+			assert_eq!(index, 0);
+			assert_eq!(random_material.len(), 38);
+
+			let oldest = random_material[0];
+			setup_blocks(81 + 10 - 38);
+
+			// the 10 oldest hashes have been replaced
+			let (index, random_material) = CollectiveFlip::random_material();
+			assert_eq!(index, 10);
+			assert_eq!(random_material.len(), 81);
+			assert_ne!(random_material[0], oldest);
+		});
+	}
+
+	#[test]
+	fn random_is_default_before_first_block() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_eq!(CollectiveFlip::random_seed(), H256::default());
+		});
+	}
+
+	#[test]
+	fn random_depends_on_subject_and_block() {
+		with_externalities(&mut new_test_ext(), || {
+			setup_blocks(162);
+
+			let random = CollectiveFlip::random(&b"subject"[..]);
+			assert_ne!(random, H256::default());
+			assert_ne!(random, CollectiveFlip::random(&b"other subject"[..]));
+			assert_eq!(CollectiveFlip::random_seed(), CollectiveFlip::random(&[][..]));
+
+			setup_blocks(1);
+			assert_ne!(random, CollectiveFlip::random(&b"subject"[..]));
+		});
+	}
+
+	#[test]
+	fn runtime_upgrade_moves_system_random_material() {
+		with_externalities(&mut new_test_ext(), || {
+			let key = Twox128::hash(SYSTEM_RANDOM_MATERIAL_KEY);
+			let random_material = (3i8, vec![H256::repeat_byte(1), H256::repeat_byte(2)]);
+			unhashed::put(&key, &random_material);
+
+			assert_eq!(<CollectiveFlip as OnRuntimeUpgrade>::on_runtime_upgrade(), 10_000);
+			assert_eq!(CollectiveFlip::random_material(), random_material);
+			assert!(!unhashed::exists(&key));
+
+			// nothing is left to migrate.
+			assert_eq!(<CollectiveFlip as OnRuntimeUpgrade>::on_runtime_upgrade(), 0);
+			assert_eq!(CollectiveFlip::random_material(), random_material);
+		});
+	}
+}
@@ -0,0 +1,924 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Recovery Module
+//!
+//! - [`recovery::Trait`](./trait.Trait.html)
+//! - [`Call`](./enum.Call.html)
+//!
+//! ## Overview
+//!
+//! The Recovery module is an M-of-N social recovery tool for users to gain access to their
+//! accounts if the private key or other authentication mechanism is lost.
+//!
+//! An account makes itself recoverable by choosing a set of *friends*, a *threshold* of how many
+//! of them must vouch for a recovery, and a *delay period* that must pass between the start of a
+//! recovery attempt and its conclusion. Creating this configuration requires a deposit, which is
+//! returned when the configuration is removed.
+//!
+//! Any account may then *initiate* the recovery of a recoverable account, placing a deposit.
+//! Friends of the lost account vouch for the recovery attempt of a particular rescuer, and once
+//! the threshold is met and the delay period has passed, the rescuer may *claim* the recovery.
+//! From then on, the rescuer may dispatch calls as if they were signed by the lost account, through
+//! `as_recovered`.
+//!
+//! The delay period gives the owner of the account, should they still have access to it, the
+//! chance to *close* a malicious recovery attempt, in which case the deposit of the rescuer is
+//! slashed and given to the lost account.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! #### For General Users
+//!
+//! - `create_recovery` - Create a recovery configuration for your account and make it recoverable.
+//! - `initiate_recovery` - Start the recovery process for a recoverable account.
+//!
+//! #### For Friends of a Recoverable Account
+//!
+//! - `vouch_recovery` - As a friend of a recoverable account, vouch for a recovery attempt on
+//!   the account.
+//!
+//! #### For a User Who Successfully Recovered an Account
+//!
+//! - `claim_recovery` - Claim access to the account that you have successfully completed the
+//!   recovery process for.
+//! - `as_recovered` - Send a transaction as an account that you have recovered.
+//! - `cancel_recovered` - Stop being able to act on behalf of an account you have recovered.
+//!
+//! #### For the Recoverable Account
+//!
+//! - `close_recovery` - Close an active recovery process for your account and reclaim the
+//!   recovery deposit.
+//! - `remove_recovery` - Remove the recovery configuration from the account, making it
+//!   un-recoverable.
+//!
+//! #### For Super Users
+//!
+//! - `set_recovered` - The ROOT origin is able to skip the recovery process and directly allow
+//!   one account to access another.
+//!
+//! [`Call`]: ./enum.Call.html
+//! [`Trait`]: ./trait.Trait.html
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use rstd::prelude::*;
+use codec::{Encode, Decode};
+use sr_primitives::traits::{CheckedAdd, CheckedMul, Saturating};
+use sr_primitives::weights::SimpleDispatchInfo;
+use srml_support::{
+	StorageMap, StorageDoubleMap, IterableStorageDoubleMap, Parameter, Dispatchable, decl_module,
+	decl_event, decl_storage, ensure, dispatch::DispatchError,
+};
+use srml_support::traits::{Currency, ReservableCurrency, Get};
+use system::{ensure_signed, ensure_root};
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+pub trait Trait: system::Trait {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The overarching call type.
+	type Call: Parameter + Dispatchable<Origin=Self::Origin>;
+
+	/// The currency mechanism.
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// The base amount of currency needed to reserve for creating a recovery configuration.
+	///
+	/// This is held for an additional storage item whose value size is
+	/// `2 + sizeof(BlockNumber, Balance)` bytes.
+	type ConfigDepositBase: Get<BalanceOf<Self>>;
+
+	/// The amount of currency needed per additional user when creating a recovery configuration.
+	///
+	/// This is held for adding `sizeof(AccountId)` bytes more into a pre-existing storage value.
+	type FriendDepositFactor: Get<BalanceOf<Self>>;
+
+	/// The maximum amount of friends allowed in a recovery configuration.
+	type MaxFriends: Get<u16>;
+
+	/// The base amount of currency needed to reserve for starting a recovery.
+	///
+	/// This is primarily held for deterring malicious recovery attempts, and should
+	/// have a value large enough that a bad actor would choose not to place this
+	/// deposit. It also acts to fund additional storage item whose value size is
+	/// `sizeof(BlockNumber, Balance + T * AccountId)` bytes. Where T is a configurable
+	/// threshold.
+	type RecoveryDeposit: Get<BalanceOf<Self>>;
+}
+
+/// An active recovery process.
+#[derive(Clone, Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct ActiveRecovery<BlockNumber, Balance, AccountId> {
+	/// The block number when the recovery process started.
+	created: BlockNumber,
+	/// The amount held in reserve of the `depositor`,
+	/// To be returned once this recovery process is closed.
+	deposit: Balance,
+	/// The friends which have vouched so far. Always sorted.
+	friends: Vec<AccountId>,
+}
+
+/// Configuration for recovering an account.
+#[derive(Clone, Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct RecoveryConfig<BlockNumber, Balance, AccountId> {
+	/// The minimum number of blocks since the start of the recovery process before the account
+	/// can be successfully recovered.
+	delay_period: BlockNumber,
+	/// The amount held in reserve of the `depositor`,
+	/// to be returned once this configuration is removed.
+	deposit: Balance,
+	/// The list of friends which can help recover an account. Always sorted.
+	friends: Vec<AccountId>,
+	/// The number of approving friends needed to recover an account.
+	threshold: u16,
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Recovery {
+		/// The set of recoverable accounts and their recovery configuration.
+		pub Recoverable get(recovery_config):
+			map T::AccountId => Option<RecoveryConfig<T::BlockNumber, BalanceOf<T>, T::AccountId>>;
+
+		/// Active recovery attempts.
+		///
+		/// First account is the account to be recovered, and the second account
+		/// is the user trying to recover the account.
+		pub ActiveRecoveries get(active_recovery):
+			double_map T::AccountId, blake2_128_concat(T::AccountId) =>
+			Option<ActiveRecovery<T::BlockNumber, BalanceOf<T>, T::AccountId>>;
+
+		/// The list of allowed proxy accounts.
+		///
+		/// Map from the user who can access it to the recovered account.
+		pub Proxy get(proxy): map T::AccountId => Option<T::AccountId>;
+	}
+}
+
+decl_event! {
+	pub enum Event<T> where AccountId = <T as system::Trait>::AccountId {
+		/// A recovery process has been set up for an account.
+		RecoveryCreated(AccountId),
+		/// A recovery process has been initiated for lost account by rescuer account.
+		RecoveryInitiated(AccountId, AccountId),
+		/// A recovery process for lost account by rescuer account has been vouched for by sender.
+		RecoveryVouched(AccountId, AccountId, AccountId),
+		/// A recovery process for lost account by rescuer account has been closed.
+		RecoveryClosed(AccountId, AccountId),
+		/// Lost account has been successfully recovered by rescuer account.
+		AccountRecovered(AccountId, AccountId),
+		/// A recovery process has been removed for an account.
+		RecoveryRemoved(AccountId),
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		/// The base amount of currency needed to reserve for creating a recovery configuration.
+		const ConfigDepositBase: BalanceOf<T> = T::ConfigDepositBase::get();
+
+		/// The amount of currency needed per additional user when creating a recovery
+		/// configuration.
+		const FriendDepositFactor: BalanceOf<T> = T::FriendDepositFactor::get();
+
+		/// The maximum amount of friends allowed in a recovery configuration.
+		const MaxFriends: u16 = T::MaxFriends::get();
+
+		/// The base amount of currency needed to reserve for starting a recovery.
+		const RecoveryDeposit: BalanceOf<T> = T::RecoveryDeposit::get();
+
+		fn deposit_event() = default;
+
+		/// Send a call through a recovered account.
+		///
+		/// The dispatch origin for this call must be _Signed_ and registered to
+		/// be able to make calls on behalf of the recovered account.
+		///
+		/// Parameters:
+		/// - `account`: The recovered account you want to make a call on-behalf-of.
+		/// - `call`: The call you want to make with the recovered account.
+		///
+		/// # <weight>
+		/// - The weight of the `call`.
+		/// - One storage lookup to check account is recovered by `who`. O(1)
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+		fn as_recovered(origin, account: T::AccountId, call: Box<<T as Trait>::Call>) {
+			let who = ensure_signed(origin)?;
+			// Check `who` is allowed to make a call on behalf of `account`
+			ensure!(Self::proxy(&who) == Some(account.clone()), "not allowed to act for account");
+			call.dispatch(system::RawOrigin::Signed(account).into())
+				.map_err(|e| -> DispatchError { e.into() })?;
+		}
+
+		/// Allow ROOT to bypass the recovery process and set a rescuer account
+		/// for a lost account directly.
+		///
+		/// The dispatch origin for this call must be _ROOT_.
+		///
+		/// Parameters:
+		/// - `lost`: The "lost account" to be recovered.
+		/// - `rescuer`: The "rescuer account" which can call as the lost account.
+		///
+		/// # <weight>
+		/// - One storage write O(1)
+		/// - One event
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedOperational(10_000)]
+		fn set_recovered(origin, lost: T::AccountId, rescuer: T::AccountId) {
+			ensure_root(origin)?;
+			// Create the recovery storage item.
+			<Proxy<T>>::insert(&rescuer, &lost);
+			Self::deposit_event(RawEvent::AccountRecovered(lost, rescuer));
+		}
+
+		/// Create a recovery configuration for your account. This makes your account recoverable.
+		///
+		/// Payment: `ConfigDepositBase` + `FriendDepositFactor` * #_of_friends balance
+		/// will be reserved for storing the recovery configuration. This deposit is returned
+		/// in full when the user calls `remove_recovery`.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// Parameters:
+		/// - `friends`: A list of friends you trust to vouch for recovery attempts.
+		///   Should be ordered and contain no duplicate values.
+		/// - `threshold`: The number of friends that must vouch for a recovery attempt
+		///   before the account can be recovered. Should be less than or equal to
+		///   the length of the list of friends.
+		/// - `delay_period`: The number of blocks after a recovery attempt is initialized
+		///   that needs to pass before the account can be recovered.
+		///
+		/// # <weight>
+		/// - Key: F (len of friends)
+		/// - One storage read to check that account is not already recoverable. O(1).
+		/// - A check that the friends list is sorted and unique. O(F)
+		/// - One currency reserve operation. O(X)
+		/// - One storage write. O(1). Codec O(F).
+		/// - One event.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(100_000)]
+		fn create_recovery(origin,
+			friends: Vec<T::AccountId>,
+			threshold: u16,
+			delay_period: T::BlockNumber
+		) {
+			let who = ensure_signed(origin)?;
+			// Check account is not already set up for recovery
+			ensure!(!<Recoverable<T>>::exists(&who), "account already recoverable");
+			// Check user input is valid
+			ensure!(threshold >= 1, "threshold must be greater than zero");
+			ensure!(!friends.is_empty(), "at least one friend required");
+			let max_friends = T::MaxFriends::get() as usize;
+			ensure!(friends.len() <= max_friends, "too many friends");
+			ensure!(threshold as usize <= friends.len(), "threshold greater than number of friends");
+			ensure!(Self::is_sorted_and_unique(&friends), "friends list must be sorted and unique");
+			// Total deposit is base fee + number of friends * factor fee
+			let friend_deposit = T::FriendDepositFactor::get()
+				.checked_mul(&<BalanceOf<T>>::from(friends.len() as u32))
+				.ok_or("overflow in deposit calculation")?;
+			let total_deposit = T::ConfigDepositBase::get()
+				.checked_add(&friend_deposit)
+				.ok_or("overflow in deposit calculation")?;
+			// Reserve the deposit
+			T::Currency::reserve(&who, total_deposit)?;
+			// Create the recovery configuration
+			let recovery_config = RecoveryConfig {
+				delay_period,
+				deposit: total_deposit,
+				friends,
+				threshold,
+			};
+			// Create the recovery configuration storage item
+			<Recoverable<T>>::insert(&who, recovery_config);
+
+			Self::deposit_event(RawEvent::RecoveryCreated(who));
+		}
+
+		/// Initiate the process for recovering a recoverable account.
+		///
+		/// Payment: `RecoveryDeposit` balance will be reserved for initiating the
+		/// recovery process. This deposit will always be repatriated to the account
+		/// trying to be recovered. See `close_recovery`.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// Parameters:
+		/// - `account`: The lost account that you want to recover. This account
+		///   needs to be recoverable (i.e. have a recovery configuration).
+		///
+		/// # <weight>
+		/// - One storage read to check that account is recoverable. O(F)
+		/// - One storage read to check that this recovery process hasn't already started. O(1)
+		/// - One currency reserve operation. O(X)
+		/// - One storage write. O(1).
+		/// - One event.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(100_000)]
+		fn initiate_recovery(origin, account: T::AccountId) {
+			let who = ensure_signed(origin)?;
+			// Check that the account is recoverable
+			ensure!(<Recoverable<T>>::exists(&account), "account not recoverable");
+			// Check that the recovery process has not already been started
+			ensure!(!<ActiveRecoveries<T>>::exists(&account, &who), "recovery already started");
+			// Take recovery deposit
+			let recovery_deposit = T::RecoveryDeposit::get();
+			T::Currency::reserve(&who, recovery_deposit)?;
+			// Create an active recovery status
+			let recovery_status = ActiveRecovery {
+				created: <system::Module<T>>::block_number(),
+				deposit: recovery_deposit,
+				friends: vec![],
+			};
+			// Create the active recovery storage item
+			<ActiveRecoveries<T>>::insert(&account, &who, &recovery_status);
+			Self::deposit_event(RawEvent::RecoveryInitiated(account, who));
+		}
+
+		/// Allow a "friend" of a recoverable account to vouch for an active recovery
+		/// process for that account.
+		///
+		/// The dispatch origin for this call must be _Signed_ and must be a "friend"
+		/// for the recoverable account.
+		///
+		/// Parameters:
+		/// - `lost`: The lost account that you want to recover.
+		/// - `rescuer`: The account trying to rescue the lost account that you
+		///   want to vouch for.
+		///
+		/// The combination of these two parameters must point to an active recovery
+		/// process.
+		///
+		/// # <weight>
+		/// Key: F (len of friends in config), V (len of vouching friends)
+		/// - One storage read to get the recovery configuration. O(1), Codec O(F)
+		/// - One storage read to get the active recovery process. O(1), Codec O(V)
+		/// - One binary search to confirm caller is a friend. O(logF)
+		/// - One binary search to confirm caller has not already vouched. O(logV)
+		/// - One storage write. O(1), Codec O(V).
+		/// - One event.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(100_000)]
+		fn vouch_recovery(origin, lost: T::AccountId, rescuer: T::AccountId) {
+			let who = ensure_signed(origin)?;
+			// Get the recovery configuration for the lost account.
+			let recovery_config = Self::recovery_config(&lost).ok_or("account not recoverable")?;
+			// Get the active recovery process for the rescuer.
+			let mut active_recovery = Self::active_recovery(&lost, &rescuer)
+				.ok_or("recovery not started")?;
+			// Make sure the voter is a friend
+			ensure!(Self::is_friend(&recovery_config.friends, &who), "not a friend");
+			// Either insert the vouch, or return an error that the user already vouched.
+			match active_recovery.friends.binary_search(&who) {
+				Ok(_) => Err("already vouched")?,
+				Err(pos) => active_recovery.friends.insert(pos, who.clone()),
+			}
+			// Update storage with the latest details
+			<ActiveRecoveries<T>>::insert(&lost, &rescuer, &active_recovery);
+			Self::deposit_event(RawEvent::RecoveryVouched(lost, rescuer, who));
+		}
+
+		/// Allow a successful rescuer to claim their recovered account.
+		///
+		/// The dispatch origin for this call must be _Signed_ and must be a "rescuer"
+		/// who has successfully completed the account recovery process: collected
+		/// `threshold` or more vouches, waited `delay_period` blocks since initiation.
+		///
+		/// Parameters:
+		/// - `account`: The lost account that you want to claim has been successfully
+		///   recovered by you.
+		///
+		/// # <weight>
+		/// Key: F (len of friends in config), V (len of vouching friends)
+		/// - One storage read to get the recovery configuration. O(1), Codec O(F)
+		/// - One storage read to get the active recovery process. O(1), Codec O(V)
+		/// - One storage read to get the current block number. O(1)
+		/// - One storage write. O(1), Codec O(V).
+		/// - One event.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(100_000)]
+		fn claim_recovery(origin, account: T::AccountId) {
+			let who = ensure_signed(origin)?;
+			// Get the recovery configuration for the lost account
+			let recovery_config = Self::recovery_config(&account).ok_or("account not recoverable")?;
+			// Get the active recovery process for the rescuer
+			let active_recovery = Self::active_recovery(&account, &who)
+				.ok_or("recovery not started")?;
+			ensure!(!<Proxy<T>>::exists(&who), "already a proxy for another account");
+			// Make sure the delay period has passed
+			let current_block_number = <system::Module<T>>::block_number();
+			let recoverable_block_number = active_recovery.created
+				.saturating_add(recovery_config.delay_period);
+			ensure!(recoverable_block_number <= current_block_number, "recovery still delayed");
+			// Make sure the threshold is met
+			ensure!(
+				recovery_config.threshold as usize <= active_recovery.friends.len(),
+				"threshold not met"
+			);
+			// Create the recovery storage item
+			<Proxy<T>>::insert(&who, &account);
+			Self::deposit_event(RawEvent::AccountRecovered(account, who));
+		}
+
+		/// As the controller of a recoverable account, close an active recovery
+		/// process for your account.
+		///
+		/// Payment: By calling this function, the recoverable account will receive
+		/// the recovery deposit `RecoveryDeposit` placed by the rescuer.
+		///
+		/// The dispatch origin for this call must be _Signed_ and must be a
+		/// recoverable account with an active recovery process for it.
+		///
+		/// Parameters:
+		/// - `rescuer`: The account trying to rescue this recoverable account.
+		///
+		/// # <weight>
+		/// Key: V (len of vouching friends)
+		/// - One storage read/remove to get the active recovery process. O(1), Codec O(V)
+		/// - One balance call to repatriate reserved. O(X)
+		/// - One event.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(30_000)]
+		fn close_recovery(origin, rescuer: T::AccountId) {
+			let who = ensure_signed(origin)?;
+			// Take the active recovery process started by the rescuer for this account.
+			let active_recovery = <ActiveRecoveries<T>>::take(&who, &rescuer)
+				.ok_or("recovery not started")?;
+			// Move the reserved funds from the rescuer to the rescued account.
+			// Acts like a slashing mechanism for those who try to maliciously recover accounts.
+			let _ = T::Currency::repatriate_reserved(&rescuer, &who, active_recovery.deposit);
+			Self::deposit_event(RawEvent::RecoveryClosed(who, rescuer));
+		}
+
+		/// Remove the recovery process for your account.
+		///
+		/// NOTE: The user must call `close_recovery` on all active recovery attempts
+		/// before calling this function, otherwise it will fail.
+		///
+		/// Payment: By calling this function the recoverable account will unreserve
+		/// their recovery configuration deposit.
+		/// (`ConfigDepositBase` + `FriendDepositFactor` * #_of_friends)
+		///
+		/// The dispatch origin for this call must be _Signed_ and must be a
+		/// recoverable account (i.e. has a recovery configuration).
+		///
+		/// # <weight>
+		/// Key: F (len of friends)
+		/// - One storage read to check there are no active recoveries. O(1)
+		/// - One storage read/remove to get the recovery configuration. O(1), Codec O(F)
+		/// - One balance call to unreserve. O(X)
+		/// - One event.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(30_000)]
+		fn remove_recovery(origin) {
+			let who = ensure_signed(origin)?;
+			// Check there are no active recoveries
+			ensure!(<ActiveRecoveries<T>>::iter_prefix(&who).next().is_none(), "recovery still active");
+			// Take the recovery configuration for this account.
+			let recovery_config = <Recoverable<T>>::take(&who).ok_or("account not recoverable")?;
+			// Unreserve the initial deposit for the recovery configuration.
+			T::Currency::unreserve(&who, recovery_config.deposit);
+			Self::deposit_event(RawEvent::RecoveryRemoved(who));
+		}
+
+		/// Cancel the ability to use `as_recovered` for `account`.
+		///
+		/// The dispatch origin for this call must be _Signed_ and registered to
+		/// be able to make calls on behalf of the recovered account.
+		///
+		/// Parameters:
+		/// - `account`: The recovered account you are able to call on-behalf-of.
+		///
+		/// # <weight>
+		/// - One storage mutation to check account is recovered by `who`. O(1)
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+		fn cancel_recovered(origin, account: T::AccountId) {
+			let who = ensure_signed(origin)?;
+			// Check `who` is allowed to make a call on behalf of `account`
+			ensure!(Self::proxy(&who) == Some(account), "not allowed to act for account");
+			<Proxy<T>>::remove(&who);
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Check that friends list is sorted and has no duplicates.
+	fn is_sorted_and_unique(friends: &[T::AccountId]) -> bool {
+		friends.windows(2).all(|w| w[0] < w[1])
+	}
+
+	/// Check that a user is a friend in the friends list.
+	fn is_friend(friends: &[T::AccountId], friend: &T::AccountId) -> bool {
+		friends.binary_search(friend).is_ok()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use srml_support::{
+		assert_ok, assert_noop, impl_outer_origin, impl_outer_dispatch, parameter_types,
+	};
+	use sr_io::with_externalities;
+	use primitives::{H256, Blake2Hasher};
+	use sr_primitives::{Perbill, traits::{BlakeTwo256, IdentityLookup}, testing::Header};
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	impl_outer_dispatch! {
+		pub enum Call for Test where origin: Origin {
+			balances::Balances,
+			recovery::Recovery,
+		}
+	}
+
+	// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+	#[derive(Clone, Eq, PartialEq, Debug)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: u32 = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::one();
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Call = Call;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type WeightMultiplierUpdate = ();
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+	}
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 0;
+		pub const TransferFee: u64 = 0;
+		pub const CreationFee: u64 = 0;
+		pub const TransactionBaseFee: u64 = 0;
+		pub const TransactionByteFee: u64 = 0;
+	}
+	impl balances::Trait for Test {
+		type Balance = u64;
+		type OnFreeBalanceZero = ();
+		type OnNewAccount = ();
+		type Event = ();
+		type TransactionPayment = ();
+		type TransferPayment = ();
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type TransferFee = TransferFee;
+		type CreationFee = CreationFee;
+		type TransactionBaseFee = TransactionBaseFee;
+		type TransactionByteFee = TransactionByteFee;
+		type WeightToFee = ();
+	}
+	parameter_types! {
+		pub const ConfigDepositBase: u64 = 10;
+		pub const FriendDepositFactor: u64 = 1;
+		pub const MaxFriends: u16 = 3;
+		pub const RecoveryDeposit: u64 = 10;
+	}
+	impl Trait for Test {
+		type Event = ();
+		type Call = Call;
+		type Currency = Balances;
+		type ConfigDepositBase = ConfigDepositBase;
+		type FriendDepositFactor = FriendDepositFactor;
+		type MaxFriends = MaxFriends;
+		type RecoveryDeposit = RecoveryDeposit;
+	}
+	type System = system::Module<Test>;
+	type Balances = balances::Module<Test>;
+	type Recovery = Module<Test>;
+
+	fn new_test_ext() -> sr_io::TestExternalities<Blake2Hasher> {
+		let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		balances::GenesisConfig::<Test> {
+			balances: vec![(1, 100), (2, 100), (3, 100), (4, 100), (5, 100)],
+		}.assimilate_storage(&mut t).unwrap();
+		t.into()
+	}
+
+	fn transfer_call(to: u64, value: u64) -> Box<Call> {
+		Box::new(Call::Balances(balances::Call::transfer(to, value)))
+	}
+
+	#[test]
+	fn set_recovered_works() {
+		with_externalities(&mut new_test_ext(), || {
+			// Not accessible by a normal user
+			assert_noop!(
+				Recovery::set_recovered(Origin::signed(1), 5, 1),
+				"bad origin: expected to be a root origin"
+			);
+			// Root can set a recovered account though
+			assert_ok!(Recovery::set_recovered(Origin::ROOT, 5, 1));
+			// Account 1 should now be able to make a call through account 5
+			assert_ok!(Recovery::as_recovered(Origin::signed(1), 5, transfer_call(1, 100)));
+			// Account 1 has successfully drained the funds from account 5
+			assert_eq!(Balances::free_balance(&1), 200);
+			assert_eq!(Balances::free_balance(&5), 0);
+		});
+	}
+
+	#[test]
+	fn recovery_life_cycle_works() {
+		with_externalities(&mut new_test_ext(), || {
+			let friends = vec![2, 3, 4];
+			let threshold = 3;
+			let delay_period = 10;
+			// Account 5 sets up a recovery configuration on their account
+			assert_ok!(Recovery::create_recovery(Origin::signed(5), friends, threshold, delay_period));
+			// Some time has passed, and the user lost their keys!
+			System::set_block_number(10);
+			// Using account 1, the user begins the recovery process to recover the lost account
+			assert_ok!(Recovery::initiate_recovery(Origin::signed(1), 5));
+			// Off chain, the user contacts their friends and asks them to vouch for the recovery
+			assert_ok!(Recovery::vouch_recovery(Origin::signed(2), 5, 1));
+			assert_ok!(Recovery::vouch_recovery(Origin::signed(3), 5, 1));
+			assert_ok!(Recovery::vouch_recovery(Origin::signed(4), 5, 1));
+			// We met the threshold, lets try to recover the account...?
+			assert_noop!(Recovery::claim_recovery(Origin::signed(1), 5), "recovery still delayed");
+			// We need to wait at least the delay_period number of blocks before we can recover
+			System::set_block_number(20);
+			assert_ok!(Recovery::claim_recovery(Origin::signed(1), 5));
+			// Account 1 can use account 5 to close the active recovery process, claiming the
+			// deposited funds used to initiate the recovery process into account 5.
+			let call = Box::new(Call::Recovery(super::Call::close_recovery(1)));
+			assert_ok!(Recovery::as_recovered(Origin::signed(1), 5, call));
+			// Account 1 can then use account 5 to remove the recovery configuration, claiming
+			// the deposited funds used to create the recovery configuration into account 5.
+			let call = Box::new(Call::Recovery(super::Call::remove_recovery()));
+			assert_ok!(Recovery::as_recovered(Origin::signed(1), 5, call));
+			// Account 1 should now be able to make a call through account 5 to get all of their
+			// funds
+			assert_eq!(Balances::free_balance(&5), 110);
+			assert_ok!(Recovery::as_recovered(Origin::signed(1), 5, transfer_call(1, 110)));
+			// All funds have been fully recovered!
+			assert_eq!(Balances::free_balance(&1), 200);
+			assert_eq!(Balances::free_balance(&5), 0);
+			// All storage items are removed from the module
+			assert!(!<ActiveRecoveries<Test>>::exists(&5, &1));
+			assert!(!<Recoverable<Test>>::exists(&5));
+			// Only proxy storage items remain
+			assert_eq!(Recovery::proxy(&1), Some(5));
+			// The rescuer can give up the recovered account
+			assert_ok!(Recovery::cancel_recovered(Origin::signed(1), 5));
+			assert_noop!(
+				Recovery::as_recovered(Origin::signed(1), 5, transfer_call(1, 1)),
+				"not allowed to act for account"
+			);
+		});
+	}
+
+	#[test]
+	fn malicious_recovery_fails() {
+		with_externalities(&mut new_test_ext(), || {
+			let friends = vec![2, 3, 4];
+			let threshold = 3;
+			let delay_period = 10;
+			// Account 5 sets up a recovery configuration on their account
+			assert_ok!(Recovery::create_recovery(Origin::signed(5), friends, threshold, delay_period));
+			// Some time has passed, and account 1 wants to try and attack this account!
+			System::set_block_number(10);
+			// Using account 1, the malicious user begins the recovery process on account 5
+			assert_ok!(Recovery::initiate_recovery(Origin::signed(1), 5));
+			// Off chain, the user **tricks** their friends and asks them to vouch for the recovery
+			assert_ok!(Recovery::vouch_recovery(Origin::signed(2), 5, 1));
+			// shame on you
+			assert_ok!(Recovery::vouch_recovery(Origin::signed(3), 5, 1));
+			// shame on you
+			assert_ok!(Recovery::vouch_recovery(Origin::signed(4), 5, 1));
+			// shame on you
+			// We met the threshold, lets try to recover the account...?
+			assert_noop!(Recovery::claim_recovery(Origin::signed(1), 5), "recovery still delayed");
+			// Account 1 needs to wait...
+			System::set_block_number(19);
+			// One more block to wait!
+			assert_noop!(Recovery::claim_recovery(Origin::signed(1), 5), "recovery still delayed");
+			// Account 5 checks their account every `delay_period` and notices the malicious attack!
+			// Account 5 can close the recovery process before account 1 can claim it
+			assert_ok!(Recovery::close_recovery(Origin::signed(5), 1));
+			// By doing so, account 5 has now claimed the deposit originally reserved by account 1
+			assert_eq!(Balances::total_balance(&1), 90);
+			// Thanks for the free money!
+			assert_eq!(Balances::total_balance(&5), 110);
+			// The recovery process has been closed, so account 1 can't make the claim
+			System::set_block_number(20);
+			assert_noop!(Recovery::claim_recovery(Origin::signed(1), 5), "recovery not started");
+			// Account 5 can remove their recovery config and pick some better friends
+			assert_ok!(Recovery::remove_recovery(Origin::signed(5)));
+			assert_ok!(Recovery::create_recovery(Origin::signed(5), vec![22, 33, 44], threshold, delay_period));
+		});
+	}
+
+	#[test]
+	fn create_recovery_handles_basic_errors() {
+		with_externalities(&mut new_test_ext(), || {
+			// No friends
+			assert_noop!(
+				Recovery::create_recovery(Origin::signed(5), vec![], 1, 0),
+				"at least one friend required"
+			);
+			// Zero threshold
+			assert_noop!(
+				Recovery::create_recovery(Origin::signed(5), vec![2], 0, 0),
+				"threshold must be greater than zero"
+			);
+			// Threshold greater than friends length
+			assert_noop!(
+				Recovery::create_recovery(Origin::signed(5), vec![2, 3, 4], 4, 0),
+				"threshold greater than number of friends"
+			);
+			// Too many friends
+			assert_noop!(
+				Recovery::create_recovery(Origin::signed(5), vec![1, 2, 3, 4], 4, 0),
+				"too many friends"
+			);
+			// Unsorted friends
+			assert_noop!(
+				Recovery::create_recovery(Origin::signed(5), vec![3, 2, 4], 3, 0),
+				"friends list must be sorted and unique"
+			);
+			// Duplicate friends
+			assert_noop!(
+				Recovery::create_recovery(Origin::signed(5), vec![2, 2, 4], 3, 0),
+				"friends list must be sorted and unique"
+			);
+		});
+	}
+
+	#[test]
+	fn create_recovery_works() {
+		with_externalities(&mut new_test_ext(), || {
+			let friends = vec![2, 3, 4];
+			let threshold = 3;
+			let delay_period = 10;
+			// Account 5 sets up a recovery configuration on their account
+			assert_ok!(Recovery::create_recovery(Origin::signed(5), friends.clone(), threshold, delay_period));
+			// Deposit is taken, and scales with the number of friends they pick
+			// Base 10 + 1 per friends = 13 total reserved
+			assert_eq!(Balances::reserved_balance(&5), 13);
+			// Recovery configuration is correctly stored
+			let recovery_config = RecoveryConfig {
+				delay_period,
+				deposit: 13,
+				friends: friends.clone(),
+				threshold,
+			};
+			assert_eq!(Recovery::recovery_config(5), Some(recovery_config));
+			// Cannot create another recovery configuration for the same account
+			assert_noop!(
+				Recovery::create_recovery(Origin::signed(5), friends, threshold, delay_period),
+				"account already recoverable"
+			);
+		});
+	}
+
+	#[test]
+	fn initiate_recovery_handles_basic_errors() {
+		with_externalities(&mut new_test_ext(), || {
+			// No recovery process set up for the account
+			assert_noop!(Recovery::initiate_recovery(Origin::signed(1), 5), "account not recoverable");
+			// Create a recovery process for next test
+			let friends = vec![2, 3, 4];
+			let threshold = 3;
+			let delay_period = 10;
+			assert_ok!(Recovery::create_recovery(Origin::signed(5), friends, threshold, delay_period));
+			// Same user cannot recover same account twice
+			assert_ok!(Recovery::initiate_recovery(Origin::signed(1), 5));
+			assert_noop!(Recovery::initiate_recovery(Origin::signed(1), 5), "recovery already started");
+			// No double deposit
+			assert_eq!(Balances::reserved_balance(&1), 10);
+		});
+	}
+
+	#[test]
+	fn vouch_recovery_handles_basic_errors() {
+		with_externalities(&mut new_test_ext(), || {
+			// Cannot vouch for non-recoverable account
+			assert_noop!(Recovery::vouch_recovery(Origin::signed(2), 5, 1), "account not recoverable");
+			// Create a recovery process for next tests
+			let friends = vec![2, 3, 4];
+			let threshold = 3;
+			let delay_period = 10;
+			assert_ok!(Recovery::create_recovery(Origin::signed(5), friends, threshold, delay_period));
+			// Cannot vouch a recovery process that has not started
+			assert_noop!(Recovery::vouch_recovery(Origin::signed(2), 5, 1), "recovery not started");
+			// Initiate a recovery process
+			assert_ok!(Recovery::initiate_recovery(Origin::signed(1), 5));
+			// Cannot vouch if you are not a friend
+			assert_noop!(Recovery::vouch_recovery(Origin::signed(22), 5, 1), "not a friend");
+			// Cannot vouch twice
+			assert_ok!(Recovery::vouch_recovery(Origin::signed(2), 5, 1));
+			assert_noop!(Recovery::vouch_recovery(Origin::signed(2), 5, 1), "already vouched");
+		});
+	}
+
+	#[test]
+	fn claim_recovery_handles_basic_errors() {
+		with_externalities(&mut new_test_ext(), || {
+			// Cannot claim a non-recoverable account
+			assert_noop!(Recovery::claim_recovery(Origin::signed(1), 5), "account not recoverable");
+			// Create a recovery process for the test
+			let friends = vec![2, 3, 4];
+			let threshold = 3;
+			let delay_period = 10;
+			assert_ok!(Recovery::create_recovery(Origin::signed(5), friends, threshold, delay_period));
+			// Cannot claim an account which has not started the recovery process
+			assert_noop!(Recovery::claim_recovery(Origin::signed(1), 5), "recovery not started");
+			assert_ok!(Recovery::initiate_recovery(Origin::signed(1), 5));
+			// Cannot claim an account which has not passed the delay period
+			assert_noop!(Recovery::claim_recovery(Origin::signed(1), 5), "recovery still delayed");
+			System::set_block_number(11);
+			// Cannot claim an account which has not passed the threshold number of votes
+			assert_ok!(Recovery::vouch_recovery(Origin::signed(2), 5, 1));
+			assert_ok!(Recovery::vouch_recovery(Origin::signed(3), 5, 1));
+			// Only 2/3 is not good enough
+			assert_noop!(Recovery::claim_recovery(Origin::signed(1), 5), "threshold not met");
+		});
+	}
+
+	#[test]
+	fn close_recovery_handles_basic_errors() {
+		with_externalities(&mut new_test_ext(), || {
+			// Cannot close a non-active recovery
+			assert_noop!(Recovery::close_recovery(Origin::signed(5), 1), "recovery not started");
+		});
+	}
+
+	#[test]
+	fn remove_recovery_works() {
+		with_externalities(&mut new_test_ext(), || {
+			// Cannot remove an unrecoverable account
+			assert_noop!(Recovery::remove_recovery(Origin::signed(5)), "account not recoverable");
+			// Create and initiate a recovery process for the test
+			let friends = vec![2, 3, 4];
+			let threshold = 3;
+			let delay_period = 10;
+			assert_ok!(Recovery::create_recovery(Origin::signed(5), friends, threshold, delay_period));
+			assert_eq!(Balances::reserved_balance(&5), 13);
+			// Removing the configuration returns the deposit
+			assert_ok!(Recovery::remove_recovery(Origin::signed(5)));
+			assert_eq!(Balances::reserved_balance(&5), 0);
+			assert_eq!(Balances::free_balance(&5), 100);
+		});
+	}
+
+	#[test]
+	fn remove_recovery_fails_while_recovery_is_active() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(Recovery::create_recovery(Origin::signed(5), vec![2, 3, 4], 3, 10));
+			assert_ok!(Recovery::initiate_recovery(Origin::signed(1), 5));
+			// Cannot remove the configuration while account 1 is trying to recover account 5
+			assert_noop!(Recovery::remove_recovery(Origin::signed(5)), "recovery still active");
+			assert!(<Recoverable<Test>>::exists(&5));
+			// Once the recovery is closed, the configuration can be removed
+			assert_ok!(Recovery::close_recovery(Origin::signed(5), 1));
+			assert_ok!(Recovery::remove_recovery(Origin::signed(5)));
+			assert!(!<Recoverable<Test>>::exists(&5));
+		});
+	}
+
+	#[test]
+	fn cancel_recovered_requires_proxy() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_noop!(
+				Recovery::cancel_recovered(Origin::signed(1), 5),
+				"not allowed to act for account"
+			);
+		});
+	}
+}
@@ -0,0 +1,647 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Scheduler Module
+//!
+//! - [`scheduler::Trait`](./trait.Trait.html)
+//! - [`Call`](./enum.Call.html)
+//!
+//! ## Overview
+//!
+//! A module for scheduling dispatches.
+//!
+//! Dispatches are placed on an *agenda* for a given block, together with a priority and the
+//! origin they should be dispatched from. At the beginning of that block the agenda is executed
+//! in order of priority, for as long as the total weight of the executed calls stays within
+//! `MaximumWeight`. Anything that does not fit is postponed to the next block, except for
+//! dispatches whose priority is at least as high as `HARD_DEADLINE`, which are always executed.
+//!
+//! A dispatch may be *periodic*, in which case it is rescheduled a given number of blocks later,
+//! a given number of times. It may also be *named*, in which case it can be cancelled by its name
+//! at any point before its final execution, otherwise it can only be cancelled by its address
+//! (block number and index in the agenda) before its first execution.
+//!
+//! Other modules use the scheduler through the `schedule::Anon` and `schedule::Named` traits.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `schedule` - Schedule a dispatch, which may be periodic, to occur at a specified block and
+//!   with a specified priority.
+//! - `cancel` - Cancel a scheduled dispatch, specified by block number and index.
+//! - `schedule_named` - Augments the `schedule` interface with an additional `Vec<u8>` parameter
+//!   that can be used for identification.
+//! - `cancel_named` - The named complement to the cancel function.
+//!
+//! [`Call`]: ./enum.Call.html
+//! [`Trait`]: ./trait.Trait.html
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use rstd::prelude::*;
+use codec::{Encode, Decode};
+use sr_primitives::traits::{Zero, One, EnsureOrigin};
+use sr_primitives::weights::{SimpleDispatchInfo, GetDispatchInfo, Weight};
+use srml_support::{
+	StorageMap, Parameter, Dispatchable, decl_module, decl_event, decl_storage, ensure,
+};
+use srml_support::traits::{Get, schedule};
+use system::RawOrigin;
+
+pub trait Trait: system::Trait {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The aggregated call type.
+	type Call: Parameter + Dispatchable<Origin=<Self as system::Trait>::Origin> + GetDispatchInfo;
+
+	/// The maximum weight that may be scheduled per block for any dispatchables of less priority
+	/// than `schedule::HARD_DEADLINE`.
+	type MaximumWeight: Get<Weight>;
+
+	/// Required origin to schedule or cancel calls.
+	type ScheduleOrigin: EnsureOrigin<<Self as system::Trait>::Origin>;
+}
+
+/// The location of a scheduled task that can be used to remove it.
+pub type TaskAddress<BlockNumber> = (BlockNumber, u32);
+
+/// Information regarding an item to be executed in the future.
+#[derive(Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(PartialEq, Eq, Debug))]
+pub struct Scheduled<Call, BlockNumber, AccountId> {
+	/// The unique identity for this task, if there is one.
+	maybe_id: Option<Vec<u8>>,
+	/// This task's priority.
+	priority: schedule::Priority,
+	/// The call to be dispatched.
+	call: Call,
+	/// If the call is periodic, then this points to the information concerning that.
+	maybe_periodic: Option<schedule::Period<BlockNumber>>,
+	/// The origin to dispatch the call from.
+	origin: RawOrigin<AccountId>,
+}
+
+type ScheduledOf<T> = Scheduled<
+	<T as Trait>::Call,
+	<T as system::Trait>::BlockNumber,
+	<T as system::Trait>::AccountId,
+>;
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Scheduler {
+		/// Items to be executed, indexed by the block number that they should be executed on.
+		pub Agenda get(agenda): map T::BlockNumber => Vec<Option<ScheduledOf<T>>>;
+
+		/// Lookup from identity to the block number and index of the task.
+		Lookup: map Vec<u8> => Option<TaskAddress<T::BlockNumber>>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where BlockNumber = <T as system::Trait>::BlockNumber {
+		/// A dispatch was scheduled at the given address.
+		Scheduled(BlockNumber, u32),
+		/// A scheduled dispatch was cancelled.
+		Canceled(BlockNumber, u32),
+		/// A scheduled dispatch was executed, with the given task name (if any) and result.
+		Dispatched(TaskAddress<BlockNumber>, Option<Vec<u8>>, bool),
+	}
+);
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: <T as system::Trait>::Origin {
+		/// The maximum weight of non hard-deadline dispatches executed per block.
+		const MaximumWeight: Weight = T::MaximumWeight::get();
+
+		fn deposit_event() = default;
+
+		/// Anonymously schedule a task, dispatched from the _Root_ origin.
+		///
+		/// The dispatch origin for this call must be `ScheduleOrigin`.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - One storage append.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedOperational(100_000)]
+		fn schedule(origin,
+			when: T::BlockNumber,
+			maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
+			priority: schedule::Priority,
+			call: Box<<T as Trait>::Call>
+		) {
+			T::ScheduleOrigin::ensure_origin(origin)?;
+			let _ = Self::do_schedule(None, when, maybe_periodic, priority, RawOrigin::Root, *call);
+		}
+
+		/// Cancel an anonymously scheduled task.
+		///
+		/// The dispatch origin for this call must be `ScheduleOrigin`.
+		///
+		/// # <weight>
+		/// - O(S) where S is the number of tasks scheduled for `when`.
+		/// - One storage read and write.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedOperational(100_000)]
+		fn cancel(origin, when: T::BlockNumber, index: u32) {
+			T::ScheduleOrigin::ensure_origin(origin)?;
+			Self::do_cancel((when, index)).map_err(|_| "scheduled dispatch not found")?;
+		}
+
+		/// Schedule a named task, dispatched from the _Root_ origin.
+		///
+		/// The dispatch origin for this call must be `ScheduleOrigin`.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - One storage append and two storage writes.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedOperational(100_000)]
+		fn schedule_named(origin,
+			id: Vec<u8>,
+			when: T::BlockNumber,
+			maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
+			priority: schedule::Priority,
+			call: Box<<T as Trait>::Call>
+		) {
+			T::ScheduleOrigin::ensure_origin(origin)?;
+			ensure!(!Lookup::<T>::exists(&id), "named dispatch already scheduled");
+			let _ = Self::do_schedule(Some(id), when, maybe_periodic, priority, RawOrigin::Root, *call);
+		}
+
+		/// Cancel a named scheduled task.
+		///
+		/// The dispatch origin for this call must be `ScheduleOrigin`.
+		///
+		/// # <weight>
+		/// - O(S) where S is the number of tasks scheduled for the block of the task.
+		/// - Two storage reads and writes.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedOperational(100_000)]
+		fn cancel_named(origin, id: Vec<u8>) {
+			T::ScheduleOrigin::ensure_origin(origin)?;
+			let address = Lookup::<T>::get(&id).ok_or("named dispatch not found")?;
+			Self::do_cancel(address).map_err(|_| "named dispatch not found")?;
+		}
+
+		fn on_initialize(now: T::BlockNumber) {
+			Self::execute_agenda(now);
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Execute the agenda for block `now`, in order of priority and within the weight limit.
+	///
+	/// Periodic tasks are placed back onto the agenda of their next due block, and tasks that did
+	/// not fit in the block are postponed to the next one.
+	fn execute_agenda(now: T::BlockNumber) {
+		let limit = T::MaximumWeight::get();
+		let mut queued = <Agenda<T>>::take(now).into_iter()
+			.enumerate()
+			.filter_map(|(index, s)| s.map(|inner| (index as u32, inner)))
+			.collect::<Vec<_>>();
+		// Stable, so tasks of equal priority keep the order in which they were scheduled.
+		queued.sort_by_key(|(_, s)| s.priority);
+
+		let mut total_weight: Weight = 0;
+		let mut postponed = Vec::new();
+		for (order, (index, mut s)) in queued.into_iter().enumerate() {
+			let call_weight = s.call.get_dispatch_info().weight;
+			let next_weight = total_weight.saturating_add(call_weight);
+			// The first task is always executed, so that a single overweight task cannot block
+			// the agenda forever.
+			if order > 0 && s.priority > schedule::HARD_DEADLINE && next_weight > limit {
+				postponed.push(s);
+				continue;
+			}
+			total_weight = next_weight;
+
+			let origin = <T as system::Trait>::Origin::from(s.origin.clone());
+			let ok = s.call.clone().dispatch(origin).is_ok();
+			Self::deposit_event(RawEvent::Dispatched((now, index), s.maybe_id.clone(), ok));
+
+			if let Some((period, count)) = s.maybe_periodic {
+				s.maybe_periodic = if count > 1 { Some((period, count - 1)) } else { None };
+				Self::place(now + period, s);
+			} else if let Some(ref id) = s.maybe_id {
+				Lookup::<T>::remove(id);
+			}
+		}
+
+		for s in postponed {
+			Self::place(now + One::one(), s);
+		}
+
+		<system::Module<T>>::register_extra_weight_unchecked(total_weight);
+	}
+
+	/// Append `s` to the agenda of block `when`, updating its lookup entry if it is named.
+	fn place(when: T::BlockNumber, s: ScheduledOf<T>) -> TaskAddress<T::BlockNumber> {
+		let index = <Agenda<T>>::decode_len(when).unwrap_or(0) as u32;
+		if let Some(ref id) = s.maybe_id {
+			Lookup::<T>::insert(id, (when, index));
+		}
+		<Agenda<T>>::append_or_insert(when, &[Some(s)][..]);
+		(when, index)
+	}
+
+	fn do_schedule(
+		maybe_id: Option<Vec<u8>>,
+		when: T::BlockNumber,
+		maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
+		priority: schedule::Priority,
+		origin: RawOrigin<T::AccountId>,
+		call: <T as Trait>::Call,
+	) -> TaskAddress<T::BlockNumber> {
+		// The agenda of the current block has already been executed, so anything scheduled for
+		// now or the past is executed in the next block.
+		let earliest = <system::Module<T>>::block_number() + One::one();
+		let when = if when < earliest { earliest } else { when };
+
+		// Sanitize the period: a zero period would never progress, and a single-execution task is
+		// not periodic at all. The stored count is the number of executions *after* the first.
+		let maybe_periodic = maybe_periodic
+			.filter(|&(period, count)| count > 1 && !period.is_zero())
+			.map(|(period, count)| (period, count - 1));
+
+		let s = Scheduled { maybe_id, priority, call, maybe_periodic, origin };
+		let address = Self::place(when, s);
+		Self::deposit_event(RawEvent::Scheduled(address.0, address.1));
+		address
+	}
+
+	fn do_cancel((when, index): TaskAddress<T::BlockNumber>) -> Result<(), ()> {
+		let scheduled = <Agenda<T>>::mutate(when, |agenda| {
+			agenda.get_mut(index as usize).and_then(Option::take)
+		});
+		let s = scheduled.ok_or(())?;
+		if let Some(ref id) = s.maybe_id {
+			Lookup::<T>::remove(id);
+		}
+		Self::deposit_event(RawEvent::Canceled(when, index));
+		Ok(())
+	}
+}
+
+impl<T: Trait> schedule::Anon<T::BlockNumber, <T as Trait>::Call, system::Origin<T>> for Module<T> {
+	type Address = TaskAddress<T::BlockNumber>;
+
+	fn schedule(
+		when: T::BlockNumber,
+		maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
+		priority: schedule::Priority,
+		origin: system::Origin<T>,
+		call: <T as Trait>::Call,
+	) -> Self::Address {
+		Self::do_schedule(None, when, maybe_periodic, priority, origin, call)
+	}
+
+	fn cancel(address: Self::Address) -> Result<(), ()> {
+		Self::do_cancel(address)
+	}
+}
+
+impl<T: Trait> schedule::Named<T::BlockNumber, <T as Trait>::Call, system::Origin<T>> for Module<T> {
+	type Address = TaskAddress<T::BlockNumber>;
+
+	fn schedule_named(
+		id: Vec<u8>,
+		when: T::BlockNumber,
+		maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
+		priority: schedule::Priority,
+		origin: system::Origin<T>,
+		call: <T as Trait>::Call,
+	) -> Result<Self::Address, ()> {
+		if Lookup::<T>::exists(&id) {
+			return Err(())
+		}
+		Ok(Self::do_schedule(Some(id), when, maybe_periodic, priority, origin, call))
+	}
+
+	fn cancel_named(id: Vec<u8>) -> Result<(), ()> {
+		let address = Lookup::<T>::get(&id).ok_or(())?;
+		Self::do_cancel(address)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use std::cell::RefCell;
+	use srml_support::{
+		assert_ok, assert_noop, impl_outer_origin, impl_outer_dispatch, parameter_types,
+	};
+	use sr_io::with_externalities;
+	use primitives::{H256, Blake2Hasher};
+	use sr_primitives::{Perbill, traits::{BlakeTwo256, IdentityLookup, OnInitialize}, testing::Header};
+	use system::EnsureRoot;
+
+	mod logger {
+		use super::*;
+
+		thread_local! {
+			static LOG: RefCell<Vec<u32>> = RefCell::new(Vec::new());
+		}
+		pub fn log() -> Vec<u32> {
+			LOG.with(|log| log.borrow().clone())
+		}
+		pub trait Trait: system::Trait {}
+		decl_module! {
+			pub struct Module<T: Trait> for enum Call where origin: <T as system::Trait>::Origin {
+				#[weight = SimpleDispatchInfo::FixedNormal(10)]
+				fn log(origin, i: u32) {
+					system::ensure_root(origin)?;
+					LOG.with(|log| log.borrow_mut().push(i));
+				}
+
+				#[weight = SimpleDispatchInfo::FixedNormal(600)]
+				fn log_heavy(origin, i: u32) {
+					system::ensure_root(origin)?;
+					LOG.with(|log| log.borrow_mut().push(i));
+				}
+			}
+		}
+	}
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	impl_outer_dispatch! {
+		pub enum Call for Test where origin: Origin {
+			logger::Logger,
+		}
+	}
+
+	// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+	#[derive(Clone, Eq, PartialEq, Debug)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: u32 = 2_000;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::one();
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Call = Call;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type WeightMultiplierUpdate = ();
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+	}
+	impl logger::Trait for Test {}
+	parameter_types! {
+		pub const MaximumWeight: Weight = 1_000;
+	}
+	impl Trait for Test {
+		type Event = ();
+		type Call = Call;
+		type MaximumWeight = MaximumWeight;
+		type ScheduleOrigin = EnsureRoot<u64>;
+	}
+	type System = system::Module<Test>;
+	type Logger = logger::Module<Test>;
+	type Scheduler = Module<Test>;
+
+	fn new_test_ext() -> sr_io::TestExternalities<Blake2Hasher> {
+		system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	}
+
+	fn run_to_block(n: u64) {
+		while System::block_number() < n {
+			System::set_block_number(System::block_number() + 1);
+			Scheduler::on_initialize(System::block_number());
+		}
+	}
+
+	fn log(i: u32) -> Call {
+		Call::Logger(logger::Call::log(i))
+	}
+
+	fn log_heavy(i: u32) -> Call {
+		Call::Logger(logger::Call::log_heavy(i))
+	}
+
+	#[test]
+	fn basic_scheduling_works() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			<Scheduler as schedule::Anon<_, _, _>>::schedule(4, None, 127, RawOrigin::Root, log(42));
+			run_to_block(3);
+			assert!(logger::log().is_empty());
+			run_to_block(4);
+			assert_eq!(logger::log(), vec![42]);
+			run_to_block(100);
+			assert_eq!(logger::log(), vec![42]);
+		});
+	}
+
+	#[test]
+	fn scheduling_in_the_past_executes_next_block() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(5);
+			let address = <Scheduler as schedule::Anon<_, _, _>>::schedule(
+				2, None, 127, RawOrigin::Root, log(42),
+			);
+			assert_eq!(address, (6, 0));
+			run_to_block(6);
+			assert_eq!(logger::log(), vec![42]);
+		});
+	}
+
+	#[test]
+	fn periodic_scheduling_works() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			// at #4, every 3 blocks, 3 times.
+			<Scheduler as schedule::Anon<_, _, _>>::schedule(
+				4, Some((3, 3)), 127, RawOrigin::Root, log(42),
+			);
+			run_to_block(3);
+			assert!(logger::log().is_empty());
+			run_to_block(4);
+			assert_eq!(logger::log(), vec![42]);
+			run_to_block(6);
+			assert_eq!(logger::log(), vec![42]);
+			run_to_block(7);
+			assert_eq!(logger::log(), vec![42, 42]);
+			run_to_block(10);
+			assert_eq!(logger::log(), vec![42, 42, 42]);
+			run_to_block(100);
+			assert_eq!(logger::log(), vec![42, 42, 42]);
+		});
+	}
+
+	#[test]
+	fn cancel_named_scheduling_works_with_normal_cancel() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			// at #4.
+			<Scheduler as schedule::Named<_, _, _>>::schedule_named(
+				b"1".to_vec(), 4, None, 127, RawOrigin::Root, log(69),
+			).unwrap();
+			let address = <Scheduler as schedule::Anon<_, _, _>>::schedule(
+				4, None, 127, RawOrigin::Root, log(42),
+			);
+			run_to_block(3);
+			assert!(logger::log().is_empty());
+			assert_ok!(<Scheduler as schedule::Named<_, _, _>>::cancel_named(b"1".to_vec()));
+			assert_ok!(<Scheduler as schedule::Anon<_, _, _>>::cancel(address));
+			run_to_block(100);
+			assert!(logger::log().is_empty());
+		});
+	}
+
+	#[test]
+	fn cancel_named_periodic_scheduling_works() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			// at #4, every 3 blocks, 3 times.
+			<Scheduler as schedule::Named<_, _, _>>::schedule_named(
+				b"1".to_vec(), 4, Some((3, 3)), 127, RawOrigin::Root, log(42),
+			).unwrap();
+			run_to_block(4);
+			assert_eq!(logger::log(), vec![42]);
+			assert_ok!(<Scheduler as schedule::Named<_, _, _>>::cancel_named(b"1".to_vec()));
+			run_to_block(100);
+			assert_eq!(logger::log(), vec![42]);
+		});
+	}
+
+	#[test]
+	fn duplicate_names_are_rejected() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			assert_ok!(Scheduler::schedule_named(
+				Origin::ROOT, b"1".to_vec(), 4, None, 127, Box::new(log(42)),
+			));
+			assert_noop!(
+				Scheduler::schedule_named(
+					Origin::ROOT, b"1".to_vec(), 5, None, 127, Box::new(log(69)),
+				),
+				"named dispatch already scheduled"
+			);
+			assert_noop!(Scheduler::cancel_named(Origin::ROOT, b"2".to_vec()), "named dispatch not found");
+			assert_noop!(Scheduler::cancel(Origin::ROOT, 4, 1), "scheduled dispatch not found");
+		});
+	}
+
+	#[test]
+	fn scheduling_requires_schedule_origin() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_noop!(
+				Scheduler::schedule(Origin::signed(1), 4, None, 127, Box::new(log(42))),
+				"Invalid origin"
+			);
+			assert_noop!(Scheduler::cancel_named(Origin::signed(1), b"1".to_vec()), "Invalid origin");
+		});
+	}
+
+	#[test]
+	fn scheduler_respects_weight_limits() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			<Scheduler as schedule::Anon<_, _, _>>::schedule(4, None, 127, RawOrigin::Root, log_heavy(42));
+			<Scheduler as schedule::Anon<_, _, _>>::schedule(4, None, 127, RawOrigin::Root, log_heavy(69));
+			// 69 and 42 do not fit together
+			run_to_block(4);
+			assert_eq!(logger::log(), vec![42]);
+			run_to_block(5);
+			assert_eq!(logger::log(), vec![42, 69]);
+		});
+	}
+
+	#[test]
+	fn scheduler_respects_hard_deadlines_more() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			<Scheduler as schedule::Anon<_, _, _>>::schedule(4, None, 0, RawOrigin::Root, log_heavy(42));
+			<Scheduler as schedule::Anon<_, _, _>>::schedule(4, None, 0, RawOrigin::Root, log_heavy(69));
+			// With base weights, 69 and 42 should not fit together, but do because of hard deadlines
+			run_to_block(4);
+			assert_eq!(logger::log(), vec![42, 69]);
+		});
+	}
+
+	#[test]
+	fn scheduler_respects_priority_ordering() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			<Scheduler as schedule::Anon<_, _, _>>::schedule(4, None, 1, RawOrigin::Root, log_heavy(42));
+			<Scheduler as schedule::Anon<_, _, _>>::schedule(4, None, 0, RawOrigin::Root, log_heavy(69));
+			run_to_block(4);
+			assert_eq!(logger::log(), vec![69, 42]);
+		});
+	}
+
+	#[test]
+	fn scheduler_respects_priority_ordering_with_soft_deadlines() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			<Scheduler as schedule::Anon<_, _, _>>::schedule(4, None, 255, RawOrigin::Root, log_heavy(42));
+			<Scheduler as schedule::Anon<_, _, _>>::schedule(4, None, 127, RawOrigin::Root, log_heavy(69));
+			<Scheduler as schedule::Anon<_, _, _>>::schedule(4, None, 126, RawOrigin::Root, log(2600));
+			// 42 does not fit together with the others and has the lowest priority
+			run_to_block(4);
+			assert_eq!(logger::log(), vec![2600, 69]);
+			// 42 is postponed to the next block
+			run_to_block(5);
+			assert_eq!(logger::log(), vec![2600, 69, 42]);
+		});
+	}
+
+	#[test]
+	fn postponed_named_task_can_still_be_cancelled() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			<Scheduler as schedule::Anon<_, _, _>>::schedule(4, None, 127, RawOrigin::Root, log_heavy(42));
+			<Scheduler as schedule::Named<_, _, _>>::schedule_named(
+				b"1".to_vec(), 4, None, 127, RawOrigin::Root, log_heavy(69),
+			).unwrap();
+			run_to_block(4);
+			assert_eq!(logger::log(), vec![42]);
+			assert_ok!(<Scheduler as schedule::Named<_, _, _>>::cancel_named(b"1".to_vec()));
+			run_to_block(100);
+			assert_eq!(logger::log(), vec![42]);
+		});
+	}
+
+	#[test]
+	fn executed_weight_is_registered() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			<Scheduler as schedule::Anon<_, _, _>>::schedule(2, None, 127, RawOrigin::Root, log(42));
+			<Scheduler as schedule::Anon<_, _, _>>::schedule(2, None, 127, RawOrigin::Root, log_heavy(69));
+			run_to_block(2);
+			assert_eq!(System::all_extrinsics_weight(), 610);
+		});
+	}
+}
@@ -145,7 +145,6 @@ pub fn new_test_ext() -> sr_io::TestExternalities<Blake2Hasher> {
 			(40, 500_000),
 			(99, 1),
 		],
-		vesting: vec![],
 	}.assimilate_storage(&mut t).unwrap();
 	GenesisConfig::<Test>{
 		pool: vec![
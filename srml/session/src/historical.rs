@@ -271,12 +271,26 @@ impl<T: Trait> ProvingTrie<T> {
 }
 
 /// Proof of ownership of a specific key.
-#[derive(Encode, Decode, Clone)]
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
 pub struct Proof {
 	session: SessionIndex,
 	trie_nodes: Vec<Vec<u8>>,
 }
 
+impl Proof {
+	/// The session the key ownership is proven for.
+	pub fn session(&self) -> SessionIndex {
+		self.session
+	}
+}
+
+impl crate::GetSessionNumber for Proof {
+	fn session(&self) -> SessionIndex {
+		self.session
+	}
+}
+
 impl<T: Trait, D: AsRef<[u8]>> srml_support::traits::KeyOwnerProofSystem<(KeyTypeId, D)>
 	for Module<T>
 {
@@ -190,6 +190,19 @@ impl<A> OnSessionEnding<A> for () {
 	fn on_session_ending(_: SessionIndex, _: SessionIndex) -> Option<Vec<A>> { None }
 }
 
+/// Something that belongs to a session, like a proof of key ownership.
+pub trait GetSessionNumber {
+	/// The index of the session `self` belongs to.
+	fn session(&self) -> SessionIndex;
+}
+
+/// The proof of the `()` key owner proof system, which is never valid whatever its session.
+impl GetSessionNumber for () {
+	fn session(&self) -> SessionIndex {
+		Zero::zero()
+	}
+}
+
 /// Handler for session lifecycle events.
 pub trait SessionHandler<ValidatorId> {
 	/// The given validator set will be used for the genesis session.
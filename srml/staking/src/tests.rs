@@ -113,7 +113,7 @@ fn change_controller_works() {
 
 		assert_noop!(
 			Staking::validate(Origin::signed(10), ValidatorPrefs::default()),
-			"not a controller"
+			Error::NotController
 		);
 		assert_ok!(Staking::validate(Origin::signed(5), ValidatorPrefs::default()));
 	})
@@ -631,10 +631,10 @@ fn double_staking_should_fail() {
 			// 4 = not used so far, 1 stashed => not allowed.
 			assert_noop!(
 				Staking::bond(Origin::signed(1), 4, arbitrary_value,
-				RewardDestination::default()), "stash already bonded"
+				RewardDestination::default()), Error::AlreadyBonded
 			);
 			// 1 = stashed => attempting to nominate should fail.
-			assert_noop!(Staking::nominate(Origin::signed(1), vec![1]), "not a controller");
+			assert_noop!(Staking::nominate(Origin::signed(1), vec![1]), Error::NotController);
 			// 2 = controller  => nominating should work.
 			assert_ok!(Staking::nominate(Origin::signed(2), vec![1]));
 		});
@@ -651,7 +651,10 @@ fn double_controlling_should_fail() {
 			// 2 = controller, 1 stashed => ok
 			assert_ok!(Staking::bond(Origin::signed(1), 2, arbitrary_value, RewardDestination::default()));
 			// 2 = controller, 3 stashed (Note that 2 is reused.) => no-op
-			assert_noop!(Staking::bond(Origin::signed(3), 2, arbitrary_value, RewardDestination::default()), "controller already paired");
+			assert_noop!(
+				Staking::bond(Origin::signed(3), 2, arbitrary_value, RewardDestination::default()),
+				Error::AlreadyPaired
+			);
 		});
 }
 
@@ -748,7 +751,10 @@ fn cannot_transfer_staked_balance() {
 		// Confirm account 11 (via controller 10) is totally staked
 		assert_eq!(Staking::stakers(&11).total, 1000);
 		// Confirm account 11 cannot transfer as a result
-		assert_noop!(Balances::transfer(Origin::signed(11), 20, 1), "account liquidity restrictions prevent withdrawal");
+		assert_noop!(
+			Balances::transfer(Origin::signed(11), 20, 1),
+			balances::Error::LiquidityRestrictions
+		);
 
 		// Give account 11 extra free balance
 		let _ = Balances::make_free_balance_be(&11, 10000);
@@ -774,7 +780,10 @@ fn cannot_transfer_staked_balance_2() {
 		// Confirm account 21 (via controller 20) is totally staked
 		assert_eq!(Staking::stakers(&21).total, 1000);
 		// Confirm account 21 can transfer at most 1000
-		assert_noop!(Balances::transfer(Origin::signed(21), 20, 1001), "account liquidity restrictions prevent withdrawal");
+		assert_noop!(
+			Balances::transfer(Origin::signed(21), 20, 1001),
+			balances::Error::LiquidityRestrictions
+		);
 		assert_ok!(Balances::transfer(Origin::signed(21), 20, 1000));
 	});
 }
@@ -1090,11 +1099,11 @@ fn too_many_unbond_calls_should_not_work() {
 		// locked at era 1 until 4
 		assert_ok!(Staking::unbond(Origin::signed(10), 1));
 		// can't do more.
-		assert_noop!(Staking::unbond(Origin::signed(10), 1), "can not schedule more unlock chunks");
+		assert_noop!(Staking::unbond(Origin::signed(10), 1), Error::NoMoreChunks);
 
 		start_era(3);
 
-		assert_noop!(Staking::unbond(Origin::signed(10), 1), "can not schedule more unlock chunks");
+		assert_noop!(Staking::unbond(Origin::signed(10), 1), Error::NoMoreChunks);
 		// free up.
 		assert_ok!(Staking::withdraw_unbonded(Origin::signed(10)));
 
@@ -1559,7 +1568,7 @@ fn bond_with_no_staked_value() {
 				// Can't bond with 1
 		assert_noop!(
 			Staking::bond(Origin::signed(1), 2, 1, RewardDestination::Controller),
-			"can not bond with value less than minimum balance"
+			Error::InsufficientValue
 		);
 		// bonded with absolute minimum value possible.
 		assert_ok!(Staking::bond(Origin::signed(1), 2, 5, RewardDestination::Controller));
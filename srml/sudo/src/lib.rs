@@ -91,7 +91,7 @@ use sr_primitives::traits::StaticLookup;
 use sr_primitives::weights::SimpleDispatchInfo;
 use srml_support::{
 	StorageValue, Parameter, Dispatchable, decl_module, decl_event,
-	decl_storage, ensure
+	decl_storage, ensure, dispatch::DispatchError,
 };
 use system::ensure_signed;
 
@@ -126,6 +126,7 @@ decl_module! {
 			let res = match proposal.dispatch(system::RawOrigin::Root.into()) {
 				Ok(_) => true,
 				Err(e) => {
+					let e: DispatchError = e.into();
 					sr_io::print(e);
 					false
 				}
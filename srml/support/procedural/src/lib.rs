@@ -65,6 +65,10 @@ use proc_macro::TokenStream;
 ///   If the keys are not trusted (e.g. can be set by a user), a cryptographic `hasher` such as
 ///   `blake2_256` must be used. Otherwise, other values in storage can be compromised.
 ///
+///   If `$hash` is `blake2_128_concat` or `twox_64_concat`, the key is instead
+///   `twox_128(module_name ++ " " ++ storage_name) ++ $hash(encoding(key))` and the map also
+///   implements [`IterableStorageMap`](../srml_support/storage/trait.IterableStorageMap.html).
+///
 /// * Linked map: `Foo: linked_map hasher($hash) type => type`: Implements the
 ///   [`StorageLinkedMap`](../srml_support/storage/trait.StorageLinkedMap.html) trait using the
 ///   [`StorageLinkedMap generator`](../srml_support/storage/generator/trait.StorageLinkedMap.html).
@@ -101,13 +105,46 @@ use proc_macro::TokenStream;
 ///   If the second key is untrusted, a cryptographic `hasher` such as `blake2_256` must be used.
 ///   Otherwise, other items in storage with the same first key can be compromised.
 ///
+///   If `$hash2` is `twox_64_concat` or `blake2_128_concat`, the double map also implements
+///   [`IterableStorageDoubleMap`](../srml_support/storage/trait.IterableStorageDoubleMap.html).
+///
+/// * Child map: `Foo: child_map hasher($hash1) u32, $hash2(u32) => u32`: Implements the
+///   [`StorageChildMap`](../srml_support/storage/trait.StorageChildMap.html) trait using the
+///   [`StorageChildMap generator`](../srml_support/storage/generator/trait.StorageChildMap.html).
+///
+///   `hasher($hash)` is optional and its default is `blake2_256`.
+///
+///   Each first key gets its own child trie, so all its entries can be removed at once and proven
+///   independently. The child trie and the key of a value in it are calculated as follows:
+///
+///   ```nocompile
+///   ":child_storage:default:" ++ $hash(module_name ++ " " ++ storage_name ++ encoding(first_key))
+///   $hash2(encoding(second_key))
+///   ```
+///
+///   The same care as for the double map must be taken when choosing the hashers.
+///
+/// * Child value: `Foo: child_value type`: Implements the
+///   [`StorageChildValue`](../srml_support/storage/trait.StorageChildValue.html) trait using the
+///   [`StorageChildValue generator`](../srml_support/storage/generator/trait.StorageChildValue.html).
+///
+///   The value gets its own child trie, so its root can be proven independently. The child trie and
+///   the key of the value in it are calculated as follows:
+///
+///   ```nocompile
+///   ":child_storage:default:" ++ Twox128(module_name ++ " " ++ storage_name)
+///   Twox128(module_name ++ " " ++ storage_name)
+///   ```
+///
 /// Supported hashers (ordered from least to best security):
 ///
 /// * `twox_64_concat` - TwoX with 64bit + key concatenated.
+/// * `blake2_128_concat` - Blake2 with 128bit + key concatenated.
 /// * `twox_128` - TwoX with 128bit.
 /// * `twox_256` - TwoX with with 256bit.
 /// * `blake2_128` - Blake2 with 128bit.
 /// * `blake2_256` - Blake2 with 256bit.
+/// * `sha2_256` - Sha2 with 256bit.
 ///
 /// Basic storage can be extended as such:
 ///
@@ -62,6 +62,15 @@ pub(crate) struct Impls<'a, I: Iterator<Item=syn::Meta>> {
 
 impl<'a, I: Iterator<Item=syn::Meta>> Impls<'a, I> {
 	pub fn simple_value(self) -> TokenStream2 {
+		self.value(quote!(StorageValue))
+	}
+
+	pub fn child_value(self) -> TokenStream2 {
+		self.value(quote!(StorageChildValue))
+	}
+
+	/// Generator for the values, `generator` being the name of the generator trait.
+	fn value(self, generator: TokenStream2) -> TokenStream2 {
 		let Self {
 			scrate,
 			visibility,
@@ -113,14 +122,13 @@ impl<'a, I: Iterator<Item=syn::Meta>> Impls<'a, I> {
 			)
 		};
 
-		// generator for value
 		quote! {
 			#( #[ #attrs ] )*
 			#visibility struct #name<#struct_trait>(
 				#scrate::rstd::marker::PhantomData<(#trait_and_instance)>
 			) #where_clause;
 
-			impl<#impl_trait> #scrate::storage::generator::StorageValue<#typ>
+			impl<#impl_trait> #scrate::storage::generator::#generator<#typ>
 				for #name<#trait_and_instance> #where_clause
 			{
 				type Query = #value_type;
@@ -329,6 +337,28 @@ impl<'a, I: Iterator<Item=syn::Meta>> Impls<'a, I> {
 		k1ty: &syn::Type,
 		k2ty: &syn::Type,
 		k2_hasher: TokenStream2,
+	) -> TokenStream2 {
+		self.two_keys_map(quote!(StorageDoubleMap), hasher, k1ty, k2ty, k2_hasher)
+	}
+
+	pub fn child_map(
+		self,
+		hasher: TokenStream2,
+		k1ty: &syn::Type,
+		k2ty: &syn::Type,
+		k2_hasher: TokenStream2,
+	) -> TokenStream2 {
+		self.two_keys_map(quote!(StorageChildMap), hasher, k1ty, k2ty, k2_hasher)
+	}
+
+	/// Generator for the maps with two keys, `generator` being the name of the generator trait.
+	fn two_keys_map(
+		self,
+		generator: TokenStream2,
+		hasher: TokenStream2,
+		k1ty: &syn::Type,
+		k2ty: &syn::Type,
+		k2_hasher: TokenStream2,
 	) -> TokenStream2 {
 		let Self {
 			scrate,
@@ -385,14 +415,13 @@ impl<'a, I: Iterator<Item=syn::Meta>> Impls<'a, I> {
 			)
 		};
 
-		// generator for double map
 		quote!{
 			#( #[ #attrs ] )*
 			#visibility struct #name<#struct_trait> (
 				#scrate::rstd::marker::PhantomData<(#trait_and_instance)>
 			) #where_clause;
 
-			impl<#impl_trait> #scrate::storage::generator::StorageDoubleMap<#k1ty, #k2ty, #typ>
+			impl<#impl_trait> #scrate::storage::generator::#generator<#k1ty, #k2ty, #typ>
 				for #name<#trait_and_instance> #where_clause
 			{
 				type Query = #value_type;
@@ -37,11 +37,15 @@ mod keyword {
 	syn::custom_keyword!(map);
 	syn::custom_keyword!(linked_map);
 	syn::custom_keyword!(double_map);
+	syn::custom_keyword!(child_map);
+	syn::custom_keyword!(child_value);
 	syn::custom_keyword!(blake2_256);
 	syn::custom_keyword!(blake2_128);
 	syn::custom_keyword!(twox_256);
 	syn::custom_keyword!(twox_128);
 	syn::custom_keyword!(twox_64_concat);
+	syn::custom_keyword!(blake2_128_concat);
+	syn::custom_keyword!(sha2_256);
 	syn::custom_keyword!(hasher);
 }
 
@@ -143,6 +147,8 @@ enum DeclStorageType {
 	Map(DeclStorageMap),
 	LinkedMap(DeclStorageLinkedMap),
 	DoubleMap(DeclStorageDoubleMap),
+	ChildMap(DeclStorageChildMap),
+	ChildValue(DeclStorageChildValue),
 	Simple(syn::Type),
 }
 
@@ -176,6 +182,24 @@ struct DeclStorageDoubleMap {
 	pub value: syn::Type,
 }
 
+#[derive(Parse, ToTokens, Debug)]
+struct DeclStorageChildMap {
+	pub map_keyword: keyword::child_map,
+	pub hasher: ext::Opt<SetHasher>,
+	pub key1: syn::Type,
+	pub comma_keyword: Token![,],
+	pub key2_hasher: Hasher,
+	pub key2: ext::Parens<syn::Type>,
+	pub ass_keyword: Token![=>],
+	pub value: syn::Type,
+}
+
+#[derive(Parse, ToTokens, Debug)]
+struct DeclStorageChildValue {
+	pub value_keyword: keyword::child_value,
+	pub value: syn::Type,
+}
+
 #[derive(Parse, ToTokens, Debug)]
 enum Hasher {
 	Blake2_256(keyword::blake2_256),
@@ -183,6 +207,8 @@ enum Hasher {
 	Twox256(keyword::twox_256),
 	Twox128(keyword::twox_128),
 	Twox64Concat(keyword::twox_64_concat),
+	Blake2_128Concat(keyword::blake2_128_concat),
+	Sha2_256(keyword::sha2_256),
 }
 
 #[derive(Parse, ToTokens, Debug)]
@@ -204,6 +230,8 @@ enum HasherKind {
 	Twox256,
 	Twox128,
 	Twox64Concat,
+	Blake2_128Concat,
+	Sha2_256,
 }
 
 impl From<&SetHasher> for HasherKind {
@@ -220,6 +248,8 @@ impl From<&Hasher> for HasherKind {
 			Hasher::Twox256(_) => HasherKind::Twox256,
 			Hasher::Twox128(_) => HasherKind::Twox128,
 			Hasher::Twox64Concat(_) => HasherKind::Twox64Concat,
+			Hasher::Blake2_128Concat(_) => HasherKind::Blake2_128Concat,
+			Hasher::Sha2_256(_) => HasherKind::Sha2_256,
 		}
 	}
 }
@@ -232,6 +262,8 @@ impl HasherKind {
 			HasherKind::Twox256 => quote!( Twox256 ),
 			HasherKind::Twox128 => quote!( Twox128 ),
 			HasherKind::Twox64Concat => quote!( Twox64Concat ),
+			HasherKind::Blake2_128Concat => quote!( Blake2_128Concat ),
+			HasherKind::Sha2_256 => quote!( Sha2_256 ),
 		}
 	}
 
@@ -242,6 +274,8 @@ impl HasherKind {
 			HasherKind::Twox256 => quote!( StorageHasher::Twox256 ),
 			HasherKind::Twox128 => quote!( StorageHasher::Twox128 ),
 			HasherKind::Twox64Concat => quote!( StorageHasher::Twox64Concat ),
+			HasherKind::Blake2_128Concat => quote!( StorageHasher::Blake2_128Concat ),
+			HasherKind::Sha2_256 => quote!( StorageHasher::Sha2_256 ),
 		}
 	}
 }
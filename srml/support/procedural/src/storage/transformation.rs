@@ -290,7 +290,7 @@ fn decl_store_extra_genesis(
 
 			let storage_type = type_infos.typ.clone();
 			config_field.extend(match type_infos.kind {
-				DeclStorageTypeInfosKind::Simple => {
+				DeclStorageTypeInfosKind::Simple { .. } => {
 					quote!( #( #[ #attrs ] )* pub #ident: #storage_type, )
 				},
 				DeclStorageTypeInfosKind::Map {key_type, .. } => {
@@ -318,7 +318,7 @@ fn decl_store_extra_genesis(
 		let typ = type_infos.typ;
 		if let Some(builder) = builder {
 			builders.extend(match type_infos.kind {
-				DeclStorageTypeInfosKind::Simple => {
+				DeclStorageTypeInfosKind::Simple { is_child } => {
 					let struct_trait = if ext::type_contains_ident(&type_infos.value_type, traitinstance) {
 						assimilate_require_generic = true;
 						quote!(#traitinstance,)
@@ -326,11 +326,17 @@ fn decl_store_extra_genesis(
 						quote!()
 					};
 
+					let value = if is_child {
+						quote! { StorageChildValue }
+					} else {
+						quote! { StorageValue }
+					};
+
 					quote!{{
 						let v = (#builder)(&self);
 						<
 							#name<#struct_trait #instance> as
-							#scrate::storage::StorageValue<#typ>
+							#scrate::storage::#value<#typ>
 						>::put(&v);
 					}}
 				},
@@ -360,7 +366,7 @@ fn decl_store_extra_genesis(
 						});
 					}}
 				},
-				DeclStorageTypeInfosKind::DoubleMap { key1_type, key2_type, .. } => {
+				DeclStorageTypeInfosKind::DoubleMap { key1_type, key2_type, is_child, .. } => {
 					let struct_trait = if ext::type_contains_ident(&type_infos.value_type, traitinstance)
 						|| ext::type_contains_ident(key1_type, traitinstance)
 						|| ext::type_contains_ident(key2_type, traitinstance)
@@ -371,12 +377,18 @@ fn decl_store_extra_genesis(
 						quote!()
 					};
 
+					let map = if is_child {
+						quote! { StorageChildMap }
+					} else {
+						quote! { StorageDoubleMap }
+					};
+
 					quote!{{
 						let data = (#builder)(&self);
 						data.into_iter().for_each(|(k1, k2, v)| {
 							<
 								#name<#struct_trait #instance> as
-								#scrate::storage::StorageDoubleMap<#key1_type, #key2_type, #typ>
+								#scrate::storage::#map<#key1_type, #key2_type, #typ>
 							>::insert(&k1, &k2, &v);
 						});
 					}}
@@ -786,18 +798,24 @@ fn decl_storage_items(
 		};
 
 		let implementation = match kind {
-			DeclStorageTypeInfosKind::Simple => {
+			DeclStorageTypeInfosKind::Simple { is_child: false } => {
 				i.simple_value()
 			},
+			DeclStorageTypeInfosKind::Simple { is_child: true } => {
+				i.child_value()
+			},
 			DeclStorageTypeInfosKind::Map { key_type, is_linked: false, hasher } => {
 				i.map(hasher.into_storage_hasher_struct(), key_type)
 			},
 			DeclStorageTypeInfosKind::Map { key_type, is_linked: true, hasher } => {
 				i.linked_map(hasher.into_storage_hasher_struct(), key_type)
 			},
-			DeclStorageTypeInfosKind::DoubleMap { key1_type, key2_type, key2_hasher, hasher } => {
+			DeclStorageTypeInfosKind::DoubleMap { key1_type, key2_type, key2_hasher, hasher, is_child: false } => {
 				i.double_map(hasher.into_storage_hasher_struct(), key1_type, key2_type, key2_hasher.into_storage_hasher_struct())
 			},
+			DeclStorageTypeInfosKind::DoubleMap { key1_type, key2_type, key2_hasher, hasher, is_child: true } => {
+				i.child_map(hasher.into_storage_hasher_struct(), key1_type, key2_type, key2_hasher.into_storage_hasher_struct())
+			},
 		};
 		impls.extend(implementation)
 	}
@@ -823,7 +841,7 @@ fn impl_store_items(
 			let name = &line.name;
 			let type_infos = get_type_infos(&line.storage_type);
 			let requires_trait = match type_infos.kind {
-				DeclStorageTypeInfosKind::Simple => {
+				DeclStorageTypeInfosKind::Simple { .. } => {
 					ext::type_contains_ident(&type_infos.value_type, traitinstance)
 				},
 				DeclStorageTypeInfosKind::Map { key_type, .. } => {
@@ -879,19 +897,25 @@ fn impl_store_fns(
 
 			let typ = type_infos.typ;
 			let item = match type_infos.kind {
-				DeclStorageTypeInfosKind::Simple => {
+				DeclStorageTypeInfosKind::Simple { is_child } => {
 					let struct_trait = if ext::type_contains_ident(&type_infos.value_type, traitinstance) {
 						quote!(#traitinstance,)
 					} else {
 						quote!()
 					};
 
+					let value = if is_child {
+						quote! { StorageChildValue }
+					} else {
+						quote! { StorageValue }
+					};
+
 					quote!{
 						#( #[ #attrs ] )*
 						pub fn #get_fn() -> #value_type {
 							<
 								#name<#struct_trait #instance> as
-								#scrate::storage::StorageValue<#typ>
+								#scrate::storage::#value<#typ>
 							>::get()
 						}
 					}
@@ -921,7 +945,7 @@ fn impl_store_fns(
 						}
 					}
 				}
-				DeclStorageTypeInfosKind::DoubleMap { key1_type, key2_type, .. } => {
+				DeclStorageTypeInfosKind::DoubleMap { key1_type, key2_type, is_child, .. } => {
 					let struct_trait = if ext::type_contains_ident(&type_infos.value_type, traitinstance)
 						|| ext::type_contains_ident(key1_type, traitinstance)
 						|| ext::type_contains_ident(key2_type, traitinstance)
@@ -931,6 +955,12 @@ fn impl_store_fns(
 						quote!()
 					};
 
+					let map = if is_child {
+						quote! { StorageChildMap }
+					} else {
+						quote! { StorageDoubleMap }
+					};
+
 					quote!{
 						pub fn #get_fn<KArg1, KArg2>(k1: &KArg1, k2: &KArg2) -> #value_type
 						where
@@ -941,7 +971,7 @@ fn impl_store_fns(
 						{
 							<
 								#name<#struct_trait #instance> as
-								#scrate::storage::StorageDoubleMap<#key1_type, #key2_type, #typ>
+								#scrate::storage::#map<#key1_type, #key2_type, #typ>
 							>::get(k1, k2)
 						}
 					}
@@ -987,9 +1017,14 @@ fn store_functions_to_metadata (
 		let typ = type_infos.typ;
 		let styp = clean_type_string(&typ.to_string());
 		let stype = match type_infos.kind {
-			DeclStorageTypeInfosKind::Simple => {
+			DeclStorageTypeInfosKind::Simple { is_child } => {
+				let value = if is_child {
+					quote! { ChildPlain }
+				} else {
+					quote! { Plain }
+				};
 				quote!{
-					#scrate::metadata::StorageEntryType::Plain(
+					#scrate::metadata::StorageEntryType::#value(
 						#scrate::metadata::DecodeDifferent::Encode(#styp),
 					)
 				}
@@ -1006,13 +1041,18 @@ fn store_functions_to_metadata (
 					}
 				}
 			},
-			DeclStorageTypeInfosKind::DoubleMap { key1_type, key2_type, key2_hasher, hasher } => {
+			DeclStorageTypeInfosKind::DoubleMap { key1_type, key2_type, key2_hasher, hasher, is_child } => {
 				let hasher = hasher.into_metadata();
 				let k1ty = clean_type_string(&quote!(#key1_type).to_string());
 				let k2ty = clean_type_string(&quote!(#key2_type).to_string());
 				let k2_hasher = key2_hasher.into_metadata();
+				let map = if is_child {
+					quote! { ChildMap }
+				} else {
+					quote! { DoubleMap }
+				};
 				quote!{
-					#scrate::metadata::StorageEntryType::DoubleMap {
+					#scrate::metadata::StorageEntryType::#map {
 						hasher: #scrate::metadata::#hasher,
 						key1: #scrate::metadata::DecodeDifferent::Encode(#k1ty),
 						key2: #scrate::metadata::DecodeDifferent::Encode(#k2ty),
@@ -1133,7 +1173,9 @@ pub(crate) struct DeclStorageTypeInfos<'a> {
 
 #[derive(Debug, Clone)]
 enum DeclStorageTypeInfosKind<'a> {
-	Simple,
+	Simple {
+		is_child: bool,
+	},
 	Map {
 		hasher: HasherKind,
 		key_type: &'a syn::Type,
@@ -1144,12 +1186,14 @@ enum DeclStorageTypeInfosKind<'a> {
 		key1_type: &'a syn::Type,
 		key2_type: &'a syn::Type,
 		key2_hasher: HasherKind,
+		is_child: bool,
 	}
 }
 
 fn get_type_infos(storage_type: &DeclStorageType) -> DeclStorageTypeInfos {
 	let (value_type, kind) = match storage_type {
-		DeclStorageType::Simple(ref st) => (st, DeclStorageTypeInfosKind::Simple),
+		DeclStorageType::Simple(ref st) => (st, DeclStorageTypeInfosKind::Simple { is_child: false }),
+		DeclStorageType::ChildValue(ref value) => (&value.value, DeclStorageTypeInfosKind::Simple { is_child: true }),
 		DeclStorageType::Map(ref map) => (&map.value, DeclStorageTypeInfosKind::Map {
 			hasher: map.hasher.inner.as_ref().map(|h| h.into()).unwrap_or(HasherKind::Blake2_256),
 			key_type: &map.key,
@@ -1165,6 +1209,14 @@ fn get_type_infos(storage_type: &DeclStorageType) -> DeclStorageTypeInfos {
 			key1_type: &map.key1,
 			key2_type: &map.key2.content,
 			key2_hasher: (&map.key2_hasher).into(),
+			is_child: false,
+		}),
+		DeclStorageType::ChildMap(ref map) => (&map.value, DeclStorageTypeInfosKind::DoubleMap {
+			hasher: map.hasher.inner.as_ref().map(|h| h.into()).unwrap_or(HasherKind::Blake2_256),
+			key1_type: &map.key1,
+			key2_type: &map.key2.content,
+			key2_hasher: (&map.key2_hasher).into(),
+			is_child: true,
 		}),
 	};
 
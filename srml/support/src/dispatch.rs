@@ -27,9 +27,10 @@ pub use srml_metadata::{
 };
 pub use sr_primitives::weights::{SimpleDispatchInfo, GetDispatchInfo, DispatchInfo, WeighData,
 	ClassifyDispatch,
-	TransactionPriority
+	TransactionPriority, Weight
 };
-pub use sr_primitives::traits::{Dispatchable, DispatchResult};
+pub use sr_primitives::traits::{Dispatchable, DispatchResult, ModuleDispatchError};
+pub use sr_primitives::DispatchError;
 
 /// A type that cannot be instantiated.
 pub enum Never {}
@@ -196,6 +197,13 @@ impl<T> Parameter for T where T: Codec + Clone + Eq {}
 /// # fn main() {}
 /// ```
 ///
+/// ## Error type
+///
+/// By default dispatchable functions return `&'static str` errors. A module can declare its own
+/// error type, usually generated with [`decl_error!`](./macro.decl_error.html), with
+/// `type Error = MyError;`. Functions without an explicit return type then return
+/// `DispatchResult<MyError>`, and the errors of the module are exposed in the runtime metadata.
+///
 /// ## Reserved Functions
 ///
 /// The following are reserved function signatures:
@@ -214,6 +222,11 @@ impl<T> Parameter for T where T: Codec + Clone + Eq {}
 /// * `offchain_worker`: Executes at the beginning of a block and produces extrinsics for a future block
 /// upon completion. Using this function will implement the
 /// [`OffchainWorker`](../sr_primitives/traits/trait.OffchainWorker.html) trait.
+///
+/// * `on_runtime_upgrade`: Executes once, at the beginning of the first block after the runtime's
+/// spec version changed, and returns the weight it consumed. It takes no parameters. Using this
+/// function will implement the [`OnRuntimeUpgrade`](../sr_primitives/traits/trait.OnRuntimeUpgrade.html)
+/// trait.
 #[macro_export]
 macro_rules! decl_module {
 	// Entry point #1.
@@ -239,6 +252,8 @@ macro_rules! decl_module {
 			{}
 			{}
 			{}
+			{}
+			{}
 			[]
 			$($t)*
 		);
@@ -270,6 +285,8 @@ macro_rules! decl_module {
 			{}
 			{}
 			{}
+			{}
+			{}
 			[]
 			$($t)*
 		);
@@ -285,7 +302,9 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_finalize:tt )* }
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $constants:tt )* }
+		{ $( $error_type:tt )* }
 		[ $( $dispatchables:tt )* ]
 		$(#[doc = $doc_attr:tt])*
 		$vis:vis fn deposit_event() = default;
@@ -300,7 +319,9 @@ macro_rules! decl_module {
 			{ $( $on_initialize )* }
 			{ $( $on_finalize )* }
 			{ $( $offchain )* }
+			{ $( $on_runtime_upgrade )* }
 			{ $( $constants )* }
+			{ $( $error_type )* }
 			[ $( $dispatchables )* ]
 			$($rest)*
 		);
@@ -314,7 +335,9 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_finalize:tt )* }
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $constants:tt )* }
+		{ $( $error_type:tt )* }
 		[ $( $dispatchables:tt )* ]
 		$(#[doc = $doc_attr:tt])*
 		$vis:vis fn deposit_event
@@ -333,7 +356,9 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{}
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $constants:tt )* }
+		{ $( $error_type:tt )* }
 		[ $( $dispatchables:tt )* ]
 		$(#[doc = $doc_attr:tt])*
 		fn on_finalize($($param_name:ident : $param:ty),* ) { $( $impl:tt )* }
@@ -348,7 +373,9 @@ macro_rules! decl_module {
 			{ $( $on_initialize )* }
 			{ fn on_finalize( $( $param_name : $param ),* ) { $( $impl )* } }
 			{ $( $offchain )* }
+			{ $( $on_runtime_upgrade )* }
 			{ $( $constants )* }
+			{ $( $error_type )* }
 			[ $( $dispatchables )* ]
 			$($rest)*
 		);
@@ -362,7 +389,9 @@ macro_rules! decl_module {
 		{}
 		{ $( $on_finalize:tt )* }
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $constants:tt )* }
+		{ $( $error_type:tt )* }
 		[ $( $dispatchables:tt )* ]
 		$(#[doc = $doc_attr:tt])*
 		fn on_initialize($($param_name:ident : $param:ty),* ) { $( $impl:tt )* }
@@ -377,7 +406,9 @@ macro_rules! decl_module {
 			{ fn on_initialize( $( $param_name : $param ),* ) { $( $impl )* } }
 			{ $( $on_finalize )* }
 			{ $( $offchain )* }
+			{ $( $on_runtime_upgrade )* }
 			{ $( $constants )* }
+			{ $( $error_type )* }
 			[ $( $dispatchables )* ]
 			$($rest)*
 		);
@@ -394,7 +425,9 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_finalize:tt )* }
 		{ }
+		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $constants:tt )* }
+		{ $( $error_type:tt )* }
 		[ $( $dispatchables:tt )* ]
 		$(#[doc = $doc_attr:tt])*
 		fn offchain_worker($($param_name:ident : $param:ty),* ) { $( $impl:tt )* }
@@ -411,7 +444,89 @@ macro_rules! decl_module {
 			{ $( $on_initialize )* }
 			{ $( $on_finalize )* }
 			{ fn offchain_worker( $( $param_name : $param ),* ) { $( $impl )* } }
+			{ $( $on_runtime_upgrade )* }
+			{ $( $constants )* }
+			{ $( $error_type )* }
+			[ $( $dispatchables )* ]
+			$($rest)*
+		);
+	};
+
+	(@normalize
+		$(#[$attr:meta])*
+		pub struct $mod_type:ident<
+			$trait_instance:ident: $trait_name:ident
+			$(<I>, I: $instantiable:path $(= $module_default_instance:path)?)?
+		>
+		for enum $call_type:ident where origin: $origin_type:ty, system = $system:ident
+		{ $( $other_where_bounds:tt )* }
+		{ $( $deposit_event:tt )* }
+		{ $( $on_initialize:tt )* }
+		{ $( $on_finalize:tt )* }
+		{ $( $offchain:tt )* }
+		{}
+		{ $( $constants:tt )* }
+		{ $( $error_type:tt )* }
+		[ $( $dispatchables:tt )* ]
+		$(#[doc = $doc_attr:tt])*
+		fn on_runtime_upgrade() -> $return:ty { $( $impl:tt )* }
+		$($rest:tt)*
+	) => {
+		$crate::decl_module!(@normalize
+			$(#[$attr])*
+			pub struct $mod_type<
+				$trait_instance: $trait_name$(<I>, I: $instantiable $(= $module_default_instance)?)?
+			>
+			for enum $call_type where origin: $origin_type, system = $system
+			{ $( $other_where_bounds )* }
+			{ $( $deposit_event )* }
+			{ $( $on_initialize )* }
+			{ $( $on_finalize )* }
+			{ $( $offchain )* }
+			{ fn on_runtime_upgrade() -> $return { $( $impl )* } }
 			{ $( $constants )* }
+			{ $( $error_type )* }
+			[ $( $dispatchables )* ]
+			$($rest)*
+		);
+	};
+
+	// This sets the error type of the module.
+	(@normalize
+		$(#[$attr:meta])*
+		pub struct $mod_type:ident<
+			$trait_instance:ident: $trait_name:ident
+			$(<I>, $instance:ident: $instantiable:path $(= $module_default_instance:path)?)?
+		>
+		for enum $call_type:ident where origin: $origin_type:ty, system = $system:ident
+		{ $( $other_where_bounds:tt )* }
+		{ $( $deposit_event:tt )* }
+		{ $( $on_initialize:tt )* }
+		{ $( $on_finalize:tt )* }
+		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
+		{ $( $constants:tt )* }
+		{}
+		[ $( $dispatchables:tt )* ]
+		$(#[doc = $doc_attr:tt])*
+		type Error = $error_type:ty;
+		$($rest:tt)*
+	) => {
+		$crate::decl_module!(@normalize
+			$(#[$attr])*
+			pub struct $mod_type<
+				$trait_instance: $trait_name
+				$( <I>, $instance: $instantiable $(= $module_default_instance)? )?
+			>
+			for enum $call_type where origin: $origin_type, system = $system
+			{ $( $other_where_bounds )* }
+			{ $( $deposit_event )* }
+			{ $( $on_initialize )* }
+			{ $( $on_finalize )* }
+			{ $( $offchain )* }
+			{ $( $on_runtime_upgrade )* }
+			{ $( $constants )* }
+			{ $error_type }
 			[ $( $dispatchables )* ]
 			$($rest)*
 		);
@@ -430,7 +545,9 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_finalize:tt )* }
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $constants:tt )* }
+		{ $( $error_type:tt )* }
 		[ $( $dispatchables:tt )* ]
 		$( #[doc = $doc_attr:tt] )*
 		const $name:ident: $ty:ty = $value:expr;
@@ -448,11 +565,13 @@ macro_rules! decl_module {
 			{ $( $on_initialize )* }
 			{ $( $on_finalize )* }
 			{ $( $offchain )* }
+			{ $( $on_runtime_upgrade )* }
 			{
 				$( $constants )*
 				$( #[doc = $doc_attr ] )*
 				$name: $ty = $value;
 			}
+			{ $( $error_type )* }
 			[ $( $dispatchables )* ]
 			$($rest)*
 		);
@@ -471,7 +590,9 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_finalize:tt )* }
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $constants:tt )* }
+		{ $( $error_type:tt )* }
 		[ $( $dispatchables:tt )* ]
 		$(#[doc = $doc_attr:tt])*
 		#[weight = $weight:expr]
@@ -491,7 +612,9 @@ macro_rules! decl_module {
 			{ $( $on_initialize )* }
 			{ $( $on_finalize )* }
 			{ $( $offchain )* }
+			{ $( $on_runtime_upgrade )* }
 			{ $( $constants )* }
+			{ $( $error_type )* }
 			[
 				$( $dispatchables )*
 				$(#[doc = $doc_attr])*
@@ -517,7 +640,9 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_finalize:tt )* }
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $constants:tt )* }
+		{ $( $error_type:tt )* }
 		[ $( $dispatchables:tt )* ]
 		$(#[doc = $doc_attr:tt])*
 		$fn_vis:vis fn $fn_name:ident(
@@ -536,7 +661,9 @@ macro_rules! decl_module {
 			{ $( $on_initialize )* }
 			{ $( $on_finalize )* }
 			{ $( $offchain )* }
+			{ $( $on_runtime_upgrade )* }
 			{ $( $constants )* }
+			{ $( $error_type )* }
 			[ $( $dispatchables )* ]
 			$(#[doc = $doc_attr])*
 			#[weight = $crate::dispatch::SimpleDispatchInfo::default()]
@@ -556,7 +683,9 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_finalize:tt )* }
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $constants:tt )* }
+		{ $( $error_type:tt )* }
 		[ $( $dispatchables:tt )* ]
 		$(#[doc = $doc_attr:tt])*
 		$(#[weight = $weight:expr])?
@@ -580,7 +709,9 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_finalize:tt )* }
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $constants:tt )* }
+		{ $( $error_type:tt )* }
 		[ $( $dispatchables:tt )* ]
 		$(#[doc = $doc_attr:tt])*
 		$(#[weight = $weight:expr])?
@@ -604,7 +735,9 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_finalize:tt )* }
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $constants:tt )* }
+		{ $( $error_type:tt )* }
 		[ $( $dispatchables:tt )* ]
 		$(#[doc = $doc_attr:tt])*
 		$(#[weight = $weight:expr])?
@@ -629,7 +762,9 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_finalize:tt )* }
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $constants:tt )* }
+		{ $( $error_type:tt )* }
 		[ $( $dispatchables:tt )* ]
 	) => {
 		$crate::decl_module!(@imp
@@ -643,10 +778,16 @@ macro_rules! decl_module {
 			{ $( $on_initialize )* }
 			{ $( $on_finalize )* }
 			{ $( $offchain )* }
+			{ $( $on_runtime_upgrade )* }
 			{ $( $constants )* }
+			{ $crate::decl_module!(@error_type $( $error_type )*) }
 		);
 	};
 
+	// The error type of the module, `&'static str` if none was declared.
+	(@error_type) => { &'static str };
+	(@error_type $error_type:ty) => { $error_type };
+
 	// Implementation of Call enum's .dispatch() method.
 	// TODO: this probably should be a different macro?
 
@@ -790,10 +931,34 @@ macro_rules! decl_module {
 		{}
 	};
 
+	(@impl_on_runtime_upgrade
+		$module:ident<$trait_instance:ident: $trait_name:ident$(<I>, $instance:ident: $instantiable:path)?>;
+		{ $( $other_where_bounds:tt )* }
+		fn on_runtime_upgrade() -> $return:ty { $( $impl:tt )* }
+	) => {
+		impl<$trait_instance: $trait_name$(<I>, $instance: $instantiable)?>
+			$crate::sr_primitives::traits::OnRuntimeUpgrade
+			for $module<$trait_instance$(, $instance)?> where $( $other_where_bounds )*
+		{
+			fn on_runtime_upgrade() -> $return { $( $impl )* }
+		}
+	};
+
+	(@impl_on_runtime_upgrade
+		$module:ident<$trait_instance:ident: $trait_name:ident$(<I>, $instance:ident: $instantiable:path)?>;
+		{ $( $other_where_bounds:tt )* }
+	) => {
+		impl<$trait_instance: $trait_name$(<I>, $instance: $instantiable)?>
+			$crate::sr_primitives::traits::OnRuntimeUpgrade
+			for $module<$trait_instance$(, $instance)?> where $( $other_where_bounds )*
+		{}
+	};
+
 	// Expansion for _origin_ dispatch functions with no return type.
 	(@impl_function
 		$module:ident<$trait_instance:ident: $trait_name:ident$(<I>, $instance:ident: $instantiable:path)?>;
 		$origin_ty:ty;
+		$error_type:ty;
 		$ignore:ident;
 		$(#[doc = $doc_attr:tt])*
 		$vis:vis fn $name:ident (
@@ -804,7 +969,7 @@ macro_rules! decl_module {
 		#[allow(unreachable_code)]
 		$vis fn $name(
 			$origin: $origin_ty $(, $param: $param_ty )*
-		) -> $crate::dispatch::Result {
+		) -> $crate::dispatch::DispatchResult<$error_type> {
 			{ $( $impl )* }
 			// May be unreachable.
 			Ok(())
@@ -815,6 +980,7 @@ macro_rules! decl_module {
 	(@impl_function
 		$module:ident<$trait_instance:ident: $trait_name:ident$(<I>, $instance:ident: $instantiable:path)?>;
 		$origin_ty:ty;
+		$error_type:ty;
 		$ignore:ident;
 		$(#[doc = $doc_attr:tt])*
 		$vis:vis fn $name:ident (
@@ -968,7 +1134,9 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_finalize:tt )* }
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $constants:tt )* }
+		{ $error_type:ty }
 	) => {
 		$crate::__check_reserved_fn_name! { $( $fn_name )* }
 
@@ -1001,6 +1169,14 @@ macro_rules! decl_module {
 			{ $( $other_where_bounds )* }
 			$( $offchain )*
 		}
+
+		$crate::decl_module! {
+			@impl_on_runtime_upgrade
+			$mod_type<$trait_instance: $trait_name $(<I>, $instance: $instantiable)?>;
+			{ $( $other_where_bounds )* }
+			$( $on_runtime_upgrade )*
+		}
+
 		$crate::decl_module! {
 			@impl_deposit_event
 			$mod_type<$trait_instance: $trait_name $(<I>, $instance: $instantiable)?>;
@@ -1020,6 +1196,7 @@ macro_rules! decl_module {
 					@impl_function
 					$mod_type<$trait_instance: $trait_name $(<I>, $fn_instance: $fn_instantiable)?>;
 					$origin_type;
+					$error_type;
 					$from;
 					$(#[doc = $doc_attr])*
 					$fn_vis fn $fn_name (
@@ -1150,7 +1327,8 @@ macro_rules! decl_module {
 		{
 			type Trait = $trait_instance;
 			type Origin = $origin_type;
-			fn dispatch(self, _origin: Self::Origin) -> $crate::dispatch::Result {
+			type Error = $error_type;
+			fn dispatch(self, _origin: Self::Origin) -> $crate::dispatch::DispatchResult<Self::Error> {
 				match self {
 					$(
 						$call_type::$fn_name( $( $param_name ),* ) => {
@@ -1158,7 +1336,7 @@ macro_rules! decl_module {
 								@call
 								$from
 								$mod_type<$trait_instance $(, $fn_instance)?> $fn_name _origin $system [ $( $param_name ),* ]
-							)
+							).map_err(Into::into)
 						},
 					)*
 					$call_type::__PhantomItem(_, _) => { unreachable!("__PhantomItem should never be used.") },
@@ -1178,9 +1356,14 @@ macro_rules! decl_module {
 			pub fn dispatch<D: $crate::dispatch::Dispatchable<Trait = $trait_instance>>(
 				d: D,
 				origin: D::Origin,
-			) -> $crate::dispatch::Result {
+			) -> $crate::dispatch::DispatchResult<D::Error> {
 				d.dispatch(origin)
 			}
+
+			#[doc(hidden)]
+			pub fn error_metadata() -> &'static [$crate::error::ErrorMetadata] {
+				<$error_type as $crate::error::ModuleErrorMetadata>::metadata()
+			}
 		}
 		$crate::__dispatch_impl_metadata! {
 			$mod_type<$trait_instance: $trait_name $(<I>, $instance: $instantiable)?>
@@ -1206,6 +1389,11 @@ pub trait IsSubType<T: Callable<R>, R> {
 }
 
 /// Implement a meta-dispatch module to dispatch to other dispatchers.
+///
+/// The `module` index of a `DispatchError` returned by a module is the position of the module in
+/// the outer `Call` enum, unless the index is given by the variants of an enum passed as
+/// `module_index`. `construct_runtime!` passes the order of the modules in the runtime metadata,
+/// which also counts the modules that have no `Call`.
 #[macro_export]
 macro_rules! impl_outer_dispatch {
 	(
@@ -1215,6 +1403,24 @@ macro_rules! impl_outer_dispatch {
 				$module:ident::$camelcase:ident,
 			)*
 		}
+	) => {
+		$crate::impl_outer_dispatch! {
+			$(#[$attr])*
+			pub enum $call_type for $runtime where origin: $origin, module_index: __CallModuleIndex {
+				$( $module::$camelcase, )*
+			}
+		}
+	};
+	(
+		$(#[$attr:meta])*
+		pub enum $call_type:ident for $runtime:ident where
+			origin: $origin:ty,
+			module_index: $module_index:ident
+		{
+			$(
+				$module:ident::$camelcase:ident,
+			)*
+		}
 	) => {
 		$(#[$attr])*
 		#[derive(Clone, PartialEq, Eq, $crate::codec::Encode, $crate::codec::Decode)]
@@ -1234,9 +1440,25 @@ macro_rules! impl_outer_dispatch {
 		impl $crate::dispatch::Dispatchable for $call_type {
 			type Origin = $origin;
 			type Trait = $call_type;
-			fn dispatch(self, origin: $origin) -> $crate::dispatch::Result {
+			type Error = $crate::dispatch::DispatchError;
+			fn dispatch(
+				self,
+				origin: $origin,
+			) -> $crate::dispatch::DispatchResult<$crate::dispatch::DispatchError> {
+				// The module index of an error when no `module_index` is given.
+				#[allow(dead_code, non_camel_case_types)]
+				enum __CallModuleIndex {
+					$( $camelcase, )*
+				}
+
 				match self {
-					$( $call_type::$camelcase(call) => call.dispatch(origin), )*
+					$(
+						$call_type::$camelcase(call) => call.dispatch(origin).map_err(|e| {
+							let mut error: $crate::dispatch::DispatchError = e.into();
+							error.module = Some($module_index::$camelcase as u8);
+							error
+						}),
+					)*
 				}
 			}
 		}
@@ -1525,6 +1747,9 @@ macro_rules! __check_reserved_fn_name {
 	(offchain_worker $( $rest:ident )*) => {
 		$crate::__check_reserved_fn_name!(@compile_error offchain_worker);
 	};
+	(on_runtime_upgrade $( $rest:ident )*) => {
+		$crate::__check_reserved_fn_name!(@compile_error on_runtime_upgrade);
+	};
 	($t:ident $( $rest:ident )*) => {
 		$crate::__check_reserved_fn_name!($( $rest )*);
 	};
@@ -1558,7 +1783,7 @@ macro_rules! __check_reserved_fn_name {
 #[allow(dead_code)]
 mod tests {
 	use super::*;
-	use crate::sr_primitives::traits::{OnInitialize, OnFinalize};
+	use crate::sr_primitives::traits::{OnInitialize, OnFinalize, OnRuntimeUpgrade};
 	use sr_primitives::weights::{DispatchInfo, DispatchClass};
 
 	pub trait Trait: system::Trait + Sized where Self::AccountId: From<u32> {
@@ -1593,6 +1818,7 @@ mod tests {
 			fn on_initialize(n: T::BlockNumber) { if n.into() == 42 { panic!("on_initialize") } }
 			fn on_finalize(n: T::BlockNumber) { if n.into() == 42 { panic!("on_finalize") } }
 			fn offchain_worker() {}
+			fn on_runtime_upgrade() -> Weight { 10 }
 
 			#[weight = SimpleDispatchInfo::FixedOperational(5)]
 			fn operational(_origin) { unreachable!() }
@@ -1733,6 +1959,11 @@ mod tests {
 		<Module<TraitImpl> as OnFinalize<u32>>::on_finalize(42);
 	}
 
+	#[test]
+	fn on_runtime_upgrade_should_work() {
+		assert_eq!(<Module<TraitImpl> as OnRuntimeUpgrade>::on_runtime_upgrade(), 10);
+	}
+
 	#[test]
 	fn weight_should_attach_to_call_enum() {
 		// operational.
@@ -0,0 +1,149 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Macro for declaring a module error.
+
+#[doc(hidden)]
+pub use sr_primitives::traits::ModuleDispatchError;
+pub use srml_metadata::{ErrorMetadata, DecodeDifferent};
+
+/// Declare an error type for a runtime module.
+///
+/// The generated enum always contains an `Other(&'static str)` variant as its first variant, so
+/// that plain string errors (e.g. from `ensure_signed` or the `Currency` trait) can still be
+/// propagated with `?`. Every other variant is a unit variant; the index of the variant is the
+/// `error` value of the `DispatchError` reported to the outside world.
+///
+/// To use the error in a module, declare `type Error = Error;` in `decl_module!`.
+///
+/// ```
+/// # use srml_support::{decl_error, decl_module};
+/// decl_error! {
+///     /// Errors that can occur in my module.
+///     pub enum MyError {
+///         /// Hey this is an error message that indicates bla.
+///         MyCoolErrorMessage,
+///         /// You are just not cool enough for my module!
+///         YouAreNotCoolEnough,
+///     }
+/// }
+///
+/// # use srml_system::{self as system, Trait, ensure_signed};
+/// decl_module! {
+///     pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+///         type Error = MyError;
+///
+///         fn do_something(origin) -> Result<(), MyError> {
+///             ensure_signed(origin)?;
+///             Err(MyError::YouAreNotCoolEnough)
+///         }
+///     }
+/// }
+///
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! decl_error {
+	(
+		$(#[$attr:meta])*
+		pub enum $error:ident {
+			$(
+				$( #[doc = $doc_attr:tt] )*
+				$name:ident
+			),*
+			$(,)?
+		}
+	) => {
+		#[derive(Clone, PartialEq, Eq)]
+		#[cfg_attr(feature = "std", derive(Debug))]
+		$(#[$attr])*
+		pub enum $error {
+			/// An error that is not described by any of the other variants.
+			Other(&'static str),
+			$(
+				$( #[doc = $doc_attr] )*
+				$name
+			),*
+		}
+
+		impl $crate::error::ModuleDispatchError for $error {
+			fn as_u8(&self) -> u8 {
+				#[allow(dead_code, non_camel_case_types)]
+				enum __Index {
+					Other,
+					$( $name ),*
+				}
+
+				match self {
+					$error::Other(_) => __Index::Other as u8,
+					$( $error::$name => __Index::$name as u8, )*
+				}
+			}
+
+			fn as_str(&self) -> &'static str {
+				match self {
+					$error::Other(err) => *err,
+					$( $error::$name => stringify!($name), )*
+				}
+			}
+		}
+
+		impl From<&'static str> for $error {
+			fn from(val: &'static str) -> $error {
+				$error::Other(val)
+			}
+		}
+
+		impl From<$error> for &'static str {
+			fn from(err: $error) -> &'static str {
+				use $crate::error::ModuleDispatchError;
+				err.as_str()
+			}
+		}
+
+		impl $crate::error::ModuleErrorMetadata for $error {
+			fn metadata() -> &'static [$crate::error::ErrorMetadata] {
+				&[
+					$crate::error::ErrorMetadata {
+						name: $crate::error::DecodeDifferent::Encode("Other"),
+						documentation: $crate::error::DecodeDifferent::Encode(&[
+							" An error that is not described by any of the other variants.",
+						]),
+					},
+					$(
+						$crate::error::ErrorMetadata {
+							name: $crate::error::DecodeDifferent::Encode(stringify!($name)),
+							documentation: $crate::error::DecodeDifferent::Encode(&[ $( $doc_attr ),* ]),
+						}
+					),*
+				]
+			}
+		}
+	};
+}
+
+/// Something that can provide the metadata of the errors of a module.
+pub trait ModuleErrorMetadata {
+	/// The metadata of every error, in variant order, so that the `error` index of a
+	/// `DispatchError` can be used to look up its entry.
+	fn metadata() -> &'static [ErrorMetadata];
+}
+
+impl ModuleErrorMetadata for &'static str {
+	fn metadata() -> &'static [ErrorMetadata] {
+		&[]
+	}
+}
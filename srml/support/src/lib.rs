@@ -39,7 +39,9 @@ pub use paste;
 #[doc(hidden)]
 pub use runtime_io::with_storage;
 
-pub use self::storage::hashed::{Twox256, Twox128, Blake2_256, Blake2_128, Twox64Concat};
+pub use self::storage::hashed::{
+	Twox256, Twox128, Blake2_256, Blake2_128, Twox64Concat, Blake2_128Concat, Sha2_256,
+};
 
 #[macro_use]
 pub mod dispatch;
@@ -49,6 +51,8 @@ mod hashable;
 #[macro_use]
 pub mod event;
 #[macro_use]
+pub mod error;
+#[macro_use]
 mod origin;
 #[macro_use]
 pub mod metadata;
@@ -61,7 +65,10 @@ pub mod unsigned;
 mod double_map;
 pub mod traits;
 
-pub use self::storage::{StorageValue, StorageMap, StorageLinkedMap, StorageDoubleMap};
+pub use self::storage::{
+	StorageValue, StorageMap, StorageLinkedMap, StorageDoubleMap, IterableStorageMap,
+	IterableStorageDoubleMap, StorageChildMap, StorageChildValue,
+};
 pub use self::hashable::Hashable;
 pub use self::dispatch::{Parameter, Dispatchable, Callable, IsSubType};
 pub use self::double_map::StorageDoubleMapWithHasher;
@@ -279,7 +286,7 @@ macro_rules! for_each_tuple {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use codec::Codec;
+	use codec::{Codec, Encode};
 	use runtime_io::{with_externalities, Blake2Hasher};
 	pub use srml_metadata::{
 		DecodeDifferent, StorageEntryMetadata, StorageMetadata, StorageEntryType,
@@ -315,6 +322,12 @@ mod tests {
 			pub GenericDataDM: double_map T::BlockNumber, twox_128(T::BlockNumber) => T::BlockNumber;
 			pub GenericData2DM: double_map T::BlockNumber, twox_256(T::BlockNumber) => Option<T::BlockNumber>;
 			pub AppendableDM: double_map u32, blake2_256(T::BlockNumber) => Vec<u32>;
+
+			pub IterableMap: map hasher(blake2_128_concat) u32 => Option<u64>;
+			pub IterableTwoxMap: map hasher(twox_64_concat) u32 => Option<u64>;
+			pub IterableDM: double_map u32, twox_64_concat(u32) => Option<u64>;
+			pub ChildDM: child_map u32, blake2_256(u32) => u64;
+			pub ChildV get(child_v) build(|_| 7u64): child_value u64;
 		}
 	}
 
@@ -501,6 +514,158 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn map_iter_and_drain_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			type Map = IterableMap;
+
+			// Another map with the same value type must not be visited.
+			OptionLinkedMap::insert(1, 1);
+			for i in 0..4u32 {
+				Map::insert(i, i as u64 * 10);
+			}
+
+			let mut items = Map::iter().collect::<Vec<_>>();
+			items.sort();
+			assert_eq!(items, vec![(0, 0), (1, 10), (2, 20), (3, 30)]);
+
+			let mut drained = Map::drain().collect::<Vec<_>>();
+			drained.sort();
+			assert_eq!(drained, items);
+			assert_eq!(Map::iter().count(), 0);
+			assert_eq!(OptionLinkedMap::get(1), Some(1));
+		});
+	}
+
+	#[test]
+	fn twox_64_concat_map_iter_and_drain_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			type Map = IterableTwoxMap;
+
+			// The blake2 iterable map with the same key and value types must not be visited.
+			IterableMap::insert(7, 70);
+			for i in 0..4u32 {
+				Map::insert(i, i as u64 * 10);
+			}
+
+			let mut items = Map::iter().collect::<Vec<_>>();
+			items.sort();
+			assert_eq!(items, vec![(0, 0), (1, 10), (2, 20), (3, 30)]);
+
+			let mut drained = Map::drain().collect::<Vec<_>>();
+			drained.sort();
+			assert_eq!(drained, items);
+			assert_eq!(Map::iter().count(), 0);
+			assert_eq!(IterableMap::get(7), Some(70));
+		});
+	}
+
+	#[test]
+	fn double_map_iter_prefix_and_drain_prefix_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			type DoubleMap = IterableDM;
+
+			DoubleMap::insert(&1u32, &1u32, &11u64);
+			DoubleMap::insert(&1u32, &2u32, &12u64);
+			DoubleMap::insert(&2u32, &1u32, &21u64);
+
+			let mut items = DoubleMap::iter_prefix(&1u32).collect::<Vec<_>>();
+			items.sort();
+			assert_eq!(items, vec![(1, 11), (2, 12)]);
+
+			let mut drained = DoubleMap::drain_prefix(&1u32).collect::<Vec<_>>();
+			drained.sort();
+			assert_eq!(drained, items);
+			assert_eq!(DoubleMap::iter_prefix(&1u32).count(), 0);
+			assert_eq!(DoubleMap::get(&2u32, &1u32), Some(21));
+		});
+	}
+
+	#[test]
+	fn child_map_basic_insert_remove_remove_child_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			type ChildMap = ChildDM;
+
+			let key1 = 17u32;
+			let key2 = 18u32;
+			let child_key = ChildMap::child_storage_key(&key1);
+			assert!(child_key.starts_with(b":child_storage:default:"));
+			assert!(child_key != ChildMap::child_storage_key(&(key1 + 1)));
+
+			// get / insert / take
+			assert_eq!(ChildMap::get(&key1, &key2), 0u64);
+			ChildMap::insert(&key1, &key2, &4u64);
+			assert!(ChildMap::exists(&key1, &key2));
+			assert_eq!(ChildMap::get(&key1, &key2), 4u64);
+			assert_eq!(ChildMap::take(&key1, &key2), 4u64);
+			assert!(!ChildMap::exists(&key1, &key2));
+
+			// mutate
+			ChildMap::mutate(&key1, &key2, |val| {
+				*val = 15;
+			});
+			assert_eq!(ChildMap::get(&key1, &key2), 15u64);
+
+			// the value lives in the child trie, not in the top trie
+			assert_eq!(
+				storage::child::get_raw(&child_key, &runtime_io::blake2_256(&key2.encode())),
+				Some(15u64.encode()),
+			);
+
+			// remove
+			ChildMap::remove(&key1, &key2);
+			assert_eq!(ChildMap::get(&key1, &key2), 0u64);
+
+			// remove child
+			let empty_root = ChildMap::child_root(&key1);
+			ChildMap::insert(&key1, &key2, &4u64);
+			ChildMap::insert(&key1, &(key2 + 1), &4u64);
+			ChildMap::insert(&(key1 + 1), &key2, &4u64);
+			assert!(ChildMap::child_root(&key1) != empty_root);
+			ChildMap::remove_child(&key1);
+			assert_eq!(ChildMap::get(&key1, &key2), 0u64);
+			assert_eq!(ChildMap::get(&key1, &(key2 + 1)), 0u64);
+			assert_eq!(ChildMap::get(&(key1 + 1), &key2), 4u64);
+			assert_eq!(ChildMap::child_root(&key1), empty_root);
+		});
+	}
+
+	#[test]
+	fn child_value_basic_put_take_kill_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			let child_key = ChildV::child_storage_key();
+			assert!(child_key.starts_with(b":child_storage:default:"));
+
+			// built at genesis
+			assert!(ChildV::exists());
+			assert_eq!(Module::<Test>::child_v(), 7u64);
+
+			// get / put / take
+			ChildV::put(4u64);
+			assert_eq!(ChildV::get(), 4u64);
+			assert_eq!(ChildV::take(), 4u64);
+			assert!(!ChildV::exists());
+			assert_eq!(ChildV::get(), 0u64);
+
+			// mutate
+			ChildV::mutate(|val| {
+				*val = 15;
+			});
+			assert_eq!(ChildV::get(), 15u64);
+
+			// the value lives in the child trie, not in the top trie
+			let final_key = runtime_io::twox_128(b"Example ChildV");
+			assert_eq!(storage::child::get_raw(&child_key, &final_key), Some(15u64.encode()));
+			assert_eq!(storage::unhashed::get_raw(&final_key), None);
+
+			// kill
+			assert!(ChildV::child_root() != ChildDM::child_root(&0));
+			ChildV::kill();
+			assert!(!ChildV::exists());
+			assert_eq!(ChildV::child_root(), ChildDM::child_root(&0));
+		});
+	}
+
 	const EXPECTED_METADATA: StorageMetadata = StorageMetadata {
 		prefix: DecodeDifferent::Encode("Example"),
 		entries: DecodeDifferent::Encode(
@@ -621,6 +786,73 @@ mod tests {
 					),
 					documentation: DecodeDifferent::Encode(&[]),
 				},
+				StorageEntryMetadata {
+					name: DecodeDifferent::Encode("IterableMap"),
+					modifier: StorageEntryModifier::Optional,
+					ty: StorageEntryType::Map {
+						hasher: StorageHasher::Blake2_128Concat,
+						key: DecodeDifferent::Encode("u32"),
+						value: DecodeDifferent::Encode("u64"),
+						is_linked: false,
+					},
+					default: DecodeDifferent::Encode(
+						DefaultByteGetter(&__GetByteStructIterableMap(PhantomData::<Test>))
+					),
+					documentation: DecodeDifferent::Encode(&[]),
+				},
+				StorageEntryMetadata {
+					name: DecodeDifferent::Encode("IterableTwoxMap"),
+					modifier: StorageEntryModifier::Optional,
+					ty: StorageEntryType::Map {
+						hasher: StorageHasher::Twox64Concat,
+						key: DecodeDifferent::Encode("u32"),
+						value: DecodeDifferent::Encode("u64"),
+						is_linked: false,
+					},
+					default: DecodeDifferent::Encode(
+						DefaultByteGetter(&__GetByteStructIterableTwoxMap(PhantomData::<Test>))
+					),
+					documentation: DecodeDifferent::Encode(&[]),
+				},
+				StorageEntryMetadata {
+					name: DecodeDifferent::Encode("IterableDM"),
+					modifier: StorageEntryModifier::Optional,
+					ty: StorageEntryType::DoubleMap {
+						hasher: StorageHasher::Blake2_256,
+						key1: DecodeDifferent::Encode("u32"),
+						key2: DecodeDifferent::Encode("u32"),
+						value: DecodeDifferent::Encode("u64"),
+						key2_hasher: StorageHasher::Twox64Concat,
+					},
+					default: DecodeDifferent::Encode(
+						DefaultByteGetter(&__GetByteStructIterableDM(PhantomData::<Test>))
+					),
+					documentation: DecodeDifferent::Encode(&[]),
+				},
+				StorageEntryMetadata {
+					name: DecodeDifferent::Encode("ChildDM"),
+					modifier: StorageEntryModifier::Default,
+					ty: StorageEntryType::ChildMap {
+						hasher: StorageHasher::Blake2_256,
+						key1: DecodeDifferent::Encode("u32"),
+						key2: DecodeDifferent::Encode("u32"),
+						value: DecodeDifferent::Encode("u64"),
+						key2_hasher: StorageHasher::Blake2_256,
+					},
+					default: DecodeDifferent::Encode(
+						DefaultByteGetter(&__GetByteStructChildDM(PhantomData::<Test>))
+					),
+					documentation: DecodeDifferent::Encode(&[]),
+				},
+				StorageEntryMetadata {
+					name: DecodeDifferent::Encode("ChildV"),
+					modifier: StorageEntryModifier::Default,
+					ty: StorageEntryType::ChildPlain(DecodeDifferent::Encode("u64")),
+					default: DecodeDifferent::Encode(
+						DefaultByteGetter(&__GetByteStructChildV(PhantomData::<Test>))
+					),
+					documentation: DecodeDifferent::Encode(&[]),
+				},
 			]
 		),
 	};
@@ -17,7 +17,7 @@
 pub use srml_metadata::{
 	DecodeDifferent, FnEncode, RuntimeMetadata, ModuleMetadata, RuntimeMetadataLastVersion,
 	DefaultByteGetter, RuntimeMetadataPrefixed, StorageEntryMetadata, StorageMetadata,
-	StorageEntryType, StorageEntryModifier, DefaultByte, StorageHasher
+	StorageEntryType, StorageEntryModifier, DefaultByte, StorageHasher, ErrorMetadata
 };
 
 /// Implements the metadata support for the given runtime and all its modules.
@@ -96,6 +96,11 @@ macro_rules! __runtime_modules_to_metadata {
 					$crate::metadata::FnEncode(
 						$mod::$module::<$runtime $(, $mod::$instance )?>::module_constants_metadata
 					)
+				),
+				errors: $crate::metadata::DecodeDifferent::Encode(
+					$crate::metadata::FnEncode(
+						$mod::$module::<$runtime $(, $mod::$instance )?>::error_metadata
+					)
 				)
 			};
 			$( $rest )*
@@ -227,6 +232,7 @@ mod tests {
 	use srml_metadata::{
 		EventMetadata, StorageEntryModifier, StorageEntryType, FunctionMetadata, StorageEntryMetadata,
 		ModuleMetadata, RuntimeMetadataPrefixed, DefaultByte, ModuleConstantMetadata, DefaultByteGetter,
+		ErrorMetadata,
 	};
 	use codec::{Encode, Decode};
 	use crate::traits::Get;
@@ -296,9 +302,21 @@ mod tests {
 
 		decl_module! {
 			pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+				type Error = Error;
+
 				fn aux_0(_origin) -> Result { unreachable!() }
 			}
 		}
+
+		crate::decl_error! {
+			pub enum Error {
+				/// Some user input error
+				UserInputError,
+				/// Something bad happened
+				/// this could be due to many reasons
+				BadThingHappened,
+			}
+		}
 	}
 
 	mod event_module2 {
@@ -447,6 +465,7 @@ mod tests {
 						}
 					])
 				),
+				errors: DecodeDifferent::Encode(FnEncode(|| &[])),
 			},
 			ModuleMetadata {
 				name: DecodeDifferent::Encode("Module"),
@@ -469,6 +488,25 @@ mod tests {
 					])
 				)),
 				constants: DecodeDifferent::Encode(FnEncode(|| &[])),
+				errors: DecodeDifferent::Encode(FnEncode(|| &[
+					ErrorMetadata {
+						name: DecodeDifferent::Encode("Other"),
+						documentation: DecodeDifferent::Encode(&[
+							" An error that is not described by any of the other variants.",
+						]),
+					},
+					ErrorMetadata {
+						name: DecodeDifferent::Encode("UserInputError"),
+						documentation: DecodeDifferent::Encode(&[" Some user input error"]),
+					},
+					ErrorMetadata {
+						name: DecodeDifferent::Encode("BadThingHappened"),
+						documentation: DecodeDifferent::Encode(&[
+							" Something bad happened",
+							" this could be due to many reasons",
+						]),
+					},
+				])),
 			},
 			ModuleMetadata {
 				name: DecodeDifferent::Encode("Module2"),
@@ -505,6 +543,7 @@ mod tests {
 					])
 				)),
 				constants: DecodeDifferent::Encode(FnEncode(|| &[])),
+				errors: DecodeDifferent::Encode(FnEncode(|| &[])),
 			},
 		])
 	};
@@ -573,7 +573,7 @@ macro_rules! __decl_outer_dispatch {
 		;
 	) => {
 		$crate::impl_outer_dispatch!(
-			pub enum Call for $runtime where origin: Origin {
+			pub enum Call for $runtime where origin: Origin, module_index: __RuntimeModuleIndex {
 				$( $parsed_modules::$parsed_name, )*
 			}
 		);
@@ -650,6 +650,14 @@ macro_rules! __decl_runtime_metadata {
 			)*
 		};
 	) => {
+		/// The index of every module in the runtime metadata, reported as the `module` of a
+		/// `DispatchError`.
+		#[doc(hidden)]
+		#[allow(dead_code, non_camel_case_types)]
+		enum __RuntimeModuleIndex {
+			$( $parsed_name, )*
+		}
+
 		$crate::impl_runtime_metadata!(
 			for $runtime with modules
 				$( $parsed_modules::Module $( < $module_instance > )? as $parsed_name
@@ -90,6 +90,11 @@ pub fn kill_storage(storage_key: &[u8]) {
 	runtime_io::kill_child_storage(storage_key)
 }
 
+/// Root of the `storage_key` child trie, as it would be committed to by the parent storage root.
+pub fn root(storage_key: &[u8]) -> Vec<u8> {
+	runtime_io::child_storage_root(storage_key)
+}
+
 /// Ensure `key` has no explicit entry in storage.
 pub fn kill(storage_key: &[u8], key: &[u8]) {
 	runtime_io::clear_child_storage(storage_key, key);
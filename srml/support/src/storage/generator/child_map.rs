@@ -0,0 +1,182 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use rstd::prelude::*;
+use codec::{Codec, Encode};
+use primitives::storage::well_known_keys::CHILD_STORAGE_KEY_PREFIX;
+use crate::{storage::{self, child, hashed::StorageHasher}, rstd::borrow::Borrow};
+
+/// Generator for `StorageChildMap` used by `decl_storage`.
+///
+/// # Mapping of keys to a storage path
+///
+/// Each first key owns a child trie, whose storage key is
+/// `:child_storage:default: ++ Hasher1(key1_prefix ++ key1)`. The value for (key1, key2) is then
+/// stored in that child trie at `Hasher2(key2)`.
+///
+/// All the entries sharing a first key can thus be removed at once, and their root can be
+/// committed to or proven against independently of the rest of the storage.
+pub trait StorageChildMap<K1: Encode, K2: Encode, V: Codec> {
+	/// The type that get/take returns.
+	type Query;
+
+	/// Hasher for the first key.
+	type Hasher1: StorageHasher;
+
+	/// Hasher for the second key.
+	type Hasher2: StorageHasher;
+
+	/// Get the prefix for first key.
+	fn key1_prefix() -> &'static [u8];
+
+	/// Convert an optional value retrieved from storage to the type queried.
+	fn from_optional_value_to_query(v: Option<V>) -> Self::Query;
+
+	/// Convert a query to an optional value into storage.
+	fn from_query_to_optional_value(v: Self::Query) -> Option<V>;
+
+	/// Generate the storage key of the child trie for the first key.
+	fn storage_child_map_child_key<KArg1>(k1: &KArg1) -> Vec<u8>
+	where
+		KArg1: ?Sized + Encode,
+		K1: Borrow<KArg1>,
+	{
+		let mut final_key1 = Self::key1_prefix().to_vec();
+		k1.encode_to(&mut final_key1);
+
+		let mut child_key = CHILD_STORAGE_KEY_PREFIX.to_vec();
+		child_key.extend_from_slice(b"default:");
+		child_key.extend_from_slice(Self::Hasher1::hash(&final_key1).as_ref());
+		child_key
+	}
+
+	/// Generate the key used in the child trie for the second key.
+	fn storage_child_map_final_key2<KArg2>(k2: &KArg2) -> <Self::Hasher2 as StorageHasher>::Output
+	where
+		KArg2: ?Sized + Encode,
+		K2: Borrow<KArg2>,
+	{
+		k2.using_encoded(Self::Hasher2::hash)
+	}
+}
+
+impl<K1, K2, V, G> storage::StorageChildMap<K1, K2, V> for G
+where
+	K1: Encode,
+	K2: Encode,
+	V: Codec,
+	G: StorageChildMap<K1, K2, V>,
+{
+	type Query = G::Query;
+
+	fn child_storage_key<KArg1>(k1: &KArg1) -> Vec<u8> where KArg1: ?Sized + Encode, K1: Borrow<KArg1> {
+		Self::storage_child_map_child_key(k1)
+	}
+
+	fn exists<KArg1, KArg2>(k1: &KArg1, k2: &KArg2) -> bool
+	where
+		K1: Borrow<KArg1>,
+		K2: Borrow<KArg2>,
+		KArg1: ?Sized + Encode,
+		KArg2: ?Sized + Encode,
+	{
+		child::exists(
+			&Self::storage_child_map_child_key(k1),
+			Self::storage_child_map_final_key2(k2).as_ref(),
+		)
+	}
+
+	fn get<KArg1, KArg2>(k1: &KArg1, k2: &KArg2) -> Self::Query
+	where
+		K1: Borrow<KArg1>,
+		K2: Borrow<KArg2>,
+		KArg1: ?Sized + Encode,
+		KArg2: ?Sized + Encode,
+	{
+		G::from_optional_value_to_query(child::get(
+			&Self::storage_child_map_child_key(k1),
+			Self::storage_child_map_final_key2(k2).as_ref(),
+		))
+	}
+
+	fn take<KArg1, KArg2>(k1: &KArg1, k2: &KArg2) -> Self::Query
+	where
+		K1: Borrow<KArg1>,
+		K2: Borrow<KArg2>,
+		KArg1: ?Sized + Encode,
+		KArg2: ?Sized + Encode,
+	{
+		G::from_optional_value_to_query(child::take(
+			&Self::storage_child_map_child_key(k1),
+			Self::storage_child_map_final_key2(k2).as_ref(),
+		))
+	}
+
+	fn insert<KArg1, KArg2, VArg>(k1: &KArg1, k2: &KArg2, val: &VArg)
+	where
+		K1: Borrow<KArg1>,
+		K2: Borrow<KArg2>,
+		V: Borrow<VArg>,
+		KArg1: ?Sized + Encode,
+		KArg2: ?Sized + Encode,
+		VArg: ?Sized + Encode,
+	{
+		child::put(
+			&Self::storage_child_map_child_key(k1),
+			Self::storage_child_map_final_key2(k2).as_ref(),
+			&val.borrow(),
+		)
+	}
+
+	fn remove<KArg1, KArg2>(k1: &KArg1, k2: &KArg2)
+	where
+		K1: Borrow<KArg1>,
+		K2: Borrow<KArg2>,
+		KArg1: ?Sized + Encode,
+		KArg2: ?Sized + Encode,
+	{
+		child::kill(
+			&Self::storage_child_map_child_key(k1),
+			Self::storage_child_map_final_key2(k2).as_ref(),
+		)
+	}
+
+	fn remove_child<KArg1>(k1: &KArg1) where KArg1: ?Sized + Encode, K1: Borrow<KArg1> {
+		child::kill_storage(&Self::storage_child_map_child_key(k1))
+	}
+
+	fn child_root<KArg1>(k1: &KArg1) -> Vec<u8> where KArg1: ?Sized + Encode, K1: Borrow<KArg1> {
+		child::root(&Self::storage_child_map_child_key(k1))
+	}
+
+	fn mutate<KArg1, KArg2, R, F>(k1: &KArg1, k2: &KArg2, f: F) -> R
+	where
+		K1: Borrow<KArg1>,
+		K2: Borrow<KArg2>,
+		KArg1: ?Sized + Encode,
+		KArg2: ?Sized + Encode,
+		F: FnOnce(&mut Self::Query) -> R,
+	{
+		let mut val = G::get(k1, k2);
+
+		let ret = f(&mut val);
+		match G::from_query_to_optional_value(val) {
+			Some(ref val) => G::insert(k1, k2, val),
+			None => G::remove(k1, k2),
+		}
+		ret
+	}
+}
@@ -0,0 +1,100 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use rstd::prelude::*;
+use rstd::borrow::Borrow;
+use codec::Codec;
+use primitives::storage::well_known_keys::CHILD_STORAGE_KEY_PREFIX;
+use crate::storage::{self, child, hashed::{Twox128, StorageHasher}};
+
+/// Generator for `StorageChildValue` used by `decl_storage`.
+///
+/// # Mapping of keys to a storage path
+///
+/// The value owns a child trie, whose storage key is
+/// `:child_storage:default: ++ Twox128(unhashed_key)`. The value is then stored in that child trie
+/// at `Twox128(unhashed_key)`.
+pub trait StorageChildValue<T: Codec> {
+	/// The type that get/take returns.
+	type Query;
+
+	/// Unhashed key used in storage
+	fn unhashed_key() -> &'static [u8];
+
+	/// Convert an optional value retrieved from storage to the type queried.
+	fn from_optional_value_to_query(v: Option<T>) -> Self::Query;
+
+	/// Convert a query to an optional value into storage.
+	fn from_query_to_optional_value(v: Self::Query) -> Option<T>;
+
+	/// Generate the storage key of the child trie.
+	fn storage_child_value_child_key() -> Vec<u8> {
+		let mut child_key = CHILD_STORAGE_KEY_PREFIX.to_vec();
+		child_key.extend_from_slice(b"default:");
+		child_key.extend_from_slice(&Twox128::hash(Self::unhashed_key()));
+		child_key
+	}
+
+	/// Generate the key used in the child trie for the value.
+	fn storage_child_value_final_key() -> [u8; 16] {
+		Twox128::hash(Self::unhashed_key())
+	}
+}
+
+impl<T: Codec, G: StorageChildValue<T>> storage::StorageChildValue<T> for G {
+	type Query = G::Query;
+
+	fn child_storage_key() -> Vec<u8> {
+		Self::storage_child_value_child_key()
+	}
+
+	fn exists() -> bool {
+		child::exists(&Self::storage_child_value_child_key(), &Self::storage_child_value_final_key())
+	}
+
+	fn get() -> Self::Query {
+		let value = child::get(&Self::storage_child_value_child_key(), &Self::storage_child_value_final_key());
+		G::from_optional_value_to_query(value)
+	}
+
+	fn put<Arg: Borrow<T>>(val: Arg) {
+		child::put(&Self::storage_child_value_child_key(), &Self::storage_child_value_final_key(), val.borrow())
+	}
+
+	fn kill() {
+		child::kill_storage(&Self::storage_child_value_child_key())
+	}
+
+	fn child_root() -> Vec<u8> {
+		child::root(&Self::storage_child_value_child_key())
+	}
+
+	fn mutate<R, F: FnOnce(&mut G::Query) -> R>(f: F) -> R {
+		let mut val = G::get();
+
+		let ret = f(&mut val);
+		match G::from_query_to_optional_value(val) {
+			Some(ref val) => G::put(val),
+			None => G::kill(),
+		}
+		ret
+	}
+
+	fn take() -> G::Query {
+		let value = child::take(&Self::storage_child_value_child_key(), &Self::storage_child_value_final_key());
+		G::from_optional_value_to_query(value)
+	}
+}
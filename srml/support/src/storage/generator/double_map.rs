@@ -16,7 +16,10 @@
 
 use rstd::prelude::*;
 use codec::{Codec, Encode, EncodeAppend};
-use crate::{storage::{self, unhashed, hashed::StorageHasher}, rstd::borrow::Borrow};
+use crate::{
+	storage::{self, unhashed, PrefixIterator, hashed::{StorageHasher, ReversibleStorageHasher}},
+	rstd::borrow::Borrow,
+};
 
 /// Generator for `StorageDoubleMap` used by `decl_storage`.
 ///
@@ -28,6 +31,9 @@ use crate::{storage::{self, unhashed, hashed::StorageHasher}, rstd::borrow::Borr
 ///
 /// Thus value for (key1, key2) is stored at `Hasher1(key1_prefix ++ key1) ++ Hasher2(key2)`.
 ///
+/// If `Hasher2` is a [`ReversibleStorageHasher`](../hashed/trait.ReversibleStorageHasher.html), the
+/// entries sharing a first key can be iterated with their second keys.
+///
 /// /!\ be careful while choosing the Hash, indeed malicious could craft second keys to lower the
 /// trie.
 pub trait StorageDoubleMap<K1: Encode, K2: Encode, V: Codec> {
@@ -192,3 +198,22 @@ where
 		Ok(())
 	}
 }
+
+impl<K1, K2, V, G> storage::IterableStorageDoubleMap<K1, K2, V> for G
+where
+	K1: Encode,
+	K2: Codec,
+	V: Codec,
+	G: StorageDoubleMap<K1, K2, V>,
+	G::Hasher2: ReversibleStorageHasher,
+{
+	type Iterator = PrefixIterator<K2, V, G::Hasher2>;
+
+	fn iter_prefix<KArg1>(k1: &KArg1) -> Self::Iterator where KArg1: ?Sized + Encode, K1: Borrow<KArg1> {
+		PrefixIterator::new(Self::storage_double_map_final_key1(k1).as_ref().to_vec(), false)
+	}
+
+	fn drain_prefix<KArg1>(k1: &KArg1) -> Self::Iterator where KArg1: ?Sized + Encode, K1: Borrow<KArg1> {
+		PrefixIterator::new(Self::storage_double_map_final_key1(k1).as_ref().to_vec(), true)
+	}
+}
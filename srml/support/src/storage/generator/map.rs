@@ -18,11 +18,17 @@
 use rstd::prelude::*;
 use rstd::borrow::Borrow;
 use codec::{Codec, Encode};
-use crate::{storage::{self, unhashed, hashed::StorageHasher}, traits::Len};
+use runtime_io::twox_128;
+use crate::{
+	storage::{self, unhashed, PrefixIterator, hashed::{StorageHasher, IterableMapHasher}},
+	traits::Len,
+};
 
 /// Generator for `StorageMap` used by `decl_storage`.
 ///
-/// For each key value is stored at `Hasher(prefix ++ key)`.
+/// For each key value is stored at `Hasher(prefix ++ key)`, or at `twox128(prefix) ++ Hasher(key)`
+/// if the hasher is an [`IterableMapHasher`](../hashed/trait.IterableMapHasher.html),
+/// in which case the map can also be iterated.
 pub trait StorageMap<K: Codec, V: Codec> {
 	/// The type that get/take returns.
 	type Query;
@@ -40,13 +46,26 @@ pub trait StorageMap<K: Codec, V: Codec> {
 	fn from_query_to_optional_value(v: Self::Query) -> Option<V>;
 
 	/// Generate the full key used in top storage.
-	fn storage_map_final_key<KeyArg>(key: KeyArg) -> <Self::Hasher as StorageHasher>::Output
+	fn storage_map_final_key<KeyArg>(key: KeyArg) -> Vec<u8>
 	where
 		KeyArg: Borrow<K>,
 	{
-		let mut final_key = Self::prefix().to_vec();
-		key.borrow().encode_to(&mut final_key);
-		Self::Hasher::hash(&final_key)
+		key.borrow().using_encoded(|key| Self::Hasher::map_final_key(Self::prefix(), key))
+	}
+}
+
+impl<K: Codec, V: Codec, G: StorageMap<K, V>> storage::IterableStorageMap<K, V> for G
+where
+	G::Hasher: IterableMapHasher,
+{
+	type Iterator = PrefixIterator<K, V, G::Hasher>;
+
+	fn iter() -> Self::Iterator {
+		PrefixIterator::new(twox_128(G::prefix()).to_vec(), false)
+	}
+
+	fn drain() -> Self::Iterator {
+		PrefixIterator::new(twox_128(G::prefix()).to_vec(), true)
 	}
 }
 
@@ -54,7 +73,7 @@ impl<K: Codec, V: Codec, G: StorageMap<K, V>> storage::StorageMap<K, V> for G {
 	type Query = G::Query;
 
 	fn hashed_key_for<KeyArg: Borrow<K>>(key: KeyArg) -> Vec<u8> {
-		Self::storage_map_final_key(key).as_ref().to_vec()
+		Self::storage_map_final_key(key)
 	}
 
 	fn swap<KeyArg1: Borrow<K>, KeyArg2: Borrow<K>>(key1: KeyArg1, key2: KeyArg2) {
@@ -24,9 +24,13 @@
 mod linked_map;
 mod map;
 mod double_map;
+mod child_map;
+mod child_value;
 mod value;
 
 pub use linked_map::{StorageLinkedMap, Enumerator};
 pub use map::StorageMap;
 pub use double_map::StorageDoubleMap;
+pub use child_map::StorageChildMap;
+pub use child_value::StorageChildValue;
 pub use value::StorageValue;
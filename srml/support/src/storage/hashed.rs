@@ -26,6 +26,35 @@ use runtime_io::{self, twox_64, twox_128, blake2_128, twox_256, blake2_256};
 pub trait StorageHasher: 'static {
 	type Output: AsRef<[u8]>;
 	fn hash(x: &[u8]) -> Self::Output;
+
+	/// The final storage key of the entry of a map stored under `prefix` for the encoded `key`.
+	///
+	/// This is `hash(prefix ++ key)`, except for iterable map hashers.
+	fn map_final_key(prefix: &[u8], key: &[u8]) -> Vec<u8> {
+		let mut final_key = prefix.to_vec();
+		final_key.extend_from_slice(key);
+		Self::hash(&final_key).as_ref().to_vec()
+	}
+}
+
+/// Hasher whose output ends with the hashed data, so that the data can be recovered from it.
+pub trait ReversibleStorageHasher: StorageHasher {
+	/// Strip the hash from an output of this hasher, returning the hashed data.
+	fn reverse(x: &[u8]) -> &[u8];
+}
+
+/// Reversible hasher whose maps can be iterated.
+///
+/// A map using such a hasher is stored at `twox128(prefix) ++ hash(key)` instead of
+/// `hash(prefix ++ key)`: all its entries share a prefix and can be iterated with their keys.
+pub trait IterableMapHasher: ReversibleStorageHasher {}
+
+/// The final storage key of the entry of a map with an iterable hasher, see
+/// [`IterableMapHasher`](./trait.IterableMapHasher.html).
+fn iterable_map_final_key<H: IterableMapHasher>(prefix: &[u8], key: &[u8]) -> Vec<u8> {
+	let mut final_key = twox_128(prefix).to_vec();
+	final_key.extend_from_slice(H::hash(key).as_ref());
+	final_key
 }
 
 /// Hash storage keys with `concat(twox64(key), key)`
@@ -39,12 +68,53 @@ impl StorageHasher for Twox64Concat {
 			.cloned()
 			.collect::<Vec<_>>()
 	}
+
+	fn map_final_key(prefix: &[u8], key: &[u8]) -> Vec<u8> {
+		iterable_map_final_key::<Self>(prefix, key)
+	}
+}
+impl ReversibleStorageHasher for Twox64Concat {
+	fn reverse(x: &[u8]) -> &[u8] {
+		&x[8..]
+	}
 }
+impl IterableMapHasher for Twox64Concat {}
 
 #[test]
 fn test_twox_64_concat() {
 	let r = Twox64Concat::hash(b"foo");
-	assert_eq!(r.split_at(8), (&twox_128(b"foo")[..8], &b"foo"[..]))
+	assert_eq!(r.split_at(8), (&twox_128(b"foo")[..8], &b"foo"[..]));
+	assert_eq!(Twox64Concat::reverse(&r), &b"foo"[..]);
+}
+
+/// Hash storage keys with `concat(blake2_128(key), key)`
+pub struct Blake2_128Concat;
+impl StorageHasher for Blake2_128Concat {
+	type Output = Vec<u8>;
+	fn hash(x: &[u8]) -> Vec<u8> {
+		blake2_128(x)
+			.iter()
+			.chain(x.into_iter())
+			.cloned()
+			.collect::<Vec<_>>()
+	}
+
+	fn map_final_key(prefix: &[u8], key: &[u8]) -> Vec<u8> {
+		iterable_map_final_key::<Self>(prefix, key)
+	}
+}
+impl ReversibleStorageHasher for Blake2_128Concat {
+	fn reverse(x: &[u8]) -> &[u8] {
+		&x[16..]
+	}
+}
+impl IterableMapHasher for Blake2_128Concat {}
+
+#[test]
+fn test_blake2_128_concat() {
+	let r = Blake2_128Concat::hash(b"foo");
+	assert_eq!(r.split_at(16), (&blake2_128(b"foo")[..], &b"foo"[..]));
+	assert_eq!(Blake2_128Concat::reverse(&r), &b"foo"[..]);
 }
 
 /// Hash storage keys with blake2 128
@@ -83,6 +153,15 @@ impl StorageHasher for Twox256 {
 	}
 }
 
+/// Hash storage keys with sha2 256
+pub struct Sha2_256;
+impl StorageHasher for Sha2_256 {
+	type Output = [u8; 32];
+	fn hash(x: &[u8]) -> [u8; 32] {
+		runtime_io::hashing::sha2_256(x)
+	}
+}
+
 /// Return the value of the item in storage under `key`, or `None` if there is no explicit entry.
 pub fn get<T, HashFn, R>(hash: &HashFn, key: &[u8]) -> Option<T>
 where
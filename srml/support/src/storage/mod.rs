@@ -17,9 +17,10 @@
 //! Stuff to do with the runtime's storage.
 
 use crate::rstd::prelude::*;
-use crate::rstd::{borrow::Borrow, iter::FromIterator};
+use crate::rstd::{borrow::Borrow, iter::FromIterator, marker::PhantomData};
 use codec::{Codec, Encode, Decode, KeyedVec, EncodeAppend};
 use crate::traits::Len;
+use self::hashed::ReversibleStorageHasher;
 
 #[macro_use]
 pub mod storage_items;
@@ -158,6 +159,21 @@ pub trait StorageMap<K: Codec, V: Codec> {
 		where V: codec::DecodeLength + Len;
 }
 
+/// A strongly-typed map in storage whose keys can be iterated.
+///
+/// Implemented by the maps using the `blake2_128_concat` or `twox_64_concat` hasher, which keep
+/// the key and store all the entries under a common prefix.
+pub trait IterableStorageMap<K: Codec, V: Codec>: StorageMap<K, V> {
+	/// The type that iterates over all `(key, value)`.
+	type Iterator: Iterator<Item = (K, V)>;
+
+	/// Enumerate all elements in the map, in no particular order.
+	fn iter() -> Self::Iterator;
+
+	/// Remove all elements from the map and iterate through them, in no particular order.
+	fn drain() -> Self::Iterator;
+}
+
 /// A strongly-typed linked map in storage.
 ///
 /// Similar to `StorageMap` but allows to enumerate other elements and doesn't implement append.
@@ -278,3 +294,158 @@ pub trait StorageDoubleMap<K1: Encode, K2: Encode, V: Codec> {
 		I: codec::Encode,
 		V: EncodeAppend<Item=I>;
 }
+
+/// A strongly-typed double map in storage whose second keys can be iterated.
+///
+/// Implemented by the double maps using a hasher that keeps the key for the second key, i.e.
+/// `twox_64_concat` or `blake2_128_concat`.
+pub trait IterableStorageDoubleMap<K1: Encode, K2: Codec, V: Codec>: StorageDoubleMap<K1, K2, V> {
+	/// The type that iterates over all `(key2, value)` sharing a first key.
+	type Iterator: Iterator<Item = (K2, V)>;
+
+	/// Enumerate all elements in the map with first key `k1`, in no particular order.
+	fn iter_prefix<KArg1>(k1: &KArg1) -> Self::Iterator where KArg1: ?Sized + Encode, K1: Borrow<KArg1>;
+
+	/// Remove all elements from the map with first key `k1` and iterate through them, in no
+	/// particular order.
+	fn drain_prefix<KArg1>(k1: &KArg1) -> Self::Iterator where KArg1: ?Sized + Encode, K1: Borrow<KArg1>;
+}
+
+/// A strongly-typed value in storage that lives in its own child trie.
+pub trait StorageChildValue<T: Codec> {
+	/// The type that get/take return.
+	type Query;
+
+	/// The storage key of the child trie holding the value.
+	fn child_storage_key() -> Vec<u8>;
+
+	/// Does the value (explicitly) exist in storage?
+	fn exists() -> bool;
+
+	/// Load the value from the provided storage instance.
+	fn get() -> Self::Query;
+
+	/// Store a value under this key into the provided storage instance.
+	fn put<Arg: Borrow<T>>(val: Arg);
+
+	/// Mutate the value
+	fn mutate<R, F: FnOnce(&mut Self::Query) -> R>(f: F) -> R;
+
+	/// Clear the storage value, i.e. remove its whole child trie.
+	fn kill();
+
+	/// Take a value from storage, removing it afterwards.
+	fn take() -> Self::Query;
+
+	/// The root of the child trie of the value.
+	fn child_root() -> Vec<u8>;
+}
+
+/// A strongly-typed map in storage whose entries sharing a first key live in their own child trie.
+pub trait StorageChildMap<K1: Encode, K2: Encode, V: Codec> {
+	/// The type that get/take returns.
+	type Query;
+
+	/// The storage key of the child trie holding all the entries with first key `k1`.
+	fn child_storage_key<KArg1>(k1: &KArg1) -> Vec<u8> where KArg1: ?Sized + Encode, K1: Borrow<KArg1>;
+
+	fn exists<KArg1, KArg2>(k1: &KArg1, k2: &KArg2) -> bool
+	where
+		K1: Borrow<KArg1>,
+		K2: Borrow<KArg2>,
+		KArg1: ?Sized + Encode,
+		KArg2: ?Sized + Encode;
+
+	fn get<KArg1, KArg2>(k1: &KArg1, k2: &KArg2) -> Self::Query
+	where
+		K1: Borrow<KArg1>,
+		K2: Borrow<KArg2>,
+		KArg1: ?Sized + Encode,
+		KArg2: ?Sized + Encode;
+
+	fn take<KArg1, KArg2>(k1: &KArg1, k2: &KArg2) -> Self::Query
+	where
+		K1: Borrow<KArg1>,
+		K2: Borrow<KArg2>,
+		KArg1: ?Sized + Encode,
+		KArg2: ?Sized + Encode;
+
+	fn insert<KArg1, KArg2, VArg>(k1: &KArg1, k2: &KArg2, val: &VArg)
+	where
+		K1: Borrow<KArg1>,
+		K2: Borrow<KArg2>,
+		V: Borrow<VArg>,
+		KArg1: ?Sized + Encode,
+		KArg2: ?Sized + Encode,
+		VArg: ?Sized + Encode;
+
+	fn remove<KArg1, KArg2>(k1: &KArg1, k2: &KArg2)
+	where
+		K1: Borrow<KArg1>,
+		K2: Borrow<KArg2>,
+		KArg1: ?Sized + Encode,
+		KArg2: ?Sized + Encode;
+
+	/// Remove the whole child trie of `k1`, i.e. all the entries with first key `k1`.
+	fn remove_child<KArg1>(k1: &KArg1) where KArg1: ?Sized + Encode, K1: Borrow<KArg1>;
+
+	/// The root of the child trie of `k1`.
+	fn child_root<KArg1>(k1: &KArg1) -> Vec<u8> where KArg1: ?Sized + Encode, K1: Borrow<KArg1>;
+
+	fn mutate<KArg1, KArg2, R, F>(k1: &KArg1, k2: &KArg2, f: F) -> R
+	where
+		K1: Borrow<KArg1>,
+		K2: Borrow<KArg2>,
+		KArg1: ?Sized + Encode,
+		KArg2: ?Sized + Encode,
+		F: FnOnce(&mut Self::Query) -> R;
+}
+
+/// Iterator over the entries of a map whose final keys are `prefix ++ Hasher(key)`.
+///
+/// Entries whose key or value can't be decoded are skipped.
+pub struct PrefixIterator<K, V, Hasher> {
+	prefix: Vec<u8>,
+	previous_key: Vec<u8>,
+	/// Whether entries are removed from storage while iterating.
+	drain: bool,
+	_phantom: PhantomData<(K, V, Hasher)>,
+}
+
+impl<K, V, Hasher> PrefixIterator<K, V, Hasher> {
+	/// Iterate over the entries whose final keys start with `prefix`, removing them if `drain`.
+	pub fn new(prefix: Vec<u8>, drain: bool) -> Self {
+		PrefixIterator {
+			previous_key: prefix.clone(),
+			prefix,
+			drain,
+			_phantom: PhantomData,
+		}
+	}
+}
+
+impl<K: Decode, V: Decode, Hasher: ReversibleStorageHasher> Iterator for PrefixIterator<K, V, Hasher> {
+	type Item = (K, V);
+
+	fn next(&mut self) -> Option<(K, V)> {
+		loop {
+			let next_key = runtime_io::next_key(&self.previous_key)
+				.filter(|next_key| next_key.starts_with(&self.prefix))?;
+			self.previous_key = next_key;
+
+			let raw_value = match unhashed::get_raw(&self.previous_key) {
+				Some(raw_value) => raw_value,
+				None => continue,
+			};
+			if self.drain {
+				unhashed::kill(&self.previous_key);
+			}
+
+			let mut key_material = Hasher::reverse(&self.previous_key[self.prefix.len()..]);
+			match (K::decode(&mut key_material), V::decode(&mut &raw_value[..])) {
+				(Ok(key), Ok(value)) => return Some((key, value)),
+				_ => runtime_io::print("ERROR: corrupted storage entry in map iteration"),
+			}
+		}
+	}
+}
@@ -0,0 +1,128 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+#![recursion_limit="128"]
+
+use srml_support::{
+	Dispatchable, dispatch::DispatchError,
+	metadata::{DecodeDifferent, RuntimeMetadata},
+	sr_primitives::{generic, traits::{BlakeTwo256, Verify}},
+};
+use primitives::{H256, sr25519};
+
+mod system;
+
+mod module_without_call {
+	use super::*;
+
+	pub trait Trait: system::Trait {}
+
+	srml_support::decl_module! {
+		pub struct Module<T: Trait> for enum Call where origin: <T as system::Trait>::Origin {}
+	}
+
+	srml_support::decl_storage! {
+		trait Store for Module<T: Trait> as ModuleWithoutCall {
+			pub Value: u32;
+		}
+	}
+}
+
+mod module_with_error {
+	use super::*;
+
+	pub trait Trait: system::Trait {}
+
+	srml_support::decl_module! {
+		pub struct Module<T: Trait> for enum Call where origin: <T as system::Trait>::Origin {
+			type Error = Error;
+
+			fn fail(_origin) -> Result<(), Error> {
+				Err(Error::Failed)
+			}
+		}
+	}
+
+	srml_support::decl_error! {
+		pub enum Error {
+			/// The call failed.
+			Failed,
+		}
+	}
+}
+
+impl module_without_call::Trait for Runtime {}
+impl module_with_error::Trait for Runtime {}
+
+pub type Signature = sr25519::Signature;
+pub type AccountId = <Signature as Verify>::Signer;
+pub type BlockNumber = u64;
+
+impl system::Trait for Runtime {
+	type Hash = H256;
+	type Origin = Origin;
+	type BlockNumber = BlockNumber;
+	type AccountId = AccountId;
+	type Event = Event;
+}
+
+srml_support::construct_runtime!(
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic
+	{
+		System: system::{Module, Call, Event},
+		ModuleWithoutCall: module_without_call::{Module, Storage},
+		ModuleWithError: module_with_error::{Module, Call},
+	}
+);
+
+srml_support::impl_outer_dispatch! {
+	pub enum DirectCall for Runtime where origin: Origin {
+		module_with_error::ModuleWithError,
+	}
+}
+
+pub type Header = generic::Header<BlockNumber, BlakeTwo256>;
+pub type Block = generic::Block<Header, UncheckedExtrinsic>;
+pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<u32, Call, Signature, ()>;
+
+#[test]
+fn construct_runtime_reports_the_metadata_module_index() {
+	let modules = match Runtime::metadata().1 {
+		RuntimeMetadata::V8(metadata) => match metadata.modules {
+			DecodeDifferent::Encode(modules) => modules,
+			_ => unreachable!(),
+		},
+		_ => unreachable!(),
+	};
+	assert_eq!(modules[2].name, DecodeDifferent::Encode("ModuleWithError"));
+
+	let call = Call::ModuleWithError(module_with_error::Call::fail());
+	assert_eq!(
+		call.dispatch(Origin::ROOT),
+		Err(DispatchError::new(Some(2), 1, Some("Failed"))),
+	);
+}
+
+#[test]
+fn impl_outer_dispatch_reports_the_call_module_index() {
+	let call = DirectCall::ModuleWithError(module_with_error::Call::fail());
+	assert_eq!(
+		call.dispatch(Origin::ROOT),
+		Err(DispatchError::new(Some(0), 1, Some("Failed"))),
+	);
+}
@@ -34,12 +34,16 @@ srml_support::decl_storage!{
 
 		pub Map: map u32 => u32;
 		pub Map2: map hasher(twox_128) u32 => u32;
+		pub Map3: map hasher(sha2_256) u32 => u32;
+		pub Map4: map hasher(twox_64_concat) u32 => u32;
+		pub Map5: map hasher(blake2_128_concat) u32 => u32;
 
 		pub LinkedMap: linked_map u32 => u32;
 		pub LinkedMap2: linked_map hasher(twox_128) u32 => u32;
 
 		pub DoubleMap: double_map u32, blake2_256(u32) => u32;
 		pub DoubleMap2: double_map hasher(twox_128) u32, blake2_128(u32) => u32;
+		pub DoubleMap3: double_map hasher(twox_128) u32, sha2_256(u32) => u32;
 
 		pub Foo get(foo) config(): Option<T::BlockNumber>;
 		pub Foo2 get(foo2) config(): double_map u32, blake2_256(T::BlockNumber) => Option<u32>;
@@ -72,6 +76,23 @@ fn final_keys() {
 		k.extend(1u32.encode());
 		assert_eq!(unhashed::get::<u32>(&runtime_io::twox_128(&k)), Some(2u32));
 
+		Map3::insert(1, 2);
+		let mut k = b"FinalKeys Map3".to_vec();
+		k.extend(1u32.encode());
+		assert_eq!(unhashed::get::<u32>(&runtime_io::hashing::sha2_256(&k)), Some(2u32));
+
+		Map4::insert(1, 2);
+		let mut k = runtime_io::twox_128(b"FinalKeys Map4").to_vec();
+		k.extend(&runtime_io::twox_64(&1u32.encode()));
+		k.extend(1u32.encode());
+		assert_eq!(unhashed::get::<u32>(&k), Some(2u32));
+
+		Map5::insert(1, 2);
+		let mut k = runtime_io::twox_128(b"FinalKeys Map5").to_vec();
+		k.extend(&runtime_io::blake2_128(&1u32.encode()));
+		k.extend(1u32.encode());
+		assert_eq!(unhashed::get::<u32>(&k), Some(2u32));
+
 		LinkedMap::insert(1, 2);
 		let mut k = b"FinalKeys LinkedMap".to_vec();
 		k.extend(1u32.encode());
@@ -95,5 +116,12 @@ fn final_keys() {
 		let mut k = runtime_io::twox_128(&k).to_vec();
 		k.extend(&runtime_io::blake2_128(&2u32.encode()));
 		assert_eq!(unhashed::get::<u32>(&k), Some(3u32));
+
+		DoubleMap3::insert(&1, &2, &3);
+		let mut k = b"FinalKeys DoubleMap3".to_vec();
+		k.extend(1u32.encode());
+		let mut k = runtime_io::twox_128(&k).to_vec();
+		k.extend(&runtime_io::hashing::sha2_256(&2u32.encode()));
+		assert_eq!(unhashed::get::<u32>(&k), Some(3u32));
 	});
 }
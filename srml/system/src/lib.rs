@@ -65,7 +65,7 @@
 //!
 //! Import the System module and derive your module's configuration trait from the system trait.
 //!
-//! ### Example - Get random seed and extrinsic count for the current block
+//! ### Example - Get extrinsic count and parent hash for the current block
 //!
 //! ```
 //! use srml_support::{decl_module, dispatch::Result};
@@ -77,8 +77,8 @@
 //! 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
 //! 		pub fn system_module_example(origin) -> Result {
 //! 			let _sender = ensure_signed(origin)?;
-//! 			let _random_seed = <system::Module<T>>::random_seed();
 //! 			let _extrinsic_count = <system::Module<T>>::extrinsic_count();
+//! 			let _parent_hash = <system::Module<T>>::parent_hash();
 //! 			Ok(())
 //! 		}
 //! 	}
@@ -96,7 +96,7 @@ use rstd::map;
 use rstd::marker::PhantomData;
 use sr_version::RuntimeVersion;
 use sr_primitives::generic::{self, Era};
-use sr_primitives::Perbill;
+use sr_primitives::{Perbill, DispatchError};
 use sr_primitives::weights::{
 	Weight, DispatchInfo, DispatchClass, WeightMultiplier, SimpleDispatchInfo
 };
@@ -104,7 +104,7 @@ use sr_primitives::transaction_validity::{
 	ValidTransaction, TransactionPriority, TransactionLongevity
 };
 use sr_primitives::traits::{self, CheckEqual, SimpleArithmetic, Zero, SignedExtension, Convert,
-	SimpleBitOps, Hash, Member, MaybeDisplay, EnsureOrigin, DispatchError, SaturatedConversion,
+	SimpleBitOps, Hash, Member, MaybeDisplay, EnsureOrigin, SaturatedConversion,
 	MaybeSerializeDebugButNotDeserialize, MaybeSerializeDebug, StaticLookup, One, Bounded, Lookup,
 };
 use primitives::storage::well_known_keys;
@@ -112,7 +112,6 @@ use srml_support::{
 	storage, decl_module, decl_event, decl_storage, StorageDoubleMap, StorageValue, StorageMap,
 	Parameter, for_each_tuple, traits::{Contains, Get}
 };
-use safe_mix::TripletMix;
 use codec::{Encode, Decode};
 
 #[cfg(any(feature = "std", test))]
@@ -323,12 +322,12 @@ decl_event!(
 		/// An extrinsic completed successfully.
 		ExtrinsicSuccess,
 		/// An extrinsic failed.
-		ExtrinsicFailed,
+		ExtrinsicFailed(DispatchError),
 	}
 );
 
 /// Origin for the System module.
-#[derive(PartialEq, Eq, Clone)]
+#[derive(PartialEq, Eq, Clone, Encode, Decode)]
 #[cfg_attr(feature = "std", derive(Debug))]
 pub enum RawOrigin<AccountId> {
 	/// The system itself ordained this dispatch to happen: this is the highest privilege level.
@@ -385,9 +384,6 @@ decl_storage! {
 		pub BlockHash get(block_hash) build(|_| vec![(T::BlockNumber::zero(), hash69())]): map T::BlockNumber => T::Hash;
 		/// Extrinsics data for the current block (maps an extrinsic's index to its data).
 		ExtrinsicData get(extrinsic_data): map u32 => Vec<u8>;
-		/// Series of block headers from the last 81 blocks that acts as random seed material. This is arranged as a
-		/// ring buffer with the `i8` prefix being the index into the `Vec` of the oldest hash.
-		RandomMaterial get(random_material): (i8, Vec<T::Hash>);
 		/// The current block number being processed. Set by `execute_block`.
 		Number get(block_number) build(|_| 1.into()): T::BlockNumber;
 		/// Hash of the previous block.
@@ -421,6 +417,11 @@ decl_storage! {
 		/// no notification will be triggered thus the event might be lost.
 		EventTopics get(event_topics): double_map hasher(blake2_256) (), blake2_256(T::Hash)
 			=> Vec<(T::BlockNumber, EventIndex)>;
+		/// The spec version of the runtime the `on_runtime_upgrade` hooks were last run for.
+		///
+		/// Set to the spec version of the genesis runtime, so the hooks don't run for it.
+		pub LastRuntimeUpgrade get(last_runtime_upgrade)
+			build(|_| T::Version::get().spec_version): u32;
 	}
 	add_extra_genesis {
 		config(changes_trie_config): Option<ChangesTrieConfiguration>;
@@ -593,6 +594,15 @@ impl<T: Trait> Module<T> {
 		}
 	}
 
+	/// Get the storage key prefix of the topics of the events.
+	///
+	/// The rest of the storage key of a topic is the `blake2_256` hash of the encoded topic. Nodes
+	/// use the prefix to index the events of the imported blocks by topic.
+	pub fn event_topics_key_prefix() -> Vec<u8> {
+		use storage::generator::StorageDoubleMap;
+		<EventTopics<T>>::storage_double_map_final_key1(&()).as_ref().to_vec()
+	}
+
 	/// Gets the index of extrinsic that is currently executing.
 	pub fn extrinsic_index() -> Option<u32> {
 		storage::unhashed::get(well_known_keys::EXTRINSIC_INDEX)
@@ -608,6 +618,17 @@ impl<T: Trait> Module<T> {
 		AllExtrinsicsWeight::get().unwrap_or_default()
 	}
 
+	/// Inform the system module of some additional weight that should be accounted for in the
+	/// current block.
+	///
+	/// NOTE: use with care; this is only meant for modules that dispatch calls outside of the
+	/// extrinsic pipeline (e.g. from `on_initialize`) and therefore bypass `CheckWeight`. The
+	/// weight is added even if the resulting block weight exceeds the limit, hence _unchecked_.
+	pub fn register_extra_weight_unchecked(weight: Weight) {
+		let current_weight = Self::all_extrinsics_weight();
+		AllExtrinsicsWeight::put(current_weight.saturating_add(weight));
+	}
+
 	pub fn all_extrinsics_len() -> u32 {
 		AllExtrinsicsLen::get().unwrap_or_default()
 	}
@@ -637,12 +658,6 @@ impl<T: Trait> Module<T> {
 		<ParentHash<T>>::put(parent_hash);
 		<BlockHash<T>>::insert(*number - One::one(), parent_hash);
 		<ExtrinsicsRoot<T>>::put(txs_root);
-		<RandomMaterial<T>>::mutate(|&mut(ref mut index, ref mut values)| if values.len() < 81 {
-			values.push(parent_hash.clone())
-		} else {
-			values[*index as usize] = parent_hash.clone();
-			*index = (*index + 1) % 81;
-		});
 		<Events<T>>::kill();
 		EventCount::kill();
 		<EventTopics<T>>::remove_prefix(&());
@@ -732,66 +747,15 @@ impl<T: Trait> Module<T> {
 	/// Return the chain's current runtime version.
 	pub fn runtime_version() -> RuntimeVersion { T::Version::get() }
 
-	/// Get the basic random seed.
-	///
-	/// In general you won't want to use this, but rather `Self::random` which
-	/// allows you to give a subject for the random result and whose value will
-	/// be independently low-influence random from any other such seeds.
-	pub fn random_seed() -> T::Hash {
-		Self::random(&[][..])
-	}
-
-	/// Get a low-influence "random" value.
-	///
-	/// Being a deterministic block chain, real randomness is difficult to come
-	/// by. This gives you something that approximates it. `subject` is a
-	/// context identifier and allows you to get a different result to other
-	/// callers of this function; use it like `random(&b"my context"[..])`.
-	///
-	/// This is initially implemented through a low-influence "triplet mix"
-	/// convolution of previous block hash values. In the future it will be
-	/// generated from a secure verifiable random function (VRF).
-	///
-	/// ### Security Notes
-	///
-	/// This randomness uses a low-influence function, drawing upon the block
-	/// hashes from the previous 81 blocks. Its result for any given subject
-	/// will be known in advance by the block producer of this block (and,
-	/// indeed, anyone who knows the block's `parent_hash`). However, it is
-	/// mostly impossible for the producer of this block *alone* to influence
-	/// the value of this hash. A sizable minority of dishonest and coordinating
-	/// block producers would be required in order to affect this value. If that
-	/// is an insufficient security guarantee then two things can be used to
-	/// improve this randomness:
-	///
-	/// - Name, in advance, the block number whose random value will be used;
-	///   ensure your module retains a buffer of previous random values for its
-	///   subject and then index into these in order to obviate the ability of
-	///   your user to look up the parent hash and choose when to transact based
-	///   upon it.
-	/// - Require your user to first commit to an additional value by first
-	///   posting its hash. Require them to reveal the value to determine the
-	///   final result, hashing it with the output of this random function. This
-	///   reduces the ability of a cabal of block producers from conspiring
-	///   against individuals.
-	///
-	/// WARNING: Hashing the result of this function will remove any
-	/// low-influnce properties it has and mean that all bits of the resulting
-	/// value are entirely manipulatable by the author of the parent block, who
-	/// can determine the value of `parent_hash`.
-	pub fn random(subject: &[u8]) -> T::Hash {
-		let (index, hash_series) = <RandomMaterial<T>>::get();
-		if hash_series.len() > 0 {
-			// Always the case after block 1 is initialised.
-			hash_series.iter()
-				.cycle()
-				.skip(index as usize)
-				.take(81)
-				.enumerate()
-				.map(|(i, h)| (i as i8, subject, h).using_encoded(T::Hashing::hash))
-				.triplet_mix()
+	/// Note the spec version of the current runtime, returning `true` if it differs from the
+	/// previously noted one, i.e. if the runtime has been upgraded since the last call.
+	pub fn note_runtime_upgrade() -> bool {
+		let spec_version = T::Version::get().spec_version;
+		if Self::last_runtime_upgrade() == spec_version {
+			false
 		} else {
-			T::Hash::default()
+			LastRuntimeUpgrade::put(spec_version);
+			true
 		}
 	}
 
@@ -811,10 +775,10 @@ impl<T: Trait> Module<T> {
 	}
 
 	/// To be called immediately after an extrinsic has been applied.
-	pub fn note_applied_extrinsic(r: &Result<(), &'static str>, _encoded_len: u32) {
+	pub fn note_applied_extrinsic(r: &Result<(), DispatchError>, _encoded_len: u32) {
 		Self::deposit_event(match r {
 			Ok(_) => Event::ExtrinsicSuccess,
-			Err(_) => Event::ExtrinsicFailed,
+			Err(err) => Event::ExtrinsicFailed(err.clone()),
 		});
 
 		let next_extrinsic_index = Self::extrinsic_index().unwrap_or_default() + 1u32;
@@ -859,14 +823,14 @@ impl<T: Trait + Send + Sync> CheckWeight<T> {
 	/// Checks if the current extrinsic can fit into the block with respect to block weight limits.
 	///
 	/// Upon successes, it returns the new block weight as a `Result`.
-	fn check_weight(info: DispatchInfo) -> Result<Weight, DispatchError> {
+	fn check_weight(info: DispatchInfo) -> Result<Weight, traits::DispatchError> {
 		let current_weight = Module::<T>::all_extrinsics_weight();
 		let maximum_weight = T::MaximumBlockWeight::get();
 		let limit = Self::get_dispatch_limit_ratio(info.class) * maximum_weight;
 		let added_weight = info.weight.min(limit);
 		let next_weight = current_weight.saturating_add(added_weight);
 		if next_weight > limit {
-			return Err(DispatchError::Exhausted)
+			return Err(traits::DispatchError::Exhausted)
 		}
 		Ok(next_weight)
 	}
@@ -874,14 +838,14 @@ impl<T: Trait + Send + Sync> CheckWeight<T> {
 	/// Checks if the current extrinsic can fit into the block with respect to block length limits.
 	///
 	/// Upon successes, it returns the new block length as a `Result`.
-	fn check_block_length(info: DispatchInfo, len: usize) -> Result<u32, DispatchError> {
+	fn check_block_length(info: DispatchInfo, len: usize) -> Result<u32, traits::DispatchError> {
 		let current_len = Module::<T>::all_extrinsics_len();
 		let maximum_len = T::MaximumBlockLength::get();
 		let limit = Self::get_dispatch_limit_ratio(info.class) * maximum_len;
 		let added_len = len as u32;
 		let next_len = current_len.saturating_add(added_len);
 		if next_len > limit {
-			return Err(DispatchError::Exhausted)
+			return Err(traits::DispatchError::Exhausted)
 		}
 		Ok(next_len)
 	}
@@ -914,7 +878,7 @@ impl<T: Trait + Send + Sync> SignedExtension for CheckWeight<T> {
 		_call: &Self::Call,
 		info: DispatchInfo,
 		len: usize,
-	) -> Result<(), DispatchError> {
+	) -> Result<(), traits::DispatchError> {
 		let next_len = Self::check_block_length(info, len)?;
 		AllExtrinsicsLen::put(next_len);
 		let next_weight = Self::check_weight(info)?;
@@ -928,7 +892,7 @@ impl<T: Trait + Send + Sync> SignedExtension for CheckWeight<T> {
 		_call: &Self::Call,
 		info: DispatchInfo,
 		len: usize,
-	) -> Result<ValidTransaction, DispatchError> {
+	) -> Result<ValidTransaction, traits::DispatchError> {
 		// There is no point in writing to storage here since changes are discarded. This basically
 		// discards any transaction which is bigger than the length or weight limit **alone**,which
 		// is a guarantee that it will fail in the pre-dispatch phase.
@@ -977,11 +941,11 @@ impl<T: Trait> SignedExtension for CheckNonce<T> {
 		_call: &Self::Call,
 		_info: DispatchInfo,
 		_len: usize,
-	) -> Result<(), DispatchError> {
+	) -> Result<(), traits::DispatchError> {
 		let expected = <AccountNonce<T>>::get(who);
 		if self.0 != expected {
 			return Err(
-				if self.0 < expected { DispatchError::Stale } else { DispatchError::Future }
+				if self.0 < expected { traits::DispatchError::Stale } else { traits::DispatchError::Future }
 			)
 		}
 		<AccountNonce<T>>::insert(who, expected + T::Index::one());
@@ -994,11 +958,11 @@ impl<T: Trait> SignedExtension for CheckNonce<T> {
 		_call: &Self::Call,
 		info: DispatchInfo,
 		_len: usize,
-	) -> Result<ValidTransaction, DispatchError> {
+	) -> Result<ValidTransaction, traits::DispatchError> {
 		// check index
 		let expected = <AccountNonce<T>>::get(who);
 		if self.0 < expected {
-			return Err(DispatchError::Stale)
+			return Err(traits::DispatchError::Stale)
 		}
 
 		let provides = vec![Encode::encode(&(who, self.0))];
@@ -1048,7 +1012,7 @@ impl<T: Trait + Send + Sync> SignedExtension for CheckEra<T> {
 		_call: &Self::Call,
 		_info: DispatchInfo,
 		_len: usize,
-	) -> Result<ValidTransaction, DispatchError> {
+	) -> Result<ValidTransaction, traits::DispatchError> {
 		let current_u64 = <Module<T>>::block_number().saturated_into::<u64>();
 		let valid_till = (self.0).0.death(current_u64);
 		Ok(ValidTransaction {
@@ -1183,7 +1147,7 @@ mod tests {
 		fn from(e: Event) -> u16 {
 			match e {
 				Event::ExtrinsicSuccess => 100,
-				Event::ExtrinsicFailed => 101,
+				Event::ExtrinsicFailed(_) => 101,
 			}
 		}
 	}
@@ -1232,7 +1196,7 @@ mod tests {
 			System::initialize(&2, &[0u8; 32].into(), &[0u8; 32].into(), &Default::default());
 			System::deposit_event(42u16);
 			System::note_applied_extrinsic(&Ok(()), 0);
-			System::note_applied_extrinsic(&Err(""), 0);
+			System::note_applied_extrinsic(&Err(DispatchError::new(Some(1), 2, None)), 0);
 			System::note_finished_extrinsics();
 			System::deposit_event(3u16);
 			System::finalize();
@@ -1302,6 +1266,14 @@ mod tests {
 				System::event_topics(&(), &topics[2]),
 				vec![(BLOCK_NUMBER, 0)],
 			);
+
+			// The topics are stored under the prefix followed by the hash of the encoded topic.
+			let mut key = System::event_topics_key_prefix();
+			key.extend_from_slice(&runtime_io::blake2_256(&topics[2].encode()));
+			assert_eq!(
+				storage::unhashed::get::<Vec<(u64, u32)>>(&key),
+				Some(vec![(BLOCK_NUMBER, 0)]),
+			);
 		});
 	}
 
@@ -0,0 +1,720 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Vesting Module
+//!
+//! - [`vesting::Trait`](./trait.Trait.html)
+//! - [`Call`](./enum.Call.html)
+//!
+//! ## Overview
+//!
+//! A simple module providing a means of placing a linear curve on an account's locked balance. This
+//! module ensures that there is a lock in place preventing the balance to drop below the *unvested*
+//! amount for any reason other than transaction fee payment.
+//!
+//! As the amount vested increases over time, the amount unvested reduces. However, locks remain in
+//! place and explicit action is needed on behalf of the user to ensure that the amount locked is
+//! equivalent to the amount remaining to be vested. This is done through a dispatchable function,
+//! either `vest` (in typical case where the sender is calling on their own behalf) or `vest_other`
+//! in case the sender is calling on another account's behalf.
+//!
+//! ### Terminology
+//!
+//! - **Vesting Schedule:** The locked amount of an account, the amount that becomes liquid every
+//! block after the starting block, and the block at which vesting begins.
+//! - **Vested:** The part of the locked amount that has become liquid according to the schedule.
+//! - **Unvested:** The part of the locked amount that is still locked at a given block.
+//!
+//! ## Interface
+//!
+//! This module implements the `OnFreeBalanceZero` trait, removing the vesting schedule of accounts
+//! that get reaped.
+//!
+//! ### Dispatchable Functions
+//!
+//! - `vest` - Update the lock, reducing it in line with the amount "vested" so far.
+//! - `vest_other` - Update the lock of another account, reducing it in line with the amount
+//!   "vested" so far.
+//! - `vested_transfer` - Transfer funds to a new account together with a vesting schedule for them.
+//!
+//! ### Public Functions
+//!
+//! - `vesting_balance` - Get the amount that is currently being vested and cannot be transferred
+//!   out of this account.
+//!
+//! ## GenesisConfig
+//!
+//! The Vesting module depends on the [`GenesisConfig`](./struct.GenesisConfig.html). Vesting
+//! schedules given at genesis are applied on top of the balances the currency module was
+//! initialized with, so the currency module must be placed before this one in the runtime.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use rstd::prelude::*;
+use codec::{Encode, Decode};
+use sr_primitives::traits::{
+	StaticLookup, Zero, SimpleArithmetic, Saturating, Convert, Bounded, One,
+};
+use sr_primitives::weights::SimpleDispatchInfo;
+use srml_support::{StorageMap, decl_module, decl_event, decl_storage, ensure};
+use srml_support::traits::{
+	Currency, LockableCurrency, WithdrawReasons, WithdrawReason, LockIdentifier, Get,
+	OnFreeBalanceZero,
+};
+use srml_support::dispatch::Result;
+use system::ensure_signed;
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+pub trait Trait: system::Trait {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The currency trait.
+	type Currency: LockableCurrency<Self::AccountId, Moment=Self::BlockNumber>;
+
+	/// Convert the block number into a balance.
+	type BlockNumberToBalance: Convert<Self::BlockNumber, BalanceOf<Self>>;
+
+	/// The minimum amount to be transferred to create a new vesting schedule.
+	type MinVestedTransfer: Get<BalanceOf<Self>>;
+}
+
+const VESTING_ID: LockIdentifier = *b"vesting ";
+
+/// Struct to encode the vesting schedule of an individual account.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct VestingInfo<Balance, BlockNumber> {
+	/// Locked amount at genesis.
+	pub locked: Balance,
+	/// Amount that gets unlocked every block after `starting_block`.
+	pub per_block: Balance,
+	/// Starting block for unlocking(vesting).
+	pub starting_block: BlockNumber,
+}
+
+impl<
+	Balance: SimpleArithmetic + Copy,
+	BlockNumber: SimpleArithmetic + Copy,
+> VestingInfo<Balance, BlockNumber> {
+	/// Amount locked at block `n`.
+	pub fn locked_at<
+		BlockNumberToBalance: Convert<BlockNumber, Balance>
+	>(&self, n: BlockNumber) -> Balance {
+		// Number of blocks that count toward vesting
+		// Saturating to 0 when n < starting_block
+		let vested_block_count = n.saturating_sub(self.starting_block);
+		let vested_block_count = BlockNumberToBalance::convert(vested_block_count);
+		// Return amount that is still locked in vesting
+		if let Some(x) = vested_block_count.checked_mul(&self.per_block) {
+			self.locked.max(x) - x
+		} else {
+			Zero::zero()
+		}
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Vesting {
+		/// Information regarding the vesting of a given account.
+		pub Vesting get(vesting):
+			map T::AccountId => Option<VestingInfo<BalanceOf<T>, T::BlockNumber>>;
+	}
+	add_extra_genesis {
+		config(vesting): Vec<(T::AccountId, T::BlockNumber, T::BlockNumber, BalanceOf<T>)>;
+		// ^^ begin, length, amount liquid at genesis
+		build(|config: &GenesisConfig<T>| {
+			// Generate initial vesting configuration
+			// * who - Account which we are generating vesting configuration for
+			// * begin - Block when the account will start to vest
+			// * length - Number of blocks from `begin` until fully vested
+			// * liquid - Number of units which can be spent before vesting begins
+			for &(ref who, begin, length, liquid) in config.vesting.iter() {
+				let balance = T::Currency::free_balance(who);
+				assert!(!balance.is_zero(), "Currencies must be initialized before vesting");
+				// Total genesis `balance` minus `liquid` equals funds locked for vesting
+				let locked = balance.saturating_sub(liquid);
+				let length_as_balance = T::BlockNumberToBalance::convert(length);
+				// Number of units unlocked per block after `begin`
+				let per_block = locked / length_as_balance.max(One::one());
+
+				<Vesting<T>>::insert(who, VestingInfo {
+					locked: locked,
+					per_block: per_block,
+					starting_block: begin
+				});
+				let reasons = WithdrawReasons::except(WithdrawReason::TransactionPayment);
+				T::Currency::set_lock(VESTING_ID, who, locked, T::BlockNumber::max_value(), reasons);
+			}
+		})
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where
+		<T as system::Trait>::AccountId,
+		Balance = BalanceOf<T>
+	{
+		/// The amount vested has been updated. This could indicate more funds are available. The
+		/// balance given is the amount which is left unvested (and thus locked).
+		VestingUpdated(AccountId, Balance),
+		/// An account (given) has become fully vested. No further vesting can happen.
+		VestingCompleted(AccountId),
+	}
+);
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		/// The minimum amount to be transferred to create a new vesting schedule.
+		const MinVestedTransfer: BalanceOf<T> = T::MinVestedTransfer::get();
+
+		fn deposit_event() = default;
+
+		/// Unlock any vested funds of the sender account.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the sender must have funds still
+		/// locked under this module.
+		///
+		/// Emits either `VestingCompleted` or `VestingUpdated`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - One balance-lock operation.
+		/// - One storage read (codec `O(1)`) and up to one removal.
+		/// - One event.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(100_000)]
+		fn vest(origin) -> Result {
+			let who = ensure_signed(origin)?;
+			Self::update_lock(who)
+		}
+
+		/// Unlock any vested funds of a `target` account.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `target`: The account whose vested funds should be unlocked. Must have funds still
+		/// locked under this module.
+		///
+		/// Emits either `VestingCompleted` or `VestingUpdated`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - Up to one account lookup.
+		/// - One balance-lock operation.
+		/// - One storage read (codec `O(1)`) and up to one removal.
+		/// - One event.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(100_000)]
+		fn vest_other(origin, target: <T::Lookup as StaticLookup>::Source) -> Result {
+			ensure_signed(origin)?;
+			Self::update_lock(T::Lookup::lookup(target)?)
+		}
+
+		/// Create a vested transfer.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `target`: The account that should be transferred the vested funds.
+		/// - `schedule`: The vesting schedule attached to the transfer. The whole `locked` amount
+		/// is transferred and then locked in `target`'s account.
+		///
+		/// Emits `VestingUpdated`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - Creates a new storage entry, but is protected by a minimum value.
+		/// - One balance transfer and one balance-lock operation.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(1_000_000)]
+		fn vested_transfer(
+			origin,
+			target: <T::Lookup as StaticLookup>::Source,
+			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>
+		) -> Result {
+			let transactor = ensure_signed(origin)?;
+			ensure!(
+				schedule.locked >= T::MinVestedTransfer::get(),
+				"amount transferred is too low to create a vesting schedule"
+			);
+			ensure!(!schedule.per_block.is_zero(), "vesting schedule never unlocks any funds");
+
+			let who = T::Lookup::lookup(target)?;
+			ensure!(!<Vesting<T>>::exists(&who), "target already has a vesting schedule");
+
+			T::Currency::transfer(&transactor, &who, schedule.locked)?;
+
+			<Vesting<T>>::insert(&who, schedule);
+			Self::update_lock(who)
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	// PUBLIC IMMUTABLES
+
+	/// Get the amount that is currently being vested and cannot be transferred out of this account.
+	pub fn vesting_balance(who: &T::AccountId) -> BalanceOf<T> {
+		if let Some(v) = Self::vesting(who) {
+			let now = <system::Module<T>>::block_number();
+			let locked_now = v.locked_at::<T::BlockNumberToBalance>(now);
+			T::Currency::free_balance(who).min(locked_now)
+		} else {
+			Zero::zero()
+		}
+	}
+
+	// PRIVATE MUTABLES
+
+	/// (Re)set or remove the module's currency lock on `who`'s account in accordance with their
+	/// current unvested amount.
+	fn update_lock(who: T::AccountId) -> Result {
+		let vesting = Self::vesting(&who).ok_or("account has no vesting schedule")?;
+		let now = <system::Module<T>>::block_number();
+		let locked_now = vesting.locked_at::<T::BlockNumberToBalance>(now);
+
+		if locked_now.is_zero() {
+			T::Currency::remove_lock(VESTING_ID, &who);
+			<Vesting<T>>::remove(&who);
+			Self::deposit_event(RawEvent::VestingCompleted(who));
+		} else {
+			let reasons = WithdrawReasons::except(WithdrawReason::TransactionPayment);
+			T::Currency::set_lock(VESTING_ID, &who, locked_now, T::BlockNumber::max_value(), reasons);
+			Self::deposit_event(RawEvent::VestingUpdated(who, locked_now));
+		}
+		Ok(())
+	}
+}
+
+impl<T: Trait> OnFreeBalanceZero<T::AccountId> for Module<T> {
+	fn on_free_balance_zero(who: &T::AccountId) {
+		<Vesting<T>>::remove(who);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use std::cell::RefCell;
+	use runtime_io::with_externalities;
+	use srml_support::{assert_ok, assert_noop, impl_outer_origin, parameter_types};
+	use primitives::{H256, Blake2Hasher};
+	use sr_primitives::{
+		Perbill, testing::Header, traits::{BlakeTwo256, IdentityLookup, ConvertInto},
+	};
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	// For testing the module, we construct most of a mock runtime. This means
+	// first constructing a configuration type (`Test`) which `impl`s each of the
+	// configuration traits of modules we want to use.
+	#[derive(Clone, Eq, PartialEq)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: u32 = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::one();
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Call = ();
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type WeightMultiplierUpdate = ();
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+	}
+	parameter_types! {
+		pub const TransferFee: u64 = 0;
+		pub const CreationFee: u64 = 0;
+		pub const TransactionBaseFee: u64 = 0;
+		pub const TransactionByteFee: u64 = 0;
+	}
+	impl balances::Trait for Test {
+		type Balance = u64;
+		type OnFreeBalanceZero = Vesting;
+		type OnNewAccount = ();
+		type Event = ();
+		type TransactionPayment = ();
+		type TransferPayment = ();
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type TransferFee = TransferFee;
+		type CreationFee = CreationFee;
+		type TransactionBaseFee = TransactionBaseFee;
+		type TransactionByteFee = TransactionByteFee;
+		type WeightToFee = ();
+	}
+	parameter_types! {
+		pub const MinVestedTransfer: u64 = 256 * 2;
+	}
+	impl Trait for Test {
+		type Event = ();
+		type Currency = Balances;
+		type BlockNumberToBalance = ConvertInto;
+		type MinVestedTransfer = MinVestedTransfer;
+	}
+	type System = system::Module<Test>;
+	type Balances = balances::Module<Test>;
+	type Vesting = Module<Test>;
+
+	thread_local! {
+		static EXISTENTIAL_DEPOSIT: RefCell<u64> = RefCell::new(0);
+	}
+	pub struct ExistentialDeposit;
+	impl Get<u64> for ExistentialDeposit {
+		fn get() -> u64 { EXISTENTIAL_DEPOSIT.with(|v| *v.borrow()) }
+	}
+
+	pub struct ExtBuilder {
+		existential_deposit: u64,
+	}
+	impl Default for ExtBuilder {
+		fn default() -> Self {
+			Self {
+				existential_deposit: 0,
+			}
+		}
+	}
+	impl ExtBuilder {
+		pub fn existential_deposit(mut self, existential_deposit: u64) -> Self {
+			self.existential_deposit = existential_deposit;
+			self
+		}
+		pub fn build(self) -> runtime_io::TestExternalities<Blake2Hasher> {
+			EXISTENTIAL_DEPOSIT.with(|v| *v.borrow_mut() = self.existential_deposit);
+			let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+			balances::GenesisConfig::<Test> {
+				balances: vec![
+					(1, 10 * self.existential_deposit),
+					(2, 20 * self.existential_deposit),
+					(3, 30 * self.existential_deposit),
+					(4, 40 * self.existential_deposit),
+					(12, 10 * self.existential_deposit)
+				],
+			}.assimilate_storage(&mut t).unwrap();
+			GenesisConfig::<Test> {
+				vesting: vec![
+					(1, 0, 10, 5 * self.existential_deposit),
+					(2, 10, 20, 0),
+					(12, 10, 20, 5 * self.existential_deposit)
+				],
+			}.assimilate_storage(&mut t).unwrap();
+			t.into()
+		}
+	}
+
+	#[test]
+	fn check_vesting_status() {
+		with_externalities(&mut ExtBuilder::default().existential_deposit(256).build(), || {
+			assert_eq!(System::block_number(), 1);
+			let user1_free_balance = Balances::free_balance(&1);
+			let user2_free_balance = Balances::free_balance(&2);
+			let user12_free_balance = Balances::free_balance(&12);
+			assert_eq!(user1_free_balance, 256 * 10); // Account 1 has free balance
+			assert_eq!(user2_free_balance, 256 * 20); // Account 2 has free balance
+			assert_eq!(user12_free_balance, 256 * 10); // Account 12 has free balance
+			let user1_vesting_schedule = VestingInfo {
+				locked: 256 * 5,
+				per_block: 128, // Vesting over 10 blocks
+				starting_block: 0,
+			};
+			let user2_vesting_schedule = VestingInfo {
+				locked: 256 * 20,
+				per_block: 256, // Vesting over 20 blocks
+				starting_block: 10,
+			};
+			let user12_vesting_schedule = VestingInfo {
+				locked: 256 * 5,
+				per_block: 64, // Vesting over 20 blocks
+				starting_block: 10,
+			};
+			assert_eq!(Vesting::vesting(&1), Some(user1_vesting_schedule)); // Account 1 has a vesting schedule
+			assert_eq!(Vesting::vesting(&2), Some(user2_vesting_schedule)); // Account 2 has a vesting schedule
+			assert_eq!(Vesting::vesting(&12), Some(user12_vesting_schedule)); // Account 12 has a vesting schedule
+
+			// Account 1 has only 128 units vested from their illiquid 256 * 5 units at block 1
+			assert_eq!(Vesting::vesting_balance(&1), 128 * 9);
+			// Account 2 has their full balance locked
+			assert_eq!(Vesting::vesting_balance(&2), user2_free_balance);
+			// Account 12 has only their illiquid funds locked
+			assert_eq!(Vesting::vesting_balance(&12), user12_free_balance - 256 * 5);
+
+			System::set_block_number(10);
+			assert_eq!(System::block_number(), 10);
+
+			// Account 1 has fully vested by block 10
+			assert_eq!(Vesting::vesting_balance(&1), 0);
+			// Account 2 has started vesting by block 10
+			assert_eq!(Vesting::vesting_balance(&2), user2_free_balance);
+			// Account 12 has started vesting by block 10
+			assert_eq!(Vesting::vesting_balance(&12), user12_free_balance - 256 * 5);
+
+			System::set_block_number(30);
+			assert_eq!(System::block_number(), 30);
+
+			assert_eq!(Vesting::vesting_balance(&1), 0); // Account 1 is still fully vested, and not negative
+			assert_eq!(Vesting::vesting_balance(&2), 0); // Account 2 has fully vested by block 30
+			assert_eq!(Vesting::vesting_balance(&12), 0); // Account 2 has fully vested by block 30
+		});
+	}
+
+	#[test]
+	fn genesis_vesting_is_a_lock() {
+		with_externalities(&mut ExtBuilder::default().existential_deposit(10).build(), || {
+			let locks = Balances::locks(&1);
+			assert_eq!(locks.len(), 1);
+			assert_eq!(locks[0].id, VESTING_ID);
+			assert_eq!(locks[0].amount, 50);
+			// No lock for an account without a schedule.
+			assert!(Balances::locks(&3).is_empty());
+		});
+	}
+
+	#[test]
+	fn unvested_balance_should_not_transfer() {
+		with_externalities(&mut ExtBuilder::default().existential_deposit(10).build(), || {
+			assert_eq!(System::block_number(), 1);
+			let user1_free_balance = Balances::free_balance(&1);
+			assert_eq!(user1_free_balance, 100); // Account 1 has free balance
+			// Account 1 has only 5 units vested at block 1 (plus 50 unvested)
+			assert_eq!(Vesting::vesting_balance(&1), 45);
+			assert_noop!(
+				Balances::transfer(Some(1).into(), 2, 56),
+				balances::Error::LiquidityRestrictions
+			); // Account 1 cannot send more than vested amount
+		});
+	}
+
+	#[test]
+	fn vested_balance_should_transfer() {
+		with_externalities(&mut ExtBuilder::default().existential_deposit(10).build(), || {
+			assert_eq!(System::block_number(), 1);
+			let user1_free_balance = Balances::free_balance(&1);
+			assert_eq!(user1_free_balance, 100); // Account 1 has free balance
+			// Account 1 has only 5 units vested at block 1 (plus 50 unvested)
+			assert_eq!(Vesting::vesting_balance(&1), 45);
+			assert_ok!(Vesting::vest(Some(1).into()));
+			assert_ok!(Balances::transfer(Some(1).into(), 2, 55));
+		});
+	}
+
+	#[test]
+	fn vested_balance_should_transfer_using_vest_other() {
+		with_externalities(&mut ExtBuilder::default().existential_deposit(10).build(), || {
+			assert_eq!(System::block_number(), 1);
+			let user1_free_balance = Balances::free_balance(&1);
+			assert_eq!(user1_free_balance, 100); // Account 1 has free balance
+			// Account 1 has only 5 units vested at block 1 (plus 50 unvested)
+			assert_eq!(Vesting::vesting_balance(&1), 45);
+			assert_ok!(Vesting::vest_other(Some(2).into(), 1));
+			assert_ok!(Balances::transfer(Some(1).into(), 2, 55));
+		});
+	}
+
+	#[test]
+	fn fully_vested_account_loses_lock_and_schedule() {
+		with_externalities(&mut ExtBuilder::default().existential_deposit(10).build(), || {
+			System::set_block_number(10);
+			assert_ok!(Vesting::vest(Some(1).into()));
+			assert_eq!(Vesting::vesting(&1), None);
+			assert!(Balances::locks(&1).is_empty());
+			assert_ok!(Balances::transfer(Some(1).into(), 2, 100));
+			// Nothing left to vest.
+			assert_noop!(Vesting::vest(Some(1).into()), "account has no vesting schedule");
+		});
+	}
+
+	#[test]
+	fn extra_balance_should_transfer() {
+		with_externalities(&mut ExtBuilder::default().existential_deposit(10).build(), || {
+			assert_eq!(System::block_number(), 1);
+			assert_ok!(Balances::transfer(Some(3).into(), 1, 100));
+			assert_ok!(Balances::transfer(Some(3).into(), 2, 100));
+
+			let user1_free_balance = Balances::free_balance(&1);
+			assert_eq!(user1_free_balance, 200); // Account 1 has 100 more free balance than normal
+
+			let user2_free_balance = Balances::free_balance(&2);
+			assert_eq!(user2_free_balance, 300); // Account 2 has 100 more free balance than normal
+
+			// Account 1 has only 5 of its 50 locked units vested at block 1, the other 150 were never locked
+			assert_eq!(Vesting::vesting_balance(&1), 45);
+			assert_ok!(Vesting::vest(Some(1).into()));
+			assert_ok!(Balances::transfer(Some(1).into(), 3, 155)); // Account 1 can send extra units gained
+
+			// Account 2 has no units vested at block 1, but gained 100
+			assert_eq!(Vesting::vesting_balance(&2), 200);
+			assert_ok!(Vesting::vest(Some(2).into()));
+			assert_ok!(Balances::transfer(Some(2).into(), 3, 100)); // Account 2 can send extra units gained
+		});
+	}
+
+	#[test]
+	fn liquid_funds_should_transfer_with_delayed_vesting() {
+		with_externalities(&mut ExtBuilder::default().existential_deposit(256).build(), || {
+			assert_eq!(System::block_number(), 1);
+			let user12_free_balance = Balances::free_balance(&12);
+
+			assert_eq!(user12_free_balance, 2560); // Account 12 has free balance
+			// Account 12 has liquid funds
+			assert_eq!(Vesting::vesting_balance(&12), user12_free_balance - 256 * 5);
+
+			// Account 12 has delayed vesting
+			let user12_vesting_schedule = VestingInfo {
+				locked: 256 * 5,
+				per_block: 64, // Vesting over 20 blocks
+				starting_block: 10,
+			};
+			assert_eq!(Vesting::vesting(&12), Some(user12_vesting_schedule));
+
+			// Account 12 can still send liquid funds
+			assert_ok!(Balances::transfer(Some(12).into(), 3, 256 * 5));
+		});
+	}
+
+	#[test]
+	fn vested_transfer_works() {
+		with_externalities(&mut ExtBuilder::default().existential_deposit(256).build(), || {
+			assert_eq!(System::block_number(), 1);
+			let user3_free_balance = Balances::free_balance(&3);
+			let user4_free_balance = Balances::free_balance(&4);
+			assert_eq!(user3_free_balance, 256 * 30);
+			assert_eq!(user4_free_balance, 256 * 40);
+			// Account 4 should not have any vesting yet.
+			assert_eq!(Vesting::vesting(&4), None);
+			// Make the schedule for the new transfer.
+			let new_vesting_schedule = VestingInfo {
+				locked: 256 * 5,
+				per_block: 64, // Vesting over 20 blocks
+				starting_block: 10,
+			};
+			assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, new_vesting_schedule));
+			// Now account 4 should have vesting.
+			assert_eq!(Vesting::vesting(&4), Some(new_vesting_schedule));
+			// Ensure the transfer happened correctly.
+			assert_eq!(Balances::free_balance(&3), user3_free_balance - 256 * 5);
+			assert_eq!(Balances::free_balance(&4), user4_free_balance + 256 * 5);
+			// Account 4 has 5 * 256 locked.
+			assert_eq!(Vesting::vesting_balance(&4), 256 * 5);
+			assert_noop!(
+				Balances::transfer(Some(4).into(), 3, 256 * 41),
+				balances::Error::LiquidityRestrictions
+			);
+
+			System::set_block_number(20);
+			assert_eq!(System::block_number(), 20);
+
+			// Account 4 has 5 * 64 units vested by block 20.
+			assert_eq!(Vesting::vesting_balance(&4), 10 * 64);
+
+			System::set_block_number(30);
+			assert_eq!(System::block_number(), 30);
+
+			// Account 4 has fully vested.
+			assert_eq!(Vesting::vesting_balance(&4), 0);
+		});
+	}
+
+	#[test]
+	fn vested_transfer_correctly_fails() {
+		with_externalities(&mut ExtBuilder::default().existential_deposit(256).build(), || {
+			assert_eq!(System::block_number(), 1);
+			let user2_free_balance = Balances::free_balance(&2);
+			let user4_free_balance = Balances::free_balance(&4);
+			assert_eq!(user2_free_balance, 256 * 20);
+			assert_eq!(user4_free_balance, 256 * 40);
+			// Account 2 should already have a vesting schedule.
+			let user2_vesting_schedule = VestingInfo {
+				locked: 256 * 20,
+				per_block: 256, // Vesting over 20 blocks
+				starting_block: 10,
+			};
+			assert_eq!(Vesting::vesting(&2), Some(user2_vesting_schedule));
+
+			// The vesting schedule we will try to create, fails due to pre-existence of schedule.
+			let new_vesting_schedule = VestingInfo {
+				locked: 256 * 5,
+				per_block: 64, // Vesting over 20 blocks
+				starting_block: 10,
+			};
+			assert_noop!(
+				Vesting::vested_transfer(Some(4).into(), 2, new_vesting_schedule),
+				"target already has a vesting schedule"
+			);
+
+			// Fails due to too low transfer amount.
+			let new_vesting_schedule_too_low = VestingInfo {
+				locked: 256 * 1,
+				per_block: 64,
+				starting_block: 10,
+			};
+			assert_noop!(
+				Vesting::vested_transfer(Some(3).into(), 4, new_vesting_schedule_too_low),
+				"amount transferred is too low to create a vesting schedule"
+			);
+
+			// Fails due to a schedule that never unlocks.
+			let new_vesting_schedule_stuck = VestingInfo {
+				locked: 256 * 5,
+				per_block: 0,
+				starting_block: 10,
+			};
+			assert_noop!(
+				Vesting::vested_transfer(Some(3).into(), 4, new_vesting_schedule_stuck),
+				"vesting schedule never unlocks any funds"
+			);
+
+			// Fails due to the sender not having the funds.
+			let new_vesting_schedule_too_big = VestingInfo {
+				locked: 256 * 100,
+				per_block: 64,
+				starting_block: 10,
+			};
+			assert_noop!(
+				Vesting::vested_transfer(Some(3).into(), 4, new_vesting_schedule_too_big),
+				"balance too low to send value"
+			);
+
+			// Verify no currency transfer happened.
+			assert_eq!(Balances::free_balance(&2), user2_free_balance);
+			assert_eq!(Balances::free_balance(&4), user4_free_balance);
+		});
+	}
+
+	#[test]
+	fn reaped_account_loses_vesting_schedule() {
+		with_externalities(&mut ExtBuilder::default().existential_deposit(10).build(), || {
+			assert!(Vesting::vesting(&1).is_some());
+			Vesting::on_free_balance_zero(&1);
+			assert_eq!(Vesting::vesting(&1), None);
+		});
+	}
+}